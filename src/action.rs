@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 use uuid::Uuid;
 
+use crate::agent::{is_adjacent, Agent};
+use crate::config::Config;
 use crate::crafting::{MaterialType, ToolType};
 use crate::structures::StructureType;
 use crate::trade::TradeableItem;
@@ -12,6 +15,10 @@ pub enum Action {
     Wait,
     /// Move in a direction
     Move(Direction),
+    /// Take one step along the easiest way to a cell
+    Travel { x: usize, y: usize },
+    /// Step away from whoever last attacked you
+    Flee,
     /// Gather food from current location
     Gather,
     /// Consume food from inventory
@@ -22,16 +29,24 @@ pub enum Action {
     Speak { target: Uuid, message: String },
     /// Give food to a nearby agent
     Give { target: Uuid, amount: u32 },
+    /// Hand materials or a tool to a nearby agent
+    GiveItem { target: Uuid, item: TradeableItem },
     /// Attack a nearby agent
     Attack { target: Uuid },
     /// Share opinion about another agent (gossip)
     Gossip { target: Uuid, about: Uuid },
+    /// Tell a nearby agent where food and danger are
+    ShareKnowledge { target: Uuid },
     /// Court a nearby agent (advance courtship)
     Court { target: Uuid },
     /// Attempt to mate with a nearby agent (requires mutual consent and courtship threshold)
     Mate { target: Uuid },
     /// Teach a skill to a nearby agent
     Teach { target: Uuid, skill: String },
+    /// Teach a skill to everyone nearby at once, each learning less
+    TeachGroup { skill: String },
+    /// Eat from the remains of someone who died here
+    Scavenge,
     /// Gather materials (wood, stone, fiber) from current location
     GatherMaterials,
     /// Craft a tool from materials
@@ -42,9 +57,15 @@ pub enum Action {
     Fish,
     /// Chop wood efficiently (requires axe)
     Chop,
+    /// Take a carried tool in hand so its bonus applies
+    Equip { tool: ToolType },
+    /// Put away the tool in hand
+    Unequip,
     // Structure actions
     /// Build or continue building a structure at current location
     Build { structure_type: StructureType },
+    /// Help build an unfinished structure here or in an adjacent cell
+    AssistBuild { structure: StructureType },
     /// Enter a shelter at current location
     EnterShelter,
     /// Leave the current shelter
@@ -57,6 +78,8 @@ pub enum Action {
     Permit { target: Uuid },
     /// Revoke access from your structure
     Deny { target: Uuid },
+    /// Tear down your structure at current location, salvaging some materials
+    Dismantle,
     // Territory actions
     /// Mark current cell as your territory
     Mark,
@@ -85,6 +108,215 @@ pub enum Action {
     },
     /// Cancel your own pending proposal
     TradeCancel { proposal_index: usize },
+    /// Promise a nearby agent a service or future gift, outside of any trade
+    Promise { target: Uuid, promise: TradeableItem },
+    /// Commit to a personal goal that persists until survival needs take over
+    SetGoal { description: String },
+    // Diplomacy actions
+    /// As a group leader, offer peace to another group (by name)
+    ProposePeace { rival_group: String },
+    /// As a group leader, declare war on another group (by name)
+    DeclareWar { rival_group: String },
+}
+
+impl Action {
+    /// The other agent this action is aimed at, if any
+    pub fn target(&self) -> Option<Uuid> {
+        match self {
+            Action::Speak { target, .. }
+            | Action::Give { target, .. }
+            | Action::GiveItem { target, .. }
+            | Action::Attack { target }
+            | Action::Gossip { target, .. }
+            | Action::ShareKnowledge { target }
+            | Action::Court { target }
+            | Action::Mate { target }
+            | Action::Teach { target, .. }
+            | Action::Permit { target }
+            | Action::Deny { target }
+            | Action::Challenge { target }
+            | Action::TradeOffer { target, .. }
+            | Action::Promise { target, .. } => Some(*target),
+            _ => None,
+        }
+    }
+
+    /// Short verb phrase for failure feedback ("your attempt to {verb} failed")
+    pub fn verb(&self) -> String {
+        match self {
+            Action::Speak { .. } => "speak".to_string(),
+            Action::Give { .. } => "give food".to_string(),
+            Action::GiveItem { item, .. } => format!("give {}", item.describe()),
+            Action::Attack { .. } => "attack".to_string(),
+            Action::Gossip { .. } => "gossip".to_string(),
+            Action::ShareKnowledge { .. } => "share knowledge".to_string(),
+            Action::Court { .. } => "court".to_string(),
+            Action::Mate { .. } => "mate".to_string(),
+            Action::Teach { .. } | Action::TeachGroup { .. } => "teach".to_string(),
+            Action::Promise { .. } => "promise".to_string(),
+            Action::TradeOffer { .. } => "offer a trade".to_string(),
+            Action::TradeAccept { .. } => "accept the trade".to_string(),
+            Action::Scavenge => "scavenge".to_string(),
+            Action::Travel { .. } => "travel".to_string(),
+            Action::Flee => "flee".to_string(),
+            Action::AssistBuild { .. } => "assist".to_string(),
+            Action::ProposePeace { .. } => "propose peace".to_string(),
+            Action::DeclareWar { .. } => "declare war".to_string(),
+            _ => "act".to_string(),
+        }
+    }
+
+    /// Check the preconditions that can be judged before resolution: the
+    /// feature is enabled, the target is alive and next to the agent, and the
+    /// agent has what the action needs
+    pub fn validate(&self, agent: &Agent, ctx: &ActionContext) -> Result<(), ActionError> {
+        match self {
+            Action::Court { .. } | Action::Mate { .. } if !ctx.config.reproduction.enabled => {
+                return Err(ActionError::Disabled("courtship"));
+            }
+            Action::Teach { .. } | Action::TeachGroup { .. } if !ctx.config.skills.enabled => {
+                return Err(ActionError::Disabled("teaching"));
+            }
+            Action::Scavenge if !ctx.config.scavenging.enabled => {
+                return Err(ActionError::Disabled("scavenging"));
+            }
+            Action::ProposePeace { .. } | Action::DeclareWar { .. } if !ctx.config.groups.diplomacy => {
+                return Err(ActionError::Disabled("diplomacy"));
+            }
+            _ => {}
+        }
+
+        if agent.physical.incapacitated && !matches!(self, Action::Rest | Action::Eat) {
+            return Err(ActionError::Incapacitated);
+        }
+
+        if let Some(target) = self.target().filter(|_| self.needs_adjacent_target()) {
+            ctx.adjacent_target(agent, target)?;
+        }
+
+        match self {
+            Action::Give { .. } if agent.physical.food == 0 => Err(ActionError::NothingToGive),
+            Action::GiveItem { item, .. } if item.is_promise() || !agent.has_items(std::slice::from_ref(item)) => {
+                Err(ActionError::MissingItem(item.describe()))
+            }
+            Action::Gossip { about, .. } if !ctx.agents.iter().any(|a| a.id == *about) => {
+                Err(ActionError::UnknownTarget)
+            }
+            Action::Mate { .. } if agent.reproduction.mating_cooldown > 0 => {
+                Err(ActionError::OnCooldown(agent.reproduction.mating_cooldown))
+            }
+            Action::Teach { skill, .. } | Action::TeachGroup { skill }
+                if agent.skills.level(skill) < ctx.config.skills.min_level_to_teach =>
+            {
+                Err(ActionError::UnknownSkill(skill.clone()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Actions that only work on a living agent within reach
+    fn needs_adjacent_target(&self) -> bool {
+        matches!(
+            self,
+            Action::Speak { .. }
+                | Action::Give { .. }
+                | Action::GiveItem { .. }
+                | Action::Attack { .. }
+                | Action::Gossip { .. }
+                | Action::ShareKnowledge { .. }
+                | Action::Court { .. }
+                | Action::Mate { .. }
+                | Action::Teach { .. }
+                | Action::Promise { .. }
+        )
+    }
+}
+
+/// Longest remark passed along with another action
+const MAX_REMARK_CHARS: usize = 120;
+
+/// A word in passing to someone nearby, made alongside the epoch's action
+#[derive(Debug, Clone, PartialEq)]
+pub struct Remark {
+    pub target: Uuid,
+    pub message: String,
+}
+
+impl Remark {
+    /// Parse a trailing quoted remark from an action line, e.g.
+    /// `GATHER "Good berries here, Bria"`. It goes to the nearby agent it
+    /// names, or to the only one nearby
+    pub fn parse(text: &str, nearby_agents: &[(Uuid, &str)]) -> Option<Self> {
+        let end = text.rfind('"')?;
+        let start = text[..end].rfind('"')?;
+        let message: String = text[start + 1..end].trim().chars().take(MAX_REMARK_CHARS).collect();
+        if message.is_empty() {
+            return None;
+        }
+
+        let named = message
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .find_map(|word| nearby_agents.iter().find(|(_, name)| name.eq_ignore_ascii_case(word)));
+        let target = match (named, nearby_agents) {
+            (Some((id, _)), _) => *id,
+            (None, [(id, _)]) => *id,
+            _ => return None,
+        };
+        Some(Self { target, message })
+    }
+}
+
+/// What `Action::validate` may look at besides the acting agent
+pub struct ActionContext<'a> {
+    pub agents: &'a [Agent],
+    pub config: &'a Config,
+}
+
+impl ActionContext<'_> {
+    /// Find a living target next to the agent
+    fn adjacent_target(&self, agent: &Agent, target: Uuid) -> Result<&Agent, ActionError> {
+        if target == agent.id {
+            return Err(ActionError::SelfTarget);
+        }
+        let target = self
+            .agents
+            .iter()
+            .find(|a| a.id == target)
+            .ok_or(ActionError::UnknownTarget)?;
+        if !target.is_alive() {
+            return Err(ActionError::TargetDead(target.name().to_string()));
+        }
+        if !is_adjacent(agent, target) {
+            return Err(ActionError::NotAdjacent(target.name().to_string()));
+        }
+        Ok(target)
+    }
+}
+
+/// Why an action can't be carried out; the message is shown to the agent
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ActionError {
+    #[error("{0} is not part of this world")]
+    Disabled(&'static str),
+    #[error("you can't do that to yourself")]
+    SelfTarget,
+    #[error("there is nobody by that name")]
+    UnknownTarget,
+    #[error("{0} is dead")]
+    TargetDead(String),
+    #[error("{0} is not next to you")]
+    NotAdjacent(String),
+    #[error("you have no food to give")]
+    NothingToGive,
+    #[error("you don't have {0}")]
+    MissingItem(String),
+    #[error("you need {0} more days before you can mate again")]
+    OnCooldown(usize),
+    #[error("you don't know {0} well enough to teach it")]
+    UnknownSkill(String),
+    #[error("you are too exhausted to do anything but rest or eat")]
+    Incapacitated,
 }
 
 /// Movement directions (8-directional)
@@ -101,6 +333,18 @@ pub enum Direction {
 }
 
 impl Direction {
+    /// All eight directions
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::South,
+        Direction::East,
+        Direction::West,
+        Direction::NorthEast,
+        Direction::NorthWest,
+        Direction::SouthEast,
+        Direction::SouthWest,
+    ];
+
     /// Get the delta for this direction
     pub fn delta(&self) -> (i32, i32) {
         match self {
@@ -115,6 +359,31 @@ impl Direction {
         }
     }
 
+    /// Get the direction that steps from one position toward another
+    pub fn toward(from: (usize, usize), to: (usize, usize)) -> Option<Self> {
+        let dx = (to.0 as i64 - from.0 as i64).signum();
+        let dy = (to.1 as i64 - from.1 as i64).signum();
+        match (dx, dy) {
+            (0, -1) => Some(Direction::North),
+            (0, 1) => Some(Direction::South),
+            (1, 0) => Some(Direction::East),
+            (-1, 0) => Some(Direction::West),
+            (1, -1) => Some(Direction::NorthEast),
+            (-1, -1) => Some(Direction::NorthWest),
+            (1, 1) => Some(Direction::SouthEast),
+            (-1, 1) => Some(Direction::SouthWest),
+            _ => None,
+        }
+    }
+
+    /// Position one step from `from` in this direction (None if off the low edge)
+    pub fn step(&self, from: (usize, usize)) -> Option<(usize, usize)> {
+        let (dx, dy) = self.delta();
+        let x = from.0.checked_add_signed(dx as isize)?;
+        let y = from.1.checked_add_signed(dy as isize)?;
+        Some((x, y))
+    }
+
     /// Get direction name for display
     pub fn name(&self) -> &'static str {
         match self {
@@ -165,6 +434,13 @@ impl Action {
                     None
                 }
             }
+            "TRAVEL" => {
+                // TRAVEL <x> <y>, also written "TRAVEL 3,4" or "TRAVEL (3, 4)"
+                let rest = words[1..].join(" ");
+                let mut numbers = rest.split(|c: char| !c.is_ascii_digit()).filter_map(|n| n.parse().ok());
+                Some(Action::Travel { x: numbers.next()?, y: numbers.next()? })
+            }
+            "FLEE" | "RUN" => Some(Action::Flee),
             "GATHER" => Some(Action::Gather),
             "EAT" => Some(Action::Eat),
             "REST" => Some(Action::Rest),
@@ -179,11 +455,19 @@ impl Action {
                 }
             }
             "GIVE" => {
+                // GIVE <name> <amount> gives food; GIVE <name> <item> hands over materials or a tool
                 if words.len() >= 3 {
                     let target_name = words[1].to_lowercase();
-                    let amount = words[2].parse().unwrap_or(1);
-                    find_agent_by_name(&target_name, nearby_agents)
-                        .map(|target| Action::Give { target, amount })
+                    let target = find_agent_by_name(&target_name, nearby_agents)?;
+                    if words.len() == 3 && words[2].parse::<u32>().is_ok() {
+                        let amount = words[2].parse().unwrap_or(1);
+                        return Some(Action::Give { target, amount });
+                    }
+                    match parse_tradeable_items(&words[2..]).into_iter().next()? {
+                        TradeableItem::Food(amount) => Some(Action::Give { target, amount }),
+                        item if item.is_promise() => None,
+                        item => Some(Action::GiveItem { target, item }),
+                    }
                 } else {
                     None
                 }
@@ -212,6 +496,15 @@ impl Action {
                     None
                 }
             }
+            "SHARE" | "TELL" => {
+                if words.len() >= 2 {
+                    let target_name = words[1].to_lowercase();
+                    find_agent_by_name(&target_name, nearby_agents)
+                        .map(|target| Action::ShareKnowledge { target })
+                } else {
+                    None
+                }
+            }
             "COURT" => {
                 if words.len() >= 2 {
                     let target_name = words[1].to_lowercase();
@@ -241,6 +534,10 @@ impl Action {
                     None
                 }
             }
+            "TEACH_GROUP" | "TEACH_ALL" | "CLASS" => {
+                // TEACH_GROUP <skill>
+                words.get(1).map(|skill| Action::TeachGroup { skill: skill.to_lowercase() })
+            }
             "GATHER_MATERIALS" | "GATHER_MAT" | "COLLECT" => Some(Action::GatherMaterials),
             "CRAFT" => {
                 if words.len() >= 2 {
@@ -250,9 +547,19 @@ impl Action {
                     None
                 }
             }
+            "SCAVENGE" => Some(Action::Scavenge),
             "HUNT" => Some(Action::Hunt),
             "FISH" => Some(Action::Fish),
             "CHOP" => Some(Action::Chop),
+            "EQUIP" | "WIELD" => {
+                if words.len() >= 2 {
+                    let tool_name = words[1..].join("_").to_lowercase();
+                    ToolType::parse(&tool_name).map(|tool| Action::Equip { tool })
+                } else {
+                    None
+                }
+            }
+            "UNEQUIP" => Some(Action::Unequip),
             // Structure actions
             "BUILD" => {
                 if words.len() >= 2 {
@@ -262,6 +569,27 @@ impl Action {
                     None
                 }
             }
+            "ASSIST" | "ASSIST_BUILD" | "HELP_BUILD" => {
+                if words.len() >= 2 {
+                    let structure_name = words[1..].join("_").to_lowercase();
+                    StructureType::parse(&structure_name).map(|structure| Action::AssistBuild { structure })
+                } else {
+                    None
+                }
+            }
+            // Diplomacy: PROPOSE_PEACE <group name> / DECLARE_WAR <group name>
+            "PROPOSE_PEACE" | "PEACE" | "DECLARE_WAR" | "WAR" => {
+                if words.len() >= 2 {
+                    let rival_group = words[1..].join(" ").to_lowercase();
+                    if words[0].ends_with("PEACE") {
+                        Some(Action::ProposePeace { rival_group })
+                    } else {
+                        Some(Action::DeclareWar { rival_group })
+                    }
+                } else {
+                    None
+                }
+            }
             "ENTER" | "ENTER_SHELTER" => Some(Action::EnterShelter),
             "LEAVE" | "LEAVE_SHELTER" => Some(Action::LeaveShelter),
             "DEPOSIT" => {
@@ -310,6 +638,7 @@ impl Action {
                     None
                 }
             }
+            "DISMANTLE" | "DEMOLISH" | "TEAR_DOWN" => Some(Action::Dismantle),
             // Territory actions
             "MARK" | "CLAIM" => Some(Action::Mark),
             "CHALLENGE" => {
@@ -323,6 +652,15 @@ impl Action {
             }
             "SUBMIT" | "YIELD" | "LEAVE_TERRITORY" => Some(Action::Submit),
             "FIGHT" | "RESIST" | "DEFEND" => Some(Action::Fight),
+            "GOAL" | "SET_GOAL" | "RESOLVE" => {
+                // GOAL <description>
+                if words.len() >= 2 {
+                    let description = words[1..].join(" ").to_lowercase();
+                    Some(Action::SetGoal { description })
+                } else {
+                    None
+                }
+            }
             // Trade actions
             "TRADE" | "OFFER" => {
                 // TRADE <name> OFFER <items> FOR <items>
@@ -404,18 +742,62 @@ impl Action {
                     None
                 }
             }
+            "PROMISE" => {
+                // PROMISE <name> <items>: "3 FOOD WITHIN 10", "TEACH HUNTING", "5 LABOR", "ALLIANCE 30";
+                // plain food ("PROMISE Bria 3 FOOD") is a gift due by the default deadline
+                if words.len() >= 3 {
+                    let target_name = words[1].to_lowercase();
+                    let target = find_agent_by_name(&target_name, nearby_agents)?;
+                    let promise = parse_tradeable_items(&words[2..]).into_iter().next()?;
+                    (promise.is_promise() || matches!(promise, TradeableItem::Food(_)))
+                        .then_some(Action::Promise { target, promise })
+                } else {
+                    None
+                }
+            }
             _ => None,
         }
     }
 
+    /// Dig an action out of a reply that doesn't start with one: whatever
+    /// follows an "ACTION:" label, else the first upper-case action keyword
+    /// ("I think I should GATHER now."). Words are stripped of punctuation.
+    pub fn salvage(text: &str, nearby_agents: &[(Uuid, &str)]) -> Option<Self> {
+        let clean = |line: &str| -> Vec<String> {
+            line.split_whitespace()
+                .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric() && c != '_').to_string())
+                .filter(|w| !w.is_empty())
+                .collect()
+        };
+        let labelled = text.find("ACTION:").or_else(|| text.find("Action:")).map(|at| &text[at + "ACTION:".len()..]);
+        if let Some(action) = labelled
+            .and_then(|rest| rest.lines().next())
+            .and_then(|line| Action::parse(&clean(line).join(" "), nearby_agents))
+        {
+            return Some(action);
+        }
+
+        // Only words written in capitals, so "the rest of the day" isn't taken for REST
+        let shouted = |word: &str| word.len() > 1 && word.chars().any(char::is_alphabetic) && !word.chars().any(char::is_lowercase);
+        text.lines().find_map(|line| {
+            let words = clean(line);
+            (0..words.len())
+                .filter(|&i| shouted(&words[i]))
+                .find_map(|i| Action::parse(&words[i..].join(" "), nearby_agents))
+        })
+    }
+
     /// Describe the action for logging
     pub fn describe(&self, agent_name: &str, agents: &[(Uuid, &str)]) -> String {
         match self {
             Action::Wait => format!("{} waits", agent_name),
             Action::Move(dir) => format!("{} moves {}", agent_name, dir.name()),
+            Action::Travel { x, y } => format!("{} heads for ({}, {})", agent_name, x, y),
+            Action::Flee => format!("{} flees", agent_name),
             Action::Gather => format!("{} gathers food", agent_name),
             Action::Eat => format!("{} eats", agent_name),
             Action::Rest => format!("{} rests", agent_name),
+            Action::Scavenge => format!("{} scavenges the remains of the dead", agent_name),
             Action::Speak { target, message } => {
                 let target_name = find_name_by_id(*target, agents).unwrap_or("someone");
                 format!("{} says to {}: \"{}\"", agent_name, target_name, message)
@@ -424,6 +806,10 @@ impl Action {
                 let target_name = find_name_by_id(*target, agents).unwrap_or("someone");
                 format!("{} gives {} food to {}", agent_name, amount, target_name)
             }
+            Action::GiveItem { target, item } => {
+                let target_name = find_name_by_id(*target, agents).unwrap_or("someone");
+                format!("{} gives {} to {}", agent_name, item.describe(), target_name)
+            }
             Action::Attack { target } => {
                 let target_name = find_name_by_id(*target, agents).unwrap_or("someone");
                 format!("{} attacks {}", agent_name, target_name)
@@ -433,6 +819,10 @@ impl Action {
                 let about_name = find_name_by_id(*about, agents).unwrap_or("someone");
                 format!("{} gossips to {} about {}", agent_name, target_name, about_name)
             }
+            Action::ShareKnowledge { target } => {
+                let target_name = find_name_by_id(*target, agents).unwrap_or("someone");
+                format!("{} tells {} what they know of the land", agent_name, target_name)
+            }
             Action::Court { target } => {
                 let target_name = find_name_by_id(*target, agents).unwrap_or("someone");
                 format!("{} courts {}", agent_name, target_name)
@@ -445,6 +835,7 @@ impl Action {
                 let target_name = find_name_by_id(*target, agents).unwrap_or("someone");
                 format!("{} teaches {} to {}", agent_name, skill, target_name)
             }
+            Action::TeachGroup { skill } => format!("{} teaches {} to those gathered around", agent_name, skill),
             Action::GatherMaterials => format!("{} gathers materials", agent_name),
             Action::Craft { tool } => format!("{} crafts a {}", agent_name, tool.display_name()),
             Action::Hunt => format!("{} hunts for prey", agent_name),
@@ -453,6 +844,9 @@ impl Action {
             Action::Build { structure_type } => {
                 format!("{} builds a {}", agent_name, structure_type.display_name())
             }
+            Action::AssistBuild { structure } => {
+                format!("{} helps build a {}", agent_name, structure.display_name())
+            }
             Action::EnterShelter => format!("{} enters shelter", agent_name),
             Action::LeaveShelter => format!("{} leaves shelter", agent_name),
             Action::Deposit { material, amount } => {
@@ -469,6 +863,9 @@ impl Action {
                 let target_name = find_name_by_id(*target, agents).unwrap_or("someone");
                 format!("{} denies {} access to their structure", agent_name, target_name)
             }
+            Action::Dismantle => format!("{} dismantles their structure", agent_name),
+            Action::Equip { tool } => format!("{} equips a {}", agent_name, tool.display_name()),
+            Action::Unequip => format!("{} puts away their tool", agent_name),
             Action::Mark => format!("{} marks territory", agent_name),
             Action::Challenge { target } => {
                 let target_name = find_name_by_id(*target, agents).unwrap_or("someone");
@@ -476,6 +873,9 @@ impl Action {
             }
             Action::Submit => format!("{} submits and leaves the territory", agent_name),
             Action::Fight => format!("{} fights to defend their position", agent_name),
+            Action::SetGoal { description } => format!("{} resolves to {}", agent_name, description),
+            Action::ProposePeace { rival_group } => format!("{} offers peace to {}", agent_name, rival_group),
+            Action::DeclareWar { rival_group } => format!("{} declares war on {}", agent_name, rival_group),
             Action::TradeOffer { target, offering, requesting } => {
                 let target_name = find_name_by_id(*target, agents).unwrap_or("someone");
                 let offer_str: Vec<_> = offering.iter().map(|i| i.describe()).collect();
@@ -496,6 +896,10 @@ impl Action {
             Action::TradeCancel { proposal_index } => {
                 format!("{} cancels their trade offer #{}", agent_name, proposal_index + 1)
             }
+            Action::Promise { target, promise } => {
+                let target_name = find_name_by_id(*target, agents).unwrap_or("someone");
+                format!("{} promises {} to {}", agent_name, promise.describe(), target_name)
+            }
         }
     }
 
@@ -533,10 +937,13 @@ impl Action {
         let mut actions: Vec<String> = vec![
             "WAIT - do nothing, recover energy".to_string(),
             "MOVE <direction> - move (north/south/east/west/ne/nw/se/sw)".to_string(),
+            "TRAVEL <x> <y> - take the next step along the easiest way to a place, such as food you remember".to_string(),
+            "FLEE - run from whoever last attacked you".to_string(),
             "GATHER - collect food from current location".to_string(),
             "EAT - eat food from your inventory".to_string(),
             "REST - rest to recover energy".to_string(),
             "GATHER_MATERIALS - collect wood, stone, fiber, or flint from the terrain".to_string(),
+            "GOAL <description> - commit to a personal goal you will pursue over the coming days".to_string(),
         ];
 
         // Tool-unlocked actions
@@ -549,6 +956,9 @@ impl Action {
         if unlocked_actions.contains(&"chop") {
             actions.push("CHOP - efficiently chop wood (requires axe)".to_string());
         }
+        if unlocked_actions.contains(&"equip") {
+            actions.push("EQUIP <tool> - take a tool you carry in hand so it helps you (UNEQUIP to put it away)".to_string());
+        }
 
         // Crafting
         if !craftable_tools.is_empty() {
@@ -569,6 +979,9 @@ impl Action {
                 .join(", ");
             actions.push(format!("BUILD <structure> - build a structure (available: {})", structures_list));
         }
+        if !nearby_agents.is_empty() {
+            actions.push("ASSIST <structure> - help build an unfinished structure here or next to you".to_string());
+        }
 
         if has_shelter && !is_sheltered {
             actions.push("ENTER - enter a shelter for protection".to_string());
@@ -586,6 +999,9 @@ impl Action {
             actions.push("PERMIT <name> - grant someone access to your structure".to_string());
             actions.push("DENY <name> - revoke someone's access to your structure".to_string());
         }
+        if owns_structure {
+            actions.push("DISMANTLE - tear down your structure here and salvage some materials".to_string());
+        }
 
         // Territory actions
         if can_mark_territory {
@@ -605,10 +1021,12 @@ impl Action {
         if !nearby_agents.is_empty() {
             actions.push("SPEAK <name> <message> - say something to someone nearby".to_string());
             actions.push("GIVE <name> <amount> - give food to someone nearby".to_string());
+            actions.push("GIVE <name> <amount> <material> or GIVE <name> <tool> - hand over materials or a tool".to_string());
             actions.push("ATTACK <name> - attack someone nearby".to_string());
             if nearby_agents.len() >= 2 {
                 actions.push("GOSSIP <name> <about> - share your opinion about <about> with <name>".to_string());
             }
+            actions.push("SHARE <name> - tell someone nearby where you've found food and met danger".to_string());
             actions.push("COURT <name> - court someone nearby (builds courtship over time)".to_string());
             actions.push("MATE <name> - attempt to mate with someone (requires mutual consent and sufficient courtship)".to_string());
 
@@ -616,11 +1034,15 @@ impl Action {
             if !teachable_skills.is_empty() {
                 let skills_list = teachable_skills.iter().map(|s| s.as_str()).collect::<Vec<_>>().join("/");
                 actions.push(format!("TEACH <name> <skill> - teach a skill ({}) to someone nearby", skills_list));
+                if nearby_agents.len() >= 2 {
+                    actions.push("TEACH_GROUP <skill> - teach everyone nearby at once (each learns less)".to_string());
+                }
             }
 
             // Trade actions
             actions.push("TRADE <name> OFFER <items> FOR <items> - propose a trade".to_string());
             actions.push("  Items: food amount, material amount (wood/stone/etc), tool name (stone axe/bow/etc), TEACH <skill>, labor <amount>".to_string());
+            actions.push("PROMISE <name> <promise> - give your word (<amount> FOOD WITHIN <days>, TEACH <skill>, <amount> LABOR); breaking it costs trust".to_string());
         }
 
         // Trade response actions (only if there are pending offers)
@@ -642,12 +1064,19 @@ impl Action {
     }
 }
 
-/// Find agent UUID by name
+/// Find agent UUID by name: an exact match wins, otherwise the prefix must
+/// match exactly one agent (ambiguous prefixes return None)
 fn find_agent_by_name(name: &str, agents: &[(Uuid, &str)]) -> Option<Uuid> {
-    agents
-        .iter()
-        .find(|(_, n)| n.to_lowercase().starts_with(name))
-        .map(|(id, _)| *id)
+    let name = name.to_lowercase();
+    if let Some((id, _)) = agents.iter().find(|(_, n)| n.to_lowercase() == name) {
+        return Some(*id);
+    }
+
+    let mut matches = agents.iter().filter(|(_, n)| n.to_lowercase().starts_with(&name));
+    match (matches.next(), matches.next()) {
+        (Some((id, _)), None) => Some(*id),
+        _ => None,
+    }
 }
 
 /// Find agent name by UUID
@@ -759,22 +1188,20 @@ fn parse_item_type_first(type_word: &str, rest: &[&str]) -> Option<(TradeableIte
 /// Returns (item, extra_words_consumed)
 fn parse_item_type(type_word: &str, amount: u32, rest: &[&str]) -> Option<(TradeableItem, usize)> {
     match type_word {
-        "FOOD" => Some((TradeableItem::Food(amount), 0)),
-        "WOOD" | "STONE" | "FIBER" | "FLINT" | "HIDE" | "BONE" => {
-            let mat = parse_material_type(type_word)?;
-            Some((TradeableItem::Materials(mat, amount), 0))
-        }
-        "LABOR" => Some((TradeableItem::HelpBuildPromise { labor_points: amount }, 0)),
         // Future gift: "10 FOOD WITHIN 20" (amount already parsed as 10)
-        // Actually this is complex, need to handle differently
-        // For now, just treat FOOD/WITHIN as future gift pattern
-        _ if type_word == "FOOD" && rest.len() >= 2 && rest[0] == "WITHIN" => {
+        "FOOD" if rest.len() >= 2 && rest[0] == "WITHIN" => {
             if let Ok(deadline) = rest[1].parse::<usize>() {
                 Some((TradeableItem::FutureGiftPromise { amount, deadline_epochs: deadline }, 2))
             } else {
                 Some((TradeableItem::Food(amount), 0))
             }
         }
+        "FOOD" => Some((TradeableItem::Food(amount), 0)),
+        "WOOD" | "STONE" | "FIBER" | "FLINT" | "HIDE" | "BONE" => {
+            let mat = parse_material_type(type_word)?;
+            Some((TradeableItem::Materials(mat, amount), 0))
+        }
+        "LABOR" => Some((TradeableItem::HelpBuildPromise { labor_points: amount }, 0)),
         _ => None,
     }
 }
@@ -816,6 +1243,81 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_salvage_action_buried_in_reasoning() {
+        assert!(Action::parse("I think I should GATHER now.", &[]).is_none());
+        assert!(matches!(Action::salvage("I think I should GATHER now.", &[]), Some(Action::Gather)));
+        assert!(matches!(
+            Action::salvage("REASONING: I'm starving, but EAT what?\nACTION: move north.", &[]),
+            Some(Action::Move(Direction::North))
+        ));
+        assert!(Action::salvage("I'll take the rest of the day to think.", &[]).is_none());
+    }
+
+    #[test]
+    fn test_find_agent_by_exact_name() {
+        let (dara, darwin) = (Uuid::new_v4(), Uuid::new_v4());
+        let agents = [(darwin, "Darwin"), (dara, "Dara")];
+        assert_eq!(find_agent_by_name("dara", &agents), Some(dara));
+        assert_eq!(find_agent_by_name("DARWIN", &agents), Some(darwin));
+    }
+
+    #[test]
+    fn test_find_agent_by_unique_prefix() {
+        let (dara, bria) = (Uuid::new_v4(), Uuid::new_v4());
+        let agents = [(dara, "Dara"), (bria, "Bria")];
+        assert_eq!(find_agent_by_name("dar", &agents), Some(dara));
+        assert!(matches!(Action::parse("ATTACK bri", &agents), Some(Action::Attack { target }) if target == bria));
+    }
+
+    #[test]
+    fn test_ambiguous_prefix_matches_nobody() {
+        let agents = [(Uuid::new_v4(), "Dara"), (Uuid::new_v4(), "Darwin")];
+        assert_eq!(find_agent_by_name("dar", &agents), None);
+        assert!(Action::parse("ATTACK dar", &agents).is_none());
+    }
+
+    #[test]
+    fn test_parse_promise() {
+        let bria = Uuid::new_v4();
+        let agents = [(bria, "Bria")];
+        assert!(matches!(
+            Action::parse("PROMISE Bria 3 food within 10", &agents),
+            Some(Action::Promise { target, promise: TradeableItem::FutureGiftPromise { amount: 3, deadline_epochs: 10 } })
+                if target == bria
+        ));
+        assert!(matches!(
+            Action::parse("PROMISE Bria teach hunting", &agents),
+            Some(Action::Promise { promise: TradeableItem::TeachSkillPromise { .. }, .. })
+        ));
+        assert!(Action::parse("PROMISE Bria 3 wood", &agents).is_none());
+    }
+
+    #[test]
+    fn test_validate_reports_failed_preconditions() {
+        let config = Config::default();
+        let mut aric = Agent::new("Aric".to_string(), 0, 0, 5);
+        let bria = Agent::new("Bria".to_string(), 1, 1, 5);
+        let cara = Agent::new("Cara".to_string(), 5, 5, 5);
+        let agents = [aric.clone(), bria.clone(), cara.clone()];
+        let ctx = ActionContext { agents: &agents, config: &config };
+
+        let speak = Action::Speak { target: cara.id, message: "hello".to_string() };
+        assert_eq!(speak.validate(&aric, &ctx), Err(ActionError::NotAdjacent("Cara".to_string())));
+
+        let mate = Action::Mate { target: bria.id };
+        assert_eq!(mate.validate(&aric, &ctx), Ok(()));
+        aric.reproduction.mating_cooldown = 7;
+        assert_eq!(mate.validate(&aric, &ctx), Err(ActionError::OnCooldown(7)));
+
+        let teach = Action::Teach { target: bria.id, skill: "weaving".to_string() };
+        assert_eq!(teach.validate(&aric, &ctx), Err(ActionError::UnknownSkill("weaving".to_string())));
+        assert_eq!(
+            teach.validate(&aric, &ctx).unwrap_err().to_string(),
+            "you don't know weaving well enough to teach it"
+        );
+    }
+
     #[test]
     fn test_direction_delta() {
         assert_eq!(Direction::North.delta(), (0, -1));
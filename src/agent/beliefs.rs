@@ -2,6 +2,21 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+/// Remembered food amount considered worth travelling for
+pub const PLENTIFUL_FOOD: u32 = 3;
+
+/// How long a food memory stays trustworthy enough to act on
+const FOOD_MEMORY_EPOCHS: usize = 20;
+
+/// How long a place stays frightening after something bad happened there
+const DANGER_MEMORY_EPOCHS: usize = 30;
+
+/// Most landmarks an agent keeps in mind; the oldest are forgotten first
+const MAX_LANDMARKS: usize = 20;
+
+/// Most food locations and dangers passed on in one telling
+const MAX_SHARED_FACTS: usize = 4;
+
 /// Agent's belief system: what they think they know (can be wrong)
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Beliefs {
@@ -22,6 +37,38 @@ pub struct WorldBeliefs {
     pub dangerous_locations: Vec<(usize, usize)>,
     /// Known territory claims
     pub territories: Vec<TerritoryBelief>,
+    /// Places remembered for what happened there
+    #[serde(default)]
+    pub landmarks: Vec<Landmark>,
+}
+
+/// What makes a remembered place significant
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LandmarkKind {
+    /// Somewhere the agent came to harm
+    Danger,
+    /// A shelter the agent built
+    Shelter,
+    /// Where the agent's family began
+    Home,
+}
+
+/// A remembered place
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Landmark {
+    pub x: usize,
+    pub y: usize,
+    pub kind: LandmarkKind,
+    /// What happened here, e.g. "Bram attacked you"
+    pub note: String,
+    pub epoch: usize,
+}
+
+impl Landmark {
+    /// Description for agent perception
+    pub fn describe(&self) -> String {
+        format!("{} at ({}, {})", self.note, self.x, self.y)
+    }
 }
 
 /// Belief about territory at a location
@@ -65,6 +112,9 @@ pub struct SocialBelief {
     pub last_seen_epoch: usize,
     /// Brief impression
     pub impression: Option<String>,
+    /// Whether we've met them in person, rather than only heard of them
+    #[serde(default)]
+    pub met: bool,
 }
 
 /// Beliefs about self
@@ -109,6 +159,71 @@ impl Beliefs {
         }
     }
 
+    /// Remembered food amount at a location (if any)
+    pub fn food_at(&self, x: usize, y: usize) -> Option<u32> {
+        self.world
+            .food_locations
+            .iter()
+            .find(|b| b.x == x && b.y == y)
+            .map(|b| b.belief.amount)
+    }
+
+    /// Find the most promising remembered food location away from (x, y).
+    /// Only reasonably fresh, plentiful memories count; distance counts against a location.
+    pub fn best_food_elsewhere(&self, x: usize, y: usize, current_epoch: usize) -> Option<&FoodLocationBelief> {
+        let score = |b: &FoodLocationBelief| {
+            let distance = b.x.abs_diff(x).max(b.y.abs_diff(y)) as f64;
+            b.belief.amount as f64 - distance * 0.5
+        };
+
+        self.world
+            .food_locations
+            .iter()
+            .filter(|b| (b.x, b.y) != (x, y))
+            .filter(|b| b.belief.amount >= PLENTIFUL_FOOD)
+            .filter(|b| current_epoch.saturating_sub(b.belief.last_seen_epoch) < FOOD_MEMORY_EPOCHS)
+            .filter(|b| !self.is_dangerous(b.x, b.y, current_epoch))
+            .max_by(|a, b| score(a).partial_cmp(&score(b)).unwrap_or(std::cmp::Ordering::Equal))
+    }
+
+    /// Remember a place, replacing any older landmark of the same kind there
+    pub fn remember_landmark(&mut self, x: usize, y: usize, kind: LandmarkKind, note: &str, epoch: usize) {
+        let landmarks = &mut self.world.landmarks;
+        landmarks.retain(|l| !(l.x == x && l.y == y && l.kind == kind));
+        landmarks.push(Landmark {
+            x,
+            y,
+            kind,
+            note: note.to_string(),
+            epoch,
+        });
+        if landmarks.len() > MAX_LANDMARKS {
+            landmarks.remove(0);
+        }
+    }
+
+    /// Whether a location is remembered as recently dangerous
+    pub fn is_dangerous(&self, x: usize, y: usize, current_epoch: usize) -> bool {
+        self.world.landmarks.iter().any(|l| {
+            l.kind == LandmarkKind::Danger
+                && (l.x, l.y) == (x, y)
+                && current_epoch.saturating_sub(l.epoch) < DANGER_MEMORY_EPOCHS
+        })
+    }
+
+    /// Landmarks within `radius` cells of (x, y), nearest first
+    pub fn landmarks_near(&self, x: usize, y: usize, radius: usize) -> Vec<&Landmark> {
+        let distance = |l: &Landmark| l.x.abs_diff(x).max(l.y.abs_diff(y));
+        let mut near: Vec<&Landmark> = self
+            .world
+            .landmarks
+            .iter()
+            .filter(|l| distance(l) <= radius)
+            .collect();
+        near.sort_by_key(|l| distance(l));
+        near
+    }
+
     /// Update belief about territory at a location
     pub fn update_territory_belief(
         &mut self,
@@ -160,9 +275,25 @@ impl Beliefs {
             interaction_count: 0,
             last_seen_epoch: 0,
             impression: None,
+            met: false,
         })
     }
 
+    /// Meet someone in person for the first time. Whatever we've heard of them
+    /// stands, shifted by `cue` (trust, sentiment) from what we can see of them;
+    /// returns false if we had already met
+    pub fn first_impression(&mut self, agent_id: Uuid, name: &str, cue: (f64, f64), epoch: usize) -> bool {
+        let belief = self.get_or_create_social(agent_id, name);
+        if belief.met {
+            return false;
+        }
+        belief.met = true;
+        belief.trust = (belief.trust + cue.0).clamp(-1.0, 1.0);
+        belief.sentiment = (belief.sentiment + cue.1).clamp(-1.0, 1.0);
+        belief.last_seen_epoch = epoch;
+        true
+    }
+
     /// Update trust based on an interaction
     pub fn update_trust(&mut self, agent_id: Uuid, name: &str, delta: f64, epoch: usize) {
         let belief = self.get_or_create_social(agent_id, name);
@@ -214,6 +345,57 @@ impl Beliefs {
         }
     }
 
+    /// Hear what another agent knows about the world: their freshest food
+    /// locations and dangers. The more we trust the teller, the more of it we
+    /// take on; what we've seen more recently ourselves is kept.
+    /// Returns how many facts were learned
+    pub fn receive_knowledge(
+        &mut self,
+        teller_id: Uuid,
+        teller_name: &str,
+        teller: &WorldBeliefs,
+        epoch: usize,
+    ) -> usize {
+        let trust = self.social.get(&teller_id).map(|b| b.trust).unwrap_or(0.0);
+        if trust < -0.3 {
+            return 0;
+        }
+        let accepted = ((trust + 1.0) / 2.0 * MAX_SHARED_FACTS as f64).ceil() as usize;
+
+        let mut food: Vec<&FoodLocationBelief> = teller
+            .food_locations
+            .iter()
+            .filter(|b| b.belief.amount >= PLENTIFUL_FOOD)
+            .filter(|b| epoch.saturating_sub(b.belief.last_seen_epoch) < FOOD_MEMORY_EPOCHS)
+            .collect();
+        food.sort_by_key(|b| std::cmp::Reverse(b.belief.last_seen_epoch));
+
+        let mut dangers: Vec<&Landmark> = teller
+            .landmarks
+            .iter()
+            .filter(|l| l.kind == LandmarkKind::Danger)
+            .filter(|l| epoch.saturating_sub(l.epoch) < DANGER_MEMORY_EPOCHS)
+            .collect();
+        dangers.sort_by_key(|l| std::cmp::Reverse(l.epoch));
+
+        let mut learned = 0;
+        for told in food.into_iter().take(accepted) {
+            let known = self.world.food_locations.iter().find(|b| b.x == told.x && b.y == told.y);
+            if known.is_none_or(|b| b.belief.last_seen_epoch < told.belief.last_seen_epoch) {
+                self.update_food_belief(told.x, told.y, told.belief.amount, told.belief.last_seen_epoch);
+                learned += 1;
+            }
+        }
+        for told in dangers.into_iter().take(accepted.saturating_sub(learned)) {
+            if !self.is_dangerous(told.x, told.y, epoch) {
+                let note = format!("{} warned of danger", teller_name);
+                self.remember_landmark(told.x, told.y, LandmarkKind::Danger, &note, told.epoch);
+                learned += 1;
+            }
+        }
+        learned
+    }
+
     /// Get social belief about an agent (if exists)
     pub fn get_social(&self, agent_id: Uuid) -> Option<&SocialBelief> {
         self.social.get(&agent_id)
@@ -11,6 +11,27 @@ pub struct Identity {
     pub personality: Personality,
     pub values: Vec<Value>,
     pub aspiration: Aspiration,
+    /// LLM model override for this agent (falls back to the global model)
+    #[serde(default)]
+    pub model: Option<String>,
+    /// How the agent looks on the map
+    #[serde(default)]
+    pub phenotype: Phenotype,
+}
+
+/// Cosmetic looks, passed down so families can be told apart at a glance
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Phenotype {
+    /// Map glyph, taken from one parent (founders use their initial)
+    pub glyph: char,
+    /// Colour as a hue in degrees (0-360), blended from both parents
+    pub hue: f64,
+}
+
+impl Default for Phenotype {
+    fn default() -> Self {
+        Self { glyph: '@', hue: 0.0 }
+    }
 }
 
 /// Big Five personality traits (simplified)
@@ -54,7 +75,7 @@ pub enum Aspiration {
 impl Personality {
     /// Generate a random personality
     pub fn random() -> Self {
-        let mut rng = rand::rng();
+        let mut rng = crate::rng::rng(crate::rng::Stream::Agents);
         Self {
             openness: rng.random(),
             conscientiousness: rng.random(),
@@ -64,6 +85,24 @@ impl Personality {
         }
     }
 
+    /// Willingness to take risks (0.0 = very cautious, 1.0 = bold),
+    /// from emotional stability and openness to new experiences
+    pub fn risk_tolerance(&self) -> f64 {
+        (((1.0 - self.neuroticism) + self.openness) / 2.0).clamp(0.0, 1.0)
+    }
+
+    /// Flavor line about risk-taking, for clearly bold or cautious agents
+    pub fn describe_risk(&self) -> Option<&'static str> {
+        let risk = self.risk_tolerance();
+        if risk > 0.7 {
+            Some("You are bold and willing to take chances others would avoid.")
+        } else if risk < 0.3 {
+            Some("You are cautious and would rather be safe than sorry.")
+        } else {
+            None
+        }
+    }
+
     /// Describe personality in natural language
     pub fn describe(&self) -> String {
         let mut traits = Vec::new();
@@ -122,7 +161,7 @@ impl Value {
 impl Aspiration {
     /// Generate a random aspiration
     pub fn random() -> Self {
-        let mut rng = rand::rng();
+        let mut rng = crate::rng::rng(crate::rng::Stream::Agents);
         match rng.random_range(0..6) {
             0 => Aspiration::BeRespected,
             1 => Aspiration::ProtectOthers,
@@ -145,10 +184,74 @@ impl Aspiration {
     }
 }
 
+impl Phenotype {
+    /// A founder's looks: their initial and a random hue
+    pub fn founder(name: &str) -> Self {
+        Self {
+            glyph: name.chars().next().map(|c| c.to_ascii_uppercase()).unwrap_or('@'),
+            hue: crate::rng::rng(crate::rng::Stream::Agents).random_range(0.0..360.0),
+        }
+    }
+
+    /// A child's looks: one parent's glyph, and a hue halfway between the
+    /// parents' (the short way round) that strays by up to `hue_drift` degrees
+    pub fn from_parents(parent_a: &Phenotype, parent_b: &Phenotype, hue_drift: f64) -> Self {
+        let mut rng = crate::rng::rng(crate::rng::Stream::Mating);
+        let glyph = if rng.random::<bool>() { parent_a.glyph } else { parent_b.glyph };
+        let drift = if hue_drift > 0.0 { rng.random_range(-hue_drift..=hue_drift) } else { 0.0 };
+        Self {
+            glyph,
+            hue: (parent_a.blended_hue(parent_b) + drift).rem_euclid(360.0),
+        }
+    }
+
+    /// Hue halfway between two, the short way round the colour wheel
+    pub fn blended_hue(&self, other: &Phenotype) -> f64 {
+        let diff = (other.hue - self.hue + 540.0).rem_euclid(360.0) - 180.0;
+        (self.hue + diff / 2.0).rem_euclid(360.0)
+    }
+
+    /// Degrees between two hues around the colour wheel (0-180)
+    pub fn hue_distance(&self, other: &Phenotype) -> f64 {
+        let diff = (self.hue - other.hue).rem_euclid(360.0);
+        diff.min(360.0 - diff)
+    }
+
+    /// Whether these looks are nearer `a`'s than `b`'s: a shared glyph
+    /// counts first, then the closer hue
+    pub fn takes_after(&self, a: &Phenotype, b: &Phenotype) -> bool {
+        match (self.glyph == a.glyph, self.glyph == b.glyph) {
+            (true, false) => true,
+            (false, true) => false,
+            _ => self.hue_distance(a) <= self.hue_distance(b),
+        }
+    }
+
+    /// The hue as a bright, readable terminal colour
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        let (saturation, value) = (0.6, 0.95);
+        let sector = self.hue.rem_euclid(360.0) / 60.0;
+        let chroma = value * saturation;
+        let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+        let (r, g, b) = match sector as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = value - chroma;
+        let channel = |c: f64| ((c + m) * 255.0).round() as u8;
+        (channel(r), channel(g), channel(b))
+    }
+}
+
 impl Identity {
-    /// Create a new identity by inheriting traits from two parents
-    pub fn from_parents(name: String, parent_a: &Identity, parent_b: &Identity) -> Self {
-        let mut rng = rand::rng();
+    /// Create a new identity by inheriting traits from two parents;
+    /// a child's colour strays up to `hue_drift` degrees from the parents' blend
+    pub fn from_parents(name: String, parent_a: &Identity, parent_b: &Identity, hue_drift: f64) -> Self {
+        let mut rng = crate::rng::rng(crate::rng::Stream::Mating);
 
         // Each Big Five trait randomly picked from one parent
         let personality = Personality {
@@ -205,12 +308,14 @@ impl Identity {
             personality,
             values,
             aspiration,
+            model: None,
+            phenotype: Phenotype::from_parents(&parent_a.phenotype, &parent_b.phenotype, hue_drift),
         }
     }
 
     /// Create a new random identity with the given name
     pub fn new(name: String) -> Self {
-        let mut rng = rand::rng();
+        let mut rng = crate::rng::rng(crate::rng::Stream::Agents);
 
         // Pick 2-3 values
         let all_values = [
@@ -233,10 +338,12 @@ impl Identity {
         }
 
         Self {
+            phenotype: Phenotype::founder(&name),
             name,
             personality: Personality::random(),
             values,
             aspiration: Aspiration::random(),
+            model: None,
         }
     }
 
@@ -256,3 +363,24 @@ impl Identity {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_child_looks_blend_parents_within_drift() {
+        let mother = Identity { phenotype: Phenotype { glyph: 'A', hue: 350.0 }, ..Identity::new("Aric".to_string()) };
+        let father = Identity { phenotype: Phenotype { glyph: 'B', hue: 30.0 }, ..Identity::new("Bria".to_string()) };
+        let blend = Phenotype { glyph: '@', hue: 10.0 };
+        assert_eq!(mother.phenotype.blended_hue(&father.phenotype), 10.0);
+
+        for _ in 0..50 {
+            let child = Identity::from_parents("Cara".to_string(), &mother, &father, 15.0);
+            let looks = child.phenotype;
+            assert!(looks.glyph == 'A' || looks.glyph == 'B', "{:?}", looks);
+            assert!(looks.hue_distance(&blend) <= 15.0 + 1e-9, "{:?}", looks);
+            assert!((0.0..360.0).contains(&looks.hue));
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Progress toward an agent's life aspiration, measured over the whole life.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use super::identity::Aspiration;
+use crate::config::LegacyConfig;
+
+/// What an agent has made of its life so far
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Legacy {
+    /// Food ever come by, however it was got
+    pub food_gathered: u32,
+    /// Distinct cells the agent has stood on
+    pub cells_visited: HashSet<(usize, usize)>,
+    /// Fellow members of the group the agent leads, if it leads one
+    pub followers: Option<usize>,
+    /// Latest progress toward the aspiration (0.0 to 1.0); None if the
+    /// aspiration isn't one that is measured
+    pub progress: Option<f64>,
+    /// Highest progress reached
+    pub peak_progress: f64,
+}
+
+impl Legacy {
+    /// Note a cell the agent stands on
+    pub fn visit(&mut self, x: usize, y: usize) {
+        self.cells_visited.insert((x, y));
+    }
+
+    /// Progress toward an aspiration: power is leading a group and having
+    /// followers, resources is food come by, exploration is cells seen
+    pub fn measure(&self, aspiration: &Aspiration, config: &LegacyConfig) -> Option<f64> {
+        let share = |have: f64, target: f64| (have / target.max(1.0)).min(1.0);
+        match aspiration {
+            Aspiration::BecomePowerful => Some(self.followers.map_or(0.0, |followers| {
+                0.5 + 0.5 * share(followers as f64, config.leader_followers as f64)
+            })),
+            Aspiration::AccumulateResources => {
+                Some(share(self.food_gathered as f64, config.food_gathered as f64))
+            }
+            Aspiration::ExploreTheWorld => {
+                Some(share(self.cells_visited.len() as f64, config.cells_visited as f64))
+            }
+            _ => None,
+        }
+    }
+
+    /// Bring progress toward the aspiration up to date
+    pub fn update(&mut self, aspiration: &Aspiration, config: &LegacyConfig) {
+        self.progress = self.measure(aspiration, config);
+        if let Some(progress) = self.progress {
+            self.peak_progress = self.peak_progress.max(progress);
+        }
+    }
+
+    /// How the agent feels about its progress, for the prompt
+    pub fn describe(&self, aspiration: &Aspiration) -> Option<String> {
+        let aim = match aspiration {
+            Aspiration::BecomePowerful => "becoming the leader you aspire to be",
+            Aspiration::AccumulateResources => "the plenty you aspire to lay up",
+            Aspiration::ExploreTheWorld => "knowing the world as you aspire to",
+            _ => return None,
+        };
+        let progress = self.progress?;
+        Some(if progress >= 1.0 {
+            "You have lived up to your aspiration.".to_string()
+        } else if progress >= 0.7 {
+            format!("You are close to {}.", aim)
+        } else if progress >= 0.3 {
+            format!("You are on your way to {}.", aim)
+        } else {
+            format!("You are still far from {}.", aim)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leading_a_group_advances_power() {
+        let config = LegacyConfig::default();
+        let mut legacy = Legacy::default();
+        legacy.update(&Aspiration::BecomePowerful, &config);
+        assert_eq!(legacy.progress, Some(0.0));
+        assert!(legacy.describe(&Aspiration::BecomePowerful).unwrap().contains("far from"));
+
+        legacy.followers = Some(config.leader_followers - 1);
+        legacy.update(&Aspiration::BecomePowerful, &config);
+        let leading = legacy.progress.unwrap();
+        assert!(leading > 0.7 && leading < 1.0, "{}", leading);
+        assert_eq!(
+            legacy.describe(&Aspiration::BecomePowerful).as_deref(),
+            Some("You are close to becoming the leader you aspire to be.")
+        );
+
+        // Losing the group doesn't take away what was reached
+        legacy.followers = None;
+        legacy.update(&Aspiration::BecomePowerful, &config);
+        assert_eq!(legacy.peak_progress, leading);
+        assert_eq!(legacy.measure(&Aspiration::LivePeacefully, &config), None);
+    }
+}
@@ -10,6 +10,38 @@ pub struct Memory {
     pub max_recent: usize,
 }
 
+/// How episodes are chosen for the prompt: each scores
+/// `salience_weight * salience + recency_weight * 0.5^(days ago / half_life)`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecallConfig {
+    /// Weight of how much an episode mattered (the strength of its valence)
+    #[serde(default = "default_salience_weight")]
+    pub salience_weight: f64,
+    /// Weight of how fresh an episode is
+    #[serde(default = "default_recency_weight")]
+    pub recency_weight: f64,
+    /// Days for an episode's freshness to halve
+    #[serde(default = "default_recall_half_life")]
+    pub half_life: f64,
+}
+
+impl Default for RecallConfig {
+    fn default() -> Self {
+        Self {
+            salience_weight: 1.0,
+            recency_weight: 1.0,
+            half_life: 5.0,
+        }
+    }
+}
+
+fn default_salience_weight() -> f64 { 1.0 }
+fn default_recency_weight() -> f64 { 1.0 }
+fn default_recall_half_life() -> f64 { 5.0 }
+
+/// Episodes shown in a prompt
+const RECALLED_EPISODES: usize = 5;
+
 /// A single memorable event
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Episode {
@@ -66,19 +98,32 @@ impl Memory {
             .collect()
     }
 
+    /// The episodes that come to mind at `current_epoch`, most recent first
+    pub fn recall(&self, current_epoch: usize, config: &RecallConfig) -> Vec<&Episode> {
+        let mut ranked: Vec<(usize, f64)> = self
+            .recent
+            .iter()
+            .enumerate()
+            .map(|(i, e)| (i, e.recall_score(current_epoch, config)))
+            .collect();
+        // Among equals the newer episode wins
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(b.0.cmp(&a.0)));
+        ranked.truncate(RECALLED_EPISODES);
+        ranked.sort_by_key(|&(i, _)| std::cmp::Reverse(i));
+        ranked.into_iter().map(|(i, _)| &self.recent[i]).collect()
+    }
+
     /// Generate a summary for LLM prompting
-    pub fn prompt_summary(&self, current_epoch: usize) -> String {
+    pub fn prompt_summary(&self, current_epoch: usize, config: &RecallConfig) -> String {
         if self.recent.is_empty() {
             return "No significant recent events.".to_string();
         }
 
         let summaries: Vec<String> = self
-            .recent
-            .iter()
-            .rev() // Most recent first
-            .take(5)
+            .recall(current_epoch, config)
+            .into_iter()
             .map(|e| {
-                let ago = current_epoch - e.epoch;
+                let ago = current_epoch.saturating_sub(e.epoch);
                 let time_desc = if ago == 0 {
                     "Just now".to_string()
                 } else if ago == 1 {
@@ -111,6 +156,18 @@ impl Episode {
         }
     }
 
+    /// How much the episode mattered: the strength of its feeling either way
+    pub fn salience(&self) -> f64 {
+        self.valence.abs().min(1.0)
+    }
+
+    /// How readily the episode comes to mind at `current_epoch`
+    pub fn recall_score(&self, current_epoch: usize, config: &RecallConfig) -> f64 {
+        let ago = current_epoch.saturating_sub(self.epoch) as f64;
+        let recency = 0.5f64.powf(ago / config.half_life.max(f64::EPSILON));
+        config.salience_weight * self.salience() + config.recency_weight * recency
+    }
+
     /// Create a survival-related episode
     pub fn survival(epoch: usize, description: &str, valence: f64) -> Self {
         Self::new(
@@ -144,3 +201,25 @@ impl Episode {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pivotal_memory_outlasts_routine_ones_in_prompt() {
+        let mut memory = Memory::new();
+        memory.remember(Episode::survival(1, "My mother died", -0.9));
+        for epoch in 2..8 {
+            memory.remember(Episode::survival(epoch, "I ate and felt better", 0.3));
+        }
+
+        let summary = memory.prompt_summary(8, &RecallConfig::default());
+        assert!(summary.contains("7 days ago: My mother died"), "{}", summary);
+        assert_eq!(summary.lines().count(), 1 + RECALLED_EPISODES);
+
+        // Weighed by recency alone it scrolls out of mind
+        let recency_only = RecallConfig { salience_weight: 0.0, ..RecallConfig::default() };
+        assert!(!memory.prompt_summary(8, &recency_only).contains("mother"));
+    }
+}
@@ -1,18 +1,37 @@
 pub mod beliefs;
 pub mod identity;
+pub mod legacy;
 pub mod memory;
 
-pub use beliefs::Beliefs;
-pub use identity::{Aspiration, Identity, Personality, Value};
-pub use memory::{Episode, EpisodeCategory, Memory};
+pub use beliefs::{Beliefs, LandmarkKind};
+pub use identity::{Aspiration, Identity, Personality, Phenotype, Value};
+pub use legacy::Legacy;
+pub use memory::{Episode, EpisodeCategory, Memory, RecallConfig};
 
 use rand::Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use uuid::Uuid;
 
-use crate::config::AgingConfig;
-use crate::crafting::{MaterialType, Tool, ToolType};
+use crate::action::Direction;
+use crate::config::{AgingConfig, InstinctConfig, MetabolismConfig, StageMetabolism};
+use crate::crafting::{MaterialType, Tool, ToolEffect, ToolType};
+use crate::trade::TradeableItem;
+
+/// Total skill points an agent can hold before learning slows sharply
+const SKILL_BUDGET: f64 = 2.5;
+
+/// Fraction of the skill budget after which learning other skills starts to slow
+const SKILL_BUDGET_SOFT_START: f64 = 0.6;
+
+/// Minimum fraction of a skill gain kept when over budget
+const MIN_SPECIALIZATION_FACTOR: f64 = 0.2;
+
+/// Energy a day of rest restores before shelter, night or age adjust it
+pub const BASE_REST_RECOVERY: f64 = 0.3;
+
+/// Hunger relieved by eating one food
+pub const MEAL_HUNGER_RELIEF: f64 = 0.3;
 
 /// A single agent in the simulation
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +44,55 @@ pub struct Agent {
     pub active_goal: Option<Goal>,
     pub reproduction: ReproductionState,
     pub skills: Skills,
+    /// Standard deviations this agent's maximum lifespan lies from the configured mean
+    #[serde(default)]
+    pub lifespan_deviation: f64,
+    /// Progress toward the agent's aspiration
+    #[serde(default)]
+    pub legacy: Legacy,
+    /// Shared pull to leave, felt with other starving agents nearby
+    #[serde(default)]
+    pub migration: Option<Migration>,
+    /// Way worked out to the place the agent is travelling to
+    #[serde(default)]
+    pub route: Option<Route>,
+    /// Whoever attacked the agent lately, and where they were last seen
+    #[serde(default)]
+    pub threat_source: Option<Threat>,
+}
+
+/// Someone who attacked the agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Threat {
+    pub attacker: Uuid,
+    /// Where the attacker was last seen
+    pub position: (usize, usize),
+    /// Epoch of the attack
+    pub epoch: usize,
+    /// A run from here found no way out, so the agent stops trying until the
+    /// attacker is seen somewhere else
+    #[serde(default)]
+    pub cornered: bool,
+}
+
+/// Cells still to walk on the way somewhere, next step first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Route {
+    pub destination: (usize, usize),
+    pub steps: Vec<(usize, usize)>,
+}
+
+/// A starving cluster's shared impulse to move on together
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Migration {
+    /// Which way the cluster means to go
+    pub direction: Direction,
+    /// Better ground one of them remembers, if anyone did
+    pub destination: Option<(usize, usize)>,
+    /// How many set out together, the agent included
+    pub companions: usize,
+    /// Epoch the impulse took hold
+    pub began: usize,
 }
 
 /// Reproduction state for an agent
@@ -81,7 +149,7 @@ impl Skills {
     /// Create skills based on personality traits
     pub fn from_personality(personality: &Personality) -> Self {
         let mut levels = HashMap::new();
-        let mut rng = rand::rng();
+        let mut rng = crate::rng::rng(crate::rng::Stream::Agents);
 
         // High openness → foraging (curiosity, exploration)
         if personality.openness > 0.6 {
@@ -172,12 +240,33 @@ impl Skills {
     }
 
     /// Improve a skill (capped at 1.0)
+    ///
+    /// Gains are scaled down by `specialization_factor`, so agents who already
+    /// hold many skill points learn new skills more slowly.
     pub fn improve(&mut self, skill: &str, amount: f64, epoch: usize) {
         let current = self.levels.get(skill).copied().unwrap_or(0.0);
-        self.levels.insert(skill.to_string(), (current + amount).min(1.0));
+        let gain = amount * self.specialization_factor(skill);
+        self.levels.insert(skill.to_string(), (current + gain).min(1.0));
         self.last_practiced.insert(skill.to_string(), epoch);
     }
 
+    /// Learning multiplier for a skill based on points invested in other skills
+    /// (1.0 = full gain, falls toward MIN_SPECIALIZATION_FACTOR near the budget)
+    pub fn specialization_factor(&self, skill: &str) -> f64 {
+        let others: f64 = self
+            .levels
+            .iter()
+            .filter(|(name, _)| name.as_str() != skill)
+            .map(|(_, level)| level)
+            .sum();
+        let soft_start = SKILL_BUDGET * SKILL_BUDGET_SOFT_START;
+        if others <= soft_start {
+            return 1.0;
+        }
+        let overage = (others - soft_start) / (SKILL_BUDGET - soft_start);
+        (1.0 - overage).max(MIN_SPECIALIZATION_FACTOR)
+    }
+
     /// Mark a skill as practiced this epoch
     pub fn practice(&mut self, skill: &str, epoch: usize) {
         if self.levels.contains_key(skill) {
@@ -201,6 +290,13 @@ impl Skills {
     }
 }
 
+/// What a meal was: foraged plants or hunted and fished meat
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FoodKind {
+    Plant,
+    Meat,
+}
+
 /// Physical state of an agent
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PhysicalState {
@@ -214,6 +310,12 @@ pub struct PhysicalState {
     pub energy: f64,
     /// Food carried
     pub food: u32,
+    /// How much of the food carried is meat; the rest is plant food
+    #[serde(default)]
+    pub meat: u32,
+    /// Kinds of food eaten at the latest meals, oldest first
+    #[serde(default)]
+    pub recent_meals: VecDeque<FoodKind>,
     /// Age in epochs
     pub age: usize,
     /// Materials inventory
@@ -222,9 +324,24 @@ pub struct PhysicalState {
     /// Tools inventory
     #[serde(default)]
     pub tools: Vec<Tool>,
+    /// Tool currently in hand - None if empty-handed
+    #[serde(default)]
+    pub equipped: Option<Uuid>,
     /// Currently sheltered at position (x, y) - None if not sheltered
     #[serde(default)]
     pub sheltered_at: Option<(usize, usize)>,
+    /// Epochs in a row the agent has been out of energy
+    #[serde(default)]
+    pub spent_epochs: usize,
+    /// Collapsed from exhaustion: can only rest or eat until recovered
+    #[serde(default)]
+    pub incapacitated: bool,
+    /// Epochs in a row the agent has been starving
+    #[serde(default)]
+    pub starving_epochs: usize,
+    /// Epoch the agent died, if it has
+    #[serde(default)]
+    pub died_at: Option<usize>,
 }
 
 impl PhysicalState {
@@ -249,6 +366,25 @@ impl PhysicalState {
         self.materials.get(&material).copied().unwrap_or(0)
     }
 
+    /// Materials and tools carried besides food, materials first by name
+    /// ("3 wood", "stone axe (in hand)")
+    pub fn inventory_summary(&self) -> Vec<String> {
+        let mut materials: Vec<(&str, u32)> =
+            self.materials.iter().filter(|(_, n)| **n > 0).map(|(m, n)| (m.display_name(), *n)).collect();
+        materials.sort();
+        let tools = self.tools.iter().map(|tool| {
+            let name = tool.tool_type.display_name();
+            if tool.is_broken() {
+                format!("broken {}", name)
+            } else if self.equipped == Some(tool.id) {
+                format!("{} (in hand)", name)
+            } else {
+                name.to_string()
+            }
+        });
+        materials.into_iter().map(|(name, n)| format!("{} {}", n, name)).chain(tools).collect()
+    }
+
     /// Get best tool for a skill (non-broken, highest bonus)
     pub fn best_tool_for_skill(&self, skill: &str) -> Option<&Tool> {
         self.tools
@@ -287,6 +423,13 @@ impl PhysicalState {
             .find(|t| t.tool_type == tool_type && !t.is_broken())
     }
 
+    /// Wear down a working tool of a type, if the agent has one
+    pub fn wear_tool(&mut self, tool_type: ToolType) {
+        if let Some(tool) = self.tools.iter_mut().find(|t| t.tool_type == tool_type && !t.is_broken()) {
+            tool.use_once();
+        }
+    }
+
     /// Get actions unlocked by current tools
     pub fn unlocked_actions(&self) -> Vec<&'static str> {
         let mut actions = Vec::new();
@@ -308,9 +451,64 @@ impl PhysicalState {
             .into_iter()
             .partition(|t| t.is_broken());
         self.tools = working;
+        if broken.iter().any(|t| Some(t.id) == self.equipped) {
+            self.equipped = None;
+        }
         broken
     }
 
+    /// Equip the best working tool of a type (returns the equipped tool)
+    pub fn equip(&mut self, tool_type: ToolType) -> Option<&Tool> {
+        let tool = self
+            .tools
+            .iter()
+            .filter(|t| t.tool_type == tool_type && !t.is_broken())
+            .max_by(|a, b| {
+                a.effective_bonus()
+                    .partial_cmp(&b.effective_bonus())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })?;
+        self.equipped = Some(tool.id);
+        Some(tool)
+    }
+
+    /// Put away the equipped tool (returns it if there was one)
+    pub fn unequip(&mut self) -> Option<Uuid> {
+        self.equipped.take()
+    }
+
+    /// Get the equipped tool, if it is still held and working
+    pub fn equipped_tool(&self) -> Option<&Tool> {
+        let id = self.equipped?;
+        self.tools.iter().find(|t| t.id == id && !t.is_broken())
+    }
+
+    /// Bonus from the equipped tool for an effect
+    pub fn equipped_bonus(&self, effect: ToolEffect) -> f64 {
+        self.equipped_tool()
+            .map(|t| t.equipped_bonus(effect))
+            .unwrap_or(0.0)
+    }
+
+    /// Wear down the equipped tool if it helps with an effect
+    pub fn use_equipped_for(&mut self, effect: ToolEffect) {
+        let Some(id) = self.equipped else {
+            return;
+        };
+        if let Some(tool) = self
+            .tools
+            .iter_mut()
+            .find(|t| t.id == id && t.equipped_bonus(effect) > 0.0)
+        {
+            tool.use_once();
+        }
+    }
+
+    /// Damage multiplier when attacking
+    pub fn attack_multiplier(&self) -> f64 {
+        1.0 + self.equipped_bonus(ToolEffect::Attack)
+    }
+
     /// Total tool bonus for a skill
     pub fn tool_bonus_for_skill(&self, skill: &str) -> f64 {
         self.best_tool_for_skill(skill)
@@ -376,17 +574,33 @@ pub enum Goal {
     Rest,
     Explore,
     Socialize,
+    FindMate,
     Flee,
     Custom(String),
 }
 
+/// Basic drives that compete for an agent's attention when no goal of its own holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Drive {
+    /// Eat when hungry, rest when exhausted or hurt
+    Survival,
+    /// Look for a mate when healthy and able
+    Reproduction,
+    /// Seek out company, if outgoing enough
+    Social,
+    /// Wander and see what's out there
+    Exploration,
+}
+
 impl Agent {
     /// Create a new agent with random identity at the given position
     pub fn new(name: String, x: usize, y: usize, starting_food: u32) -> Self {
         let identity = Identity::new(name);
         let skills = Skills::from_personality(&identity.personality);
+        let lifespan_deviation = draw_lifespan_deviation(&identity.personality);
         Self {
-            id: Uuid::new_v4(),
+            id: crate::rng::uuid(),
             identity,
             beliefs: Beliefs::new(),
             memory: Memory::new(),
@@ -397,14 +611,26 @@ impl Agent {
                 hunger: 0.3, // Slightly hungry to start
                 energy: 1.0,
                 food: starting_food,
+                meat: 0,
+                recent_meals: VecDeque::new(),
                 age: 0,
                 materials: HashMap::new(),
                 tools: Vec::new(),
+                equipped: None,
                 sheltered_at: None,
+                spent_epochs: 0,
+                incapacitated: false,
+                starving_epochs: 0,
+                died_at: None,
             },
             active_goal: Some(Goal::Explore),
             reproduction: ReproductionState::default(),
             skills,
+            lifespan_deviation,
+            legacy: Legacy::default(),
+            migration: None,
+            route: None,
+            threat_source: None,
         }
     }
 
@@ -425,9 +651,10 @@ impl Agent {
             }
             None => Skills::from_personality(&identity.personality),
         };
+        let lifespan_deviation = draw_lifespan_deviation(&identity.personality);
 
         Self {
-            id: Uuid::new_v4(),
+            id: crate::rng::uuid(),
             identity,
             beliefs: Beliefs::new(),
             memory: Memory::new(),
@@ -438,10 +665,17 @@ impl Agent {
                 hunger: 0.2, // Newborns start less hungry
                 energy: 0.8,
                 food: starting_food,
+                meat: 0,
+                recent_meals: VecDeque::new(),
                 age: 0,
                 materials: HashMap::new(),
                 tools: Vec::new(),
+                equipped: None,
                 sheltered_at: None,
+                spent_epochs: 0,
+                incapacitated: false,
+                starving_epochs: 0,
+                died_at: None,
             },
             active_goal: Some(Goal::Explore),
             reproduction: ReproductionState {
@@ -454,6 +688,25 @@ impl Agent {
                 ..Default::default()
             },
             skills,
+            lifespan_deviation,
+            legacy: Legacy::default(),
+            migration: None,
+            route: None,
+            threat_source: None,
+        }
+    }
+
+    /// Note a step onto `cell`, using up the route's next step if that is
+    /// where it led
+    pub fn stepped_onto(&mut self, cell: (usize, usize)) {
+        let Some(route) = &mut self.route else {
+            return;
+        };
+        if route.steps.first() == Some(&cell) {
+            route.steps.remove(0);
+        }
+        if route.steps.is_empty() {
+            self.route = None;
         }
     }
 
@@ -472,6 +725,13 @@ impl Agent {
         self.physical.age
     }
 
+    /// This agent's own maximum lifespan: the configured mean shifted by
+    /// their lifespan deviation, never before elderly age
+    pub fn max_lifespan(&self, config: &AgingConfig) -> usize {
+        let lifespan = config.max_lifespan as f64 + self.lifespan_deviation * config.lifespan_stddev;
+        (lifespan.round().max(0.0) as usize).max(config.elderly_start.max(config.prime_end) + 1)
+    }
+
     /// Calculate age-based capability modifier (0.5 to 1.0)
     /// Youth: 0.7 to 1.0, Prime: 1.0, Elderly/Ancient: 1.0 to 0.5
     pub fn age_modifier(&self, config: &AgingConfig) -> f64 {
@@ -487,10 +747,10 @@ impl Agent {
         } else if age < config.prime_end {
             // Prime: 100% capability
             1.0
-        } else if age < config.max_lifespan {
+        } else if age < self.max_lifespan(config) {
             // Elderly/Ancient: linear decline from 1.0 to 0.5
             let decline_progress = (age - config.prime_end) as f64
-                / (config.max_lifespan - config.prime_end) as f64;
+                / (self.max_lifespan(config) - config.prime_end) as f64;
             1.0 - (decline_progress * 0.5)
         } else {
             // Beyond max lifespan (shouldn't happen, but cap at 0.5)
@@ -498,6 +758,11 @@ impl Agent {
         }
     }
 
+    /// Whether this agent can still (or will eventually) have children
+    pub fn is_fertile(&self, config: &AgingConfig) -> bool {
+        self.is_alive() && matches!(self.life_stage(config), "youth" | "prime" | "ageless")
+    }
+
     /// Get the current life stage as a string
     pub fn life_stage(&self, config: &AgingConfig) -> &'static str {
         if !config.enabled {
@@ -510,17 +775,21 @@ impl Agent {
             "youth"
         } else if age < config.prime_end {
             "prime"
-        } else if age < config.elderly_start + (config.max_lifespan - config.elderly_start) / 2 {
+        } else if age < config.elderly_start + (self.max_lifespan(config) - config.elderly_start) / 2 {
             "elderly"
         } else {
             "ancient"
         }
     }
 
+    /// Metabolic rates for this agent's current life stage
+    pub fn metabolism(&self, aging: &AgingConfig, metabolism: &MetabolismConfig) -> StageMetabolism {
+        metabolism.for_stage(self.life_stage(aging))
+    }
+
     /// Update hunger (called each epoch)
-    pub fn tick_hunger(&mut self) {
-        // Hunger increases by 0.1 per epoch
-        self.physical.hunger = (self.physical.hunger + 0.1).min(1.0);
+    pub fn tick_hunger(&mut self, rate: f64) {
+        self.physical.hunger = (self.physical.hunger + rate).min(1.0);
 
         // High hunger damages health
         if self.physical.hunger > 0.8 {
@@ -529,25 +798,100 @@ impl Agent {
     }
 
     /// Update energy (slight natural drain)
-    pub fn tick_energy(&mut self) {
-        self.physical.energy = (self.physical.energy - 0.05).max(0.0);
+    pub fn tick_energy(&mut self, drain: f64) {
+        self.physical.energy = (self.physical.energy - drain).max(0.0);
     }
 
-    /// Eat food from inventory
-    pub fn eat(&mut self) -> bool {
-        if self.physical.food > 0 {
-            self.physical.food -= 1;
-            self.physical.hunger = (self.physical.hunger - 0.3).max(0.0);
-            self.physical.health = (self.physical.health + 0.05).min(1.0);
-            true
+    /// Collapse after `collapse_epochs` epochs out of energy, and get back up
+    /// once rested to `recover_energy`; returns the new state when it changes
+    pub fn tick_exhaustion(&mut self, config: &MetabolismConfig) -> Option<bool> {
+        let physical = &mut self.physical;
+        if physical.incapacitated {
+            if physical.energy < config.recover_energy {
+                return None;
+            }
+            physical.incapacitated = false;
+            physical.spent_epochs = 0;
+            return Some(false);
+        }
+
+        if physical.energy <= config.collapse_energy {
+            physical.spent_epochs += 1;
         } else {
-            false
+            physical.spent_epochs = 0;
+        }
+        if config.collapse_epochs > 0 && physical.spent_epochs >= config.collapse_epochs {
+            physical.incapacitated = true;
+            return Some(true);
+        }
+        None
+    }
+
+    /// Eat food from inventory, regaining `healing` health; the kind of food
+    /// the agent has had less of lately goes first, and with a balanced diet
+    /// required a monotonous one heals less
+    pub fn eat(&mut self, healing: f64, metabolism: &MetabolismConfig) -> bool {
+        let physical = &mut self.physical;
+        if physical.food == 0 {
+            return false;
+        }
+        physical.meat = physical.meat.min(physical.food);
+        let plant = physical.food - physical.meat;
+        let meat_meals = physical.recent_meals.iter().filter(|k| **k == FoodKind::Meat).count();
+        let craves_meat = meat_meals * 2 < physical.recent_meals.len();
+        let kind = if physical.meat > 0 && (plant == 0 || craves_meat) { FoodKind::Meat } else { FoodKind::Plant };
+
+        physical.food -= 1;
+        if kind == FoodKind::Meat {
+            physical.meat -= 1;
+        }
+        physical.recent_meals.push_back(kind);
+        while physical.recent_meals.len() > metabolism.diet_window.max(1) {
+            physical.recent_meals.pop_front();
+        }
+
+        let healing = if metabolism.balanced_diet { healing * self.diet_variety_factor(metabolism) } else { healing };
+        self.physical.hunger = (self.physical.hunger - MEAL_HUNGER_RELIEF).max(0.0);
+        self.physical.health = (self.physical.health + healing).min(1.0);
+        true
+    }
+
+    /// Share of a meal's healing that recent meals allow: all of it for an
+    /// even mix of plants and meat, down to `monotony_healing` for one kind only
+    pub fn diet_variety_factor(&self, metabolism: &MetabolismConfig) -> f64 {
+        let meals = &self.physical.recent_meals;
+        if meals.len() < 2 {
+            return 1.0;
+        }
+        let meat = meals.iter().filter(|k| **k == FoodKind::Meat).count();
+        let minority = meat.min(meals.len() - meat) as f64;
+        let variety = (2.0 * minority / meals.len() as f64).min(1.0);
+        metabolism.monotony_healing + (1.0 - metabolism.monotony_healing) * variety
+    }
+
+    /// The kind of food missing from a monotonous diet, if the agent has eaten
+    /// only one kind at its latest meals
+    pub fn craving(&self) -> Option<FoodKind> {
+        let meals = &self.physical.recent_meals;
+        let first = *meals.front()?;
+        if meals.len() < 3 || meals.iter().any(|k| *k != first) {
+            return None;
         }
+        Some(match first {
+            FoodKind::Plant => FoodKind::Meat,
+            FoodKind::Meat => FoodKind::Plant,
+        })
     }
 
     /// Rest to recover energy
-    pub fn rest(&mut self) {
-        self.physical.energy = (self.physical.energy + 0.3).min(1.0);
+    ///
+    /// `bonus` is added to the base recovery (shelter, territory, night) and the
+    /// total is scaled by `age_modifier`. Returns the energy recovered.
+    pub fn rest(&mut self, bonus: f64, age_modifier: f64) -> f64 {
+        let before = self.physical.energy;
+        let recovery = (BASE_REST_RECOVERY + bonus) * age_modifier;
+        self.physical.energy = (self.physical.energy + recovery).min(1.0);
+        self.physical.energy - before
     }
 
     /// Take damage
@@ -558,16 +902,39 @@ impl Agent {
     /// Add food to inventory
     pub fn add_food(&mut self, amount: u32) {
         self.physical.food += amount;
+        self.legacy.food_gathered += amount;
     }
 
-    /// Remove food from inventory (returns actual amount removed)
+    /// Add hunted or fished meat to inventory
+    pub fn add_meat(&mut self, amount: u32) {
+        self.add_food(amount);
+        self.physical.meat += amount;
+    }
+
+    /// Remove food from inventory, plant food first (returns actual amount removed)
     pub fn remove_food(&mut self, amount: u32) -> u32 {
         let removed = amount.min(self.physical.food);
         self.physical.food -= removed;
+        self.physical.meat = self.physical.meat.min(self.physical.food);
         removed
     }
 
-    /// Generate the full state summary for LLM prompting
+    /// Check whether the agent holds all the given items (promises need nothing)
+    pub fn has_items(&self, items: &[TradeableItem]) -> bool {
+        items.iter().all(|item| match item {
+            TradeableItem::Food(amount) => self.physical.food >= *amount,
+            TradeableItem::Materials(mat, amount) => self.physical.material_count(*mat) >= *amount,
+            TradeableItem::Tool(tool_id) => self.physical.tools.iter().any(|t| t.id == *tool_id),
+            TradeableItem::ToolByType(tool_type) => self.physical.tools.iter().any(|t| t.tool_type == *tool_type),
+            TradeableItem::TeachSkillPromise { .. }
+            | TradeableItem::HelpBuildPromise { .. }
+            | TradeableItem::FutureGiftPromise { .. }
+            | TradeableItem::AlliancePromise { .. } => true,
+        })
+    }
+
+    /// Generate the agent's own state (identity, body, skills, goal) for LLM prompting;
+    /// relations and memories are summarized by `beliefs` and `memory`
     pub fn prompt_state(&self, epoch: usize) -> String {
         // Physical state
         let health_desc = if self.physical.health > 0.8 {
@@ -611,15 +978,31 @@ impl Agent {
             _ => format!("You are {} days old", self.physical.age),
         };
 
-        let physical = format!(
-            "Physical state: {}. You are {}, {}, and {}. You carry {} food.",
-            age_desc, health_desc, hunger_desc, energy_desc, self.physical.food
+        let carried = match self.physical.meat.min(self.physical.food) {
+            0 => format!("{} food", self.physical.food),
+            meat => format!("{} food ({} of it meat)", self.physical.food, meat),
+        };
+        let inventory = self.physical.inventory_summary();
+        let carried = if inventory.is_empty() {
+            carried
+        } else {
+            format!("{}, as well as {}", carried, inventory.join(", "))
+        };
+        let mut physical = format!(
+            "Physical state: {}. You are {}, {}, and {}. You carry {}.",
+            age_desc, health_desc, hunger_desc, energy_desc, carried
         );
+        if self.physical.incapacitated {
+            physical.push_str(" You have collapsed from exhaustion and can only rest or eat until you recover.");
+        }
 
-        let goal = match &self.active_goal {
+        let mut goal = match &self.active_goal {
             Some(g) => format!("Current focus: {}", g.describe()),
             None => "You have no particular goal right now.".to_string(),
         };
+        if let Some(legacy) = self.legacy.describe(&self.identity.aspiration) {
+            goal = format!("{}\n{}", goal, legacy);
+        }
 
         // Reproduction state
         let mut reproduction_parts = Vec::new();
@@ -669,14 +1052,12 @@ impl Agent {
         let skills = self.skills_prompt_summary();
 
         format!(
-            "{}\n\n{}{}\n\n{}\n\n{}\n\n{}\n\n{}",
+            "{}\n\n{}{}\n\n{}\n\n{}",
             self.identity.prompt_description(),
             physical,
             reproduction,
             skills,
             goal,
-            self.beliefs.prompt_summary(epoch),
-            self.memory.prompt_summary(epoch),
         )
     }
 
@@ -710,29 +1091,78 @@ impl Agent {
         }
     }
 
-    /// Determine a new goal based on current state
-    pub fn update_goal(&mut self) {
-        // Priority: survival first
-        if self.physical.hunger > 0.7 {
-            if self.physical.food > 0 {
-                self.active_goal = Some(Goal::Eat);
-            } else {
-                self.active_goal = Some(Goal::FindFood);
+    /// Determine a new goal from the first drive, in instinct order, that is
+    /// active; near-starvation preempts everything
+    pub fn update_goal(&mut self, instincts: &InstinctConfig) {
+        if self.physical.hunger > instincts.starvation_hunger {
+            self.active_goal = self.drive_goal(Drive::Survival, instincts);
+            return;
+        }
+
+        // A self-chosen goal holds until survival needs take over
+        let holding_own_goal = matches!(self.active_goal, Some(Goal::Custom(_)));
+        for drive in &instincts.order {
+            if holding_own_goal && *drive != Drive::Survival {
+                continue;
             }
-        } else if self.physical.energy < 0.2 {
-            self.active_goal = Some(Goal::Rest);
-        } else if self.physical.health < 0.3 {
-            self.active_goal = Some(Goal::Rest);
-        } else {
-            // Non-urgent: based on personality
-            let mut rng = rand::rng();
-            if self.identity.personality.extraversion > 0.6 && rng.random::<f64>() < 0.3 {
-                self.active_goal = Some(Goal::Socialize);
-            } else {
-                self.active_goal = Some(Goal::Explore);
+            if let Some(goal) = self.drive_goal(*drive, instincts) {
+                self.active_goal = Some(goal);
+                return;
+            }
+        }
+        if !holding_own_goal {
+            self.active_goal = Some(Goal::Explore);
+        }
+    }
+
+    /// The goal a drive suggests right now, if it is active
+    fn drive_goal(&self, drive: Drive, instincts: &InstinctConfig) -> Option<Goal> {
+        let physical = &self.physical;
+        match drive {
+            Drive::Survival => {
+                if self.should_flee(instincts) {
+                    Some(Goal::Flee)
+                } else if physical.hunger > instincts.hunger_threshold
+                    || physical.hunger > instincts.starvation_hunger
+                {
+                    Some(if physical.food > 0 { Goal::Eat } else { Goal::FindFood })
+                } else if physical.energy < instincts.energy_threshold
+                    || physical.health < instincts.health_threshold
+                {
+                    Some(Goal::Rest)
+                } else {
+                    None
+                }
+            }
+            Drive::Reproduction => {
+                let able = self.reproduction.mating_cooldown == 0
+                    && self.reproduction.gestation.is_none()
+                    && physical.health > instincts.mating_health_threshold;
+                able.then_some(Goal::FindMate)
             }
+            Drive::Social => {
+                let outgoing = self.identity.personality.extraversion > instincts.social_extraversion;
+                (outgoing && crate::rng::random::<f64>(crate::rng::Stream::Decisions) < instincts.social_chance).then_some(Goal::Socialize)
+            }
+            Drive::Exploration => self.has_exhausted_spot().then_some(Goal::Explore),
         }
     }
+
+    /// Whether the agent is too hurt to face an attacker last seen close by,
+    /// and has somewhere to run
+    pub fn should_flee(&self, instincts: &InstinctConfig) -> bool {
+        let Some(threat) = &self.threat_source else {
+            return false;
+        };
+        let (dx, dy) = (self.physical.x.abs_diff(threat.position.0), self.physical.y.abs_diff(threat.position.1));
+        !threat.cornered && dx.max(dy) <= instincts.flee_distance && self.physical.health < instincts.flee_health
+    }
+
+    /// Whether the agent remembers no food left where it stands, and so has
+    /// reason to explore
+    pub fn has_exhausted_spot(&self) -> bool {
+        self.beliefs.food_at(self.physical.x, self.physical.y) == Some(0)
+    }
 }
 
 impl Goal {
@@ -743,12 +1173,31 @@ impl Goal {
             Goal::Rest => "resting",
             Goal::Explore => "exploring",
             Goal::Socialize => "meeting others",
+            Goal::FindMate => "looking for a mate",
             Goal::Flee => "escaping danger",
             Goal::Custom(s) => s,
         }
     }
 }
 
+/// Check if two agents are adjacent (within 1 cell)
+pub fn is_adjacent(a: &Agent, b: &Agent) -> bool {
+    let dx = (a.physical.x as i32 - b.physical.x as i32).abs();
+    let dy = (a.physical.y as i32 - b.physical.y as i32).abs();
+    dx <= 1 && dy <= 1
+}
+
+/// Draw how far an agent's lifespan lies from the mean, in standard deviations:
+/// a normal sample nudged upward for calm (low-neuroticism) temperaments
+fn draw_lifespan_deviation(personality: &Personality) -> f64 {
+    let mut rng = crate::rng::rng(crate::rng::Stream::Agents);
+    // Box-Muller transform
+    let u1: f64 = rng.random_range(f64::EPSILON..1.0);
+    let u2: f64 = rng.random();
+    let normal = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+    normal + (0.5 - personality.neuroticism)
+}
+
 /// Names for generating agents
 const NAMES: &[&str] = &[
     "Aric", "Bria", "Corin", "Dara", "Elwyn", "Faye", "Garen", "Hana", "Isen", "Jora",
@@ -758,7 +1207,7 @@ const NAMES: &[&str] = &[
 /// Generate N unique agent names
 pub fn generate_names(count: usize) -> Vec<String> {
     let mut names: Vec<String> = NAMES.iter().map(|s| s.to_string()).collect();
-    let mut rng = rand::rng();
+    let mut rng = crate::rng::rng(crate::rng::Stream::Agents);
 
     // Shuffle
     for i in (1..names.len()).rev() {
@@ -771,7 +1220,7 @@ pub fn generate_names(count: usize) -> Vec<String> {
 
 /// Generate a unique offspring name based on parents
 pub fn generate_offspring_name(parent_a_name: &str, parent_b_name: &str, existing_names: &[String]) -> String {
-    let mut rng = rand::rng();
+    let mut rng = crate::rng::rng(crate::rng::Stream::Agents);
 
     // First try: unused names from the pool
     let unused: Vec<_> = NAMES
@@ -803,3 +1252,94 @@ pub fn generate_offspring_name(parent_a_name: &str, parent_b_name: &str, existin
 
     blended
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mixed_diet_heals_more_than_one_food() {
+        let metabolism = MetabolismConfig { balanced_diet: true, ..MetabolismConfig::default() };
+        let health_after_meals = |meat: u32| {
+            let mut agent = Agent::new("Aric".to_string(), 0, 0, 0);
+            agent.physical.health = 0.2;
+            agent.add_food(6 - meat);
+            agent.add_meat(meat);
+            while agent.eat(metabolism.meal_healing, &metabolism) {}
+            agent.physical.health
+        };
+
+        let mixed = health_after_meals(3);
+        let plants_only = health_after_meals(0);
+        assert!(mixed > plants_only, "{} vs {}", mixed, plants_only);
+    }
+
+    #[test]
+    fn test_specialization_slows_generalists() {
+        let mut generalist = Skills::default();
+        for skill in ["foraging", "crafting", "hunting"] {
+            generalist.levels.insert(skill.to_string(), 0.7);
+        }
+
+        let mut specialist = Skills::default();
+        specialist.levels.insert("hunting".to_string(), 0.7);
+
+        generalist.improve("diplomacy", 0.1, 1);
+        specialist.improve("diplomacy", 0.1, 1);
+
+        assert!(generalist.level("diplomacy") < specialist.level("diplomacy"));
+        assert!((specialist.level("diplomacy") - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_youth_and_ancient_drain_energy_at_their_stage_rates() {
+        let aging = AgingConfig { lifespan_stddev: 0.0, ..AgingConfig::default() };
+        let mut metabolism = MetabolismConfig::default();
+        metabolism.youth.energy = 1.5;
+        metabolism.ancient.energy = 2.0;
+
+        let mut youth = Agent::new("Ila".to_string(), 0, 0, 5);
+        let mut ancient = Agent::new("Oru".to_string(), 0, 0, 5);
+        youth.physical.age = 5;
+        ancient.physical.age = 140;
+        assert_eq!(youth.life_stage(&aging), "youth");
+        assert_eq!(ancient.life_stage(&aging), "ancient");
+
+        for agent in [&mut youth, &mut ancient] {
+            agent.physical.energy = 1.0;
+            let rates = agent.metabolism(&aging, &metabolism);
+            agent.tick_energy(metabolism.energy_drain * rates.energy);
+        }
+
+        assert!((1.0 - youth.physical.energy - 0.075).abs() < 1e-9);
+        assert!((1.0 - ancient.physical.energy - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_instinct_order_decides_healthy_agents_goal() {
+        let mut agent = Agent::new("Aric".to_string(), 0, 0, 5);
+        agent.identity.personality.extraversion = 0.0;
+        agent.physical.hunger = 0.1;
+        agent.physical.energy = 1.0;
+        agent.physical.health = 1.0;
+
+        // Nothing pulls them away from here, so they look for a mate
+        let mut instincts = InstinctConfig::default();
+        agent.update_goal(&instincts);
+        assert!(matches!(agent.active_goal, Some(Goal::FindMate)));
+
+        // Once they've picked their spot clean, exploring comes first
+        agent.beliefs.update_food_belief(0, 0, 0, 1);
+        agent.update_goal(&instincts);
+        assert!(matches!(agent.active_goal, Some(Goal::Explore)));
+
+        instincts.order = vec![Drive::Survival, Drive::Reproduction, Drive::Exploration];
+        agent.update_goal(&instincts);
+        assert!(matches!(agent.active_goal, Some(Goal::FindMate)));
+
+        // Survival still wins when it's first and the agent is hungry
+        agent.physical.hunger = 0.8;
+        agent.update_goal(&instincts);
+        assert!(matches!(agent.active_goal, Some(Goal::Eat)));
+    }
+}
@@ -0,0 +1,297 @@
+//! Post-run analysis: `terrarium analyze <run-dir>` reads a run's event log and
+//! state snapshots and writes `report.md` with the population over time, the
+//! strongest and bitterest relationships, notable groups, lineage depth, what
+//! people died of and the colony's milestones.
+
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::agent::Agent;
+use crate::observation::milestones::Milestones;
+use crate::observation::{Event, EventType, Snapshot, SnapshotFormat};
+
+/// Widest bar in the population chart
+const CHART_WIDTH: usize = 40;
+
+/// A group as seen through its events
+struct GroupRecord {
+    formed: usize,
+    dissolved: Option<usize>,
+    /// Everyone who was ever a member
+    members: Vec<Uuid>,
+}
+
+/// Read every event in `events.jsonl`
+fn load_events(run_dir: &Path) -> Result<Vec<Event>> {
+    let path = run_dir.join("events.jsonl");
+    let text = fs::read_to_string(&path).with_context(|| format!("can't read {}", path.display()))?;
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(n, line)| serde_json::from_str(line).with_context(|| format!("{} line {}", path.display(), n + 1)))
+        .collect()
+}
+
+/// Read every state snapshot in `states/`, oldest first
+fn load_snapshots(run_dir: &Path) -> Result<Vec<Snapshot>> {
+    let states_dir = run_dir.join("states");
+    let mut snapshots = Vec::new();
+    for entry in fs::read_dir(&states_dir).with_context(|| format!("can't read {}", states_dir.display()))? {
+        let path = entry?.path();
+        let is_snapshot = path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with("epoch_"));
+        if is_snapshot && SnapshotFormat::from_path(&path).is_some() {
+            snapshots.push(Snapshot::load(&path)?);
+        }
+    }
+    snapshots.sort_by_key(|s| s.epoch);
+    Ok(snapshots)
+}
+
+/// Analyze the run in `run_dir` and write its report to `run_dir/report.md`,
+/// listing `top` relationships of each kind
+pub fn analyze(run_dir: &Path, top: usize) -> Result<String> {
+    let events = load_events(run_dir)?;
+    let snapshots = load_snapshots(run_dir)?;
+    let (Some(first), Some(last)) = (snapshots.first(), snapshots.last()) else {
+        anyhow::bail!("no state snapshots in {}", run_dir.join("states").display());
+    };
+
+    let mut names: HashMap<Uuid, String> = HashMap::new();
+    for agent in snapshots.iter().flat_map(|s| &s.agents) {
+        names.entry(agent.id).or_insert_with(|| agent.name().to_string());
+    }
+    for event in &events {
+        if let (Some(child), Some(name)) = (event.data.child, &event.data.child_name) {
+            names.entry(child).or_insert_with(|| name.clone());
+        }
+    }
+    let name = |id: &Uuid| names.get(id).cloned().unwrap_or_else(|| "someone".to_string());
+
+    let run_name = run_dir.file_name().map_or_else(|| run_dir.display().to_string(), |n| n.to_string_lossy().into_owned());
+    let mut doc = String::new();
+    let _ = writeln!(doc, "# Run report: {}", run_name);
+    let _ = writeln!(doc);
+    let final_epoch = events.iter().map(|e| e.epoch).max().unwrap_or(last.epoch).max(last.epoch);
+    let living = |snapshot: &Snapshot| snapshot.agents.iter().filter(|a| a.is_alive()).count();
+    let _ = writeln!(
+        doc,
+        "{} epochs. Final population: {} ({} ever lived).",
+        final_epoch,
+        living(last),
+        names.len()
+    );
+
+    // Population at each snapshot
+    let _ = writeln!(doc);
+    let _ = writeln!(doc, "## Population");
+    let _ = writeln!(doc);
+    let peak = snapshots.iter().map(living).max().unwrap_or(0).max(1);
+    let _ = writeln!(doc, "| Epoch | Living | |");
+    let _ = writeln!(doc, "|---:|---:|:---|");
+    for snapshot in &snapshots {
+        let count = living(snapshot);
+        let bar = "█".repeat((count * CHART_WIDTH).div_ceil(peak));
+        let _ = writeln!(doc, "| {} | {} | {} |", snapshot.epoch, count, bar);
+    }
+
+    // Causes of death, "attack by Aric" counted as "attack"
+    let _ = writeln!(doc);
+    let _ = writeln!(doc, "## Causes of death");
+    let _ = writeln!(doc);
+    let mut causes: BTreeMap<String, usize> = BTreeMap::new();
+    for event in events.iter().filter(|e| matches!(e.event_type, EventType::Died)) {
+        let cause = event.data.description.as_deref().unwrap_or("unknown");
+        let cause = cause.split(" by ").next().unwrap_or(cause);
+        *causes.entry(cause.to_string()).or_insert(0) += 1;
+    }
+    let mut causes: Vec<(String, usize)> = causes.into_iter().collect();
+    causes.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    if causes.is_empty() {
+        let _ = writeln!(doc, "No one died.");
+    }
+    for (cause, count) in &causes {
+        let _ = writeln!(doc, "- {}: {}", cause, count);
+    }
+
+    // Relationships as they stood at the end, by how the two feel about each other
+    let mut pairs: Vec<(f64, f64, &Agent, &Agent)> = Vec::new();
+    for (i, a) in last.agents.iter().enumerate() {
+        for b in &last.agents[i + 1..] {
+            if let (Some(ab), Some(ba)) = (a.beliefs.get_social(b.id), b.beliefs.get_social(a.id)) {
+                pairs.push(((ab.sentiment + ba.sentiment) / 2.0, (ab.trust + ba.trust) / 2.0, a, b));
+            }
+        }
+    }
+    pairs.sort_by(|x, y| y.0.total_cmp(&x.0));
+    let mut relationships = |title: &str, pairs: &mut dyn Iterator<Item = &(f64, f64, &Agent, &Agent)>| {
+        let _ = writeln!(doc);
+        let _ = writeln!(doc, "## {}", title);
+        let _ = writeln!(doc);
+        let mut any = false;
+        for (sentiment, trust, a, b) in pairs.take(top) {
+            any = true;
+            let _ = writeln!(doc, "- {} and {}: sentiment {:+.2}, trust {:+.2}", a.name(), b.name(), sentiment, trust);
+        }
+        if !any {
+            let _ = writeln!(doc, "None.");
+        }
+    };
+    relationships("Strongest bonds", &mut pairs.iter().filter(|p| p.0 > 0.0));
+    relationships("Bitterest enmities", &mut pairs.iter().rev().filter(|p| p.0 < 0.0));
+
+    // Groups from their formation, changes and dissolution
+    let mut groups: Vec<(String, GroupRecord)> = Vec::new();
+    for event in &events {
+        let Some(group_name) = &event.data.group_name else {
+            continue;
+        };
+        let members = event.data.members.clone().unwrap_or_default();
+        match event.event_type {
+            EventType::GroupFormed => groups.push((
+                group_name.clone(),
+                GroupRecord { formed: event.epoch, dissolved: None, members },
+            )),
+            EventType::GroupChanged | EventType::GroupDissolved => {
+                let Some((_, group)) = groups.iter_mut().rev().find(|(n, g)| n == group_name && g.dissolved.is_none())
+                else {
+                    continue;
+                };
+                for member in members {
+                    if !group.members.contains(&member) {
+                        group.members.push(member);
+                    }
+                }
+                if matches!(event.event_type, EventType::GroupDissolved) {
+                    group.dissolved = Some(event.epoch);
+                }
+            }
+            _ => {}
+        }
+    }
+    let _ = writeln!(doc);
+    let _ = writeln!(doc, "## Groups");
+    let _ = writeln!(doc);
+    let span = |group: &GroupRecord| group.dissolved.unwrap_or(final_epoch) - group.formed;
+    let largest = groups.iter().max_by_key(|(_, g)| g.members.len());
+    let longest = groups.iter().max_by_key(|(_, g)| span(g));
+    match (largest, longest) {
+        (Some((largest_name, largest)), Some((longest_name, longest))) => {
+            let _ = writeln!(doc, "{} groups formed.", groups.len());
+            let _ = writeln!(doc);
+            let _ = writeln!(
+                doc,
+                "- Largest: {}, {} members ({})",
+                largest_name,
+                largest.members.len(),
+                largest.members.iter().map(name).collect::<Vec<_>>().join(", ")
+            );
+            let ended = match longest.dissolved {
+                Some(epoch) => format!("dissolved day {}", epoch),
+                None => "still standing".to_string(),
+            };
+            let _ = writeln!(
+                doc,
+                "- Longest-lived: {}, {} epochs (formed day {}, {})",
+                longest_name,
+                span(longest),
+                longest.formed,
+                ended
+            );
+        }
+        _ => {
+            let _ = writeln!(doc, "No groups formed.");
+        }
+    }
+
+    // Lineage
+    let _ = writeln!(doc);
+    let _ = writeln!(doc, "## Lineage");
+    let _ = writeln!(doc);
+    let births = events.iter().filter(|e| matches!(e.event_type, EventType::BirthOccurred)).count();
+    match last.agents.iter().max_by_key(|a| a.reproduction.family.generation) {
+        Some(deepest) if deepest.reproduction.family.generation > 0 => {
+            let _ = writeln!(
+                doc,
+                "{} born. Deepest lineage: {} generations below the founders, down to {}.",
+                births,
+                deepest.reproduction.family.generation,
+                deepest.name()
+            );
+        }
+        _ => {
+            let _ = writeln!(doc, "{} born. No lineage reached past the founders.", births);
+        }
+    }
+
+    // Milestones, replayed from the event log
+    let mut milestones = Milestones::new();
+    milestones.set_population(first.epoch, living(first));
+    for event in &events {
+        milestones.record(event, &names);
+    }
+    let _ = writeln!(doc);
+    let _ = writeln!(doc, "## Milestones");
+    let _ = writeln!(doc);
+    for milestone in milestones.milestones() {
+        let _ = writeln!(doc, "- Day {}: {}", milestone.epoch, milestone.description);
+    }
+
+    fs::write(run_dir.join("report.md"), &doc)?;
+    Ok(doc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::world::World;
+
+    #[test]
+    fn test_report_counts_survivors_and_causes_of_death() {
+        let run_dir = std::env::temp_dir().join(format!("terrarium-analysis-{}", Uuid::new_v4()));
+        fs::create_dir_all(run_dir.join("states")).unwrap();
+        let world = World::new(&Config::default().world);
+        let mut agents: Vec<Agent> =
+            ["Aric", "Bria", "Cara", "Dax"].iter().map(|n| Agent::new(n.to_string(), 0, 0, 5)).collect();
+        let ids: Vec<Uuid> = agents.iter().map(|a| a.id).collect();
+        let snapshot = |epoch, world, agents| Snapshot {
+            epoch,
+            world,
+            agents,
+            groups: Default::default(),
+            trade: Default::default(),
+        };
+        snapshot(0, world.clone(), agents.clone())
+            .save(&run_dir.join("states/epoch_0000.json"), SnapshotFormat::Json)
+            .unwrap();
+
+        let events = [
+            Event::died(4, ids[2], "starvation"),
+            Event::died(6, ids[3], "attack by Aric"),
+            Event::group_formed(7, "The Kin", vec![ids[0], ids[1]]),
+        ];
+        let log: Vec<String> = events.iter().map(|e| serde_json::to_string(e).unwrap()).collect();
+        fs::write(run_dir.join("events.jsonl"), log.join("\n")).unwrap();
+        agents[2].physical.health = 0.0;
+        agents[3].physical.health = 0.0;
+        agents[0].beliefs.update_sentiment(ids[1], "Bria", 0.6, 5);
+        agents[1].beliefs.update_sentiment(ids[0], "Aric", 0.4, 5);
+        snapshot(10, world, agents)
+            .save(&run_dir.join("states/epoch_0010.json"), SnapshotFormat::Json)
+            .unwrap();
+
+        let report = analyze(&run_dir, 3).unwrap();
+        assert!(report.contains("Final population: 2 (4 ever lived)"), "{}", report);
+        assert!(report.contains("- attack: 1\n- starvation: 1"), "{}", report);
+        assert!(report.contains("- Aric and Bria: sentiment"));
+        assert!(report.contains("- Largest: The Kin, 2 members"));
+        assert!(report.contains("Day 4: **Cara** was the first to die"));
+        assert_eq!(fs::read_to_string(run_dir.join("report.md")).unwrap(), report);
+
+        fs::remove_dir_all(&run_dir).unwrap();
+    }
+}
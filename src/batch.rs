@@ -0,0 +1,185 @@
+//! Batch mode: run one scenario several times from derived seeds and
+//! aggregate the outcomes, for studying variance between runs.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use tracing::info;
+
+use crate::config::Config;
+use crate::engine::Engine;
+
+/// Outcome of one run in a batch
+#[derive(Debug, Clone, Serialize)]
+pub struct RunSample {
+    pub run: usize,
+    pub seed: u64,
+    pub final_population: usize,
+    /// Mean age reached, counting survivors at their age when the run ended
+    pub mean_lifespan: f64,
+    pub groups: usize,
+    pub extinct: bool,
+}
+
+impl RunSample {
+    fn from_engine(run: usize, seed: u64, engine: &Engine) -> Self {
+        // The archived dead count too, or the long-dead would drop out
+        let agents = engine.roster();
+        let total_age: usize = agents.iter().map(|a| a.age()).sum();
+        let final_population = engine.alive_count();
+        Self {
+            run,
+            seed,
+            final_population,
+            mean_lifespan: total_age as f64 / agents.len().max(1) as f64,
+            groups: engine.current_groups().len(),
+            extinct: final_population == 0,
+        }
+    }
+}
+
+/// Mean and standard deviation of a statistic across runs
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Spread {
+    pub mean: f64,
+    pub stddev: f64,
+}
+
+impl Spread {
+    fn of(values: &[f64]) -> Self {
+        if values.is_empty() {
+            return Self { mean: 0.0, stddev: 0.0 };
+        }
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+        Self { mean, stddev: variance.sqrt() }
+    }
+}
+
+/// Aggregate written to `summary.json`
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchSummary {
+    pub base_seed: u64,
+    pub final_population: Spread,
+    pub mean_lifespan: Spread,
+    pub groups: Spread,
+    /// Fraction of runs in which everyone died
+    pub extinction_rate: f64,
+    pub samples: Vec<RunSample>,
+}
+
+impl BatchSummary {
+    fn from_samples(base_seed: u64, samples: Vec<RunSample>) -> Self {
+        let stat = |f: fn(&RunSample) -> f64| Spread::of(&samples.iter().map(f).collect::<Vec<_>>());
+        let extinct = samples.iter().filter(|s| s.extinct).count();
+        Self {
+            base_seed,
+            final_population: stat(|s| s.final_population as f64),
+            mean_lifespan: stat(|s| s.mean_lifespan),
+            groups: stat(|s| s.groups as f64),
+            extinction_rate: extinct as f64 / samples.len().max(1) as f64,
+            samples,
+        }
+    }
+}
+
+/// Seed for run `index` of a batch
+pub fn run_seed(base_seed: u64, index: usize) -> u64 {
+    base_seed.wrapping_add((index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+}
+
+/// Run the scenario `runs` times into `output_dir/run_000`, `run_001`, ...
+/// and write the aggregate to `output_dir/summary.json`
+pub async fn run_batch(config: &Config, output_dir: &Path, runs: usize, base_seed: u64) -> Result<BatchSummary> {
+    let mut samples = Vec::with_capacity(runs);
+
+    for run in 0..runs {
+        let seed = run_seed(base_seed, run);
+        info!("Batch run {}/{} (seed {})", run + 1, runs, seed);
+
+        let mut run_config = config.clone();
+        run_config.simulation.seed = Some(seed);
+        let run_dir = output_dir.join(format!("run_{:03}", run));
+        if let Some(db) = &config.simulation.database {
+            // One database per run, so their epochs don't interleave
+            let name = Path::new(db).file_name().unwrap_or("events.db".as_ref());
+            run_config.simulation.database = Some(run_dir.join(name).to_string_lossy().into_owned());
+        }
+        let mut engine = Engine::new(run_config, &run_dir.to_string_lossy())?;
+        engine.run().await?;
+
+        samples.push(RunSample::from_engine(run, seed, &engine));
+    }
+
+    let summary = BatchSummary::from_samples(base_seed, samples);
+    fs::write(output_dir.join("summary.json"), serde_json::to_string_pretty(&summary)?)?;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_three_runs_produce_three_run_directories() {
+        let mut config = Config::default();
+        config.agents.count = 2;
+        config.simulation.epochs = 2;
+        config.llm.api_key_env = "TERRARIUM_TEST_NO_API_KEY".to_string();
+        let output_dir = std::env::temp_dir().join(format!("terrarium-batch-{}", uuid::Uuid::new_v4()));
+
+        let summary = run_batch(&config, &output_dir, 3, 42).await.unwrap();
+
+        for run in 0..3 {
+            assert!(output_dir.join(format!("run_{:03}", run)).join("events.jsonl").exists());
+        }
+        assert!(!output_dir.join("run_003").exists());
+
+        let written: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(output_dir.join("summary.json")).unwrap()).unwrap();
+        assert_eq!(written["samples"].as_array().unwrap().len(), 3);
+        assert_eq!(summary.samples.iter().map(|s| s.seed).collect::<Vec<_>>(), vec![
+            run_seed(42, 0),
+            run_seed(42, 1),
+            run_seed(42, 2),
+        ]);
+
+        fs::remove_dir_all(&output_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_mean_lifespan_counts_the_archived_dead() {
+        use crate::config::{ScriptedAction, ScriptedEvent};
+
+        // The founders die of old age and are archived long before the end;
+        // only a late newcomer is still about when the run stops
+        let mut config = Config::default();
+        config.agents.count = 3;
+        config.simulation.epochs = 6;
+        config.simulation.archive_dead_after = 1;
+        config.aging.youth_end = 1;
+        config.aging.prime_end = 2;
+        config.aging.elderly_start = 3;
+        config.aging.max_lifespan = 4;
+        config.aging.lifespan_stddev = 0.0;
+        config.aging.death_probability_rate = 0.0;
+        config.scenario.scripted_events.push(ScriptedEvent {
+            epoch: 3,
+            action: ScriptedAction::SpawnAgent { name: "Latecomer".to_string(), x: None, y: None, model: None },
+        });
+        config.llm.api_key_env = "TERRARIUM_TEST_NO_API_KEY".to_string();
+        let output_dir = std::env::temp_dir().join(format!("terrarium-batch-{}", uuid::Uuid::new_v4()));
+
+        let summary = run_batch(&config, &output_dir, 2, 7).await.unwrap();
+
+        for sample in &summary.samples {
+            assert_eq!(sample.final_population, 1);
+            // Three founders at 4, the newcomer at 3
+            assert_eq!(sample.mean_lifespan, 3.75, "archived founders left out: {:?}", sample);
+        }
+
+        fs::remove_dir_all(&output_dir).unwrap();
+    }
+}
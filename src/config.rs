@@ -1,13 +1,18 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
 
+use crate::agent::{Drive, BASE_REST_RECOVERY, MEAL_HUNGER_RELIEF};
 use crate::environment::EnvironmentConfig;
+use crate::groups::GroupAlgorithm;
 use crate::llm::LlmConfig;
-use crate::world::WorldConfig;
+use crate::observation::{content_hash, ChronicleStyle, ChronicleVerbosity, LivesFormat, SnapshotFormat};
+use crate::pipeline::{default_pipeline, Step};
+use crate::structures::StructureType;
+use crate::world::{Terrain, TerrainYields, WorldConfig, WorldEvent};
 
 /// Top-level configuration
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub meta: MetaConfig,
     pub world: WorldConfig,
@@ -24,34 +29,142 @@ pub struct Config {
     pub skills: SkillsConfig,
     #[serde(default)]
     pub trade: TradeConfig,
+    #[serde(default)]
+    pub world_events: WorldEventsConfig,
+    #[serde(default)]
+    pub combat: CombatConfig,
+    #[serde(default)]
+    pub scenario: ScenarioConfig,
+    #[serde(default)]
+    pub inheritance: InheritanceConfig,
+    #[serde(default)]
+    pub scavenging: ScavengingConfig,
+    #[serde(default)]
+    pub perception: PerceptionConfig,
+    #[serde(default)]
+    pub groups: GroupsConfig,
+    #[serde(default)]
+    pub instincts: InstinctConfig,
+    #[serde(default)]
+    pub metabolism: MetabolismConfig,
+    #[serde(default)]
+    pub reputation: ReputationConfig,
+    #[serde(default)]
+    pub structures: StructuresConfig,
+    #[serde(default)]
+    pub legacy: LegacyConfig,
+    #[serde(default)]
+    pub migration: MigrationConfig,
+    #[serde(default)]
+    pub movement: MovementConfig,
+    #[serde(default)]
+    pub rumors: RumorConfig,
+    /// Hash of the scenario file this was loaded from, for provenance
+    #[serde(skip)]
+    pub scenario_hash: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetaConfig {
     pub name: String,
     #[serde(default)]
     pub description: String,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentsConfig {
     pub count: usize,
     pub starting_food: u32,
     #[serde(default = "default_personality")]
     pub personality: String,
+    /// Hand-picked agents; these fill the first slots, the rest are generated
+    #[serde(default)]
+    pub roster: Vec<RosterEntry>,
+}
+
+/// A named agent in the roster
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RosterEntry {
+    /// Agent name
+    pub name: String,
+    /// LLM model override for this agent
+    #[serde(default)]
+    pub model: Option<String>,
+    /// How this agent already feels about other roster agents
+    #[serde(default)]
+    pub relationships: Vec<RelationshipSeed>,
+    /// Names of this agent's parents among the roster
+    #[serde(default)]
+    pub parents: Vec<String>,
+    /// Names of roster agents this agent has mated with
+    #[serde(default)]
+    pub mates: Vec<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// A pre-existing belief about another named agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelationshipSeed {
+    /// Name of the other agent
+    pub with: String,
+    /// -1.0 (complete distrust) to 1.0 (complete trust)
+    #[serde(default)]
+    pub trust: f64,
+    /// -1.0 (hate) to 1.0 (love)
+    #[serde(default)]
+    pub sentiment: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulationConfig {
     pub epochs: usize,
     #[serde(default = "default_snapshot_interval")]
     pub snapshot_interval: usize,
     #[serde(default = "default_log_thoughts")]
     pub log_thoughts: bool,
+    /// Tell agents why their previous action failed
+    #[serde(default = "default_action_feedback")]
+    pub action_feedback: bool,
+    /// How much detail the chronicle narrates (minimal, normal, verbose)
+    #[serde(default)]
+    pub chronicle_verbosity: ChronicleVerbosity,
+    /// Voice of the chronicle (third_person_past, present_tense, first_person_per_protagonist)
+    #[serde(default)]
+    pub chronicle_style: ChronicleStyle,
+    /// Agent whose journal a first-person chronicle is (None = the first agent)
+    #[serde(default)]
+    pub chronicle_protagonist: Option<String>,
+    /// Encoding for state snapshots (json_pretty, json, message_pack, bincode)
+    #[serde(default)]
+    pub snapshot_format: SnapshotFormat,
+    /// Seed for the simulation RNG (None = fresh randomness every run)
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Log how long each phase of every epoch takes, plus a summary at the end
+    #[serde(default)]
+    pub profile: bool,
+    /// Per-agent lifetime summaries written at the end (off, markdown, json)
+    #[serde(default)]
+    pub lives_format: LivesFormat,
+    /// SQLite database to also write events and agent snapshots to
+    /// (needs the `sqlite` feature)
+    #[serde(default)]
+    pub database: Option<String>,
+    /// Order of the steps in each epoch (every step exactly once, deliberate
+    /// before resolve and mate)
+    #[serde(default = "default_pipeline")]
+    pub pipeline: Vec<Step>,
+    /// Epochs after death before an agent leaves the active list for the
+    /// archive of the deceased (0 = never)
+    #[serde(default = "default_archive_dead_after")]
+    pub archive_dead_after: usize,
+    /// Append the trust/sentiment network, groups and rivalries to
+    /// network_timeline.jsonl at every snapshot
+    #[serde(default)]
+    pub network_timeline: bool,
 }
 
 /// Reproduction system configuration
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReproductionConfig {
     /// Whether reproduction is enabled
     #[serde(default = "default_reproduction_enabled")]
@@ -86,6 +199,18 @@ pub struct ReproductionConfig {
     /// Minimum energy to reproduce
     #[serde(default = "default_min_energy_to_reproduce")]
     pub min_energy_to_reproduce: f64,
+    /// End the run early once the population can no longer reproduce
+    #[serde(default = "default_stop_when_nonviable")]
+    pub stop_when_nonviable: bool,
+    /// Consecutive non-viable epochs before the run ends
+    #[serde(default = "default_nonviable_grace_epochs")]
+    pub nonviable_grace_epochs: usize,
+    /// Log a MateFailed event naming the precondition that stopped a willing pair
+    #[serde(default = "default_log_failed_matings")]
+    pub log_failed_matings: bool,
+    /// How far a child's colour may stray from the blend of its parents', in degrees
+    #[serde(default = "default_hue_drift")]
+    pub hue_drift: f64,
 }
 
 impl Default for ReproductionConfig {
@@ -102,10 +227,99 @@ impl Default for ReproductionConfig {
             offspring_starting_food: 5,
             min_health_to_reproduce: 0.5,
             min_energy_to_reproduce: 0.4,
+            stop_when_nonviable: false,
+            nonviable_grace_epochs: 20,
+            log_failed_matings: true,
+            hue_drift: 20.0,
         }
     }
 }
 
+impl ReproductionConfig {
+    /// Combinations of settings that make reproduction impossible or ruinous,
+    /// each described in a sentence; empty when the economics hold up
+    pub fn validate(&self, metabolism: &MetabolismConfig) -> Vec<String> {
+        let mut problems = Vec::new();
+        if !self.enabled {
+            return problems;
+        }
+
+        if self.courtship_increment <= self.courtship_decay {
+            problems.push(format!(
+                "courtship_increment ({}) does not exceed courtship_decay ({}); courtships between agents who part ways will never reach the threshold",
+                self.courtship_increment, self.courtship_decay
+            ));
+        }
+        for (name, value) in [
+            ("courtship_threshold", self.courtship_threshold),
+            ("min_health_to_reproduce", self.min_health_to_reproduce),
+            ("min_energy_to_reproduce", self.min_energy_to_reproduce),
+        ] {
+            if value > 1.0 {
+                problems.push(format!("{} ({}) is above 1.0, so no one can ever reproduce", name, value));
+            }
+        }
+
+        let report = self.balance_report(metabolism, 0);
+        if report.rest_share >= 1.0 {
+            problems.push(format!(
+                "gestation_energy_drain ({}) plus everyday tiredness outpaces a full day's rest ({:.2} energy); carriers will collapse before giving birth",
+                self.gestation_energy_drain, BASE_REST_RECOVERY
+            ));
+        }
+        if self.offspring_starting_food == 0 {
+            problems.push("offspring_starting_food is 0; newborns will starve unless someone feeds them".to_string());
+        }
+        problems
+    }
+
+    /// Estimate how a typical prime-age carrier fares over a full gestation,
+    /// starting with `food` in hand after paying for mating
+    pub fn balance_report(&self, metabolism: &MetabolismConfig, food: u32) -> BalanceReport {
+        let energy_per_epoch = metabolism.energy_drain + self.gestation_energy_drain;
+        let rest_share = energy_per_epoch / BASE_REST_RECOVERY;
+        let food_needed = self.gestation_period as f64 * metabolism.hunger_rate / MEAL_HUNGER_RELIEF;
+        BalanceReport {
+            gestation_period: self.gestation_period,
+            energy_per_epoch,
+            rest_share,
+            food_needed,
+            food_in_hand: food,
+            survivable: rest_share < 1.0,
+        }
+    }
+}
+
+/// What a gestation costs the carrier, from `ReproductionConfig::balance_report`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceReport {
+    pub gestation_period: usize,
+    /// Energy lost per epoch to tiredness and gestation together
+    pub energy_per_epoch: f64,
+    /// Share of days the carrier must rest to keep their energy up (1.0 or more is unsustainable)
+    pub rest_share: f64,
+    /// Food eaten over the gestation to keep hunger level
+    pub food_needed: f64,
+    pub food_in_hand: u32,
+    /// Whether resting part of the time is enough to carry to term
+    pub survivable: bool,
+}
+
+impl BalanceReport {
+    /// One-line summary for the log
+    pub fn describe(&self) -> String {
+        format!(
+            "a {}-epoch gestation costs {:.2} energy a day (rest {:.0}% of days to keep up) and about {:.1} food ({} in hand after mating){}",
+            self.gestation_period,
+            self.energy_per_epoch,
+            self.rest_share * 100.0,
+            self.food_needed,
+            self.food_in_hand,
+            if self.survivable { "" } else { "; carriers cannot survive it" }
+        )
+    }
+}
+
 fn default_reproduction_enabled() -> bool { true }
 fn default_gestation_period() -> usize { 10 }
 fn default_mating_food_cost() -> u32 { 5 }
@@ -117,9 +331,13 @@ fn default_mating_cooldown() -> usize { 20 }
 fn default_offspring_starting_food() -> u32 { 5 }
 fn default_min_health_to_reproduce() -> f64 { 0.5 }
 fn default_min_energy_to_reproduce() -> f64 { 0.4 }
+fn default_stop_when_nonviable() -> bool { false }
+fn default_nonviable_grace_epochs() -> usize { 20 }
+fn default_log_failed_matings() -> bool { true }
+fn default_hue_drift() -> f64 { 20.0 }
 
 /// Aging system configuration
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgingConfig {
     /// Whether aging is enabled
     #[serde(default = "default_aging_enabled")]
@@ -133,9 +351,12 @@ pub struct AgingConfig {
     /// Start of elderly period (when death probability begins)
     #[serde(default = "default_elderly_start")]
     pub elderly_start: usize,
-    /// Maximum lifespan (certain death)
+    /// Maximum lifespan (certain death), the mean across agents
     #[serde(default = "default_max_lifespan")]
     pub max_lifespan: usize,
+    /// Standard deviation of each agent's own maximum lifespan (0 = everyone the same)
+    #[serde(default = "default_lifespan_stddev")]
+    pub lifespan_stddev: f64,
     /// Base probability of death per epoch after elderly_start
     #[serde(default = "default_death_probability_rate")]
     pub death_probability_rate: f64,
@@ -152,6 +373,7 @@ impl Default for AgingConfig {
             prime_end: 60,
             elderly_start: 60,
             max_lifespan: 150,
+            lifespan_stddev: 0.0,
             death_probability_rate: 0.02,
             capability_affects_actions: true,
         }
@@ -163,11 +385,314 @@ fn default_youth_end() -> usize { 15 }
 fn default_prime_end() -> usize { 60 }
 fn default_elderly_start() -> usize { 60 }
 fn default_max_lifespan() -> usize { 150 }
+fn default_lifespan_stddev() -> f64 { 0.0 }
 fn default_death_probability_rate() -> f64 { 0.02 }
 fn default_capability_affects_actions() -> bool { true }
 
+/// How reputations precede encounters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReputationConfig {
+    /// Colour first impressions of strangers by how the observer feels about
+    /// the stranger's group (what was heard through gossip always carries over)
+    #[serde(default = "default_stranger_priors")]
+    pub stranger_priors: bool,
+    /// Share of the observer's average trust and sentiment toward a stranger's
+    /// group-mates that carries over to the stranger
+    #[serde(default = "default_group_prior_weight")]
+    pub group_weight: f64,
+}
+
+impl Default for ReputationConfig {
+    fn default() -> Self {
+        Self {
+            stranger_priors: true,
+            group_weight: 0.5,
+        }
+    }
+}
+
+fn default_stranger_priors() -> bool { true }
+fn default_group_prior_weight() -> f64 { 0.5 }
+
+/// What it takes to fulfil an aspiration over a lifetime
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegacyConfig {
+    /// Followers a leader needs to feel powerful
+    #[serde(default = "default_leader_followers")]
+    pub leader_followers: usize,
+    /// Food an agent needs to have come by to feel secure
+    #[serde(default = "default_legacy_food")]
+    pub food_gathered: u32,
+    /// Distinct cells an explorer needs to have seen
+    #[serde(default = "default_legacy_cells")]
+    pub cells_visited: usize,
+}
+
+impl Default for LegacyConfig {
+    fn default() -> Self {
+        Self {
+            leader_followers: 4,
+            food_gathered: 100,
+            cells_visited: 30,
+        }
+    }
+}
+
+fn default_leader_followers() -> usize { 4 }
+fn default_legacy_food() -> u32 { 100 }
+fn default_legacy_cells() -> usize { 30 }
+
+/// Limits on where agents can go
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MovementConfig {
+    /// Most living agents one cell holds; moves into a full cell fail, and
+    /// moves resolve in agent id order (None = no limit)
+    #[serde(default)]
+    pub max_occupancy: Option<usize>,
+    /// Swimming level needed to move onto water
+    #[serde(default = "default_swim_skill")]
+    pub swim_skill: f64,
+    /// Swimming learned each time the water turns an agent back
+    #[serde(default = "default_shore_practice")]
+    pub shore_practice: f64,
+}
+
+fn default_swim_skill() -> f64 { 0.3 }
+fn default_shore_practice() -> f64 { 0.05 }
+
+impl Default for MovementConfig {
+    fn default() -> Self {
+        Self {
+            max_occupancy: None,
+            swim_skill: 0.3,
+            shore_practice: 0.05,
+        }
+    }
+}
+
+/// How gossip is judged against what its subject has been doing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RumorConfig {
+    #[serde(default = "default_rumors_enabled")]
+    pub enabled: bool,
+    /// Epochs of the subject's deeds a rumor is judged by
+    #[serde(default = "default_rumor_window")]
+    pub window: usize,
+    /// Fidelity below which a rumor counts as distorted
+    #[serde(default = "default_low_fidelity")]
+    pub low_fidelity: f64,
+}
+
+impl Default for RumorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window: 30,
+            low_fidelity: 0.5,
+        }
+    }
+}
+
+fn default_rumors_enabled() -> bool { true }
+fn default_rumor_window() -> usize { 30 }
+fn default_low_fidelity() -> f64 { 0.5 }
+
+/// When a starving cluster of agents feels the pull to move on together
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationConfig {
+    #[serde(default = "default_migration_enabled")]
+    pub enabled: bool,
+    /// Hunger at or above which an agent counts as starving
+    #[serde(default = "default_migration_hunger")]
+    pub hunger: f64,
+    /// Epochs the cluster must have starved before it moves on
+    #[serde(default = "default_migration_epochs")]
+    pub epochs: usize,
+    /// Fewest starving agents that make an exodus
+    #[serde(default = "default_migration_min_agents")]
+    pub min_agents: usize,
+    /// Cells between agents that still count as starving together
+    #[serde(default = "default_migration_radius")]
+    pub radius: usize,
+    /// Epochs the impulse lasts unless hunger is relieved first
+    #[serde(default = "default_migration_duration")]
+    pub duration: usize,
+}
+
+impl Default for MigrationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            hunger: 0.7,
+            epochs: 3,
+            min_agents: 3,
+            radius: 2,
+            duration: 15,
+        }
+    }
+}
+
+fn default_migration_enabled() -> bool { true }
+fn default_migration_hunger() -> f64 { 0.7 }
+fn default_migration_epochs() -> usize { 3 }
+fn default_migration_min_agents() -> usize { 3 }
+fn default_migration_radius() -> usize { 2 }
+fn default_migration_duration() -> usize { 15 }
+
+/// Terrain each kind of structure may be built on; an empty list allows any terrain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructuresConfig {
+    #[serde(default)]
+    pub lean_to: Vec<Terrain>,
+    #[serde(default)]
+    pub shelter: Vec<Terrain>,
+    #[serde(default)]
+    pub storage: Vec<Terrain>,
+    #[serde(default)]
+    pub workbench: Vec<Terrain>,
+    /// Farms need ground that crops grow in
+    #[serde(default = "default_farm_terrain")]
+    pub farm: Vec<Terrain>,
+    /// Durability finished structures lose each epoch on top of the usual 1,
+    /// per unit of environmental hazard
+    #[serde(default = "default_hazard_decay")]
+    pub hazard_decay: f64,
+}
+
+fn default_hazard_decay() -> f64 { 2.0 }
+
+impl Default for StructuresConfig {
+    fn default() -> Self {
+        Self {
+            lean_to: Vec::new(),
+            shelter: Vec::new(),
+            storage: Vec::new(),
+            workbench: Vec::new(),
+            farm: default_farm_terrain(),
+            hazard_decay: 2.0,
+        }
+    }
+}
+
+impl StructuresConfig {
+    /// Terrain a structure type may be built on
+    pub fn allowed_terrain(&self, structure_type: StructureType) -> &[Terrain] {
+        match structure_type {
+            StructureType::LeanTo => &self.lean_to,
+            StructureType::Shelter => &self.shelter,
+            StructureType::Storage => &self.storage,
+            StructureType::Workbench => &self.workbench,
+            StructureType::Farm => &self.farm,
+        }
+    }
+}
+
+fn default_farm_terrain() -> Vec<Terrain> { vec![Terrain::Fertile] }
+
+/// Per-epoch upkeep of the body, scaled by life stage (prime adults run at the base rates)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetabolismConfig {
+    /// Hunger a prime adult gains per epoch
+    #[serde(default = "default_hunger_rate")]
+    pub hunger_rate: f64,
+    /// Energy a prime adult loses per epoch
+    #[serde(default = "default_energy_drain")]
+    pub energy_drain: f64,
+    /// Health a prime adult regains from a meal
+    #[serde(default = "default_meal_healing")]
+    pub meal_healing: f64,
+    /// Energy at or below which an agent is spent
+    #[serde(default = "default_collapse_energy")]
+    pub collapse_energy: f64,
+    /// Epochs in a row spent before an agent collapses and can only rest or
+    /// eat (0 = never)
+    #[serde(default = "default_collapse_epochs")]
+    pub collapse_epochs: usize,
+    /// Energy a collapsed agent has to rest back up to before acting again
+    #[serde(default = "default_recover_energy")]
+    pub recover_energy: f64,
+    /// Whether meals heal less when an agent eats only plants or only meat
+    #[serde(default)]
+    pub balanced_diet: bool,
+    /// Latest meals that count toward the balance of a diet
+    #[serde(default = "default_diet_window")]
+    pub diet_window: usize,
+    /// Share of a meal's healing left on a diet of one kind of food
+    #[serde(default = "default_monotony_healing")]
+    pub monotony_healing: f64,
+    /// Children eat less but tire faster
+    #[serde(default = "default_youth_metabolism")]
+    pub youth: StageMetabolism,
+    /// The elderly tire faster and heal slower
+    #[serde(default = "default_elderly_metabolism")]
+    pub elderly: StageMetabolism,
+    /// The ancient tire and heal slower still
+    #[serde(default = "default_ancient_metabolism")]
+    pub ancient: StageMetabolism,
+}
+
+/// Multipliers on the base metabolic rates for one life stage
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct StageMetabolism {
+    #[serde(default = "default_stage_rate")]
+    pub hunger: f64,
+    #[serde(default = "default_stage_rate")]
+    pub energy: f64,
+    #[serde(default = "default_stage_rate")]
+    pub healing: f64,
+}
+
+impl StageMetabolism {
+    /// The base rates, unscaled
+    pub const BASE: Self = Self { hunger: 1.0, energy: 1.0, healing: 1.0 };
+}
+
+impl Default for MetabolismConfig {
+    fn default() -> Self {
+        Self {
+            hunger_rate: 0.1,
+            energy_drain: 0.05,
+            meal_healing: 0.05,
+            collapse_energy: 0.05,
+            collapse_epochs: 2,
+            recover_energy: 0.4,
+            balanced_diet: false,
+            diet_window: 6,
+            monotony_healing: 0.5,
+            youth: default_youth_metabolism(),
+            elderly: default_elderly_metabolism(),
+            ancient: default_ancient_metabolism(),
+        }
+    }
+}
+
+impl MetabolismConfig {
+    /// Multipliers for a life stage as named by `Agent::life_stage`
+    pub fn for_stage(&self, stage: &str) -> StageMetabolism {
+        match stage {
+            "youth" => self.youth,
+            "elderly" => self.elderly,
+            "ancient" => self.ancient,
+            _ => StageMetabolism::BASE,
+        }
+    }
+}
+
+fn default_hunger_rate() -> f64 { 0.1 }
+fn default_energy_drain() -> f64 { 0.05 }
+fn default_meal_healing() -> f64 { 0.05 }
+fn default_collapse_energy() -> f64 { 0.05 }
+fn default_collapse_epochs() -> usize { 2 }
+fn default_recover_energy() -> f64 { 0.4 }
+fn default_diet_window() -> usize { 6 }
+fn default_monotony_healing() -> f64 { 0.5 }
+fn default_stage_rate() -> f64 { 1.0 }
+fn default_youth_metabolism() -> StageMetabolism { StageMetabolism { hunger: 0.7, energy: 1.4, healing: 1.0 } }
+fn default_elderly_metabolism() -> StageMetabolism { StageMetabolism { hunger: 1.0, energy: 1.3, healing: 0.7 } }
+fn default_ancient_metabolism() -> StageMetabolism { StageMetabolism { hunger: 0.9, energy: 1.6, healing: 0.5 } }
+
 /// Skills system configuration
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SkillsConfig {
     /// Whether skills are enabled
     #[serde(default = "default_skills_enabled")]
@@ -190,6 +715,13 @@ pub struct SkillsConfig {
     /// Minimum skill level to teach
     #[serde(default = "default_min_level_to_teach")]
     pub min_level_to_teach: f64,
+    /// Most students one TEACH_GROUP lesson reaches
+    #[serde(default = "default_max_students")]
+    pub max_students: usize,
+    /// How much each extra student dilutes a lesson: each of n students
+    /// learns 1 / (1 + penalty * (n - 1)) of a one-on-one lesson
+    #[serde(default = "default_class_size_penalty")]
+    pub class_size_penalty: f64,
 }
 
 impl Default for SkillsConfig {
@@ -202,6 +734,8 @@ impl Default for SkillsConfig {
             decay_rate: 0.005,
             decay_threshold_epochs: 30,
             min_level_to_teach: 0.5,
+            max_students: 5,
+            class_size_penalty: 0.25,
         }
     }
 }
@@ -213,9 +747,11 @@ fn default_practice_improvement() -> f64 { 0.02 }
 fn default_decay_rate() -> f64 { 0.005 }
 fn default_decay_threshold_epochs() -> usize { 30 }
 fn default_min_level_to_teach() -> f64 { 0.5 }
+fn default_max_students() -> usize { 5 }
+fn default_class_size_penalty() -> f64 { 0.25 }
 
 /// Trade system configuration
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradeConfig {
     /// Whether trading is enabled
     #[serde(default = "default_trade_enabled")]
@@ -238,6 +774,9 @@ pub struct TradeConfig {
     /// Default deadline for promises (epochs from trade)
     #[serde(default = "default_promise_deadline")]
     pub default_promise_deadline: usize,
+    /// Number of resolved proposals kept as trade history
+    #[serde(default = "default_trade_history_size")]
+    pub history_size: usize,
 }
 
 impl Default for TradeConfig {
@@ -250,6 +789,7 @@ impl Default for TradeConfig {
             renege_trust_penalty: 0.5,
             fulfill_trust_bonus: 0.15,
             default_promise_deadline: 20,
+            history_size: 50,
         }
     }
 }
@@ -261,6 +801,392 @@ fn default_decline_trust_penalty() -> f64 { 0.05 }
 fn default_renege_trust_penalty() -> f64 { 0.5 }
 fn default_fulfill_trust_bonus() -> f64 { 0.15 }
 fn default_promise_deadline() -> usize { 20 }
+fn default_trade_history_size() -> usize { 50 }
+
+/// World event configuration (plagues, abundance, scarcity, disasters)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldEventsConfig {
+    /// Whether world events occur
+    #[serde(default = "default_world_events_enabled")]
+    pub enabled: bool,
+    /// Chance per epoch that a new world event begins
+    #[serde(default = "default_world_event_chance")]
+    pub chance: f64,
+    /// How many epochs a world event lasts
+    #[serde(default = "default_world_event_duration")]
+    pub duration: usize,
+    /// Whether agents are told about world events affecting them
+    #[serde(default = "default_broadcast_world_events")]
+    pub broadcast: bool,
+    /// Health a plague takes each epoch from those it sickens, scaled by its
+    /// severity (which is also the chance of falling sick)
+    #[serde(default = "default_plague_damage")]
+    pub plague_damage: f64,
+    /// Health a disaster takes from everyone within its radius when it strikes
+    #[serde(default = "default_disaster_damage")]
+    pub disaster_damage: f64,
+    /// Gathering yield under abundance
+    #[serde(default = "default_abundance_yield")]
+    pub abundance_yield: f64,
+    /// Gathering yield under scarcity
+    #[serde(default = "default_scarcity_yield")]
+    pub scarcity_yield: f64,
+}
+
+impl Default for WorldEventsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            chance: 0.02,
+            duration: 10,
+            broadcast: true,
+            plague_damage: 0.1,
+            disaster_damage: 0.3,
+            abundance_yield: 1.5,
+            scarcity_yield: 0.5,
+        }
+    }
+}
+
+fn default_world_events_enabled() -> bool { true }
+fn default_world_event_chance() -> f64 { 0.02 }
+fn default_world_event_duration() -> usize { 10 }
+fn default_broadcast_world_events() -> bool { true }
+fn default_plague_damage() -> f64 { 0.1 }
+fn default_disaster_damage() -> f64 { 0.3 }
+fn default_abundance_yield() -> f64 { 1.5 }
+fn default_scarcity_yield() -> f64 { 0.5 }
+
+/// Combat configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CombatConfig {
+    /// Chance that a beaten victim keeps taking full damage (1.0 = fights to the death,
+    /// 0.0 = beaten agents always flee, get robbed, or are subdued instead)
+    #[serde(default = "default_combat_lethality")]
+    pub lethality: f64,
+    /// Health below which a victim is considered beaten
+    #[serde(default = "default_surrender_threshold")]
+    pub surrender_threshold: f64,
+    /// Share of an attack's damage each defender beside the victim turns aside
+    #[serde(default = "default_ally_reduction")]
+    pub ally_reduction: f64,
+    /// Most of an attack's damage defenders can turn aside together
+    #[serde(default = "default_max_ally_reduction")]
+    pub max_ally_reduction: f64,
+    /// Share of the damage turned aside that the first defender takes instead
+    #[serde(default = "default_ally_share")]
+    pub ally_share: f64,
+    /// Trust or sentiment toward the victim at which a neighbour steps in,
+    /// alliance or group membership aside
+    #[serde(default = "default_ally_devotion")]
+    pub ally_devotion: f64,
+}
+
+impl Default for CombatConfig {
+    fn default() -> Self {
+        Self {
+            lethality: 1.0,
+            surrender_threshold: 0.3,
+            ally_reduction: 0.2,
+            max_ally_reduction: 0.5,
+            ally_share: 0.3,
+            ally_devotion: 0.6,
+        }
+    }
+}
+
+fn default_combat_lethality() -> f64 { 1.0 }
+fn default_surrender_threshold() -> f64 { 0.3 }
+fn default_ally_reduction() -> f64 { 0.2 }
+fn default_max_ally_reduction() -> f64 { 0.5 }
+fn default_ally_share() -> f64 { 0.3 }
+fn default_ally_devotion() -> f64 { 0.6 }
+
+/// Where a dead agent's food and tools end up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InheritanceMode {
+    /// Left on the cell where the agent died, for anyone to gather
+    #[default]
+    Drop,
+    /// Handed to the nearest living parent, child or mate (dropped if there is none)
+    Kin,
+}
+
+/// Inheritance configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InheritanceConfig {
+    /// What happens to belongings when their owner dies
+    #[serde(default)]
+    pub mode: InheritanceMode,
+}
+
+/// Eating from the remains of the dead, a last resort in desperate times
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScavengingConfig {
+    /// Whether agents may SCAVENGE remains at all
+    #[serde(default)]
+    pub enabled: bool,
+    /// Food a body yields
+    #[serde(default = "default_remains_food")]
+    pub remains_food: u32,
+    /// Epochs remains stay edible after death
+    #[serde(default = "default_remains_epochs")]
+    pub remains_epochs: usize,
+    /// Trust lost with each agent who sees it happen
+    #[serde(default = "default_witness_trust_penalty")]
+    pub witness_trust_penalty: f64,
+    /// Sentiment lost with each agent who sees it happen
+    #[serde(default = "default_witness_sentiment_penalty")]
+    pub witness_sentiment_penalty: f64,
+    /// Sense of belonging the scavenger loses
+    #[serde(default = "default_scavenger_shame")]
+    pub scavenger_shame: f64,
+}
+
+impl Default for ScavengingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            remains_food: 6,
+            remains_epochs: 10,
+            witness_trust_penalty: 0.3,
+            witness_sentiment_penalty: 0.5,
+            scavenger_shame: 0.2,
+        }
+    }
+}
+
+fn default_remains_food() -> u32 { 6 }
+fn default_remains_epochs() -> usize { 10 }
+fn default_witness_trust_penalty() -> f64 { 0.3 }
+fn default_witness_sentiment_penalty() -> f64 { 0.5 }
+fn default_scavenger_shame() -> f64 { 0.2 }
+
+/// What agents are told beyond their immediate surroundings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerceptionConfig {
+    /// Warn agents of harsh phases and scripted disasters ahead of time
+    #[serde(default = "default_catastrophe_warning")]
+    pub catastrophe_warning: bool,
+    /// How many epochs ahead a catastrophe can be foreseen
+    #[serde(default = "default_warning_horizon")]
+    pub warning_horizon: usize,
+    /// Phases whose hazard modifier reaches this are harsh enough to warn of
+    #[serde(default = "default_harsh_hazard_modifier")]
+    pub harsh_hazard_modifier: f64,
+}
+
+impl Default for PerceptionConfig {
+    fn default() -> Self {
+        Self {
+            catastrophe_warning: true,
+            warning_horizon: 10,
+            harsh_hazard_modifier: 1.5,
+        }
+    }
+}
+
+fn default_catastrophe_warning() -> bool { true }
+fn default_warning_horizon() -> usize { 10 }
+fn default_harsh_hazard_modifier() -> f64 { 1.5 }
+
+/// How groups are detected from mutual trust
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupsConfig {
+    /// maximal_clique (everyone trusts everyone), connected_component, or k_core
+    #[serde(default)]
+    pub algorithm: GroupAlgorithm,
+    /// For k_core: how many fellow members each member must trust
+    #[serde(default = "default_core_degree")]
+    pub core_degree: usize,
+    /// Let group leaders propose peace to or declare war on another group
+    #[serde(default = "default_diplomacy")]
+    pub diplomacy: bool,
+    /// How far a leader's word moves members' trust and sentiment across the two groups
+    #[serde(default = "default_diplomacy_shift")]
+    pub diplomacy_shift: f64,
+    /// Epochs a declared peace or war holds before feelings decide again
+    #[serde(default = "default_treaty_epochs")]
+    pub treaty_epochs: usize,
+}
+
+impl Default for GroupsConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: GroupAlgorithm::MaximalClique,
+            core_degree: 2,
+            diplomacy: true,
+            diplomacy_shift: 0.2,
+            treaty_epochs: 20,
+        }
+    }
+}
+
+fn default_core_degree() -> usize { 2 }
+fn default_diplomacy() -> bool { true }
+fn default_diplomacy_shift() -> f64 { 0.2 }
+fn default_treaty_epochs() -> usize { 20 }
+
+/// Baseline drives behind goal selection and the heuristic decider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstinctConfig {
+    /// Drives in priority order; the first one active sets the goal
+    #[serde(default = "default_drive_order")]
+    pub order: Vec<Drive>,
+    /// Hunger above which survival wants food
+    #[serde(default = "default_instinct_hunger")]
+    pub hunger_threshold: f64,
+    /// Energy below which survival wants rest
+    #[serde(default = "default_instinct_energy")]
+    pub energy_threshold: f64,
+    /// Health below which survival wants rest
+    #[serde(default = "default_instinct_health")]
+    pub health_threshold: f64,
+    /// Hunger above which survival comes first whatever the order
+    #[serde(default = "default_starvation_hunger")]
+    pub starvation_hunger: f64,
+    /// Health below which an agent attacked lately runs from its attacker
+    #[serde(default = "default_flee_health")]
+    pub flee_health: f64,
+    /// Cells from where the attacker was last seen within which it's still worth running
+    #[serde(default = "default_flee_distance")]
+    pub flee_distance: usize,
+    /// Epochs an attack keeps its victim wary of the attacker
+    #[serde(default = "default_threat_memory")]
+    pub threat_memory: usize,
+    /// Health above which reproduction looks for a mate
+    #[serde(default = "default_mating_health_threshold")]
+    pub mating_health_threshold: f64,
+    /// Extraversion above which the social drive can stir
+    #[serde(default = "default_social_extraversion")]
+    pub social_extraversion: f64,
+    /// Chance per epoch that an outgoing agent's social drive stirs
+    #[serde(default = "default_social_chance")]
+    pub social_chance: f64,
+    /// What the heuristic decider does when no drive is pressing
+    #[serde(default)]
+    pub heuristic: HeuristicConfig,
+}
+
+impl Default for InstinctConfig {
+    fn default() -> Self {
+        Self {
+            order: default_drive_order(),
+            hunger_threshold: 0.7,
+            energy_threshold: 0.2,
+            health_threshold: 0.3,
+            starvation_hunger: 0.9,
+            flee_health: 0.5,
+            flee_distance: 2,
+            threat_memory: 10,
+            mating_health_threshold: 0.5,
+            social_extraversion: 0.6,
+            social_chance: 0.3,
+            heuristic: HeuristicConfig::default(),
+        }
+    }
+}
+
+fn default_drive_order() -> Vec<Drive> {
+    vec![Drive::Survival, Drive::Social, Drive::Exploration, Drive::Reproduction]
+}
+fn default_instinct_hunger() -> f64 { 0.7 }
+fn default_instinct_energy() -> f64 { 0.2 }
+fn default_instinct_health() -> f64 { 0.3 }
+fn default_starvation_hunger() -> f64 { 0.9 }
+fn default_flee_health() -> f64 { 0.5 }
+fn default_flee_distance() -> usize { 2 }
+fn default_threat_memory() -> usize { 10 }
+fn default_mating_health_threshold() -> f64 { 0.5 }
+fn default_social_extraversion() -> f64 { 0.6 }
+fn default_social_chance() -> f64 { 0.3 }
+
+/// Relative weights of the heuristic decider's everyday choices. Temperament
+/// bends them per agent: bold agents explore more, conscientious ones gather
+/// more, extraverts seek out company.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeuristicConfig {
+    #[serde(default = "default_heuristic_explore")]
+    pub explore: f64,
+    #[serde(default = "default_heuristic_gather")]
+    pub gather: f64,
+    #[serde(default = "default_heuristic_gather")]
+    pub gather_materials: f64,
+    #[serde(default = "default_heuristic_rest")]
+    pub rest: f64,
+    /// Only counts with someone nearby
+    #[serde(default = "default_heuristic_socialize")]
+    pub socialize: f64,
+    #[serde(default = "default_heuristic_wait")]
+    pub wait: f64,
+    /// How far temperament bends the weights: each is scaled by
+    /// 1 + personality * (2 * trait - 1), so 0 treats everyone alike
+    #[serde(default = "default_heuristic_personality")]
+    pub personality: f64,
+}
+
+impl Default for HeuristicConfig {
+    fn default() -> Self {
+        Self {
+            explore: 4.0,
+            gather: 2.0,
+            gather_materials: 2.0,
+            rest: 1.0,
+            socialize: 1.0,
+            wait: 2.0,
+            personality: 0.8,
+        }
+    }
+}
+
+fn default_heuristic_explore() -> f64 { 4.0 }
+fn default_heuristic_gather() -> f64 { 2.0 }
+fn default_heuristic_rest() -> f64 { 1.0 }
+fn default_heuristic_socialize() -> f64 { 1.0 }
+fn default_heuristic_wait() -> f64 { 2.0 }
+fn default_heuristic_personality() -> f64 { 0.8 }
+
+/// Scenario scripting configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScenarioConfig {
+    /// Events applied at the start of specific epochs
+    #[serde(default)]
+    pub scripted_events: Vec<ScriptedEvent>,
+}
+
+/// A scripted action tied to an epoch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptedEvent {
+    /// Epoch at whose start the action is applied
+    pub epoch: usize,
+    #[serde(flatten)]
+    pub action: ScriptedAction,
+}
+
+/// What a scripted event does
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScriptedAction {
+    /// Introduce a newcomer (placed at the world's centre unless given a position)
+    SpawnAgent {
+        name: String,
+        #[serde(default)]
+        x: Option<usize>,
+        #[serde(default)]
+        y: Option<usize>,
+        #[serde(default)]
+        model: Option<String>,
+    },
+    /// Begin a world event (lasting `world_events.duration` unless given)
+    WorldEvent {
+        event: WorldEvent,
+        #[serde(default)]
+        duration: Option<usize>,
+    },
+    /// Jump the environment cycle to the start of a named phase
+    SetPhase { phase: String },
+}
 
 fn default_personality() -> String {
     "random".to_string()
@@ -270,15 +1196,24 @@ fn default_snapshot_interval() -> usize {
     10
 }
 
+fn default_archive_dead_after() -> usize {
+    20
+}
+
 fn default_log_thoughts() -> bool {
     true
 }
 
+fn default_action_feedback() -> bool {
+    true
+}
+
 impl Config {
     /// Load configuration from a TOML file
     pub fn from_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
         let content = fs::read_to_string(path)?;
-        let config: Config = toml::from_str(&content)?;
+        let mut config: Config = toml::from_str(&content)?;
+        config.scenario_hash = Some(content_hash(content.as_bytes()));
         Ok(config)
     }
 }
@@ -296,16 +1231,34 @@ impl Default for Config {
                 fertile_fraction: 0.3,
                 initial_food_per_fertile: 15,
                 food_regen_rate: 0.1,
+                forest_fraction: 0.3,
+                desert_fraction: 0.5,
+                mountain_fraction: 0.0,
+                water_fraction: 0.0,
+                terrain_yields: TerrainYields::default(),
             },
             agents: AgentsConfig {
                 count: 10,
                 starting_food: 10,
                 personality: "random".to_string(),
+                roster: Vec::new(),
             },
             simulation: SimulationConfig {
                 epochs: 100,
                 snapshot_interval: 10,
                 log_thoughts: true,
+                action_feedback: true,
+                chronicle_verbosity: ChronicleVerbosity::Normal,
+                chronicle_style: ChronicleStyle::ThirdPersonPast,
+                chronicle_protagonist: None,
+                snapshot_format: SnapshotFormat::JsonPretty,
+                seed: None,
+                profile: false,
+                lives_format: LivesFormat::Markdown,
+                database: None,
+                pipeline: default_pipeline(),
+                archive_dead_after: default_archive_dead_after(),
+                network_timeline: false,
             },
             llm: LlmConfig::default(),
             environment: None,
@@ -313,6 +1266,39 @@ impl Default for Config {
             aging: AgingConfig::default(),
             skills: SkillsConfig::default(),
             trade: TradeConfig::default(),
+            world_events: WorldEventsConfig::default(),
+            combat: CombatConfig::default(),
+            scenario: ScenarioConfig::default(),
+            inheritance: InheritanceConfig::default(),
+            scavenging: ScavengingConfig::default(),
+            perception: PerceptionConfig::default(),
+            groups: GroupsConfig::default(),
+            instincts: InstinctConfig::default(),
+            metabolism: MetabolismConfig::default(),
+            reputation: ReputationConfig::default(),
+            structures: StructuresConfig::default(),
+            legacy: LegacyConfig::default(),
+            migration: MigrationConfig::default(),
+            movement: MovementConfig::default(),
+            rumors: RumorConfig::default(),
+            scenario_hash: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lethal_gestation_drain_is_flagged() {
+        let metabolism = MetabolismConfig::default();
+        assert!(ReproductionConfig::default().validate(&metabolism).is_empty());
+
+        let lethal = ReproductionConfig { gestation_energy_drain: 0.4, ..ReproductionConfig::default() };
+        let problems = lethal.validate(&metabolism);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("gestation_energy_drain (0.4)"), "{}", problems[0]);
+        assert!(!lethal.balance_report(&metabolism, 5).survivable);
+    }
+}
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use crate::world::Terrain;
 
 /// Types of materials that can be gathered
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum MaterialType {
     Wood,
     Stone,
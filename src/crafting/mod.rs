@@ -6,4 +6,4 @@ pub mod tools;
 
 pub use materials::MaterialType;
 pub use recipes::{Recipe, RecipeRegistry};
-pub use tools::{Tool, ToolQuality, ToolType};
+pub use tools::{Tool, ToolEffect, ToolQuality, ToolType};
@@ -28,6 +28,17 @@ pub enum ToolQuality {
     Excellent,
 }
 
+/// What a tool does for its wielder while equipped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolEffect {
+    /// Extra damage when attacking
+    Attack,
+    /// Extra wood when gathering materials or chopping
+    WoodGathering,
+    /// Extra food when gathering
+    Foraging,
+}
+
 /// A specific tool instance with durability
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tool {
@@ -81,6 +92,21 @@ impl ToolType {
         }
     }
 
+    /// Effect and base bonus this tool grants while equipped
+    pub fn equipped_effect(&self) -> Option<(ToolEffect, f64)> {
+        match self {
+            ToolType::StoneAxe => Some((ToolEffect::WoodGathering, 0.3)),
+            ToolType::FlintAxe => Some((ToolEffect::WoodGathering, 0.5)),
+            ToolType::StoneKnife => Some((ToolEffect::Attack, 0.15)),
+            ToolType::FlintKnife => Some((ToolEffect::Attack, 0.25)),
+            ToolType::WoodenSpear => Some((ToolEffect::Attack, 0.4)),
+            ToolType::Bow => Some((ToolEffect::Attack, 0.3)),
+            ToolType::Basket => Some((ToolEffect::Foraging, 0.3)),
+            ToolType::FishingPole => Some((ToolEffect::Foraging, 0.2)),
+            ToolType::Rope => None,
+        }
+    }
+
     /// Actions unlocked by this tool
     pub fn unlocked_actions(&self) -> &'static [&'static str] {
         match self {
@@ -186,7 +212,7 @@ impl Tool {
         let base_dur = tool_type.base_durability();
         let max_durability = (base_dur as f64 * quality.durability_modifier()) as u32;
         Self {
-            id: Uuid::new_v4(),
+            id: crate::rng::uuid(),
             tool_type,
             durability: max_durability,
             max_durability,
@@ -206,9 +232,9 @@ impl Tool {
         }
     }
 
-    /// Use the tool once (alias for use_tool)
-    pub fn use_once(&mut self) {
-        self.use_tool();
+    /// Use the tool once, returning whether that use broke it
+    pub fn use_once(&mut self) -> bool {
+        self.use_tool() && self.is_broken()
     }
 
     /// Check if tool is broken
@@ -226,6 +252,16 @@ impl Tool {
         self.tool_type.skill_bonus() * self.quality.effectiveness_modifier()
     }
 
+    /// Bonus this tool grants for an effect while equipped (quality-adjusted)
+    pub fn equipped_bonus(&self, effect: ToolEffect) -> f64 {
+        match self.tool_type.equipped_effect() {
+            Some((e, bonus)) if e == effect && !self.is_broken() => {
+                bonus * self.quality.effectiveness_modifier()
+            }
+            _ => 0.0,
+        }
+    }
+
     /// Durability as percentage
     pub fn durability_percent(&self) -> f64 {
         if self.max_durability == 0 {
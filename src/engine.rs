@@ -1,25 +1,123 @@
 use anyhow::Result;
-use std::collections::HashMap;
+use futures_util::stream::{self, StreamExt};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
-use crate::action::{Action, Direction};
-use crate::agent::{generate_names, generate_offspring_name, Agent, Episode, EpisodeCategory, Identity};
-use crate::config::Config;
-use crate::crafting::{MaterialType, RecipeRegistry, Tool, ToolQuality, ToolType};
+use crate::action::{Action, ActionContext, Direction, Remark};
+use crate::agent::{generate_names, generate_offspring_name, is_adjacent, Agent, Episode, EpisodeCategory, FoodKind, Goal, Identity, LandmarkKind, Migration, Route, Threat};
+use crate::config::{Config, InheritanceMode, RosterEntry, ScriptedAction};
+use crate::crafting::{MaterialType, RecipeRegistry, Tool, ToolEffect, ToolQuality, ToolType};
 use crate::environment::{EnvironmentConfig, EnvironmentState};
-use crate::groups::{GroupTracker, Group};
-use crate::llm::LlmClient;
-use crate::observation::{Chronicle, Event};
-use crate::observer::{AgentView, EventView, ServiceDebtView, TradeProposalView, TradeStateView, WorldView};
+use crate::groups::{GroupTracker, Group, RivalryType};
+use crate::llm::{situation_salience, LlmClient, PendingTrade, Promise, Situation};
+use crate::observation::{Chronicle, Event, NetworkRecord, Provenance, Snapshot};
+use crate::observer::{AgentView, CellView, EventView, ServiceDebtView, TradeProposalView, TradeStateView, WorldView};
+use crate::pipeline::Step;
+use crate::profiler::Profiler;
+use crate::spatial::SpatialIndex;
 use crate::trade::{ProposalStatus, ServiceDebt, ServiceType, TradeableItem, TradeProposal, TradeState};
-use crate::world::{Terrain, World};
+use crate::world::{Cell, Remains, Terrain, World, WorldEvent};
+
+/// Extra energy recovered when resting at night
+const NIGHT_REST_BONUS: f64 = 0.1;
+
+/// Epochs of health history kept per agent for trend reporting
+const VITALS_WINDOW: usize = 10;
+
+/// How far away remembered landmarks are mentioned in perception
+const LANDMARK_RANGE: usize = 5;
+
+/// Energy an attacker spends breaking into a shelter, scaled by the shelter's defense
+const SHELTER_ASSAULT_ENERGY: f64 = 0.2;
+
+/// Extra damage dealt to someone collapsed from exhaustion, who can't fight back
+const COLLAPSED_VULNERABILITY: f64 = 1.5;
+
+/// Recent vital signs for an agent, used to tell them how things are trending
+#[derive(Debug, Clone)]
+struct VitalSigns {
+    /// Health at the end of each recent epoch, oldest first
+    health: VecDeque<f64>,
+    /// Epoch the agent last ate
+    last_meal: Option<usize>,
+    /// Epoch tracking began
+    since: usize,
+}
+
+impl VitalSigns {
+    fn new(epoch: usize) -> Self {
+        Self {
+            health: VecDeque::with_capacity(VITALS_WINDOW),
+            last_meal: None,
+            since: epoch,
+        }
+    }
+
+    fn record_health(&mut self, health: f64) {
+        if self.health.len() == VITALS_WINDOW {
+            self.health.pop_front();
+        }
+        self.health.push_back(health);
+    }
+
+    /// Consecutive epochs health has moved in one direction: negative when falling
+    fn health_streak(&self) -> i32 {
+        let changes: Vec<f64> = self
+            .health
+            .iter()
+            .zip(self.health.iter().skip(1))
+            .map(|(before, after)| after - before)
+            .collect();
+
+        let mut streak = 0;
+        for change in changes.iter().rev() {
+            if *change < -f64::EPSILON && streak <= 0 {
+                streak -= 1;
+            } else if *change > f64::EPSILON && streak >= 0 {
+                streak += 1;
+            } else {
+                break;
+            }
+        }
+        streak
+    }
+
+    /// Short trend lines for perception
+    fn describe(&self, epoch: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        match self.last_meal {
+            Some(ate) if epoch > ate => {
+                let days = epoch - ate;
+                lines.push(format!("You last ate {} day{} ago.", days, if days == 1 { "" } else { "s" }));
+            }
+            None if epoch > self.since => {
+                lines.push(format!("You have not eaten in {} days.", epoch - self.since));
+            }
+            _ => {}
+        }
+
+        let streak = self.health_streak();
+        if streak <= -2 {
+            lines.push(format!("Your health has been falling for {} days.", -streak));
+        } else if streak >= 2 {
+            lines.push(format!("Your health has been improving for {} days.", streak));
+        }
+
+        lines
+    }
+}
 
 /// The simulation engine
 pub struct Engine {
     config: Config,
     world: World,
     agents: Vec<Agent>,
+    /// Where each agent in `agents` is, kept in step whenever the list changes
+    agent_indices: HashMap<Uuid, usize>,
+    /// Who stands in which cell, rebuilt each epoch and kept up as agents move
+    spatial: SpatialIndex,
     llm: LlmClient,
     chronicle: Chronicle,
     /// Recent events for observer clients (last N epochs)
@@ -36,32 +134,139 @@ pub struct Engine {
     recipe_registry: RecipeRegistry,
     /// Trade system state
     trade_state: TradeState,
+    /// Outcome of each agent's last action, reported in their next perception
+    action_results: HashMap<Uuid, String>,
+    /// Consecutive epochs the population has been unable to reproduce
+    nonviable_epochs: usize,
+    /// Rolling vital signs per agent, for trend reporting
+    vitals: HashMap<Uuid, VitalSigns>,
+    /// Epoch each cell last changed, for incremental observers
+    cell_changes: HashMap<(usize, usize), usize>,
+    /// Epoch each agent last changed, for incremental observers
+    agent_changes: HashMap<Uuid, usize>,
+    /// Per-phase wall-clock timings (only recorded when profiling is on)
+    profiler: Profiler,
+    /// Callbacks handed every event as it is logged
+    subscribers: Vec<Subscriber>,
+    /// Agents long dead, moved out of `agents` but kept for lineage and the record
+    deceased: Vec<Agent>,
+    /// Output directory to append the social network to at each snapshot, if enabled
+    network_timeline: Option<std::path::PathBuf>,
+    /// Where logged events go out to live stream clients, once streaming
+    event_stream: Option<tokio::sync::broadcast::Sender<EventView>>,
+}
+
+/// Where each agent is in the list
+fn index_agents(agents: &[Agent]) -> HashMap<Uuid, usize> {
+    agents.iter().enumerate().map(|(i, agent)| (agent.id, i)).collect()
+}
+
+/// Callback registered with `Engine::subscribe`
+type Subscriber = Box<dyn Fn(&Event) + Send>;
+
+/// Cheap summary of a cell's observable state, to spot changes between epochs
+type CellFingerprint = (u32, Option<(Uuid, u32, u32, usize)>, Option<(Uuid, u64, usize)>);
+
+/// Cheap summary of an agent's observable state, to spot changes between
+/// epochs. Age is left out: it advances with the epoch for everyone
+type AgentFingerprint = ((usize, usize), u32, [u64; 3], bool, u32, usize);
+
+fn agent_fingerprint(agent: &Agent) -> AgentFingerprint {
+    let physical = &agent.physical;
+    (
+        (physical.x, physical.y),
+        physical.food,
+        [physical.health.to_bits(), physical.hunger.to_bits(), physical.energy.to_bits()],
+        agent.is_alive(),
+        physical.materials.values().sum(),
+        physical.tools.len(),
+    )
+}
+
+fn cell_fingerprint(cell: &Cell) -> CellFingerprint {
+    (
+        cell.food,
+        cell.structure
+            .as_ref()
+            .map(|s| (s.id, s.build_progress, s.durability, s.allowed_guests.len())),
+        cell.territory
+            .as_ref()
+            .map(|t| (t.owner, t.strength.to_bits(), t.allowed_guests.len())),
+    )
 }
 
 impl Engine {
     /// Create a new simulation engine
-    pub fn new(config: Config, output_dir: &str) -> Result<Self> {
-        // Create world
-        let world = World::new(&config.world);
+    pub fn new(mut config: Config, output_dir: &str) -> Result<Self> {
+        // Pick a seed if none was given, so every run can be repeated
+        let seed = *config.simulation.seed.get_or_insert_with(rand::random);
+        crate::rng::seed(seed);
 
-        // Create agents
-        let names = generate_names(config.agents.count);
-        let mut agents = Vec::with_capacity(config.agents.count);
+        // Create world
+        let mut world = World::new(&config.world);
+
+        // Create agents: roster entries first, then generated names for the rest
+        let roster = &config.agents.roster;
+        let count = config.agents.count.max(roster.len());
+        let generated: Vec<String> = generate_names(count + roster.len())
+            .into_iter()
+            .filter(|n| !roster.iter().any(|r| r.name.eq_ignore_ascii_case(n)))
+            .collect();
+        let names = roster.iter().map(|r| r.name.clone()).chain(generated).take(count);
+        let mut agents = Vec::with_capacity(count);
 
-        for (i, name) in names.into_iter().enumerate() {
+        for (i, name) in names.enumerate() {
             // Scatter agents across the world
             let x = (i * 3) % config.world.width;
             let y = (i * 3) / config.world.width % config.world.height;
-            agents.push(Agent::new(name, x, y, config.agents.starting_food));
+            let mut agent = Agent::new(name, x, y, config.agents.starting_food);
+            if let Some(entry) = roster.get(i) {
+                agent.identity.model = entry.model.clone();
+            }
+            agents.push(agent);
+        }
+        seed_roster_relationships(roster, &mut agents)?;
+
+        crate::pipeline::validate(&config.simulation.pipeline)?;
+
+        let reproduction = &config.reproduction;
+        for problem in reproduction.validate(&config.metabolism) {
+            warn!("Reproduction config: {}", problem);
+        }
+        if reproduction.enabled {
+            let food_after_mating = config.agents.starting_food.saturating_sub(reproduction.mating_food_cost);
+            debug!("Reproduction balance: {}", reproduction.balance_report(&config.metabolism, food_after_mating).describe());
         }
 
         // Create LLM client
-        let llm = LlmClient::new(config.llm.clone());
+        let llm = LlmClient::new(config.llm.clone())?;
+        if let Some(cap) = config.llm.max_llm_agents.filter(|cap| llm.is_available() && config.agents.count > *cap) {
+            warn!(
+                "{} agents but llm.max_llm_agents is {}; each epoch the rest are decided by the heuristic",
+                config.agents.count, cap
+            );
+        }
 
         // Create chronicle
         let mut chronicle = Chronicle::new(output_dir)?;
+        chronicle.set_verbosity(config.simulation.chronicle_verbosity);
+        chronicle.set_style(config.simulation.chronicle_style, config.simulation.chronicle_protagonist.clone());
+        chronicle.set_snapshot_format(config.simulation.snapshot_format);
+        chronicle.set_lives_format(config.simulation.lives_format);
+        chronicle.set_rumors(config.rumors.clone());
+        if let Some(path) = &config.simulation.database {
+            #[cfg(feature = "sqlite")]
+            chronicle.add_sink(Box::new(crate::observation::sqlite::SqliteSink::open(std::path::Path::new(path))?));
+            #[cfg(not(feature = "sqlite"))]
+            anyhow::bail!("cannot write events to {}: terrarium was built without the `sqlite` feature", path);
+        }
         chronicle.register_agents(&agents);
 
+        let provenance = Provenance::of(&config)?;
+        provenance.write(std::path::Path::new(output_dir))?;
+        chronicle.set_provenance(&provenance.config_hash);
+        info!("Seed {}, configuration {}", seed, provenance.config_hash);
+
         // Get environment config (use from config or default to earth temperate)
         let environment = config
             .environment
@@ -69,20 +274,37 @@ impl Engine {
             .unwrap_or_else(EnvironmentConfig::default);
 
         info!("Environment: {} (cycle: {} epochs)", environment.name, environment.cycle_length);
+        world.phase = environment.state_at(world.epoch).current_phase;
+
+        let profiler = Profiler::new(config.simulation.profile);
+        let group_tracker = GroupTracker::new(config.groups.clone());
+        let network_timeline = config.simulation.network_timeline.then(|| std::path::PathBuf::from(output_dir));
 
         Ok(Self {
             config,
             world,
+            agent_indices: index_agents(&agents),
+            spatial: SpatialIndex::build(&agents),
             agents,
             llm,
             chronicle,
             recent_events: Vec::new(),
             max_event_epochs: 10,
-            group_tracker: GroupTracker::new(),
+            group_tracker,
             environment,
             pending_births: Vec::new(),
             recipe_registry: RecipeRegistry::new(),
             trade_state: TradeState::new(),
+            action_results: HashMap::new(),
+            nonviable_epochs: 0,
+            vitals: HashMap::new(),
+            cell_changes: HashMap::new(),
+            agent_changes: HashMap::new(),
+            profiler,
+            subscribers: Vec::new(),
+            deceased: Vec::new(),
+            network_timeline,
+            event_stream: None,
         })
     }
 
@@ -97,16 +319,88 @@ impl Engine {
     pub fn agent_views(&self) -> Vec<AgentView> {
         self.agents
             .iter()
-            .map(|a| AgentView::from_agent(a, &self.agents, &self.config.aging))
+            .map(|a| AgentView::from_agent(a, &self.agents, &self.config.aging, &self.trade_state))
             .collect()
     }
 
-    /// Get view of a specific agent by ID
+    /// Get view of a specific agent by ID, the archived dead included
     pub fn agent_view(&self, id: Uuid) -> Option<AgentView> {
+        self.find_agent(id)
+            .map(|a| AgentView::from_agent(a, &self.agents, &self.config.aging, &self.trade_state))
+    }
+
+    /// An agent by ID, living, dead or archived
+    pub fn find_agent(&self, id: Uuid) -> Option<&Agent> {
+        self.agents.iter().chain(&self.deceased).find(|a| a.id == id)
+    }
+
+    /// Every known ancestor of an agent, parents first, the archived dead included
+    #[cfg(test)]
+    fn ancestors(&self, id: Uuid) -> Vec<&Agent> {
+        let mut ancestors: Vec<&Agent> = Vec::new();
+        let mut queue: VecDeque<Uuid> = self
+            .find_agent(id)
+            .map(|a| a.reproduction.family.parents.iter().copied().collect())
+            .unwrap_or_default();
+        while let Some(parent_id) = queue.pop_front() {
+            if ancestors.iter().any(|a| a.id == parent_id) {
+                continue;
+            }
+            if let Some(parent) = self.find_agent(parent_id) {
+                queue.extend(parent.reproduction.family.parents.iter().copied());
+                ancestors.push(parent);
+            }
+        }
+        ancestors
+    }
+
+    /// Save a state snapshot, and the social network alongside it if enabled
+    fn save_snapshot(&mut self, epoch: usize) -> Result<()> {
+        let snapshot = Snapshot {
+            epoch,
+            world: self.world.clone(),
+            agents: self.roster(),
+            groups: self.group_tracker.clone(),
+            trade: self.trade_state.clone(),
+        };
+        self.chronicle.save_snapshot(&snapshot)?;
+        if let Some(output_dir) = &self.network_timeline {
+            let tracker = &self.group_tracker;
+            NetworkRecord::capture(epoch, &self.agents, tracker.current_groups(), tracker.current_rivalries())
+                .append(output_dir)?;
+        }
+        Ok(())
+    }
+
+    /// Every agent, the archived dead included, for snapshots, the footer
+    /// and batch summaries
+    pub fn roster(&self) -> Vec<Agent> {
+        self.agents.iter().chain(&self.deceased).cloned().collect()
+    }
+
+    /// Views of cells that changed at or after `epoch`
+    pub fn changed_cells_since(&self, epoch: usize) -> Vec<CellView> {
+        let mut positions: Vec<(usize, usize)> = self
+            .cell_changes
+            .iter()
+            .filter(|(_, changed)| **changed >= epoch)
+            .map(|(pos, _)| *pos)
+            .collect();
+        positions.sort_by_key(|&(x, y)| (y, x));
+        positions
+            .into_iter()
+            .filter_map(|(x, y)| self.world.get(x, y))
+            .map(|cell| CellView::from_cell(cell, &self.agents))
+            .collect()
+    }
+
+    /// Views of agents that changed at or after `epoch`
+    pub fn agent_deltas_since(&self, epoch: usize) -> Vec<AgentView> {
         self.agents
             .iter()
-            .find(|a| a.id == id)
-            .map(|a| AgentView::from_agent(a, &self.agents, &self.config.aging))
+            .filter(|a| self.agent_changes.get(&a.id).is_some_and(|changed| *changed >= epoch))
+            .map(|a| AgentView::from_agent(a, &self.agents, &self.config.aging, &self.trade_state))
+            .collect()
     }
 
     /// Get recent events as views
@@ -192,6 +486,13 @@ impl Engine {
     pub fn is_complete(&self) -> bool {
         self.world.epoch >= self.config.simulation.epochs
             || self.agents.iter().all(|a| !a.is_alive())
+            || self.population_collapsed()
+    }
+
+    /// Whether the population has been non-viable for longer than the grace period
+    fn population_collapsed(&self) -> bool {
+        let config = &self.config.reproduction;
+        config.enabled && config.stop_when_nonviable && self.nonviable_epochs >= config.nonviable_grace_epochs
     }
 
     /// Get count of living agents
@@ -227,7 +528,7 @@ impl Engine {
 
         // Periodic snapshot
         if epoch % self.config.simulation.snapshot_interval == 0 && epoch > 0 {
-            self.chronicle.save_snapshot(epoch, &self.world, &self.agents)?;
+            self.save_snapshot(epoch)?;
         }
 
         // Prune old events
@@ -236,6 +537,28 @@ impl Engine {
         Ok(())
     }
 
+    /// Continue from a saved snapshot's world and agents
+    ///
+    /// Trades, groups and other bookkeeping start fresh; the long dead go back
+    /// to the archive at the end of the first epoch.
+    pub fn restore(&mut self, snapshot: Snapshot) {
+        self.world = snapshot.world;
+        self.agents = snapshot.agents;
+        self.agent_indices = index_agents(&self.agents);
+        self.reindex_positions();
+        self.deceased.clear();
+        self.group_tracker.resume(snapshot.groups);
+        self.trade_state = snapshot.trade;
+        self.chronicle.register_agents(&self.agents);
+    }
+
+    /// An engine for `config` picking up where the snapshot at `path` left off
+    pub fn from_snapshot(path: &std::path::Path, config: Config, output_dir: &str) -> Result<Self> {
+        let mut engine = Self::new(config, output_dir)?;
+        engine.restore(Snapshot::load(path)?);
+        Ok(engine)
+    }
+
     /// Initialize the simulation (write header, etc.)
     pub fn initialize(&mut self) -> Result<()> {
         self.chronicle.write_header(
@@ -243,24 +566,49 @@ impl Engine {
             &self.world,
             &self.agents,
         )?;
-        self.chronicle.save_snapshot(0, &self.world, &self.agents)?;
+        self.save_snapshot(0)?;
         Ok(())
     }
 
     /// Finalize the simulation (write footer, final snapshot)
     pub fn finalize(&mut self) -> Result<()> {
-        self.chronicle.save_snapshot(self.world.epoch, &self.world, &self.agents)?;
-        self.chronicle.write_footer(&self.world, &self.agents)?;
+        self.save_snapshot(self.world.epoch)?;
+        self.chronicle.write_footer(&self.world, &self.roster())?;
         Ok(())
     }
 
     /// Log and track an event
     fn log_and_track(&mut self, event: Event) -> Result<()> {
+        for subscriber in &self.subscribers {
+            subscriber(&event);
+        }
+        if let Some(stream) = &self.event_stream {
+            let view = EventView::from_event(&event, &self.agents);
+            // No one listening is fine
+            if let Some(view) = view {
+                let _ = stream.send(view);
+            }
+        }
         self.recent_events.push(event.clone());
         self.chronicle.log_event(&event)?;
         Ok(())
     }
 
+    /// Have a callback see every event as it is logged, for embedders that
+    /// want to react to particular events rather than diff whole epochs
+    #[allow(dead_code)]
+    pub fn subscribe(&mut self, callback: impl Fn(&Event) + Send + 'static) {
+        self.subscribers.push(Box::new(callback));
+    }
+
+    /// Broadcast every event logged from now on as an `EventView`, for the
+    /// live stream (see `stream::serve`)
+    pub fn stream_events(&mut self) -> tokio::sync::broadcast::Sender<EventView> {
+        self.event_stream
+            .get_or_insert_with(|| tokio::sync::broadcast::channel(crate::stream::BUFFER).0)
+            .clone()
+    }
+
     /// Prune events older than max_event_epochs
     fn prune_old_events(&mut self) {
         let cutoff = self.world.epoch.saturating_sub(self.max_event_epochs);
@@ -282,16 +630,16 @@ impl Engine {
             &self.agents,
         )?;
 
-        // Initial snapshot
-        self.chronicle.save_snapshot(0, &self.world, &self.agents)?;
+        // Initial snapshot (where a resumed run picks up)
+        self.save_snapshot(self.world.epoch)?;
 
         // Main loop
-        for epoch in 0..self.config.simulation.epochs {
+        for epoch in self.world.epoch..self.config.simulation.epochs {
             self.run_epoch(epoch).await?;
 
             // Periodic snapshot
             if epoch % self.config.simulation.snapshot_interval == 0 && epoch > 0 {
-                self.chronicle.save_snapshot(epoch, &self.world, &self.agents)?;
+                self.save_snapshot(epoch)?;
             }
 
             // Check if everyone is dead
@@ -299,46 +647,153 @@ impl Engine {
                 info!("All agents have perished at epoch {}", epoch);
                 break;
             }
+
+            if self.population_collapsed() {
+                info!("Population can no longer reproduce; ending at epoch {}", epoch);
+                break;
+            }
         }
 
         // Final snapshot and footer
-        self.chronicle.save_snapshot(self.world.epoch, &self.world, &self.agents)?;
-        self.chronicle.write_footer(&self.world, &self.agents)?;
+        self.save_snapshot(self.world.epoch)?;
+        self.chronicle.write_footer(&self.world, &self.roster())?;
 
         info!("Simulation complete after {} epochs", self.world.epoch);
+        self.profiler.log_summary();
+        self.llm.log_cache_summary();
         Ok(())
     }
 
     /// Run a single epoch
     async fn run_epoch(&mut self, epoch: usize) -> Result<()> {
         debug!("Epoch {} starting", epoch);
+        self.reindex_positions();
 
-        // Get current environment state
-        let env_state = self.environment.state_at(epoch);
+        let cells_before: Vec<CellFingerprint> = self.world.cells.iter().map(cell_fingerprint).collect();
+        let agents_before: HashMap<Uuid, AgentFingerprint> =
+            self.agents.iter().map(|a| (a.id, agent_fingerprint(a))).collect();
 
-        // Log epoch start
-        self.log_and_track(Event::epoch_start(epoch))?;
+        // 0. Open the epoch, then apply its scripted scenario events, so they
+        // read as part of this day rather than the last
+        self.profiler.start();
+        let opening_phase = self.environment.state_at(epoch).current_phase;
+        self.log_and_track(Event::epoch_start(epoch, &opening_phase))?;
+        self.apply_scripted_events(epoch)?;
+
+        // Get current environment state, after any scripted phase change
+        let env_state = self.environment.state_at(epoch);
 
         // 1. World tick (regenerate resources with environmental modifier)
-        self.world.tick(self.config.world.food_regen_rate, env_state.food_regen_modifier);
+        self.world.tick(&self.config.world, env_state.food_regen_modifier, &env_state.current_phase);
+
+        // 1a. World events (plague, abundance, scarcity, disasters)
+        if self.config.world_events.enabled {
+            let events_config = &self.config.world_events;
+            if let Some(event) = self.world.maybe_generate_event(events_config.chance, events_config.duration) {
+                info!("World event began: {}", event.describe());
+            }
+        }
+        self.apply_world_events(epoch)?;
 
         // 1b. Structure production (farms produce food)
         self.process_structure_production(epoch)?;
+        self.profiler.stop("world");
+
+        // 2. The configured steps, by default: needs, deliberation, action
+        // resolution, mating, gestation, aging, belief upkeep, groups
+        let mut actions: BTreeMap<Uuid, Action> = BTreeMap::new();
+        let mut remarks: BTreeMap<Uuid, Remark> = BTreeMap::new();
+        for step in self.config.simulation.pipeline.clone() {
+            self.profiler.start();
+            match step {
+                Step::Needs => {
+                    self.tick_needs(epoch, &env_state)?;
+                    self.detect_migrations(epoch)?;
+                }
+                Step::Deliberate => {
+                    (actions, remarks) = self.deliberate(epoch, &env_state).await?;
+                }
+                Step::Resolve => {
+                    // Actions resolve simultaneously
+                    self.resolve_actions(epoch, actions.clone())?;
+                    self.resolve_remarks(epoch, std::mem::take(&mut remarks))?;
+
+                    // Trade maintenance (expiry, deadline checking)
+                    self.expire_trade_proposals(epoch)?;
+                    self.check_service_deadlines(epoch)?;
+                }
+                // Requires mutual consent
+                Step::Mate => self.resolve_mating(epoch, &actions)?,
+                Step::Gestate => {
+                    self.tick_gestations(epoch)?;
+                    self.tick_courtship_decay();
+                    self.process_births();
+                }
+                Step::Age => self.tick_aging(epoch)?,
+                Step::Beliefs => {
+                    // Update beliefs based on what happened
+                    self.update_beliefs(epoch);
+
+                    // Territories (decay, group sharing), structure decay, remains
+                    self.update_territories(epoch)?;
+                    self.decay_structures(epoch)?;
+                    self.decay_remains(epoch);
+                }
+                Step::Groups => {
+                    self.detect_groups(epoch)?;
+                    self.update_legacies();
+                }
+            }
+            self.profiler.stop(step.phase());
+        }
 
-        // 2. Update agent needs (with environmental effects)
-        let mut death_events = Vec::new();
-        for agent in &mut self.agents {
+        // 3. Track whether the population can still sustain itself
+        self.profiler.start();
+        self.track_viability(epoch);
+
+        // 4. Record vital signs for trend reporting
+        self.record_vitals(epoch);
+
+        // 5. Note what changed for incremental observers
+        self.track_changes(epoch, &cells_before, &agents_before);
+
+        // 6. Move the long dead out of the active list
+        self.archive_dead(epoch);
+
+        // Log epoch end
+        self.log_and_track(Event::epoch_end(epoch))?;
+        self.chronicle.observe_agents(&self.agents);
+        self.chronicle.flush()?;
+        self.profiler.stop("chronicle");
+        self.profiler.finish_epoch(epoch);
+
+        // Progress update
+        if epoch % 10 == 0 {
+            let alive = self.agents.iter().filter(|a| a.is_alive()).count();
+            info!("Epoch {}: {} agents alive", epoch, alive);
+        }
+
+        Ok(())
+    }
+
+    /// Hunger, energy drain and environmental hazards for every living agent,
+    /// then deaths from them; agents who come face to face meet
+    fn tick_needs(&mut self, epoch: usize, env_state: &EnvironmentState) -> Result<()> {
+        let mut deaths = Vec::new();
+        for (agent_idx, agent) in self.agents.iter_mut().enumerate() {
             if agent.is_alive() {
-                agent.tick_hunger();
-                agent.tick_energy();
+                let metabolism = &self.config.metabolism;
+                let rates = agent.metabolism(&self.config.aging, metabolism);
+                agent.tick_hunger(metabolism.hunger_rate * rates.hunger);
+                agent.tick_energy(metabolism.energy_drain * rates.energy);
 
                 // Apply environmental hazard effects (reduced by shelter)
                 if env_state.hazard_level > 0.0 {
-                    // Calculate shelter protection
+                    // Calculate shelter protection against this kind of hazard
                     let shelter_protection = if let Some((sx, sy)) = agent.physical.sheltered_at {
                         self.world.get(sx, sy)
                             .and_then(|c| c.structure.as_ref())
-                            .map(|s| s.effective_protection())
+                            .map(|s| s.effective_protection() * env_state.hazard_type.mitigated_by(s.structure_type))
                             .unwrap_or(0.0)
                     } else {
                         0.0
@@ -357,7 +812,16 @@ impl Engine {
                     }
                 }
 
-                agent.update_goal();
+                match agent.tick_exhaustion(&self.config.metabolism) {
+                    Some(true) => {
+                        info!("{} collapsed from exhaustion", agent.name());
+                        agent.memory.remember(Episode::survival(epoch, "I collapsed from exhaustion", 0.6));
+                    }
+                    Some(false) => debug!("{} is back on their feet", agent.name()),
+                    None => {}
+                }
+
+                agent.update_goal(&self.config.instincts);
 
                 // Check for death (starvation or environmental)
                 if !agent.is_alive() {
@@ -368,39 +832,121 @@ impl Engine {
                     } else {
                         "exhaustion"
                     };
-                    death_events.push(Event::died(epoch, agent.id, cause));
+                    deaths.push((agent_idx, cause));
                 }
             }
         }
-        for event in death_events {
-            self.log_and_track(event)?;
+        for (agent_idx, cause) in deaths {
+            self.handle_death(epoch, agent_idx, cause)?;
         }
+        self.meet_strangers(epoch);
+        Ok(())
+    }
+
+    /// Bring the active world events to bear: a plague sickens each agent with a
+    /// chance of its severity every epoch, a disaster hurts everyone near it
+    /// when it strikes; abundance and scarcity act on gathering yields
+    fn apply_world_events(&mut self, epoch: usize) -> Result<()> {
+        let config = &self.config.world_events;
+        let mut struck = Vec::new();
+        let mut deaths = Vec::new();
+        for active in &mut self.world.active_events {
+            let first = !active.struck;
+            active.struck = true;
+            let mut affected = Vec::new();
+            for (agent_idx, agent) in self.agents.iter_mut().enumerate() {
+                if !agent.is_alive() || !active.event.affects(agent.physical.x, agent.physical.y) {
+                    continue;
+                }
+                let (damage, cause) = match active.event {
+                    WorldEvent::Plague { severity } => {
+                        if crate::rng::random::<f64>(crate::rng::Stream::World) >= severity {
+                            continue;
+                        }
+                        (config.plague_damage * severity, "plague")
+                    }
+                    WorldEvent::Disaster { .. } if first => (config.disaster_damage, "disaster"),
+                    _ => (0.0, ""),
+                };
+                if first {
+                    affected.push(agent.id);
+                }
+                if damage > 0.0 {
+                    agent.physical.health = (agent.physical.health - damage).max(0.0);
+                    if !agent.is_alive() {
+                        deaths.push((agent_idx, cause));
+                    }
+                }
+            }
+            if first {
+                struck.push((active.event.headline(), affected));
+            }
+        }
+
+        for (headline, affected) in struck {
+            info!("World event struck: {} ({} affected)", headline, affected.len());
+            self.log_and_track(Event::world_event_struck(epoch, &headline, affected))?;
+        }
+        for (agent_idx, cause) in deaths {
+            self.handle_death(epoch, agent_idx, cause)?;
+        }
+        Ok(())
+    }
+
+    /// How active abundance and scarcity change what gathering yields at a position
+    fn world_event_yield(&self, x: usize, y: usize) -> f64 {
+        let config = &self.config.world_events;
+        self.world
+            .active_events
+            .iter()
+            .filter(|e| e.event.affects(x, y))
+            .map(|e| match e.event {
+                WorldEvent::Abundance { .. } => config.abundance_yield,
+                WorldEvent::Scarcity { .. } => config.scarcity_yield,
+                _ => 1.0,
+            })
+            .product()
+    }
 
-        // 3. Perception and deliberation (collect actions)
-        let mut actions: HashMap<Uuid, Action> = HashMap::new();
+    /// Ask every living agent for their action (and any remark in passing)
+    async fn deliberate(
+        &self,
+        epoch: usize,
+        env_state: &EnvironmentState,
+    ) -> Result<(BTreeMap<Uuid, Action>, BTreeMap<Uuid, Remark>)> {
+        // Keyed by agent id so resolution order is stable no matter how decisions arrive
+        let mut actions: BTreeMap<Uuid, Action> = BTreeMap::new();
+        let mut remarks: BTreeMap<Uuid, Remark> = BTreeMap::new();
 
         // Build environment perception
         let env_perception = self.environment.describe(epoch);
+        let llm_deciders = self.llm_deciders(env_state.hazard_level);
 
-        for agent in &self.agents {
+        let mut situations = Vec::new();
+        for (agent_idx, agent) in self.agents.iter().enumerate() {
             if !agent.is_alive() {
                 continue;
             }
 
-            // Get perception (world + environment)
-            let world_perception = self.world.perception_summary(agent.physical.x, agent.physical.y);
-            let perception = format!("{}\n{}", env_perception, world_perception);
+            // The collapsed have no choice to make
+            if agent.physical.incapacitated {
+                let action = if agent.physical.hunger >= 0.5 && agent.physical.food > 0 { Action::Eat } else { Action::Rest };
+                debug!("Agent {} has collapsed and will {:?}", agent.name(), action);
+                actions.insert(agent.id, action);
+                continue;
+            }
+
+            let perception = self.build_perception(agent, &env_perception, epoch);
 
             // Get nearby agents
             let nearby: Vec<(Uuid, &str)> = self
-                .agents
-                .iter()
-                .filter(|a| a.is_alive() && a.id != agent.id && is_adjacent(agent, a))
-                .map(|a| (a.id, a.name()))
+                .nearby_indices(agent_idx)
+                .into_iter()
+                .map(|i| (self.agents[i].id, self.agents[i].name()))
                 .collect();
 
             // Get pending trade proposals for this agent (offers from others)
-            let pending_trades: Vec<(usize, Uuid, &str, String, String, Option<usize>)> = self
+            let pending_trades: Vec<PendingTrade> = self
                 .trade_state
                 .pending_proposals_for(agent.id)
                 .into_iter()
@@ -422,7 +968,7 @@ impl Engine {
                 .collect();
 
             // Get unfulfilled service debts this agent owes (their obligations)
-            let debts_owed: Vec<(Uuid, &str, String, Option<usize>)> = self
+            let debts_owed: Vec<Promise> = self
                 .trade_state
                 .service_debts
                 .iter()
@@ -436,7 +982,7 @@ impl Engine {
                 .collect();
 
             // Get unfulfilled service debts owed TO this agent (credits)
-            let credits_owed: Vec<(Uuid, &str, String, Option<usize>)> = self
+            let credits_owed: Vec<Promise> = self
                 .trade_state
                 .service_debts
                 .iter()
@@ -457,70 +1003,327 @@ impl Engine {
                 .filter(|p| p.proposer == agent.id && p.status == ProposalStatus::Pending)
                 .count();
 
-            // Get action from LLM
-            let action = self
-                .llm
-                .decide_action(
-                    agent,
-                    &perception,
-                    &nearby,
-                    epoch,
-                    &pending_trades,
-                    &debts_owed,
-                    &credits_owed,
-                    my_proposals,
-                )
-                .await?;
+            situations.push(Situation {
+                agent,
+                perception,
+                nearby,
+                pending_trades,
+                debts_owed,
+                credits_owed,
+                my_proposals,
+                epoch,
+                hazard_level: env_state.hazard_level,
+                ask_llm: llm_deciders.as_ref().is_none_or(|ids| ids.contains(&agent.id)),
+                rng_seed: crate::rng::random(crate::rng::Stream::Decisions),
+            });
+        }
+
+        // Decide concurrently, up to `llm.concurrency` calls in flight; results
+        // come back in agent order, so everything after is as deterministic as before
+        let cache_hits = self.llm.cache_hits();
+        let instincts = &self.config.instincts;
+        let decisions: Vec<_> = stream::iter(situations.iter().map(|s| self.llm.decide_action(s, instincts)))
+            .buffered(self.config.llm.concurrency.max(1))
+            .collect()
+            .await;
 
+        for (situation, decision) in situations.iter().zip(decisions) {
+            let (action, remark) = decision?;
+            let agent = situation.agent;
             debug!("Agent {} chooses: {:?}", agent.name(), action);
             actions.insert(agent.id, action);
+            if let Some(remark) = remark {
+                remarks.insert(agent.id, remark);
+            }
         }
 
-        // 4. Resolve actions (simultaneous)
-        self.resolve_actions(epoch, actions.clone())?;
+        let skipped = self.llm.take_skipped_calls();
+        if skipped > 0 {
+            info!("Epoch {}: {} isolated agents decided without an LLM call", epoch, skipped);
+        }
+        let cached = self.llm.cache_hits() - cache_hits;
+        if cached > 0 {
+            info!("Epoch {}: {} prompts answered from the response cache", epoch, cached);
+        }
 
-        // 4b. Trade maintenance (expiry, deadline checking)
-        self.expire_trade_proposals(epoch)?;
-        self.check_service_deadlines(epoch)?;
+        Ok((actions, remarks))
+    }
 
-        // 5. Resolve mating (requires mutual consent check)
-        self.resolve_mating(epoch, &actions)?;
+    /// Agents to ask the LLM this epoch when `llm.max_llm_agents` caps them:
+    /// those in the most salient situations. None when everyone may be asked.
+    fn llm_deciders(&self, hazard_level: f64) -> Option<std::collections::HashSet<Uuid>> {
+        let cap = self.config.llm.max_llm_agents?;
+        let mut candidates: Vec<(f64, Uuid)> = self
+            .agents
+            .iter()
+            .enumerate()
+            .filter(|(_, a)| a.is_alive() && !a.physical.incapacitated)
+            .map(|(idx, a)| {
+                let company = self.nearby_indices(idx).len();
+                let promises = self
+                    .trade_state
+                    .service_debts
+                    .iter()
+                    .filter(|d| (d.debtor == a.id || d.creditor == a.id) && !d.fulfilled && !d.reneged)
+                    .count();
+                let dealings = self.trade_state.pending_proposals_for(a.id).len() + promises;
+                (situation_salience(a, company, dealings, hazard_level), a.id)
+            })
+            .collect();
+        if candidates.len() <= cap {
+            return None;
+        }
+        candidates.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+        debug!("{} agents over llm.max_llm_agents decide by heuristic", candidates.len() - cap);
+        Some(candidates.into_iter().take(cap).map(|(_, id)| id).collect())
+    }
 
-        // 6. Tick reproduction systems
-        self.tick_gestations(epoch)?;
-        self.tick_courtship_decay();
-        self.process_births();
+    /// Build an agent's perception: environment, surroundings, nearby trade
+    /// reputations and the outcome of their previous action
+    fn build_perception(&self, agent: &Agent, env_perception: &str, epoch: usize) -> String {
+        let world_perception = self.world.perception_summary(agent.physical.x, agent.physical.y);
+        let mut perception = format!("{}\n{}", env_perception, world_perception);
+
+        // World events affecting this agent's location
+        if self.config.world_events.broadcast {
+            let events = self.world.describe_events_at(agent.physical.x, agent.physical.y);
+            if !events.is_empty() {
+                perception.push_str(&format!("\nWorld events: {}", events.join("; ")));
+            }
+        }
 
-        // 7. Tick aging (after reproduction so newborns get their first epoch)
-        self.tick_aging(epoch)?;
+        if let Some(warning) = self.catastrophe_warning(agent, epoch) {
+            perception.push_str(&format!("\n{}", warning));
+        }
 
-        // 8. Update beliefs based on what happened
-        self.update_beliefs(epoch);
+        // How this spot compares to places the agent remembers
+        let (x, y) = (agent.physical.x, agent.physical.y);
+        let local_food = self.world.get(x, y).map(|c| c.food).unwrap_or(0);
+        let better_spot = agent
+            .beliefs
+            .best_food_elsewhere(x, y, epoch)
+            .filter(|_| local_food <= 1)
+            .and_then(|loc| Direction::toward((x, y), (loc.x, loc.y)).map(|d| (loc, d)));
+        if let Some((loc, direction)) = better_spot {
+            perception.push_str(&format!(
+                "\nFood is scarce here; it was more plentiful to the {} around ({}, {}).",
+                direction.name(),
+                loc.x,
+                loc.y
+            ));
+        }
 
-        // 9. Update territories (decay, group sharing)
-        self.update_territories(epoch)?;
+        if let Some(migration) = &agent.migration {
+            let toward = migration
+                .destination
+                .map(|(x, y)| format!(", toward the food remembered around ({}, {})", x, y))
+                .unwrap_or_default();
+            perception.push_str(&format!(
+                "\nYou and {} others around you have starved here for days; together you feel the pull to move on to the {}{}.",
+                migration.companions.saturating_sub(1),
+                migration.direction.name(),
+                toward
+            ));
+        }
 
-        // 10. Structure decay
-        self.decay_structures(epoch)?;
+        if let Some(missing) = agent.craving().filter(|_| self.config.metabolism.balanced_diet) {
+            let missing = if missing == FoodKind::Meat { "meat (HUNT or FISH)" } else { "plants (GATHER)" };
+            perception.push_str(&format!(
+                "\nYou have eaten the same food for days; meals do you less good until you eat some {}.",
+                missing
+            ));
+        }
 
-        // 11. Detect groups/alliances
-        self.detect_groups(epoch)?;
+        // Tools someone left behind on this cell
+        let dropped: Vec<&str> = self
+            .world
+            .get(x, y)
+            .map(|c| c.dropped_tools.iter().map(|t| t.tool_type.display_name()).collect())
+            .unwrap_or_default();
+        if !dropped.is_empty() {
+            perception.push_str(&format!("\nLying on the ground here: {} (GATHER to pick up)", dropped.join(", ")));
+        }
 
-        // Log epoch end
-        self.log_and_track(Event::epoch_end(epoch))?;
-        self.chronicle.flush()?;
+        // Cover from attack
+        if let Some(defense) = self.shelter_defense(agent) {
+            perception.push_str(&format!(
+                "\nYou are defended by your shelter: attacks on you here do {:.0}% less harm.",
+                defense * 100.0
+            ));
+        }
 
-        // Progress update
-        if epoch % 10 == 0 {
-            let alive = self.agents.iter().filter(|a| a.is_alive()).count();
-            info!("Epoch {}: {} agents alive", epoch, alive);
+        // Bodies of the dead lying here
+        if self.config.scavenging.enabled {
+            let remains: Vec<&str> = self
+                .world
+                .get(x, y)
+                .map(|c| c.remains.iter().map(|r| r.name.as_str()).collect())
+                .unwrap_or_default();
+            if !remains.is_empty() {
+                perception.push_str(&format!(
+                    "\nThe remains of {} lie here. (SCAVENGE to eat from them; anyone who sees will think less of you)",
+                    remains.join(", ")
+                ));
+            }
         }
 
-        Ok(())
+        // How the agent feels about taking chances
+        if let Some(line) = agent.identity.personality.describe_risk() {
+            perception.push_str(&format!("\n{}", line));
+        }
+
+        // Places the agent remembers nearby
+        let landmarks: Vec<String> = agent
+            .beliefs
+            .landmarks_near(x, y, LANDMARK_RANGE)
+            .into_iter()
+            .take(3)
+            .map(|l| l.describe())
+            .collect();
+        if !landmarks.is_empty() {
+            perception.push_str(&format!("\nPlaces you remember: {}", landmarks.join("; ")));
+        }
+
+        // Rank within the agent's group and how trusted they are at large
+        let mut standing = Vec::new();
+        if let Some(rank) = self.group_tracker.group_of(agent.id).and_then(|g| g.describe_standing(agent.id)) {
+            standing.push(rank);
+        }
+        if let Some(trust) = self.reputation_of(agent.id) {
+            standing.push(format!("those who know you {}", describe_reputation(trust)));
+        }
+        if !standing.is_empty() {
+            perception.push_str(&format!("\nYour standing: {}.", standing.join("; ")));
+        }
+
+        // Leaders speak for their group toward the others
+        let led = self.group_tracker.group_of(agent.id).filter(|g| g.leader == Some(agent.id));
+        if let Some(group) = led.filter(|_| self.config.groups.diplomacy) {
+            let relations: Vec<String> = self
+                .group_tracker
+                .current_groups()
+                .iter()
+                .filter(|g| g.id != group.id)
+                .map(|g| format!("{} ({})", g.name, self.group_tracker.relation_between(group.id, g.id).describe()))
+                .collect();
+            if !relations.is_empty() {
+                perception.push_str(&format!(
+                    "\nAs leader of {} you may PROPOSE_PEACE <group> or DECLARE_WAR <group>. Other groups: {}.",
+                    group.name,
+                    relations.join(", ")
+                ));
+            }
+        }
+
+        // Trading reputation of nearby agents
+        let nearby = self.agent_index(agent.id).map(|idx| self.nearby_indices(idx)).unwrap_or_default();
+        let reputations: Vec<String> = nearby
+            .into_iter()
+            .map(|i| &self.agents[i])
+            .filter_map(|a| {
+                let stats = self.trade_state.stats_for(a.id);
+                (!stats.is_empty()).then(|| format!("{} is a {}", a.name(), stats.describe()))
+            })
+            .collect();
+        if !reputations.is_empty() {
+            perception.push_str(&format!("\nTrade reputations: {}", reputations.join("; ")));
+        }
+
+        // How hunger and health have been trending
+        if let Some(vitals) = self.vitals.get(&agent.id) {
+            for line in vitals.describe(epoch) {
+                perception.push_str(&format!("\n{}", line));
+            }
+        }
+
+        // Feedback on what happened to the previous action
+        if let Some(result) = self.action_results.get(&agent.id) {
+            perception.push_str(&format!("\nLast action: {}", result));
+        }
+
+        perception
+    }
+
+    /// Average trust living agents who know this agent place in them
+    fn reputation_of(&self, agent_id: Uuid) -> Option<f64> {
+        let trust: Vec<f64> = self
+            .agents
+            .iter()
+            .filter(|a| a.is_alive() && a.id != agent_id)
+            .filter_map(|a| a.beliefs.social.get(&agent_id))
+            .map(|b| b.trust)
+            .collect();
+        (!trust.is_empty()).then(|| trust.iter().sum::<f64>() / trust.len() as f64)
     }
 
-    /// Resolve all actions for an epoch
-    fn resolve_actions(&mut self, epoch: usize, actions: HashMap<Uuid, Action>) -> Result<()> {
+    /// Which way an agent travelling to `to` steps next: along the route they
+    /// worked out before while it still leads on from where they stand over
+    /// passable ground, else along a freshly found one
+    fn next_travel_step(&mut self, agent_idx: usize, to: (usize, usize)) -> Result<Direction, &'static str> {
+        let agent = &self.agents[agent_idx];
+        let from = (agent.physical.x, agent.physical.y);
+        let can_swim = agent.skills.level("swimming") >= self.config.movement.swim_skill;
+        if from == to {
+            self.agents[agent_idx].route = None;
+            return Err("you are already there");
+        }
+        let cached = agent
+            .route
+            .as_ref()
+            .filter(|route| route.destination == to)
+            .and_then(|route| route.steps.first().copied())
+            .filter(|&(x, y)| {
+                x.abs_diff(from.0) <= 1
+                    && y.abs_diff(from.1) <= 1
+                    && self.world.get(x, y).is_some_and(|cell| can_swim || cell.terrain.is_passable())
+            });
+        let step = match cached {
+            Some(step) => step,
+            None => {
+                let steps = crate::world::pathfind(from, to, &self.world, can_swim).ok_or("you can't find a way there")?;
+                let step = steps[0];
+                self.agents[agent_idx].route = Some(Route { destination: to, steps });
+                step
+            }
+        };
+        Direction::toward(from, step).ok_or("you can't find a way there")
+    }
+
+    /// Which way takes an agent furthest from where their attacker was last
+    /// seen, over ground they can walk
+    fn flee_direction(&self, agent_idx: usize) -> Result<Direction, &'static str> {
+        let agent = &self.agents[agent_idx];
+        let threat = agent.threat_source.as_ref().ok_or("nobody is after you")?;
+        let pos = (agent.physical.x, agent.physical.y);
+        let distance = |(x, y): (usize, usize)| {
+            let (dx, dy) = (x.abs_diff(threat.position.0), y.abs_diff(threat.position.1));
+            (dx.max(dy), dx * dx + dy * dy)
+        };
+        Direction::ALL
+            .into_iter()
+            .filter_map(|direction| Some((direction, direction.step(pos)?)))
+            .filter(|(_, (x, y))| self.world.get(*x, *y).is_some_and(|cell| cell.terrain.is_passable()))
+            .filter(|(_, to)| distance(*to) > distance(pos))
+            .max_by_key(|(_, to)| distance(*to))
+            .map(|(direction, _)| direction)
+            .ok_or("there is nowhere left to run")
+    }
+
+    /// Record why an agent's action failed so they learn about it next epoch
+    fn record_action_failure(&mut self, agent_id: Uuid, action: &str, reason: &str) {
+        if self.config.simulation.action_feedback {
+            self.action_results.insert(
+                agent_id,
+                format!("your attempt to {} failed: {}", action, reason),
+            );
+        }
+    }
+
+    /// Resolve all actions for an epoch, in agent id order
+    fn resolve_actions(&mut self, epoch: usize, actions: BTreeMap<Uuid, Action>) -> Result<()> {
+        // Previous outcomes have been perceived; start fresh
+        self.action_results.clear();
+
         // Get environment state for movement cost
         let env_state = self.environment.state_at(epoch);
         let base_movement_cost = 0.05 * env_state.movement_cost;
@@ -532,7 +1335,7 @@ impl Engine {
         // First pass: categorize actions
         for (agent_id, action) in &actions {
             if let Action::Gather = action {
-                if let Some(agent) = self.agents.iter().find(|a| a.id == *agent_id) {
+                if let Some(agent) = self.agent(*agent_id) {
                     let pos = (agent.physical.x, agent.physical.y);
                     gathers_per_cell.entry(pos).or_default().push(*agent_id);
                 }
@@ -541,12 +1344,47 @@ impl Engine {
 
         // Second pass: resolve actions
         for (agent_id, action) in actions {
-            let agent_idx = self.agents.iter().position(|a| a.id == agent_id);
+            let agent_idx = self.agent_index(agent_id);
             if agent_idx.is_none() {
                 continue;
             }
             let agent_idx = agent_idx.unwrap();
 
+            // Dirty-track whoever this action touches; cells agents end up in are marked below
+            self.mark_agent_changed(agent_id, epoch);
+            if let Some(target) = action.target() {
+                self.mark_agent_changed(target, epoch);
+            }
+
+            let ctx = ActionContext { agents: &self.agents, config: &self.config };
+            if let Err(e) = action.validate(&self.agents[agent_idx], &ctx) {
+                self.record_action_failure(agent_id, &action.verb(), &e.to_string());
+                continue;
+            }
+
+            // A journey is walked, and an escape run, one step a day
+            let action = match action {
+                Action::Travel { x, y } => match self.next_travel_step(agent_idx, (x, y)) {
+                    Ok(direction) => Action::Move(direction),
+                    Err(reason) => {
+                        self.record_action_failure(agent_id, "travel", reason);
+                        continue;
+                    }
+                },
+                Action::Flee => match self.flee_direction(agent_idx) {
+                    Ok(direction) => Action::Move(direction),
+                    Err(reason) => {
+                        // Boxed in, they give up running rather than try again every day
+                        if let Some(threat) = &mut self.agents[agent_idx].threat_source {
+                            threat.cornered = true;
+                        }
+                        self.record_action_failure(agent_id, "flee", reason);
+                        continue;
+                    }
+                },
+                action => action,
+            };
+
             match action {
                 Action::Wait => {
                     let age_mod = self.agents[agent_idx].age_modifier(&aging_config);
@@ -557,19 +1395,42 @@ impl Engine {
 
                 Action::Move(dir) => {
                     let age_mod = self.agents[agent_idx].age_modifier(&aging_config);
-                    let agent = &mut self.agents[agent_idx];
+                    let agent = &self.agents[agent_idx];
                     let (dx, dy) = dir.delta();
                     let new_x = (agent.physical.x as i32 + dx).max(0) as usize;
                     let new_y = (agent.physical.y as i32 + dy).max(0) as usize;
 
                     if new_x < self.world.width && new_y < self.world.height {
-                        let from = (agent.physical.x, agent.physical.y);
-                        agent.physical.x = new_x;
-                        agent.physical.y = new_y;
-                        // Movement cost affected by environment and age (elderly use more energy)
-                        let movement_cost = base_movement_cost / age_mod;
-                        agent.physical.energy = (agent.physical.energy - movement_cost).max(0.0);
-
+                        let occupants = self
+                            .spatial
+                            .neighbors_within(new_x, new_y, 0)
+                            .into_iter()
+                            .filter(|&id| id != agent_id && self.agent(id).is_some_and(|a| a.is_alive()))
+                            .count();
+                        if self.config.movement.max_occupancy.is_some_and(|cap| occupants >= cap) {
+                            self.record_action_failure(agent_id, "move", "there is no room for you there");
+                            continue;
+                        }
+                        let terrain = self.world.get(new_x, new_y).map_or(Terrain::Barren, |cell| cell.terrain);
+                        let swimming = !terrain.is_passable();
+                        if swimming && self.agents[agent_idx].skills.level("swimming") < self.config.movement.swim_skill {
+                            // Each try at the shore teaches a little
+                            let practice = self.config.movement.shore_practice;
+                            self.agents[agent_idx].skills.improve("swimming", practice, epoch);
+                            self.record_action_failure(agent_id, "move", "the water is too deep for you to cross");
+                            continue;
+                        }
+                        let from = (self.agents[agent_idx].physical.x, self.agents[agent_idx].physical.y);
+                        self.relocate_agent(agent_idx, (new_x, new_y));
+                        let agent = &mut self.agents[agent_idx];
+                        agent.stepped_onto((new_x, new_y));
+                        if swimming {
+                            agent.skills.practice("swimming", epoch);
+                        }
+                        // Movement cost affected by environment, the ground moved onto and age (elderly use more energy)
+                        let movement_cost = base_movement_cost * terrain.movement_cost() / age_mod;
+                        agent.physical.energy = (agent.physical.energy - movement_cost).max(0.0);
+
                         self.log_and_track(Event::moved(
                             epoch,
                             agent_id,
@@ -579,6 +1440,9 @@ impl Engine {
                     }
                 }
 
+                // Already turned into the move they take
+                Action::Travel { .. } | Action::Flee => {}
+
                 Action::Gather => {
                     let age_mod = self.agents[agent_idx].age_modifier(&aging_config);
                     let agent = &self.agents[agent_idx];
@@ -597,6 +1461,7 @@ impl Engine {
 
                     if !can_gather {
                         debug!("{} cannot gather on others' territory", agent.name());
+                        self.record_action_failure(agent_id, "gather", "this is someone else's territory");
                         continue;
                     }
 
@@ -604,22 +1469,52 @@ impl Engine {
                     let hunting_level = agent.skills.level("hunting");
                     let foraging_level = agent.skills.level("foraging");
                     let skill_bonus = 1.0 + hunting_level * 0.5 + foraging_level * 0.3;
+                    let tool_bonus = 1.0 + agent.physical.equipped_bonus(ToolEffect::Foraging);
 
                     // How many agents are gathering here?
                     let num_gatherers = gathers_per_cell.get(&pos).map(|v| v.len()).unwrap_or(1);
 
-                    // Split the take amount, modified by age and skills
+                    // Split the take amount, modified by age, skills and any abundance or scarcity
                     let base_max = 5 / num_gatherers as u32;
-                    let max_take = ((base_max as f64 * age_mod * skill_bonus).round() as u32).max(1);
+                    let event_yield = self.world_event_yield(pos.0, pos.1);
+                    let max_take =
+                        ((base_max as f64 * age_mod * skill_bonus * tool_bonus * event_yield).round() as u32).max(1);
 
-                    // Take food from cell
-                    let (taken, remaining_food) = if let Some(cell) = self.world.get_mut(pos.0, pos.1) {
+                    // Take food from cell, along with any tools left lying there
+                    let (taken, remaining_food, found_tools) = if let Some(cell) = self.world.get_mut(pos.0, pos.1) {
                         let taken = cell.take_food(max_take);
-                        (taken, cell.food)
+                        (taken, cell.food, std::mem::take(&mut cell.dropped_tools))
                     } else {
-                        (0, 0)
+                        (0, 0, Vec::new())
                     };
 
+                    if !found_tools.is_empty() {
+                        let names: Vec<&str> = found_tools.iter().map(|t| t.tool_type.display_name()).collect();
+                        self.agents[agent_idx].memory.remember(Episode::new(
+                            epoch,
+                            format!("I found {} lying on the ground", names.join(", ")),
+                            0.3,
+                            vec![],
+                            EpisodeCategory::Discovery,
+                        ));
+                        self.agents[agent_idx].physical.tools.extend(found_tools);
+                    }
+
+                    // Foraging in woods or on mountainsides turns up a little wood or stone too
+                    let byproduct = match self.world.get(pos.0, pos.1).map(|cell| cell.terrain) {
+                        Some(Terrain::Forest) => Some(MaterialType::Wood),
+                        Some(Terrain::Mountain) => Some(MaterialType::Stone),
+                        _ => None,
+                    };
+                    if let Some(material) = byproduct {
+                        self.agents[agent_idx].physical.add_material(material, 1);
+                        self.log_and_track(Event::gathered_materials(
+                            epoch,
+                            agent_id,
+                            vec![(material.display_name().to_string(), 1)],
+                        ))?;
+                    }
+
                     if taken > 0 {
                         self.agents[agent_idx].add_food(taken);
                         // Gathering energy cost affected by age (elderly use more energy)
@@ -629,6 +1524,7 @@ impl Engine {
 
                         // Practice foraging skill when gathering
                         self.agents[agent_idx].skills.practice("foraging", epoch);
+                        self.agents[agent_idx].physical.use_equipped_for(ToolEffect::Foraging);
 
                         self.log_and_track(Event::gathered(epoch, agent_id, taken))?;
 
@@ -640,9 +1536,16 @@ impl Engine {
                 }
 
                 Action::Eat => {
-                    let ate = self.agents[agent_idx].eat();
+                    let metabolism = &self.config.metabolism;
+                    let healing = metabolism.meal_healing
+                        * self.agents[agent_idx].metabolism(&self.config.aging, metabolism).healing;
+                    let ate = self.agents[agent_idx].eat(healing, metabolism);
                     if ate {
                         self.log_and_track(Event::ate(epoch, agent_id))?;
+                        self.vitals
+                            .entry(agent_id)
+                            .or_insert_with(|| VitalSigns::new(epoch))
+                            .last_meal = Some(epoch);
 
                         self.agents[agent_idx].memory.remember(Episode::survival(
                             epoch,
@@ -659,19 +1562,7 @@ impl Engine {
                     let pos = (agent.physical.x, agent.physical.y);
 
                     // Shelter rest bonus
-                    let shelter_bonus = if let Some((sx, sy)) = agent.physical.sheltered_at {
-                        if let Some(cell) = self.world.get(sx, sy) {
-                            if let Some(ref structure) = cell.structure {
-                                structure.effective_rest_bonus()
-                            } else {
-                                0.0
-                            }
-                        } else {
-                            0.0
-                        }
-                    } else {
-                        0.0
-                    };
+                    let shelter_bonus = self.shelter_rest_bonus(agent_idx);
 
                     // Territory rest bonus (resting on own territory feels safer)
                     let territory_bonus = if let Some(cell) = self.world.get(pos.0, pos.1) {
@@ -688,479 +1579,530 @@ impl Engine {
                         0.0
                     };
 
-                    let recovery = (0.3 + shelter_bonus + territory_bonus) * age_mod;
-                    self.agents[agent_idx].physical.energy =
-                        (self.agents[agent_idx].physical.energy + recovery).min(1.0);
+                    // Sleeping through the night is more restful
+                    let is_night = self.environment.is_night(epoch);
+                    let night_bonus = if is_night { NIGHT_REST_BONUS } else { 0.0 };
+
+                    let bonus = shelter_bonus.unwrap_or(0.0) + territory_bonus + night_bonus;
+                    self.agents[agent_idx].rest(bonus, age_mod);
+
+                    if shelter_bonus.is_some() {
+                        let description = if is_night {
+                            "I slept comfortably through the night in my shelter"
+                        } else {
+                            "I rested comfortably in my shelter"
+                        };
+                        self.agents[agent_idx]
+                            .memory
+                            .remember(Episode::survival(epoch, description, 0.2));
+                    }
                     self.log_and_track(Event::rested(epoch, agent_id))?;
                 }
 
                 Action::Speak { target, message } => {
-                    let target_idx = self.agents.iter().position(|a| a.id == target);
-                    if let Some(target_idx) = target_idx {
-                        // Check proximity
-                        let agent = &self.agents[agent_idx];
-                        let target_agent = &self.agents[target_idx];
+                    let Some(target_idx) = self.agent_index(target) else {
+                        continue;
+                    };
+                    let agent = &self.agents[agent_idx];
 
-                        if is_adjacent(agent, target_agent) {
-                            // Leadership bonus: +50% sentiment gain at max level
-                            let leadership_bonus = 1.0 + agent.skills.level("leadership") * 0.5;
+                    // Leadership bonus: +50% sentiment gain at max level
+                    let leadership_bonus = 1.0 + agent.skills.level("leadership") * 0.5;
 
-                            self.log_and_track(Event::spoke(
-                                epoch,
-                                agent_id,
-                                target,
-                                &message,
-                            ))?;
+                    self.log_and_track(Event::spoke(
+                        epoch,
+                        agent_id,
+                        target,
+                        &message,
+                    ))?;
 
-                            // Practice leadership when speaking
-                            self.agents[agent_idx].skills.practice("leadership", epoch);
+                    // Practice leadership when speaking
+                    self.agents[agent_idx].skills.practice("leadership", epoch);
 
-                            // Both agents remember the interaction
-                            let agent_name = self.agents[agent_idx].name().to_string();
-                            let target_name = self.agents[target_idx].name().to_string();
+                    // Both agents remember the interaction
+                    let agent_name = self.agents[agent_idx].name().to_string();
+                    let target_name = self.agents[target_idx].name().to_string();
 
-                            self.agents[agent_idx].memory.remember(Episode::social(
-                                epoch,
-                                &format!("I spoke to {}: \"{}\"", target_name, message),
-                                0.1,
-                                target,
-                            ));
+                    self.agents[agent_idx].memory.remember(Episode::social(
+                        epoch,
+                        &format!("I spoke to {}: \"{}\"", target_name, message),
+                        0.1,
+                        target,
+                    ));
 
-                            self.agents[target_idx].memory.remember(Episode::social(
-                                epoch,
-                                &format!("{} said to me: \"{}\"", agent_name, message),
-                                0.1,
-                                agent_id,
-                            ));
+                    self.agents[target_idx].memory.remember(Episode::social(
+                        epoch,
+                        &format!("{} said to me: \"{}\"", agent_name, message),
+                        0.1,
+                        agent_id,
+                    ));
 
-                            // Update familiarity (speaker gets leadership bonus for target's sentiment)
-                            self.agents[agent_idx].beliefs.update_sentiment(
-                                target,
-                                &target_name,
-                                0.05,
-                                epoch,
-                            );
-                            self.agents[target_idx].beliefs.update_sentiment(
-                                agent_id,
-                                &agent_name,
-                                0.05 * leadership_bonus,
-                                epoch,
-                            );
-                        }
-                    }
+                    // Update familiarity (speaker gets leadership bonus for target's sentiment)
+                    self.agents[agent_idx].beliefs.update_sentiment(
+                        target,
+                        &target_name,
+                        0.05,
+                        epoch,
+                    );
+                    self.agents[target_idx].beliefs.update_sentiment(
+                        agent_id,
+                        &agent_name,
+                        0.05 * leadership_bonus,
+                        epoch,
+                    );
                 }
 
                 Action::Give { target, amount } => {
-                    let target_idx = self.agents.iter().position(|a| a.id == target);
-                    if let Some(target_idx) = target_idx {
-                        let agent = &self.agents[agent_idx];
-                        let target_agent = &self.agents[target_idx];
+                    let Some(target_idx) = self.agent_index(target) else {
+                        continue;
+                    };
+                    let agent = &self.agents[agent_idx];
 
-                        if is_adjacent(agent, target_agent) {
-                            // Leadership bonus: +50% trust/sentiment gain at max level
-                            let leadership_bonus = 1.0 + agent.skills.level("leadership") * 0.5;
+                    // Leadership bonus: +50% trust/sentiment gain at max level
+                    let leadership_bonus = 1.0 + agent.skills.level("leadership") * 0.5;
 
-                            let actual = self.agents[agent_idx].remove_food(amount);
-                            if actual > 0 {
-                                self.agents[target_idx].add_food(actual);
+                    let actual = self.agents[agent_idx].remove_food(amount);
+                    if actual > 0 {
+                        self.agents[target_idx].add_food(actual);
 
-                                self.log_and_track(Event::gave(
-                                    epoch,
-                                    agent_id,
-                                    target,
-                                    actual,
-                                ))?;
+                        self.log_and_track(Event::gave(
+                            epoch,
+                            agent_id,
+                            target,
+                            actual,
+                        ))?;
 
-                                let agent_name = self.agents[agent_idx].name().to_string();
-                                let target_name = self.agents[target_idx].name().to_string();
+                        let agent_name = self.agents[agent_idx].name().to_string();
+                        let target_name = self.agents[target_idx].name().to_string();
 
-                                // Memories
-                                self.agents[agent_idx].memory.remember(Episode::new(
-                                    epoch,
-                                    format!("I gave {} food to {}", actual, target_name),
-                                    0.2,
-                                    vec![target],
-                                    EpisodeCategory::Gift,
-                                ));
+                        // Memories
+                        self.agents[agent_idx].memory.remember(Episode::new(
+                            epoch,
+                            format!("I gave {} food to {}", actual, target_name),
+                            0.2,
+                            vec![target],
+                            EpisodeCategory::Gift,
+                        ));
 
-                                self.agents[target_idx].memory.remember(Episode::new(
-                                    epoch,
-                                    format!("{} gave me {} food", agent_name, actual),
-                                    0.5,
-                                    vec![agent_id],
-                                    EpisodeCategory::Gift,
-                                ));
+                        self.agents[target_idx].memory.remember(Episode::new(
+                            epoch,
+                            format!("{} gave me {} food", agent_name, actual),
+                            0.5,
+                            vec![agent_id],
+                            EpisodeCategory::Gift,
+                        ));
 
-                                // Practice leadership when giving
-                                self.agents[agent_idx].skills.practice("leadership", epoch);
+                        // Practice leadership when giving
+                        self.agents[agent_idx].skills.practice("leadership", epoch);
 
-                                // Update trust (giver gets leadership bonus)
-                                self.agents[target_idx].beliefs.update_trust(
-                                    agent_id,
-                                    &agent_name,
-                                    0.2 * leadership_bonus,
-                                    epoch,
-                                );
-                                self.agents[target_idx].beliefs.update_sentiment(
-                                    agent_id,
-                                    &agent_name,
-                                    0.2 * leadership_bonus,
-                                    epoch,
-                                );
+                        // Update trust (giver gets leadership bonus)
+                        self.agents[target_idx].beliefs.update_trust(
+                            agent_id,
+                            &agent_name,
+                            0.2 * leadership_bonus,
+                            epoch,
+                        );
+                        self.agents[target_idx].beliefs.update_sentiment(
+                            agent_id,
+                            &agent_name,
+                            0.2 * leadership_bonus,
+                            epoch,
+                        );
 
-                                // Check if this contributes to a FutureGift debt
-                                self.check_give_fulfills_debt(agent_id, target, actual, epoch);
-                            }
-                        }
+                        // Check if this contributes to a FutureGift debt
+                        self.check_give_fulfills_debt(agent_id, target, actual, epoch);
                     }
                 }
 
-                Action::Attack { target } => {
-                    let target_idx = self.agents.iter().position(|a| a.id == target);
-                    if let Some(target_idx) = target_idx {
-                        let agent = &self.agents[agent_idx];
-                        let target_agent = &self.agents[target_idx];
-
-                        if is_adjacent(agent, target_agent) && target_agent.is_alive() {
-                            // Calculate base damage (0.1 - 0.3 based on attacker's... randomness for now)
-                            let base_damage = 0.15 + rand::random::<f64>() * 0.1;
-
-                            // Check for defender's allies
-                            let defender_allies = self.find_nearby_allies(target, target_idx, epoch);
-
-                            // Calculate damage reduction from allies (20% per ally, max 50%)
-                            let ally_reduction = (defender_allies.len() as f64 * 0.20).min(0.50);
-                            let damage = base_damage * (1.0 - ally_reduction);
-
-                            // Log ally intervention if any allies defended
-                            if !defender_allies.is_empty() {
-                                // Pick the first ally as the primary defender
-                                let (primary_ally_id, _) = defender_allies[0];
-                                self.log_and_track(Event::ally_intervened(
-                                    epoch,
-                                    agent_id,
-                                    target,
-                                    primary_ally_id,
-                                    ally_reduction,
-                                ))?;
-                            }
-
-                            self.agents[target_idx].take_damage(damage);
-
-                            self.log_and_track(Event::attacked(
-                                epoch,
-                                agent_id,
-                                target,
-                                damage,
-                            ))?;
+                Action::GiveItem { target, item } => {
+                    let Some(target_idx) = self.agent_index(target) else {
+                        continue;
+                    };
+                    let description = item.describe();
 
-                            let agent_name = self.agents[agent_idx].name().to_string();
-                            let target_name = self.agents[target_idx].name().to_string();
+                    self.transfer_items(agent_idx, target_idx, std::slice::from_ref(&item));
+                    self.log_and_track(Event::gave_item(epoch, agent_id, target, &description))?;
 
-                            // Check if target died
-                            if !self.agents[target_idx].is_alive() {
-                                self.log_and_track(Event::died(
-                                    epoch,
-                                    target,
-                                    &format!("attack by {}", agent_name),
-                                ))?;
-                            }
+                    let agent_name = self.agents[agent_idx].name().to_string();
+                    let target_name = self.agents[target_idx].name().to_string();
 
-                            // Memories
-                            self.agents[agent_idx].memory.remember(Episode::conflict(
-                                epoch,
-                                &format!("I attacked {}", target_name),
-                                -0.2,
-                                target,
-                            ));
+                    self.agents[agent_idx].memory.remember(Episode::new(
+                        epoch,
+                        format!("I gave {} to {}", description, target_name),
+                        0.2,
+                        vec![target],
+                        EpisodeCategory::Gift,
+                    ));
+                    self.agents[target_idx].memory.remember(Episode::new(
+                        epoch,
+                        format!("{} gave me {}", agent_name, description),
+                        0.4,
+                        vec![agent_id],
+                        EpisodeCategory::Gift,
+                    ));
 
-                            self.agents[target_idx].memory.remember(Episode::conflict(
-                                epoch,
-                                &format!("{} attacked me!", agent_name),
-                                -0.8,
-                                agent_id,
-                            ));
+                    self.agents[target_idx].beliefs.update_trust(agent_id, &agent_name, 0.15, epoch);
+                    self.agents[target_idx].beliefs.update_sentiment(agent_id, &agent_name, 0.15, epoch);
+                }
 
-                            // Update beliefs
-                            self.agents[target_idx].beliefs.update_trust(
-                                agent_id,
-                                &agent_name,
-                                -0.5,
-                                epoch,
-                            );
-                            self.agents[target_idx].beliefs.update_sentiment(
-                                agent_id,
-                                &agent_name,
-                                -0.5,
-                                epoch,
-                            );
-                            self.agents[target_idx].beliefs.self_belief.perceived_safety -= 0.2;
+                Action::Attack { target } => {
+                    let Some(target_idx) = self.agent_index(target) else {
+                        continue;
+                    };
+                    let agent = &self.agents[agent_idx];
+                    let target_agent = &self.agents[target_idx];
+
+                    // Calculate base damage (0.1 - 0.3 based on attacker's... randomness for now)
+                    let base_damage = 0.15 + crate::rng::random::<f64>(crate::rng::Stream::Combat) * 0.1;
+
+                    // Those beside the victim who stand by them turn part of the blow aside
+                    let defenders = self.find_defenders(target_idx, agent_id, epoch);
+                    let combat = &self.config.combat;
+                    let ally_reduction =
+                        (defenders.len() as f64 * combat.ally_reduction).min(combat.max_ally_reduction);
+
+                    // A defender in their own shelter is harder to hurt, and breaking in is tiring
+                    let shelter_defense = self.shelter_defense(target_agent).unwrap_or(0.0);
+                    let vulnerability = if target_agent.physical.incapacitated { COLLAPSED_VULNERABILITY } else { 1.0 };
+                    let undefended = base_damage
+                        * agent.physical.attack_multiplier()
+                        * vulnerability
+                        * (1.0 - shelter_defense);
+                    let damage = undefended * (1.0 - ally_reduction);
+
+                    // The first defender takes some of what they turned aside
+                    if let Some(&ally_idx) = defenders.first() {
+                        let ally_id = self.agents[ally_idx].id;
+                        self.agents[ally_idx].take_damage((undefended - damage) * combat.ally_share);
+                        self.log_and_track(Event::ally_intervened(
+                            epoch,
+                            agent_id,
+                            target,
+                            ally_id,
+                            ally_reduction,
+                        ))?;
+                        let (attacker_name, target_name) =
+                            (self.agents[agent_idx].name().to_string(), self.agents[target_idx].name().to_string());
+                        self.agents[ally_idx].memory.remember(Episode::conflict(
+                            epoch,
+                            &format!("I stepped in when {} attacked {}", attacker_name, target_name),
+                            -0.3,
+                            agent_id,
+                        ));
+                        let ally_name = self.agents[ally_idx].name().to_string();
+                        self.agents[target_idx].beliefs.update_sentiment(ally_id, &ally_name, 0.2, epoch);
+                        if !self.agents[ally_idx].is_alive() {
+                            self.handle_death(epoch, ally_idx, &format!("attack by {}", attacker_name))?;
                         }
                     }
-                }
 
-                Action::Gossip { target, about } => {
-                    let target_idx = self.agents.iter().position(|a| a.id == target);
-                    let about_idx = self.agents.iter().position(|a| a.id == about);
+                    // A beaten victim may be spared death depending on lethality
+                    let combat = &self.config.combat;
+                    let health = self.agents[target_idx].physical.health;
+                    let attack_site = (self.agents[target_idx].physical.x, self.agents[target_idx].physical.y);
+                    let beaten = health - damage < combat.surrender_threshold;
+                    let spared = beaten && crate::rng::random::<f64>(crate::rng::Stream::Combat) >= combat.lethality;
+                    let damage = if spared { damage.min((health - 0.05).max(0.0)) } else { damage };
+
+                    self.agents[target_idx].take_damage(damage);
+                    self.agents[agent_idx].physical.use_equipped_for(ToolEffect::Attack);
+                    if shelter_defense > 0.0 {
+                        let attacker = &mut self.agents[agent_idx].physical;
+                        attacker.energy = (attacker.energy - SHELTER_ASSAULT_ENERGY * shelter_defense).max(0.0);
+                    }
 
-                    if let (Some(target_idx), Some(about_idx)) = (target_idx, about_idx) {
-                        let agent = &self.agents[agent_idx];
-                        let target_agent = &self.agents[target_idx];
+                    self.log_and_track(Event::attacked(
+                        epoch,
+                        agent_id,
+                        target,
+                        damage,
+                    ))?;
 
-                        if is_adjacent(agent, target_agent) && target_agent.is_alive() {
-                            // Diplomacy bonus: gossip is 2x as influential at max level
-                            let diplomacy_bonus = 1.0 + agent.skills.level("diplomacy");
+                    if spared {
+                        self.resolve_beaten_victim(epoch, agent_idx, target_idx)?;
+                    }
 
-                            // Get the gossiper's beliefs about the subject
-                            let (gossiper_trust, gossiper_sentiment) = self.agents[agent_idx]
-                                .beliefs
-                                .get_social(about)
-                                .map(|b| (b.trust, b.sentiment))
-                                .unwrap_or((0.0, 0.0));
+                    let agent_name = self.agents[agent_idx].name().to_string();
+                    let target_name = self.agents[target_idx].name().to_string();
 
-                            // Apply diplomacy bonus to influence
-                            let effective_trust = gossiper_trust * diplomacy_bonus;
-                            let effective_sentiment = gossiper_sentiment * diplomacy_bonus;
+                    // Check if target died
+                    if !self.agents[target_idx].is_alive() {
+                        self.handle_death(epoch, target_idx, &format!("attack by {}", agent_name))?;
+                    }
 
-                            let agent_name = self.agents[agent_idx].name().to_string();
-                            let target_name = self.agents[target_idx].name().to_string();
-                            let about_name = self.agents[about_idx].name().to_string();
+                    // Memories
+                    self.agents[agent_idx].memory.remember(Episode::conflict(
+                        epoch,
+                        &format!("I attacked {}", target_name),
+                        -0.2,
+                        target,
+                    ));
 
-                            // Practice diplomacy when gossiping
-                            self.agents[agent_idx].skills.practice("diplomacy", epoch);
+                    self.agents[target_idx].memory.remember(Episode::conflict(
+                        epoch,
+                        &format!("{} attacked me!", agent_name),
+                        -0.8,
+                        agent_id,
+                    ));
 
-                            // Target receives the gossip and updates their belief
-                            let sentiment_desc = self.agents[target_idx].beliefs.receive_gossip(
-                                agent_id,
-                                about,
-                                &about_name,
-                                effective_trust,
-                                effective_sentiment,
-                                epoch,
-                            );
+                    // Update beliefs
+                    self.agents[target_idx].beliefs.update_trust(
+                        agent_id,
+                        &agent_name,
+                        -0.5,
+                        epoch,
+                    );
+                    self.agents[target_idx].beliefs.update_sentiment(
+                        agent_id,
+                        &agent_name,
+                        -0.5,
+                        epoch,
+                    );
+                    self.agents[target_idx].beliefs.self_belief.perceived_safety -= 0.2;
+                    self.agents[target_idx].threat_source = Some(Threat {
+                        attacker: agent_id,
+                        position: (self.agents[agent_idx].physical.x, self.agents[agent_idx].physical.y),
+                        epoch,
+                        cornered: false,
+                    });
+                    self.agents[target_idx].beliefs.remember_landmark(
+                        attack_site.0,
+                        attack_site.1,
+                        LandmarkKind::Danger,
+                        &format!("{} attacked you", agent_name),
+                        epoch,
+                    );
+                }
 
-                            // Log the gossip event
-                            self.log_and_track(Event::gossiped(
-                                epoch,
-                                agent_id,
-                                target,
-                                about,
-                                &sentiment_desc,
-                            ))?;
+                Action::Gossip { target, about } => {
+                    let (Some(target_idx), Some(about_idx)) = (
+                        self.agent_index(target),
+                        self.agent_index(about),
+                    ) else {
+                        continue;
+                    };
+                    let agent = &self.agents[agent_idx];
 
-                            // Both agents remember the gossip
-                            self.agents[agent_idx].memory.remember(Episode::social(
-                                epoch,
-                                &format!("I told {} about {}", target_name, about_name),
-                                0.1,
-                                target,
-                            ));
+                    // Diplomacy bonus: gossip is 2x as influential at max level
+                    let diplomacy_bonus = 1.0 + agent.skills.level("diplomacy");
 
-                            self.agents[target_idx].memory.remember(Episode::social(
-                                epoch,
-                                &format!("{} told me about {}", agent_name, about_name),
-                                0.1,
-                                agent_id,
-                            ));
+                    // Get the gossiper's beliefs about the subject
+                    let (gossiper_trust, gossiper_sentiment) = self.agents[agent_idx]
+                        .beliefs
+                        .get_social(about)
+                        .map(|b| (b.trust, b.sentiment))
+                        .unwrap_or((0.0, 0.0));
 
-                            // Gossiping increases familiarity
-                            self.agents[agent_idx].beliefs.update_sentiment(
-                                target,
-                                &target_name,
-                                0.05,
-                                epoch,
-                            );
-                            self.agents[target_idx].beliefs.update_sentiment(
-                                agent_id,
-                                &agent_name,
-                                0.05,
-                                epoch,
-                            );
-                        }
-                    }
-                }
+                    // Apply diplomacy bonus to influence
+                    let effective_trust = gossiper_trust * diplomacy_bonus;
+                    let effective_sentiment = gossiper_sentiment * diplomacy_bonus;
 
-                Action::Court { target } => {
-                    if !self.config.reproduction.enabled {
-                        continue;
-                    }
-                    let target_idx = self.agents.iter().position(|a| a.id == target);
-                    if let Some(target_idx) = target_idx {
-                        let agent = &self.agents[agent_idx];
-                        let target_agent = &self.agents[target_idx];
+                    let agent_name = self.agents[agent_idx].name().to_string();
+                    let target_name = self.agents[target_idx].name().to_string();
+                    let about_name = self.agents[about_idx].name().to_string();
 
-                        if is_adjacent(agent, target_agent) && target_agent.is_alive() {
-                            let agent_name = self.agents[agent_idx].name().to_string();
-                            let target_name = self.agents[target_idx].name().to_string();
-
-                            // Increase courtship score for both parties
-                            let increment = self.config.reproduction.courtship_increment;
-
-                            let new_score_a = self.agents[agent_idx]
-                                .reproduction
-                                .courtship_progress
-                                .entry(target)
-                                .or_insert(0.0);
-                            *new_score_a = (*new_score_a + increment).min(1.0);
-                            let score_from_agent = *new_score_a;
-
-                            let new_score_b = self.agents[target_idx]
-                                .reproduction
-                                .courtship_progress
-                                .entry(agent_id)
-                                .or_insert(0.0);
-                            *new_score_b = (*new_score_b + increment * 0.5).min(1.0); // Recipient gains less
-                            let score_from_target = *new_score_b;
-
-                            // Log courtship event
-                            self.log_and_track(Event::courted(
-                                epoch,
-                                agent_id,
-                                target,
-                                score_from_agent,
-                            ))?;
+                    // Practice diplomacy when gossiping
+                    self.agents[agent_idx].skills.practice("diplomacy", epoch);
 
-                            // Create memories
-                            self.agents[agent_idx].memory.remember(Episode::social(
-                                epoch,
-                                &format!("I courted {}", target_name),
-                                0.2,
-                                target,
-                            ));
+                    // Target receives the gossip and updates their belief
+                    let sentiment_desc = self.agents[target_idx].beliefs.receive_gossip(
+                        agent_id,
+                        about,
+                        &about_name,
+                        effective_trust,
+                        effective_sentiment,
+                        epoch,
+                    );
 
-                            self.agents[target_idx].memory.remember(Episode::social(
-                                epoch,
-                                &format!("{} courted me", agent_name),
-                                0.15,
-                                agent_id,
-                            ));
+                    // Log the gossip event
+                    self.log_and_track(Event::gossiped(
+                        epoch,
+                        agent_id,
+                        target,
+                        about,
+                        &sentiment_desc,
+                        gossiper_sentiment,
+                    ))?;
 
-                            // Boost sentiment
-                            self.agents[agent_idx].beliefs.update_sentiment(
-                                target,
-                                &target_name,
-                                0.1,
-                                epoch,
-                            );
-                            self.agents[target_idx].beliefs.update_sentiment(
-                                agent_id,
-                                &agent_name,
-                                0.08,
-                                epoch,
-                            );
+                    // Both agents remember the gossip
+                    self.agents[agent_idx].memory.remember(Episode::social(
+                        epoch,
+                        &format!("I told {} about {}", target_name, about_name),
+                        0.1,
+                        target,
+                    ));
 
-                            debug!(
-                                "{} courted {} (courtship: {:.2} / {:.2})",
-                                agent_name, target_name, score_from_agent, score_from_target
-                            );
-                        }
-                    }
-                }
+                    self.agents[target_idx].memory.remember(Episode::social(
+                        epoch,
+                        &format!("{} told me about {}", agent_name, about_name),
+                        0.1,
+                        agent_id,
+                    ));
 
-                Action::Mate { target: _ } => {
-                    // Mate actions are handled separately after all actions are collected
-                    // to check for mutual consent
+                    // Gossiping increases familiarity
+                    self.agents[agent_idx].beliefs.update_sentiment(
+                        target,
+                        &target_name,
+                        0.05,
+                        epoch,
+                    );
+                    self.agents[target_idx].beliefs.update_sentiment(
+                        agent_id,
+                        &agent_name,
+                        0.05,
+                        epoch,
+                    );
                 }
 
-                Action::Teach { target, skill } => {
-                    if !self.config.skills.enabled {
+                Action::ShareKnowledge { target } => {
+                    let Some(target_idx) = self.agent_index(target) else {
                         continue;
-                    }
+                    };
 
-                    let target_idx = self.agents.iter().position(|a| a.id == target);
-                    if let Some(target_idx) = target_idx {
-                        let agent = &self.agents[agent_idx];
-                        let target_agent = &self.agents[target_idx];
+                    let agent_name = self.agents[agent_idx].name().to_string();
+                    let target_name = self.agents[target_idx].name().to_string();
+                    let knowledge = self.agents[agent_idx].beliefs.world.clone();
+                    let learned = self.agents[target_idx].beliefs.receive_knowledge(agent_id, &agent_name, &knowledge, epoch);
 
-                        // Check: adjacent, target alive, teacher has skill at teachable level
-                        let teacher_level = agent.skills.level(&skill);
-                        let min_level = self.config.skills.min_level_to_teach;
+                    if learned == 0 {
+                        let reason = format!("{} learned nothing new from you", target_name);
+                        self.record_action_failure(agent_id, "share knowledge", &reason);
+                        continue;
+                    }
 
-                        if is_adjacent(agent, target_agent)
-                            && target_agent.is_alive()
-                            && teacher_level >= min_level
-                        {
-                            let agent_name = self.agents[agent_idx].name().to_string();
-                            let target_name = self.agents[target_idx].name().to_string();
-
-                            // Calculate skill improvement
-                            // Base: teacher_level * teaching_multiplier * learning_rate
-                            // Bonus from target's openness (learning aptitude)
-                            let learning_rate = self.config.skills.learning_rate;
-                            let teaching_mult = self.config.skills.teaching_multiplier;
-                            let teacher_teaching_skill = self.agents[agent_idx].skills.level("teaching");
-                            let target_openness = self.agents[target_idx].identity.personality.openness;
-
-                            let improvement = teacher_level
-                                * learning_rate
-                                * teaching_mult
-                                * (1.0 + teacher_teaching_skill * 0.5)
-                                * (1.0 + target_openness * 0.3);
-
-                            // Target can't exceed teacher's level
-                            let target_current = self.agents[target_idx].skills.level(&skill);
-                            let max_new_level = teacher_level.min(1.0);
-                            let new_level = (target_current + improvement).min(max_new_level);
-
-                            if new_level > target_current {
-                                self.agents[target_idx].skills.improve(&skill, improvement, epoch);
-
-                                // Teacher practices teaching skill
-                                self.agents[agent_idx].skills.practice("teaching", epoch);
-                                let practice_imp = self.config.skills.practice_improvement;
-                                self.agents[agent_idx].skills.improve("teaching", practice_imp * 0.5, epoch);
-
-                                // Energy cost for teaching
-                                self.agents[agent_idx].physical.energy =
-                                    (self.agents[agent_idx].physical.energy - 0.1).max(0.0);
-
-                                // Log event
-                                self.log_and_track(Event::skill_taught(
-                                    epoch,
-                                    agent_id,
-                                    target,
-                                    &skill,
-                                    new_level,
-                                ))?;
+                    self.log_and_track(Event::spoke(
+                        epoch,
+                        agent_id,
+                        target,
+                        &format!("Let me tell you what I've seen of the land ({} things you didn't know)", learned),
+                    ))?;
+                    self.agents[agent_idx].memory.remember(Episode::social(
+                        epoch,
+                        &format!("I told {} where food and danger lie", target_name),
+                        0.1,
+                        target,
+                    ));
+                    self.agents[target_idx].memory.remember(Episode::social(
+                        epoch,
+                        &format!("{} told me where food and danger lie", agent_name),
+                        0.2,
+                        agent_id,
+                    ));
+                    self.agents[target_idx].beliefs.update_sentiment(agent_id, &agent_name, 0.05, epoch);
+                }
 
-                                // Create memories
-                                self.agents[agent_idx].memory.remember(Episode::social(
-                                    epoch,
-                                    &format!("I taught {} about {}", target_name, skill),
-                                    0.2,
-                                    target,
-                                ));
+                Action::Court { target } => {
+                    let Some(target_idx) = self.agent_index(target) else {
+                        continue;
+                    };
 
-                                self.agents[target_idx].memory.remember(Episode::social(
-                                    epoch,
-                                    &format!("{} taught me {}", agent_name, skill),
-                                    0.3,
-                                    agent_id,
-                                ));
+                    let agent_name = self.agents[agent_idx].name().to_string();
+                    let target_name = self.agents[target_idx].name().to_string();
 
-                                // Boost trust and sentiment
-                                self.agents[target_idx].beliefs.update_trust(
-                                    agent_id,
-                                    &agent_name,
-                                    0.1,
-                                    epoch,
-                                );
-                                self.agents[target_idx].beliefs.update_sentiment(
-                                    agent_id,
-                                    &agent_name,
-                                    0.1,
-                                    epoch,
-                                );
+                    // Increase courtship score for both parties
+                    let increment = self.config.reproduction.courtship_increment;
+
+                    let new_score_a = self.agents[agent_idx]
+                        .reproduction
+                        .courtship_progress
+                        .entry(target)
+                        .or_insert(0.0);
+                    *new_score_a = (*new_score_a + increment).min(1.0);
+                    let score_from_agent = *new_score_a;
+
+                    let new_score_b = self.agents[target_idx]
+                        .reproduction
+                        .courtship_progress
+                        .entry(agent_id)
+                        .or_insert(0.0);
+                    *new_score_b = (*new_score_b + increment * 0.5).min(1.0); // Recipient gains less
+                    let score_from_target = *new_score_b;
+
+                    // Log courtship event
+                    self.log_and_track(Event::courted(
+                        epoch,
+                        agent_id,
+                        target,
+                        score_from_agent,
+                    ))?;
 
-                                // Check if this fulfills a TeachSkill debt
-                                self.check_teach_fulfills_debt(agent_id, target, &skill, epoch);
+                    // Create memories
+                    self.agents[agent_idx].memory.remember(Episode::social(
+                        epoch,
+                        &format!("I courted {}", target_name),
+                        0.2,
+                        target,
+                    ));
 
-                                debug!(
-                                    "{} taught {} to {} (now at {:.2})",
-                                    agent_name, skill, target_name, new_level
-                                );
-                            }
-                        }
+                    self.agents[target_idx].memory.remember(Episode::social(
+                        epoch,
+                        &format!("{} courted me", agent_name),
+                        0.15,
+                        agent_id,
+                    ));
+
+                    // Boost sentiment
+                    self.agents[agent_idx].beliefs.update_sentiment(
+                        target,
+                        &target_name,
+                        0.1,
+                        epoch,
+                    );
+                    self.agents[target_idx].beliefs.update_sentiment(
+                        agent_id,
+                        &agent_name,
+                        0.08,
+                        epoch,
+                    );
+
+                    debug!(
+                        "{} courted {} (courtship: {:.2} / {:.2})",
+                        agent_name, target_name, score_from_agent, score_from_target
+                    );
+                }
+
+                Action::Mate { target: _ } => {
+                    // Mate actions are handled separately after all actions are collected
+                    // to check for mutual consent
+                }
+
+                Action::Teach { target, skill } => {
+                    let Some(target_idx) = self.agent_index(target) else {
+                        continue;
+                    };
+                    if self.teach_student(agent_idx, target_idx, &skill, 1.0, epoch)? {
+                        self.finish_lesson(agent_idx, epoch);
+                    } else {
+                        let target_name = self.agents[target_idx].name();
+                        let reason = format!("{} already knows as much {} as you", target_name, skill);
+                        self.record_action_failure(agent_id, "teach", &reason);
+                    }
+                }
+
+                Action::TeachGroup { skill } => {
+                    // Everyone next to the teacher who has something left to learn
+                    let teacher_level = self.agents[agent_idx].skills.level(&skill);
+                    let max_students = self.config.skills.max_students;
+                    let students: Vec<usize> = self
+                        .nearby_indices(agent_idx)
+                        .into_iter()
+                        .filter(|&i| self.agents[i].skills.level(&skill) < teacher_level)
+                        .take(max_students)
+                        .collect();
+                    if students.is_empty() {
+                        let reason = format!("there is nobody nearby with {} left to learn from you", skill);
+                        self.record_action_failure(agent_id, "teach", &reason);
+                        continue;
+                    }
+
+                    // Attention is split across the class
+                    let class_size = students.len() as f64;
+                    let effectiveness = 1.0 / (1.0 + self.config.skills.class_size_penalty * (class_size - 1.0));
+                    let mut taught = false;
+                    for target_idx in students {
+                        taught |= self.teach_student(agent_idx, target_idx, &skill, effectiveness, epoch)?;
+                    }
+                    if taught {
+                        self.finish_lesson(agent_idx, epoch);
                     }
                 }
 
@@ -1178,23 +2120,26 @@ impl Engine {
                         let mut gathered: Vec<(MaterialType, u32)> = Vec::new();
 
                         match terrain {
-                            Terrain::Fertile => {
-                                // Wood and fiber from fertile terrain
-                                let wood_amount = (1.0 + foraging_skill * 2.0 + tool_bonus).round() as u32;
+                            Terrain::Fertile | Terrain::Forest => {
+                                // Wood and fiber from fertile terrain, with extra wood in forests
+                                let base_wood = if terrain == Terrain::Forest { 2.0 } else { 1.0 };
+                                let wood_bonus = 1.0 + agent.physical.equipped_bonus(ToolEffect::WoodGathering);
+                                let wood_amount = ((base_wood + foraging_skill * 2.0 + tool_bonus) * wood_bonus).round() as u32;
                                 let fiber_amount = (1.0 + foraging_skill * 2.0).round() as u32;
                                 gathered.push((MaterialType::Wood, wood_amount));
                                 gathered.push((MaterialType::Fiber, fiber_amount));
                             }
-                            Terrain::Barren => {
+                            Terrain::Barren | Terrain::Desert | Terrain::Mountain => {
                                 // Stone and occasionally flint from barren terrain
                                 let stone_amount = (2.0 + foraging_skill).round() as u32;
                                 gathered.push((MaterialType::Stone, stone_amount));
 
                                 // 20% chance for flint
-                                if rand::random::<f64>() < 0.2 + foraging_skill * 0.1 {
+                                if crate::rng::random::<f64>(crate::rng::Stream::Gathering) < 0.2 + foraging_skill * 0.1 {
                                     gathered.push((MaterialType::Flint, 1));
                                 }
                             }
+                            Terrain::Water => {}
                         }
 
                         // Add materials to inventory
@@ -1204,6 +2149,9 @@ impl Engine {
 
                         // Practice foraging
                         self.agents[agent_idx].skills.practice("foraging", epoch);
+                        if gathered.iter().any(|(m, _)| *m == MaterialType::Wood) {
+                            self.agents[agent_idx].physical.use_equipped_for(ToolEffect::WoodGathering);
+                        }
 
                         // Energy cost
                         self.agents[agent_idx].physical.energy =
@@ -1228,75 +2176,118 @@ impl Engine {
                     let agent = &self.agents[agent_idx];
                     let crafting_skill = agent.skills.level("crafting");
 
-                    // Check if we have the recipe
-                    if let Some(recipe) = self.recipe_registry.get(&tool) {
-                        // Check skill requirement
-                        if crafting_skill < recipe.min_crafting_skill {
-                            continue;
-                        }
+                    let Some(recipe) = self.recipe_registry.get(&tool).cloned() else {
+                        self.record_action_failure(agent_id, "craft", "you don't know how to make that");
+                        continue;
+                    };
+                    if crafting_skill < recipe.min_crafting_skill {
+                        let reason = format!("you aren't skilled enough to make a {}", tool.display_name());
+                        self.record_action_failure(agent_id, "craft", &reason);
+                        continue;
+                    }
+                    let missing: Vec<String> = recipe
+                        .ingredients
+                        .iter()
+                        .filter(|(mat_type, amount)| agent.physical.material_count(*mat_type) < *amount)
+                        .map(|(mat_type, amount)| {
+                            format!("{} more {}", amount - agent.physical.material_count(*mat_type), mat_type.display_name())
+                        })
+                        .collect();
+                    if !missing.is_empty() {
+                        let reason = format!("you need {}", missing.join(" and "));
+                        self.record_action_failure(agent_id, "craft", &reason);
+                        continue;
+                    }
+                    if let Some(required_tool) = recipe.required_tool.filter(|t| !agent.physical.has_tool(*t)) {
+                        let reason = format!("you need a {} to make it", required_tool.display_name());
+                        self.record_action_failure(agent_id, "craft", &reason);
+                        continue;
+                    }
 
-                        // Check material requirements
-                        let mut can_craft = true;
-                        for (mat_type, amount) in &recipe.ingredients {
-                            if agent.physical.material_count(*mat_type) < *amount {
-                                can_craft = false;
-                                break;
-                            }
-                        }
+                    // Consume materials
+                    for (mat_type, amount) in &recipe.ingredients {
+                        self.agents[agent_idx].physical.remove_material(*mat_type, *amount);
+                    }
 
-                        // Check tool requirement
-                        if let Some(required_tool) = recipe.required_tool {
-                            if !agent.physical.has_tool(required_tool) {
-                                can_craft = false;
-                            }
-                        }
+                    // Determine quality based on crafting skill
+                    let quality = ToolQuality::from_skill(crafting_skill);
 
-                        if can_craft {
-                            // Consume materials
-                            for (mat_type, amount) in &recipe.ingredients {
-                                self.agents[agent_idx].physical.remove_material(*mat_type, *amount);
-                            }
+                    // Create the tool
+                    let new_tool = Tool::new(tool, quality, Some(agent_id), epoch);
+                    let tool_name = new_tool.display_name();
+                    self.agents[agent_idx].physical.tools.push(new_tool);
 
-                            // Determine quality based on crafting skill
-                            let quality = ToolQuality::from_skill(crafting_skill);
+                    // Practice crafting
+                    self.agents[agent_idx].skills.practice("crafting", epoch);
+                    let improvement = 0.02 + recipe.min_crafting_skill * 0.05;
+                    self.agents[agent_idx].skills.improve("crafting", improvement, epoch);
 
-                            // Create the tool
-                            let new_tool = Tool::new(tool, quality, Some(agent_id), epoch);
-                            let tool_name = new_tool.display_name();
-                            self.agents[agent_idx].physical.tools.push(new_tool);
+                    // Energy cost
+                    self.agents[agent_idx].physical.energy =
+                        (self.agents[agent_idx].physical.energy - 0.2).max(0.0);
 
-                            // Practice crafting
-                            self.agents[agent_idx].skills.practice("crafting", epoch);
-                            let improvement = 0.02 + recipe.min_crafting_skill * 0.05;
-                            self.agents[agent_idx].skills.improve("crafting", improvement, epoch);
+                    // Log event
+                    self.log_and_track(Event::crafted(
+                        epoch,
+                        agent_id,
+                        tool.display_name(),
+                        quality.name(),
+                    ))?;
 
-                            // Energy cost
-                            self.agents[agent_idx].physical.energy =
-                                (self.agents[agent_idx].physical.energy - 0.2).max(0.0);
+                    // Memory
+                    self.agents[agent_idx].memory.remember(Episode::survival(
+                        epoch,
+                        &format!("I crafted a {}", tool_name),
+                        0.3,
+                    ));
 
-                            // Log event
-                            self.log_and_track(Event::crafted(
-                                epoch,
-                                agent_id,
-                                tool.display_name(),
-                                quality.name(),
-                            ))?;
+                    debug!(
+                        "{} crafted a {} {}",
+                        self.agents[agent_idx].name(),
+                        quality.name(),
+                        tool.display_name()
+                    );
+                }
 
-                            // Memory
-                            self.agents[agent_idx].memory.remember(Episode::survival(
-                                epoch,
-                                &format!("I crafted a {}", tool_name),
-                                0.3,
-                            ));
-
-                            debug!(
-                                "{} crafted a {} {}",
-                                self.agents[agent_idx].name(),
-                                quality.name(),
-                                tool.display_name()
-                            );
-                        }
+                Action::Scavenge => {
+                    let (x, y) = (self.agents[agent_idx].physical.x, self.agents[agent_idx].physical.y);
+                    let Some(remains) = self
+                        .world
+                        .get_mut(x, y)
+                        .and_then(|c| c.remains.iter_mut().find(|r| r.food > 0))
+                    else {
+                        self.record_action_failure(agent_id, "scavenge", "there are no remains here");
+                        continue;
+                    };
+                    let food = std::mem::take(&mut remains.food);
+                    let (dead_id, dead_name) = (remains.agent_id, remains.name.clone());
+
+                    let scavenging = self.config.scavenging.clone();
+                    let agent_name = self.agents[agent_idx].name().to_string();
+                    let agent = &mut self.agents[agent_idx];
+                    agent.add_food(food);
+                    let belonging = &mut agent.beliefs.self_belief.perceived_belonging;
+                    *belonging = (*belonging - scavenging.scavenger_shame).max(0.0);
+                    agent.memory.remember(Episode::survival(
+                        epoch,
+                        &format!("I ate from the remains of {} to survive", dead_name),
+                        -0.6,
+                    ));
+
+                    let witnesses = self.nearby_indices(agent_idx);
+                    for idx in witnesses {
+                        let witness = &mut self.agents[idx];
+                        witness.beliefs.update_trust(agent_id, &agent_name, -scavenging.witness_trust_penalty, epoch);
+                        witness.beliefs.update_sentiment(agent_id, &agent_name, -scavenging.witness_sentiment_penalty, epoch);
+                        witness.memory.remember(Episode::social(
+                            epoch,
+                            &format!("I saw {} eat from the remains of {}", agent_name, dead_name),
+                            -0.5,
+                            agent_id,
+                        ));
                     }
+
+                    self.log_and_track(Event::scavenged(epoch, agent_id, dead_id, food))?;
                 }
 
                 Action::Hunt => {
@@ -1316,16 +2307,16 @@ impl Engine {
                     // Calculate success chance (base 40% + skill + tool)
                     let success_chance = 0.4 + hunting_skill * 0.3 + tool_bonus * 0.2;
 
-                    if rand::random::<f64>() < success_chance {
+                    if crate::rng::random::<f64>(crate::rng::Stream::Gathering) < success_chance {
                         // Successful hunt!
                         let food_gained = (3.0 + hunting_skill * 4.0 + tool_bonus * 2.0).round() as u32;
-                        self.agents[agent_idx].add_food(food_gained);
+                        self.agents[agent_idx].add_meat(food_gained);
 
                         // Chance to get hide and bone
-                        if rand::random::<f64>() < 0.7 {
+                        if crate::rng::random::<f64>(crate::rng::Stream::Gathering) < 0.7 {
                             self.agents[agent_idx].physical.add_material(MaterialType::Hide, 1);
                         }
-                        if rand::random::<f64>() < 0.5 {
+                        if crate::rng::random::<f64>(crate::rng::Stream::Gathering) < 0.5 {
                             self.agents[agent_idx].physical.add_material(MaterialType::Bone, 1);
                         }
 
@@ -1373,10 +2364,10 @@ impl Engine {
                     // Calculate success chance (base 50% + skill + tool)
                     let success_chance = 0.5 + foraging_skill * 0.25 + tool_bonus * 0.15;
 
-                    if rand::random::<f64>() < success_chance {
+                    if crate::rng::random::<f64>(crate::rng::Stream::Gathering) < success_chance {
                         // Successful fishing!
                         let food_gained = (2.0 + foraging_skill * 3.0 + tool_bonus).round() as u32;
-                        self.agents[agent_idx].add_food(food_gained);
+                        self.agents[agent_idx].add_meat(food_gained);
 
                         // Practice foraging
                         self.agents[agent_idx].skills.practice("foraging", epoch);
@@ -1402,6 +2393,20 @@ impl Engine {
                         (self.agents[agent_idx].physical.energy - 0.1).max(0.0);
                 }
 
+                Action::Equip { tool } => {
+                    let agent = &mut self.agents[agent_idx];
+                    let Some(equipped) = agent.physical.equip(tool).map(|t| t.display_name()) else {
+                        let reason = format!("you have no working {}", tool.display_name());
+                        self.record_action_failure(agent_id, "equip", &reason);
+                        continue;
+                    };
+                    debug!("{} equipped a {}", agent.name(), equipped);
+                }
+
+                Action::Unequip => {
+                    self.agents[agent_idx].physical.unequip();
+                }
+
                 Action::Chop => {
                     let agent = &self.agents[agent_idx];
 
@@ -1416,8 +2421,9 @@ impl Engine {
                     let foraging_skill = agent.skills.level("foraging");
                     let tool_bonus = agent.physical.tool_bonus_for_skill("foraging");
 
-                    // Chopping is efficient wood gathering
-                    let wood_amount = (3.0 + foraging_skill * 3.0 + tool_bonus * 2.0).round() as u32;
+                    // Chopping is efficient wood gathering, more so with an axe in hand
+                    let wood_bonus = 1.0 + agent.physical.equipped_bonus(ToolEffect::WoodGathering);
+                    let wood_amount = ((3.0 + foraging_skill * 3.0 + tool_bonus * 2.0) * wood_bonus).round() as u32;
                     self.agents[agent_idx].physical.add_material(MaterialType::Wood, wood_amount);
 
                     // Practice foraging
@@ -1446,7 +2452,8 @@ impl Engine {
 
                     let agent = &self.agents[agent_idx];
                     let pos = (agent.physical.x, agent.physical.y);
-                    let registry = StructureRecipeRegistry::new();
+                    let rules = &self.config.structures;
+                    let registry = StructureRecipeRegistry::with_terrain(|t| rules.allowed_terrain(t).to_vec());
 
                     // Get recipe for this structure type
                     let recipe = match registry.get(structure_type) {
@@ -1459,6 +2466,14 @@ impl Engine {
                     if let Some(terrain) = cell_terrain {
                         if !recipe.valid_terrain(terrain) {
                             debug!("{} cannot build {} on this terrain", self.agents[agent_idx].name(), structure_type.display_name());
+                            let allowed: Vec<String> =
+                                recipe.allowed_terrain.iter().map(|t| format!("{:?}", t).to_lowercase()).collect();
+                            let reason = format!(
+                                "a {} can only be built on {} ground",
+                                structure_type.display_name(),
+                                allowed.join(" or ")
+                            );
+                            self.record_action_failure(agent_id, "build", &reason);
                             continue;
                         }
                     }
@@ -1473,43 +2488,17 @@ impl Engine {
                             continue;
                         }
 
-                        // Track owner before we lose reference
-                        let structure_owner = structure.owner;
-
                         // Add progress
                         let crafting_skill = self.agents[agent_idx].skills.level("crafting");
                         let progress = 1 + (crafting_skill * 5.0).round() as u32;
-
-                        if let Some(cell) = self.world.get_mut(pos.0, pos.1) {
-                            if let Some(ref mut s) = cell.structure {
-                                let was_complete = s.is_complete();
-                                s.add_progress(progress, epoch);
-
-                                // Practice crafting
-                                self.agents[agent_idx].skills.practice("crafting", epoch);
-                                self.agents[agent_idx].physical.energy =
-                                    (self.agents[agent_idx].physical.energy - 0.15).max(0.0);
-
-                                if !was_complete && s.is_complete() {
-                                    debug!("{} completed building {}", self.agents[agent_idx].name(), s.display_name());
-                                    self.agents[agent_idx].memory.remember(Episode::survival(
-                                        epoch,
-                                        &format!("I completed building a {}", s.structure_type.display_name()),
-                                        0.5,
-                                    ));
-                                }
-                            }
-                        }
-
-                        // If helping someone else's structure, check for HelpBuild debt fulfillment
-                        if structure_owner != agent_id {
-                            self.check_build_fulfills_debt(agent_id, structure_owner, progress, epoch);
-                        }
+                        self.work_on_structure(agent_idx, pos, progress, epoch)?;
                     } else {
                         // Start a new structure - check if agent can afford materials
                         let can_afford = recipe.can_afford(&self.agents[agent_idx].physical.materials);
                         if !can_afford {
                             debug!("{} cannot afford to build {}", self.agents[agent_idx].name(), structure_type.display_name());
+                            let reason = format!("you need {}", recipe.material_cost_string());
+                            self.record_action_failure(agent_id, "build", &reason);
                             continue;
                         }
 
@@ -1517,14 +2506,19 @@ impl Engine {
                         if let Some(tool_type) = recipe.required_tool {
                             if !self.agents[agent_idx].physical.has_tool(tool_type) {
                                 debug!("{} needs {} to build {}", self.agents[agent_idx].name(), tool_type.display_name(), structure_type.display_name());
+                                let reason = format!("you need a {}", tool_type.display_name());
+                                self.record_action_failure(agent_id, "build", &reason);
                                 continue;
                             }
                         }
 
-                        // Consume materials
+                        // Consume materials, and wear the tool the work takes
                         for (material, amount) in &recipe.materials {
                             self.agents[agent_idx].physical.remove_material(*material, *amount);
                         }
+                        if let Some(tool_type) = recipe.required_tool {
+                            self.agents[agent_idx].physical.wear_tool(tool_type);
+                        }
 
                         // Create the structure
                         let crafting_skill = self.agents[agent_idx].skills.level("crafting");
@@ -1539,9 +2533,11 @@ impl Engine {
 
                         // Add initial progress
                         let progress = 1 + (crafting_skill * 5.0).round() as u32;
+                        let mut completed = false;
                         if let Some(cell) = self.world.get_mut(pos.0, pos.1) {
                             let mut s = new_structure;
                             s.add_progress(progress, epoch);
+                            completed = s.is_complete();
                             cell.structure = Some(s);
                         }
 
@@ -1557,9 +2553,45 @@ impl Engine {
                             &format!("I started building a {}", structure_type.display_name()),
                             0.3,
                         ));
+                        if completed {
+                            self.finish_structure(agent_idx, pos, epoch)?;
+                        }
                     }
                 }
 
+                Action::AssistBuild { structure } => {
+                    // Pitch in on an unfinished structure here or in a neighbouring cell;
+                    // several helpers in one epoch each add their share
+                    let (x, y) = (self.agents[agent_idx].physical.x as i32, self.agents[agent_idx].physical.y as i32);
+                    let site = (-1..=1)
+                        .flat_map(|dy| (-1..=1).map(move |dx| (x + dx, y + dy)))
+                        .filter(|&(sx, sy)| sx >= 0 && sy >= 0)
+                        .map(|(sx, sy)| (sx as usize, sy as usize))
+                        .find(|&(sx, sy)| {
+                            self.world
+                                .get(sx, sy)
+                                .and_then(|c| c.structure.as_ref())
+                                .is_some_and(|s| s.structure_type == structure && !s.is_complete())
+                        });
+                    let Some(pos) = site else {
+                        let reason = format!("there is no unfinished {} nearby", structure.display_name());
+                        self.record_action_failure(agent_id, "assist", &reason);
+                        continue;
+                    };
+
+                    // Crafters do the work; teachers keep everyone at it
+                    let crafting_skill = self.agents[agent_idx].skills.level("crafting");
+                    let teaching_skill = self.agents[agent_idx].skills.level("teaching");
+                    let progress = 1 + ((crafting_skill + teaching_skill * 0.5) * 4.0).round() as u32;
+                    debug!(
+                        "{} helps build a {} (+{})",
+                        self.agents[agent_idx].name(),
+                        structure.display_name(),
+                        progress
+                    );
+                    self.work_on_structure(agent_idx, pos, progress, epoch)?;
+                }
+
                 Action::EnterShelter => {
                     let agent = &self.agents[agent_idx];
                     let pos = (agent.physical.x, agent.physical.y);
@@ -1588,6 +2620,54 @@ impl Engine {
                     }
                 }
 
+                Action::Dismantle => {
+                    use crate::structures::StructureRecipeRegistry;
+
+                    let pos = (self.agents[agent_idx].physical.x, self.agents[agent_idx].physical.y);
+                    let owned = self.world.get(pos.0, pos.1)
+                        .and_then(|c| c.structure.as_ref())
+                        .is_some_and(|s| s.owner == agent_id);
+                    if !owned {
+                        self.record_action_failure(agent_id, "dismantle", "you don't own a structure here");
+                        continue;
+                    }
+
+                    let structure = match self.world.get_mut(pos.0, pos.1).and_then(|c| c.structure.take()) {
+                        Some(s) => s,
+                        None => continue,
+                    };
+
+                    // Salvage materials and any stored food
+                    let registry = StructureRecipeRegistry::new();
+                    let salvaged = registry
+                        .get(structure.structure_type)
+                        .map(|recipe| structure.salvage(recipe))
+                        .unwrap_or_default();
+                    for (material, amount) in &salvaged {
+                        self.agents[agent_idx].physical.add_material(*material, *amount);
+                    }
+                    if let Some(ref inventory) = structure.inventory {
+                        self.agents[agent_idx].physical.food += inventory.food;
+                    }
+
+                    // Nobody can shelter in it anymore
+                    for agent in &mut self.agents {
+                        if agent.physical.sheltered_at == Some(pos) {
+                            agent.physical.leave_shelter();
+                        }
+                    }
+
+                    let name = structure.display_name();
+                    self.log_and_track(Event::structure_dismantled(epoch, agent_id, pos.0, pos.1, &name))?;
+                    self.agents[agent_idx].memory.remember(Episode::survival(
+                        epoch,
+                        &format!("I dismantled my {}", structure.structure_type.display_name()),
+                        0.0,
+                    ));
+
+                    debug!("{} dismantled {} at {:?}", self.agents[agent_idx].name(), name, pos);
+                }
+
                 Action::Deposit { material, amount } => {
                     let agent = &self.agents[agent_idx];
                     let pos = (agent.physical.x, agent.physical.y);
@@ -1681,7 +2761,7 @@ impl Engine {
                                 debug!("{} permitted access to structure", self.agents[agent_idx].name());
 
                                 // Update trust between agents
-                                if let Some(target_idx) = self.agents.iter().position(|a| a.id == target) {
+                                if let Some(target_idx) = self.agent_index(target) {
                                     let agent_name = self.agents[agent_idx].name().to_string();
                                     self.agents[target_idx].beliefs.update_trust(agent_id, &agent_name, 0.2, epoch);
                                 }
@@ -1790,7 +2870,7 @@ impl Engine {
                     }
 
                     // Check if target is present and not a guest
-                    let target_idx = self.agents.iter().position(|a| a.id == target);
+                    let target_idx = self.agent_index(target);
                     let is_trespasser = if let Some(t_idx) = target_idx {
                         let target_agent = &self.agents[t_idx];
                         let same_pos = target_agent.physical.x == pos.0 && target_agent.physical.y == pos.1;
@@ -1854,21 +2934,20 @@ impl Engine {
                             Direction::NorthEast, Direction::NorthWest, Direction::SouthEast, Direction::SouthWest,
                         ];
                         use rand::Rng;
-                        let mut rng = rand::rng();
+                        let mut rng = crate::rng::rng(crate::rng::Stream::Combat);
                         let dir = directions[rng.random_range(0..8)];
                         let (dx, dy) = dir.delta();
                         let new_x = (pos.0 as i32 + dx).max(0) as usize;
                         let new_y = (pos.1 as i32 + dy).max(0) as usize;
 
                         if new_x < self.world.width && new_y < self.world.height {
-                            self.agents[agent_idx].physical.x = new_x;
-                            self.agents[agent_idx].physical.y = new_y;
+                            self.relocate_agent(agent_idx, (new_x, new_y));
                         }
 
                         self.log_and_track(Event::territory_submitted(epoch, owner_id, agent_id))?;
 
                         // Update trust between agents (-0.2 mutual)
-                        if let Some(owner_idx) = self.agents.iter().position(|a| a.id == owner_id) {
+                        if let Some(owner_idx) = self.agent_index(owner_id) {
                             let agent_name = self.agents[agent_idx].name().to_string();
                             let owner_name = self.agents[owner_idx].name().to_string();
                             self.agents[agent_idx].beliefs.update_trust(owner_id, &owner_name, -0.2, epoch);
@@ -1899,15 +2978,17 @@ impl Engine {
 
                     if let Some((owner_id, x, y)) = territory_info {
                         // Combat resolution
-                        let owner_idx = self.agents.iter().position(|a| a.id == owner_id);
+                        let owner_idx = self.agent_index(owner_id);
                         if let Some(o_idx) = owner_idx {
                             // Check for allies on both sides
                             let trespasser_allies = self.find_nearby_allies(agent_id, agent_idx, epoch);
                             let owner_allies = self.find_nearby_allies(owner_id, o_idx, epoch);
 
-                            // Alliance bonuses reduce damage taken (20% per ally, max 50%)
-                            let trespasser_defense = (trespasser_allies.len() as f64 * 0.20).min(0.50);
-                            let owner_defense = (owner_allies.len() as f64 * 0.20).min(0.50);
+                            // Alliance bonuses reduce damage taken, as allies do in any attack
+                            let combat = &self.config.combat;
+                            let defense = |allies: usize| (allies as f64 * combat.ally_reduction).min(combat.max_ally_reduction);
+                            let trespasser_defense = defense(trespasser_allies.len());
+                            let owner_defense = defense(owner_allies.len());
 
                             // Combat damage with alliance effects
                             let base_damage = 0.15;
@@ -1971,15 +3052,14 @@ impl Engine {
                                     Direction::North, Direction::South, Direction::East, Direction::West,
                                 ];
                                 use rand::Rng;
-                                let mut rng = rand::rng();
+                                let mut rng = crate::rng::rng(crate::rng::Stream::Combat);
                                 let dir = directions[rng.random_range(0..4)];
                                 let (dx, dy) = dir.delta();
                                 let new_x = (pos.0 as i32 + dx).max(0) as usize;
                                 let new_y = (pos.1 as i32 + dy).max(0) as usize;
 
                                 if new_x < self.world.width && new_y < self.world.height {
-                                    self.agents[agent_idx].physical.x = new_x;
-                                    self.agents[agent_idx].physical.y = new_y;
+                                    self.relocate_agent(agent_idx, (new_x, new_y));
                                 }
                                 debug!("{} lost territory fight", self.agents[agent_idx].name());
                             }
@@ -2002,7 +3082,7 @@ impl Engine {
 
                     // Check if target is nearby
                     let agent = &self.agents[agent_idx];
-                    let target_idx = self.agents.iter().position(|a| a.id == target && a.is_alive());
+                    let target_idx = self.agent_index(target).filter(|&i| self.agents[i].is_alive());
                     if target_idx.is_none() {
                         continue;
                     }
@@ -2017,12 +3097,14 @@ impl Engine {
                     // Check proposal limit
                     if self.trade_state.count_pending_from(agent_id) >= trade_config.max_pending_proposals {
                         debug!("{} has too many pending proposals", agent_id);
+                        self.record_action_failure(agent_id, "offer a trade", "you already have too many offers waiting");
                         continue;
                     }
 
                     // Validate agent has the items they're offering (except promises)
-                    if !self.agent_has_items(agent_idx, &offering) {
+                    if !self.agents[agent_idx].has_items(&offering) {
                         debug!("{} doesn't have items to offer", agent_id);
+                        self.record_action_failure(agent_id, "offer a trade", "you don't have what you offered");
                         continue;
                     }
 
@@ -2080,6 +3162,7 @@ impl Engine {
                         .collect();
 
                     if proposal_index >= pending.len() {
+                        self.record_action_failure(agent_id, "accept the trade", "there is no such offer");
                         continue;
                     }
                     let proposal_id = pending[proposal_index];
@@ -2090,19 +3173,23 @@ impl Engine {
                         _ => continue,
                     };
 
-                    let proposer_idx = self.agents.iter().position(|a| a.id == proposal.proposer);
+                    let proposer_idx = self.agent_index(proposal.proposer);
                     if proposer_idx.is_none() {
                         continue;
                     }
                     let proposer_idx = proposer_idx.unwrap();
 
                     // Validate both parties still have items
-                    if !self.agent_has_items(proposer_idx, &proposal.offering) {
+                    if !self.agents[proposer_idx].has_items(&proposal.offering) {
                         debug!("Proposer no longer has offered items");
+                        let reason = format!("{} no longer has what they offered", self.agents[proposer_idx].name());
+                        self.record_action_failure(agent_id, "accept the trade", &reason);
                         continue;
                     }
-                    if !self.agent_has_items(agent_idx, &proposal.requesting) {
+                    if !self.agents[agent_idx].has_items(&proposal.requesting) {
                         debug!("Accepter doesn't have requested items");
+                        let reason = format!("you don't have {}", proposal.requesting_description());
+                        self.record_action_failure(agent_id, "accept the trade", &reason);
                         continue;
                     }
 
@@ -2179,7 +3266,7 @@ impl Engine {
                     ))?;
 
                     // Minor sentiment penalty
-                    let proposer_idx = self.agents.iter().position(|a| a.id == proposal.proposer);
+                    let proposer_idx = self.agent_index(proposal.proposer);
                     if let Some(p_idx) = proposer_idx {
                         let agent_name = self.agents[agent_idx].name().to_string();
                         self.agents[p_idx].beliefs.update_sentiment(agent_id, &agent_name, -trade_config.decline_trust_penalty, epoch);
@@ -2210,7 +3297,7 @@ impl Engine {
                     };
 
                     // Validate agent has items they're offering
-                    if !self.agent_has_items(agent_idx, &offering) {
+                    if !self.agents[agent_idx].has_items(&offering) {
                         continue;
                     }
 
@@ -2273,15 +3360,98 @@ impl Engine {
 
                     debug!("{} cancels their trade offer", agent_id);
                 }
+
+                Action::Promise { target, promise } => {
+                    let Some(target_idx) = self.agent_index(target) else {
+                        continue;
+                    };
+
+                    // A bare food promise falls due by the usual promise deadline
+                    let deadline = self.config.trade.default_promise_deadline;
+                    let promise = match promise {
+                        TradeableItem::Food(amount) => TradeableItem::FutureGiftPromise {
+                            amount,
+                            deadline_epochs: deadline,
+                        },
+                        other => other,
+                    };
+
+                    // Casual promises have no source trade, so they carry a nil trade id
+                    let Some(debt) = ServiceDebt::from_promise(&promise, agent_id, target, Uuid::nil(), epoch, deadline) else {
+                        continue;
+                    };
+                    self.trade_state.add_debt(debt);
+
+                    let promise_desc = promise.describe();
+                    let agent_name = self.agents[agent_idx].name().to_string();
+                    let target_name = self.agents[target_idx].name().to_string();
+                    self.agents[agent_idx].memory.remember(Episode::social(
+                        epoch,
+                        &format!("I promised {} {}", target_name, promise_desc),
+                        0.1,
+                        target,
+                    ));
+                    self.agents[target_idx].memory.remember(Episode::social(
+                        epoch,
+                        &format!("{} promised me {}", agent_name, promise_desc),
+                        0.2,
+                        agent_id,
+                    ));
+
+                    self.log_and_track(Event::spoke(
+                        epoch,
+                        agent_id,
+                        target,
+                        &format!("I promise you {}", promise_desc),
+                    ))?;
+
+                    debug!("{} promises {} to {}", agent_name, promise_desc, target_name);
+                }
+
+                Action::SetGoal { description } => {
+                    let description = description.trim().to_string();
+                    if description.is_empty() {
+                        continue;
+                    }
+
+                    self.agents[agent_idx].active_goal = Some(Goal::Custom(description.clone()));
+                    self.log_and_track(Event::goal_set(epoch, agent_id, &description))?;
+
+                    debug!("{} resolves to {}", self.agents[agent_idx].name(), description);
+                }
+
+                Action::ProposePeace { rival_group } => {
+                    self.conduct_diplomacy(agent_idx, &rival_group, true, epoch)?;
+                }
+
+                Action::DeclareWar { rival_group } => {
+                    self.conduct_diplomacy(agent_idx, &rival_group, false, epoch)?;
+                }
             }
         }
 
-        // Clean up broken tools at end of action resolution
+        // Agents may have moved; mark the cells they ended up in
+        let touched: Vec<Uuid> = self
+            .agent_changes
+            .iter()
+            .filter(|(_, changed)| **changed == epoch)
+            .map(|(id, _)| *id)
+            .collect();
+        for agent_id in touched {
+            self.mark_agent_changed(agent_id, epoch);
+        }
+
+        // Clean up broken tools at end of action resolution; their owners will need new ones
         let mut tool_break_events = Vec::new();
         for agent in &mut self.agents {
             if agent.is_alive() {
                 let broken = agent.physical.cleanup_broken_tools();
                 for tool in broken {
+                    agent.memory.remember(Episode::survival(
+                        epoch,
+                        &format!("My {} wore out and broke", tool.tool_type.display_name()),
+                        0.3,
+                    ));
                     tool_break_events.push((agent.id, tool.display_name()));
                 }
             }
@@ -2301,6 +3471,28 @@ impl Engine {
             .map(|a| (a.id, a.name().to_string()))
             .collect();
 
+        // Keep an eye on attackers still in sight; forget old or dead ones
+        let threat_memory = self.config.instincts.threat_memory;
+        for idx in 0..self.agents.len() {
+            let Some(threat) = &self.agents[idx].threat_source else {
+                continue;
+            };
+            let attacker = self.agent(threat.attacker).filter(|a| a.is_alive());
+            if attacker.is_none() || epoch > threat.epoch + threat_memory {
+                self.agents[idx].threat_source = None;
+                continue;
+            }
+            let seen = attacker
+                .filter(|a| is_adjacent(a, &self.agents[idx]))
+                .map(|a| (a.physical.x, a.physical.y));
+            if let (Some(position), Some(threat)) = (seen, &mut self.agents[idx].threat_source)
+                && threat.position != position
+            {
+                threat.position = position;
+                threat.cornered = false;
+            }
+        }
+
         // Update perceived safety based on recent events
         for agent in &mut self.agents {
             if !agent.is_alive() {
@@ -2311,7 +3503,8 @@ impl Engine {
 
             // Update food location beliefs based on current perception
             if let Some(cell) = self.world.get(pos.0, pos.1) {
-                if cell.food > 0 {
+                // Also refresh known spots that have since been picked clean
+                if cell.food > 0 || agent.beliefs.food_at(pos.0, pos.1).is_some() {
                     agent.beliefs.update_food_belief(
                         pos.0,
                         pos.1,
@@ -2379,13 +3572,114 @@ impl Engine {
         }
     }
 
+    /// Note where agents stand and who leads whom, and measure how far each
+    /// has come toward its aspiration
+    fn update_legacies(&mut self) {
+        let followers: HashMap<Uuid, usize> = self
+            .group_tracker
+            .current_groups()
+            .iter()
+            .filter_map(|g| g.leader.map(|leader| (leader, g.members.len().saturating_sub(1))))
+            .collect();
+        for agent in self.agents.iter_mut().filter(|a| a.is_alive()) {
+            agent.legacy.visit(agent.physical.x, agent.physical.y);
+            agent.legacy.followers = followers.get(&agent.id).copied();
+            agent.legacy.update(&agent.identity.aspiration, &self.config.legacy);
+        }
+    }
+
+    /// Track how long each agent has starved, and send clusters that have
+    /// starved together long enough off in one direction, toward the best food
+    /// any of them remembers
+    fn detect_migrations(&mut self, epoch: usize) -> Result<()> {
+        use rand::Rng;
+        let config = self.config.migration.clone();
+        if !config.enabled {
+            return Ok(());
+        }
+
+        // Relieved hunger, arrival or a spent impulse ends a migration
+        for agent in self.agents.iter_mut().filter(|a| a.is_alive()) {
+            if agent.physical.hunger >= config.hunger {
+                agent.physical.starving_epochs += 1;
+            } else {
+                agent.physical.starving_epochs = 0;
+                agent.migration = None;
+            }
+            let pos = (agent.physical.x, agent.physical.y);
+            if agent
+                .migration
+                .as_ref()
+                .is_some_and(|m| epoch >= m.began + config.duration || m.destination == Some(pos))
+            {
+                agent.migration = None;
+            }
+        }
+
+        // Cluster the long-starving agents not already on the move
+        let mut unassigned: Vec<usize> = (0..self.agents.len())
+            .filter(|&i| {
+                let agent = &self.agents[i];
+                agent.is_alive() && agent.physical.starving_epochs >= config.epochs && agent.migration.is_none()
+            })
+            .collect();
+        let near = |a: &Agent, b: &Agent| {
+            a.physical.x.abs_diff(b.physical.x).max(a.physical.y.abs_diff(b.physical.y)) <= config.radius
+        };
+        let mut clusters = Vec::new();
+        while let Some(first) = unassigned.pop() {
+            let mut cluster = vec![first];
+            let mut next = 0;
+            while next < cluster.len() {
+                let current = &self.agents[cluster[next]];
+                let (joining, rest): (Vec<usize>, Vec<usize>) =
+                    unassigned.into_iter().partition(|&i| near(current, &self.agents[i]));
+                cluster.extend(joining);
+                unassigned = rest;
+                next += 1;
+            }
+            if cluster.len() >= config.min_agents {
+                cluster.sort_unstable();
+                clusters.push(cluster);
+            }
+        }
+
+        for cluster in clusters {
+            let count = cluster.len();
+            let centre = (
+                cluster.iter().map(|&i| self.agents[i].physical.x).sum::<usize>() / count,
+                cluster.iter().map(|&i| self.agents[i].physical.y).sum::<usize>() / count,
+            );
+            let destination = cluster
+                .iter()
+                .filter_map(|&i| self.agents[i].beliefs.best_food_elsewhere(centre.0, centre.1, epoch))
+                .max_by_key(|b| b.belief.amount)
+                .map(|b| (b.x, b.y));
+            // With nowhere better remembered, any way out is as good as another
+            let direction = destination.and_then(|to| Direction::toward(centre, to)).unwrap_or_else(|| {
+                Direction::ALL[crate::rng::rng(crate::rng::Stream::Decisions).random_range(0..Direction::ALL.len())]
+            });
+
+            // Setting out starts the count afresh, so a failed migration
+            // isn't followed at once by another
+            for &i in &cluster {
+                self.agents[i].migration = Some(Migration { direction, destination, companions: count, began: epoch });
+                self.agents[i].physical.starving_epochs = 0;
+            }
+            info!("{} starving agents set out {} together", count, direction.name());
+            let members = cluster.iter().map(|&i| self.agents[i].id).collect();
+            self.log_and_track(Event::migration_began(epoch, members, direction.name(), destination))?;
+        }
+        Ok(())
+    }
+
     /// Detect and log group/alliance changes
     fn detect_groups(&mut self, epoch: usize) -> Result<()> {
         let changes = self.group_tracker.detect(&self.agents, epoch);
 
         // Log new groups
         for group in &changes.formed {
-            let members: Vec<_> = group.members.iter().copied().collect();
+            let members = group.member_ids();
             self.log_and_track(Event::group_formed(epoch, &group.name, members))?;
             info!(
                 "Group formed: {} with {} members",
@@ -2396,7 +3690,7 @@ impl Engine {
 
         // Log dissolved groups
         for group in &changes.dissolved {
-            let members: Vec<_> = group.members.iter().copied().collect();
+            let members = group.member_ids();
             self.log_and_track(Event::group_dissolved(epoch, &group.name, members))?;
             info!("Group dissolved: {}", group.name);
         }
@@ -2405,12 +3699,12 @@ impl Engine {
         for (group, added, removed) in &changes.changed {
             let added_names: Vec<_> = added
                 .iter()
-                .filter_map(|id| self.agents.iter().find(|a| a.id == *id))
+                .filter_map(|id| self.agent(*id))
                 .map(|a| a.name())
                 .collect();
             let removed_names: Vec<_> = removed
                 .iter()
-                .filter_map(|id| self.agents.iter().find(|a| a.id == *id))
+                .filter_map(|id| self.agent(*id))
                 .map(|a| a.name())
                 .collect();
 
@@ -2426,7 +3720,7 @@ impl Engine {
                 format!("{} left", removed_names.join(", "))
             };
 
-            self.log_and_track(Event::group_changed(epoch, &group.name, &description))?;
+            self.log_and_track(Event::group_changed(epoch, &group.name, &description, added.clone()))?;
             debug!("Group {} changed: {}", group.name, description);
         }
 
@@ -2447,7 +3741,7 @@ impl Engine {
                 .unwrap_or("Unknown");
 
             let old_leader_name = old_leader
-                .and_then(|id| self.agents.iter().find(|a| a.id == id))
+                .and_then(|id| self.agent(id))
                 .map(|a| a.name());
 
             if let Some(old_name) = old_leader_name {
@@ -2563,12 +3857,12 @@ impl Engine {
         }
 
         use rand::Rng;
-        let mut rng = rand::rng();
+        let mut rng = crate::rng::rng(crate::rng::Stream::Aging);
         let aging_config = &self.config.aging;
 
-        let mut death_events = Vec::new();
+        let mut deaths = Vec::new();
 
-        for agent in &mut self.agents {
+        for (agent_idx, agent) in self.agents.iter_mut().enumerate() {
             if !agent.is_alive() {
                 continue;
             }
@@ -2578,30 +3872,138 @@ impl Engine {
             let age = agent.physical.age;
 
             // Check for natural death
-            if age >= aging_config.max_lifespan {
+            let max_lifespan = agent.max_lifespan(aging_config);
+            if age >= max_lifespan {
                 // Certain death at max lifespan
                 agent.physical.health = 0.0;
-                death_events.push(Event::died(epoch, agent.id, "old age"));
+                deaths.push(agent_idx);
             } else if age >= aging_config.elderly_start {
                 // Probabilistic death after elderly_start
                 let age_factor = (age - aging_config.elderly_start) as f64
-                    / (aging_config.max_lifespan - aging_config.elderly_start) as f64;
+                    / (max_lifespan - aging_config.elderly_start) as f64;
                 let death_probability = aging_config.death_probability_rate * age_factor;
 
                 if rng.random::<f64>() < death_probability {
                     agent.physical.health = 0.0;
-                    death_events.push(Event::died(epoch, agent.id, "old age"));
+                    deaths.push(agent_idx);
                 }
             }
         }
 
-        for event in death_events {
-            self.log_and_track(event)?;
+        for agent_idx in deaths {
+            self.handle_death(epoch, agent_idx, "old age")?;
         }
 
         Ok(())
     }
 
+    /// Log an agent's death and pass on what they carried
+    fn handle_death(&mut self, epoch: usize, agent_idx: usize, cause: &str) -> Result<()> {
+        self.agents[agent_idx].physical.died_at = Some(epoch);
+        self.log_and_track(Event::died(epoch, self.agents[agent_idx].id, cause))?;
+        self.settle_estate(epoch, agent_idx);
+
+        if self.config.scavenging.enabled {
+            let dead = &self.agents[agent_idx];
+            let remains = Remains {
+                agent_id: dead.id,
+                name: dead.name().to_string(),
+                died_epoch: epoch,
+                food: self.config.scavenging.remains_food,
+            };
+            if let Some(cell) = self.world.get_mut(dead.physical.x, dead.physical.y) {
+                cell.remains.push(remains);
+            }
+        }
+        Ok(())
+    }
+
+    /// Move agents dead for `archive_dead_after` epochs into the archive, so
+    /// every phase stops walking past them
+    fn archive_dead(&mut self, epoch: usize) {
+        let after = self.config.simulation.archive_dead_after;
+        if after == 0 {
+            return;
+        }
+        let (archived, active): (Vec<Agent>, Vec<Agent>) = std::mem::take(&mut self.agents)
+            .into_iter()
+            .partition(|a| a.physical.died_at.is_some_and(|died| epoch >= died + after));
+        self.agents = active;
+        self.agent_indices = index_agents(&self.agents);
+        self.reindex_positions();
+        if archived.is_empty() {
+            return;
+        }
+        debug!("Archived {} agents dead for {} epochs", archived.len(), after);
+        for agent in &archived {
+            self.vitals.remove(&agent.id);
+        }
+        self.deceased.extend(archived);
+    }
+
+    /// Let remains that are too old to eat, or already picked clean, disappear
+    fn decay_remains(&mut self, epoch: usize) {
+        let max_age = self.config.scavenging.remains_epochs;
+        for cell in &mut self.world.cells {
+            cell.remains.retain(|r| r.food > 0 && epoch.saturating_sub(r.died_epoch) < max_age);
+        }
+    }
+
+    /// Hand a dead agent's food and tools to their nearest kin when inheritance
+    /// is configured that way, otherwise leave them on the cell where they died
+    fn settle_estate(&mut self, epoch: usize, agent_idx: usize) {
+        let dead = &mut self.agents[agent_idx];
+        let (dead_id, x, y) = (dead.id, dead.physical.x, dead.physical.y);
+        let food = std::mem::take(&mut dead.physical.food);
+        let mut tools = std::mem::take(&mut dead.physical.tools);
+        dead.physical.equipped = None;
+        if food == 0 && tools.is_empty() {
+            return;
+        }
+
+        let heir_idx = if self.config.inheritance.mode == InheritanceMode::Kin {
+            let family = &self.agents[agent_idx].reproduction.family;
+            self.agents
+                .iter()
+                .enumerate()
+                .filter(|(_, a)| {
+                    a.is_alive()
+                        && (family.parents.contains(&a.id)
+                            || family.children.contains(&a.id)
+                            || family.mate_history.contains(&a.id))
+                })
+                .min_by_key(|(_, a)| a.physical.x.abs_diff(x).max(a.physical.y.abs_diff(y)))
+                .map(|(idx, _)| idx)
+        } else {
+            None
+        };
+
+        let dead_name = self.agents[agent_idx].name().to_string();
+        match heir_idx {
+            Some(heir_idx) => {
+                let tool_count = tools.len();
+                let heir = &mut self.agents[heir_idx];
+                heir.add_food(food);
+                heir.physical.tools.append(&mut tools);
+                heir.memory.remember(Episode::new(
+                    epoch,
+                    format!("I inherited {} food and {} tools from {}", food, tool_count, dead_name),
+                    0.2,
+                    vec![dead_id],
+                    EpisodeCategory::Gift,
+                ));
+                debug!("{} inherited {}'s belongings", heir.name(), dead_name);
+            }
+            None => {
+                if let Some(cell) = self.world.get_mut(x, y) {
+                    cell.food += food;
+                    cell.dropped_tools.append(&mut tools);
+                }
+                debug!("{}'s belongings were left at ({}, {})", dead_name, x, y);
+            }
+        }
+    }
+
     // ==================== Reproduction System ====================
 
     /// Tick gestations: energy drain during pregnancy, check for births
@@ -2640,28 +4042,27 @@ impl Engine {
 
         // Process births
         for (_agent_id, carrier_id, partner_id, offspring_identity, _offspring_name) in births {
-            let carrier_idx = match self.agents.iter().position(|a| a.id == carrier_id) {
+            let carrier_idx = match self.agent_index(carrier_id) {
                 Some(idx) => idx,
                 None => continue,
             };
             let carrier = &self.agents[carrier_idx];
             let spawn_pos = self.find_adjacent_spawn(carrier.physical.x, carrier.physical.y);
 
-            // Calculate generation (max of parents + 1)
+            // Calculate generation (max of parents + 1); the partner may have
+            // died, or even been archived, since conception
             let carrier_gen = self.agents[carrier_idx].reproduction.family.generation;
-            let partner_idx = self.agents.iter().position(|a| a.id == partner_id);
-            let partner_gen = partner_idx
-                .map(|idx| self.agents[idx].reproduction.family.generation)
+            let partner = self.find_agent(partner_id);
+            let partner_gen = partner
+                .map(|p| p.reproduction.family.generation)
                 .unwrap_or(0);
             let offspring_generation = carrier_gen.max(partner_gen) + 1;
 
             // Get parent skills for inheritance
-            let parent_skills = partner_idx.map(|idx| {
-                (&self.agents[carrier_idx].skills, &self.agents[idx].skills)
-            });
+            let parent_skills = partner.map(|p| (&self.agents[carrier_idx].skills, &p.skills));
 
             // Create the child
-            let child = Agent::new_with_identity(
+            let mut child = Agent::new_with_identity(
                 offspring_identity,
                 spawn_pos.0,
                 spawn_pos.1,
@@ -2673,17 +4074,27 @@ impl Engine {
             let child_id = child.id;
             let child_name = child.name().to_string();
 
-            // Log birth event
-            self.log_and_track(Event::birth_occurred(
-                epoch,
-                carrier_id,
-                partner_id,
-                child_id,
-                &child_name,
-            ))?;
+            // Log birth event, noting which parent the child looks like
+            let mut birth = Event::birth_occurred(epoch, carrier_id, partner_id, child_id, &child_name);
+            if let Some(partner) = self.find_agent(partner_id) {
+                let looks = &child.identity.phenotype;
+                let (carrier_looks, partner_looks) =
+                    (&self.agents[carrier_idx].identity.phenotype, &partner.identity.phenotype);
+                let resembled = if looks.takes_after(carrier_looks, partner_looks) { carrier_id } else { partner_id };
+                birth.data.about = Some(resembled);
+            }
+            self.log_and_track(birth)?;
 
             info!("{} was born to the family!", child_name);
 
+            // The birthplace becomes home for the new family
+            let home = format!("where {} was born", child_name);
+            child.beliefs.remember_landmark(spawn_pos.0, spawn_pos.1, LandmarkKind::Home, "where you were born", epoch);
+            self.agents[carrier_idx].beliefs.remember_landmark(spawn_pos.0, spawn_pos.1, LandmarkKind::Home, &home, epoch);
+            if let Some(partner_idx) = self.agent_index(partner_id) {
+                self.agents[partner_idx].beliefs.remember_landmark(spawn_pos.0, spawn_pos.1, LandmarkKind::Home, &home, epoch);
+            }
+
             // Queue the child to be added
             self.pending_births.push(child);
 
@@ -2691,9 +4102,9 @@ impl Engine {
             self.agents[carrier_idx].reproduction.gestation = None;
             self.agents[carrier_idx].reproduction.family.children.push(child_id);
 
-            // Update family records for partner (if alive)
-            if let Some(partner_idx) = self.agents.iter().position(|a| a.id == partner_id) {
-                self.agents[partner_idx].reproduction.family.children.push(child_id);
+            // Update family records for partner, alive or not
+            if let Some(partner) = self.agents.iter_mut().chain(&mut self.deceased).find(|a| a.id == partner_id) {
+                partner.reproduction.family.children.push(child_id);
             }
 
             // Create memories for parents
@@ -2704,7 +4115,7 @@ impl Engine {
                 child_id,
             ));
 
-            if let Some(partner_idx) = self.agents.iter().position(|a| a.id == partner_id) {
+            if let Some(partner_idx) = self.agent_index(partner_id) {
                 let carrier_name = self.agents[carrier_idx].name().to_string();
                 self.agents[partner_idx].memory.remember(Episode::social(
                     epoch,
@@ -2725,15 +4136,27 @@ impl Engine {
         }
 
         let decay = self.config.reproduction.courtship_decay;
+        let positions: HashMap<Uuid, (usize, usize)> = self
+            .agents
+            .iter()
+            .filter(|a| a.is_alive())
+            .map(|a| (a.id, (a.physical.x, a.physical.y)))
+            .collect();
 
         for agent in &mut self.agents {
             if !agent.is_alive() {
                 continue;
             }
 
-            // Decay courtship scores
-            agent.reproduction.courtship_progress.retain(|_, score| {
-                *score -= decay;
+            // Decay courtship scores, except with partners still close by
+            let pos = (agent.physical.x, agent.physical.y);
+            agent.reproduction.courtship_progress.retain(|partner, score| {
+                let together = positions
+                    .get(partner)
+                    .is_some_and(|p| p.0.abs_diff(pos.0) <= 1 && p.1.abs_diff(pos.1) <= 1);
+                if !together {
+                    *score -= decay;
+                }
                 *score > 0.0
             });
 
@@ -2744,21 +4167,61 @@ impl Engine {
         }
     }
 
+    /// Sample each living agent's health for trend reporting
+    fn record_vitals(&mut self, epoch: usize) {
+        for agent in self.agents.iter().filter(|a| a.is_alive()) {
+            self.vitals
+                .entry(agent.id)
+                .or_insert_with(|| VitalSigns::new(epoch))
+                .record_health(agent.physical.health);
+        }
+        let agents = &self.agents;
+        self.vitals.retain(|id, _| agents.iter().any(|a| a.id == *id && a.is_alive()));
+    }
+
+    /// Count consecutive epochs with fewer than two fertile agents and nobody gestating
+    fn track_viability(&mut self, epoch: usize) {
+        if !self.config.reproduction.enabled || !self.config.reproduction.stop_when_nonviable {
+            return;
+        }
+
+        let alive = self.alive_count();
+        let fertile = self.agents.iter().filter(|a| a.is_fertile(&self.config.aging)).count();
+        let gestating = self.agents.iter().any(|a| a.is_alive() && a.reproduction.gestation.is_some());
+
+        if fertile >= 2 || gestating {
+            if self.nonviable_epochs > 0 {
+                info!("Epoch {}: population viable again ({} fertile)", epoch, fertile);
+            }
+            self.nonviable_epochs = 0;
+            return;
+        }
+
+        if self.nonviable_epochs == 0 {
+            info!("Epoch {}: population no longer viable ({} alive, {} fertile)", epoch, alive, fertile);
+        } else {
+            debug!("Epoch {}: non-viable for {} epochs ({} alive)", epoch, self.nonviable_epochs, alive);
+        }
+        self.nonviable_epochs += 1;
+    }
+
     /// Resolve mating actions - requires mutual consent
-    fn resolve_mating(&mut self, epoch: usize, actions: &HashMap<Uuid, Action>) -> Result<()> {
+    fn resolve_mating(&mut self, epoch: usize, actions: &BTreeMap<Uuid, Action>) -> Result<()> {
         if !self.config.reproduction.enabled {
             return Ok(());
         }
 
-        // Find all Mate actions
+        // Find all Mate actions; those failing validation were already reported
+        let ctx = ActionContext { agents: &self.agents, config: &self.config };
         let mate_actions: Vec<(Uuid, Uuid)> = actions
             .iter()
             .filter_map(|(agent_id, action)| {
-                if let Action::Mate { target } = action {
-                    Some((*agent_id, *target))
-                } else {
-                    None
-                }
+                let Action::Mate { target } = action else {
+                    return None;
+                };
+                let agent = self.agent(*agent_id)?;
+                action.validate(agent, &ctx).ok()?;
+                Some((*agent_id, *target))
             })
             .collect();
 
@@ -2781,7 +4244,7 @@ impl Engine {
                 processed.insert(*target_a);
             } else {
                 // One-sided - rejection
-                if let Some(agent_idx) = self.agents.iter().position(|a| a.id == *agent_a) {
+                if let Some(agent_idx) = self.agent_index(*agent_a) {
                     let target_name = self.agents
                         .iter()
                         .find(|a| a.id == *target_a)
@@ -2794,6 +4257,8 @@ impl Engine {
                         -0.1,
                         *target_a,
                     ));
+                    let reason = format!("{} did not want to mate with you", target_name);
+                    self.record_action_failure(*agent_a, "mate", &reason);
                 }
             }
         }
@@ -2801,82 +4266,82 @@ impl Engine {
         Ok(())
     }
 
-    /// Attempt mating between two agents
-    fn attempt_mating(&mut self, epoch: usize, agent_a: Uuid, agent_b: Uuid) -> Result<()> {
-        let idx_a = self.agents.iter().position(|a| a.id == agent_a);
-        let idx_b = self.agents.iter().position(|a| a.id == agent_b);
-
-        let (idx_a, idx_b) = match (idx_a, idx_b) {
-            (Some(a), Some(b)) => (a, b),
-            _ => return Ok(()),
-        };
-
-        // Validate mating conditions
+    /// The first precondition that keeps a willing pair from mating, if any
+    fn mating_block(&self, idx_a: usize, idx_b: usize) -> Option<String> {
         let config = &self.config.reproduction;
+        let (a, b) = (&self.agents[idx_a], &self.agents[idx_b]);
+        let pair = [a, b];
 
-        // Check adjacency
-        if !is_adjacent(&self.agents[idx_a], &self.agents[idx_b]) {
-            return Ok(());
+        if !is_adjacent(a, b) {
+            return Some(format!("{} and {} are not next to each other", a.name(), b.name()));
         }
-
-        // Check if both are alive
-        if !self.agents[idx_a].is_alive() || !self.agents[idx_b].is_alive() {
-            return Ok(());
+        if let Some(dead) = pair.iter().find(|x| !x.is_alive()) {
+            return Some(format!("{} is dead", dead.name()));
         }
-
-        // Check health requirements
-        if self.agents[idx_a].physical.health < config.min_health_to_reproduce
-            || self.agents[idx_b].physical.health < config.min_health_to_reproduce
-        {
-            return Ok(());
+        if let Some(weak) = pair.iter().find(|x| x.physical.health < config.min_health_to_reproduce) {
+            return Some(format!("{} is not healthy enough", weak.name()));
         }
-
-        // Check energy requirements
-        if self.agents[idx_a].physical.energy < config.min_energy_to_reproduce
-            || self.agents[idx_b].physical.energy < config.min_energy_to_reproduce
-        {
-            return Ok(());
+        if let Some(tired) = pair.iter().find(|x| x.physical.energy < config.min_energy_to_reproduce) {
+            return Some(format!("{} has too little energy", tired.name()));
         }
-
-        // Check food requirements
-        if self.agents[idx_a].physical.food < config.mating_food_cost
-            || self.agents[idx_b].physical.food < config.mating_food_cost
-        {
-            return Ok(());
+        if let Some(hungry) = pair.iter().find(|x| x.physical.food < config.mating_food_cost) {
+            return Some(format!(
+                "{} has too little food ({} of {} needed)",
+                hungry.name(),
+                hungry.physical.food,
+                config.mating_food_cost
+            ));
         }
-
-        // Check mating cooldowns
-        if self.agents[idx_a].reproduction.mating_cooldown > 0
-            || self.agents[idx_b].reproduction.mating_cooldown > 0
-        {
-            return Ok(());
+        if let Some(resting) = pair.iter().find(|x| x.reproduction.mating_cooldown > 0) {
+            return Some(format!(
+                "{} needs {} more days before mating again",
+                resting.name(),
+                resting.reproduction.mating_cooldown
+            ));
         }
-
-        // Check if either is already gestating
-        if self.agents[idx_a].reproduction.gestation.is_some()
-            || self.agents[idx_b].reproduction.gestation.is_some()
-        {
-            return Ok(());
+        if let Some(expecting) = pair.iter().find(|x| x.reproduction.gestation.is_some()) {
+            return Some(format!("{} is already expecting", expecting.name()));
         }
 
-        // Check courtship threshold (average of both scores)
-        let score_a = self.agents[idx_a]
-            .reproduction
-            .courtship_progress
-            .get(&agent_b)
-            .copied()
-            .unwrap_or(0.0);
-        let score_b = self.agents[idx_b]
-            .reproduction
-            .courtship_progress
-            .get(&agent_a)
-            .copied()
-            .unwrap_or(0.0);
+        // Courtship threshold (average of both scores)
+        let score_a = a.reproduction.courtship_progress.get(&b.id).copied().unwrap_or(0.0);
+        let score_b = b.reproduction.courtship_progress.get(&a.id).copied().unwrap_or(0.0);
         let avg_score = (score_a + score_b) / 2.0;
-
         if avg_score < config.courtship_threshold {
+            return Some(format!(
+                "their courtship has not gone far enough ({:.0}% of {:.0}%)",
+                avg_score * 100.0,
+                config.courtship_threshold * 100.0
+            ));
+        }
+        None
+    }
+
+    /// Attempt mating between two agents
+    fn attempt_mating(&mut self, epoch: usize, agent_a: Uuid, agent_b: Uuid) -> Result<()> {
+        let idx_a = self.agent_index(agent_a);
+        let idx_b = self.agent_index(agent_b);
+
+        let (idx_a, idx_b) = match (idx_a, idx_b) {
+            (Some(a), Some(b)) => (a, b),
+            _ => return Ok(()),
+        };
+
+        if let Some(reason) = self.mating_block(idx_a, idx_b) {
+            debug!(
+                "{} and {} could not mate: {}",
+                self.agents[idx_a].name(),
+                self.agents[idx_b].name(),
+                reason
+            );
+            if self.config.reproduction.log_failed_matings {
+                self.log_and_track(Event::mate_failed(epoch, agent_a, agent_b, &reason))?;
+            }
+            self.record_action_failure(agent_a, "mate", &reason);
+            self.record_action_failure(agent_b, "mate", &reason);
             return Ok(());
         }
+        let config = &self.config.reproduction;
 
         // All checks passed - proceed with mating!
         let name_a = self.agents[idx_a].name().to_string();
@@ -2897,7 +4362,7 @@ impl Engine {
         self.agents[idx_b].reproduction.family.mate_history.push(agent_a);
 
         // Randomly select carrier (who gestates)
-        let carrier_idx = if rand::random::<bool>() { idx_a } else { idx_b };
+        let carrier_idx = if crate::rng::random::<bool>(crate::rng::Stream::Mating) { idx_a } else { idx_b };
         let partner_idx = if carrier_idx == idx_a { idx_b } else { idx_a };
         let carrier_id = self.agents[carrier_idx].id;
         let partner_id = self.agents[partner_idx].id;
@@ -2913,6 +4378,7 @@ impl Engine {
             offspring_name.clone(),
             &self.agents[idx_a].identity,
             &self.agents[idx_b].identity,
+            config.hue_drift,
         );
 
         // Create gestation
@@ -2956,17 +4422,191 @@ impl Engine {
         Ok(())
     }
 
+    /// Apply the scenario's scripted events for this epoch, in config order
+    fn apply_scripted_events(&mut self, epoch: usize) -> Result<()> {
+        let scripted: Vec<ScriptedAction> = self
+            .config
+            .scenario
+            .scripted_events
+            .iter()
+            .filter(|e| e.epoch == epoch)
+            .map(|e| e.action.clone())
+            .collect();
+
+        for action in scripted {
+            match action {
+                ScriptedAction::SpawnAgent { name, x, y, model } => {
+                    let x = x.unwrap_or(self.world.width / 2).min(self.world.width - 1);
+                    let y = y.unwrap_or(self.world.height / 2).min(self.world.height - 1);
+                    let mut agent = Agent::new(name, x, y, self.config.agents.starting_food);
+                    agent.identity.model = model;
+                    info!("Scripted arrival: {} at ({}, {})", agent.name(), x, y);
+                    let description = format!("{} arrived at ({}, {})", agent.name(), x, y);
+                    let id = agent.id;
+                    self.add_agent(agent);
+                    self.log_and_track(Event::scripted(epoch, &description, Some(id)))?;
+                }
+                ScriptedAction::WorldEvent { event, duration } => {
+                    let duration = duration.unwrap_or(self.config.world_events.duration);
+                    info!("Scripted world event began: {}", event.describe());
+                    let description = format!("{} began", event.describe());
+                    self.world.start_event_at(event, epoch, duration);
+                    self.log_and_track(Event::scripted(epoch, &description, None))?;
+                }
+                ScriptedAction::SetPhase { phase } => {
+                    if self.environment.jump_to_phase(epoch, &phase) {
+                        info!("Scripted phase change: {}", phase);
+                        let description = format!("The season turned to {}", phase);
+                        self.log_and_track(Event::scripted(epoch, &description, None))?;
+                    } else {
+                        warn!("Scripted phase change to unknown phase '{}'", phase);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Advance warning of harsh phases and scripted catastrophes within the
+    /// warning horizon, so agents can stockpile and seek shelter in time
+    fn catastrophe_warning(&self, agent: &Agent, epoch: usize) -> Option<String> {
+        let perception = &self.config.perception;
+        if !perception.catastrophe_warning {
+            return None;
+        }
+        let horizon = 1..=perception.warning_horizon;
+        let is_harsh = |name: &str| {
+            self.environment
+                .phases
+                .iter()
+                .any(|p| p.name.eq_ignore_ascii_case(name) && p.hazard_modifier >= perception.harsh_hazard_modifier)
+        };
+
+        let mut coming: Vec<(usize, String)> = Vec::new();
+        let harsh_phase = self
+            .environment
+            .next_harsh_phase(epoch, perception.harsh_hazard_modifier)
+            .filter(|(_, until)| horizon.contains(until));
+        if let Some((phase, until)) = harsh_phase {
+            coming.push((until, phase.name.clone()));
+        }
+        for scripted in &self.config.scenario.scripted_events {
+            let until = scripted.epoch.saturating_sub(epoch);
+            if !horizon.contains(&until) {
+                continue;
+            }
+            let what = match &scripted.action {
+                ScriptedAction::WorldEvent { event, .. } if event.affects(agent.physical.x, agent.physical.y) => {
+                    event.forecast()
+                }
+                ScriptedAction::SetPhase { phase } if is_harsh(phase) => Some(phase.clone()),
+                _ => None,
+            };
+            if let Some(what) = what.filter(|what| !coming.iter().any(|(_, w)| w == what)) {
+                coming.push((until, what));
+            }
+        }
+        if coming.is_empty() {
+            return None;
+        }
+
+        coming.sort_by_key(|(until, _)| *until);
+        let described: Vec<String> = coming
+            .iter()
+            .map(|(until, what)| format!("{} in {} day{}", what, until, if *until == 1 { "" } else { "s" }))
+            .collect();
+        Some(format!(
+            "Hard times are coming: {}. Stockpile food and secure shelter while you can.",
+            described.join(", ")
+        ))
+    }
+
+    /// Note that an agent (and the cell they stand on) changed this epoch
+    fn mark_agent_changed(&mut self, agent_id: Uuid, epoch: usize) {
+        self.agent_changes.insert(agent_id, epoch);
+        if let Some(agent) = self.agent(agent_id) {
+            self.cell_changes.insert((agent.physical.x, agent.physical.y), epoch);
+        }
+    }
+
+    /// Mark everything that changed over an epoch outside action resolution:
+    /// cells whose contents differ, and agents who are new or whose state does
+    fn track_changes(
+        &mut self,
+        epoch: usize,
+        cells_before: &[CellFingerprint],
+        agents_before: &HashMap<Uuid, AgentFingerprint>,
+    ) {
+        for (cell, before) in self.world.cells.iter().zip(cells_before) {
+            if cell_fingerprint(cell) != *before {
+                self.cell_changes.insert((cell.x, cell.y), epoch);
+            }
+        }
+        let changed: Vec<Uuid> = self
+            .agents
+            .iter()
+            .filter(|a| agents_before.get(&a.id) != Some(&agent_fingerprint(a)))
+            .map(|a| a.id)
+            .collect();
+        for agent_id in changed {
+            self.mark_agent_changed(agent_id, epoch);
+        }
+    }
+
     /// Add pending births to the simulation
     fn process_births(&mut self) {
         let births = std::mem::take(&mut self.pending_births);
-        // Register and add each new birth
         for child in births {
-            // Register the child's name in the chronicle
-            self.chronicle.register_agents(std::slice::from_ref(&child));
-            self.agents.push(child);
+            self.add_agent(child);
         }
     }
 
+    /// Bring a newcomer into the simulation, registering their name in the chronicle
+    fn add_agent(&mut self, agent: Agent) {
+        self.chronicle.register_agents(std::slice::from_ref(&agent));
+        self.agent_indices.insert(agent.id, self.agents.len());
+        self.spatial.insert(agent.id, (agent.physical.x, agent.physical.y));
+        self.agents.push(agent);
+    }
+
+    /// Rebuild the spatial index from where agents stand
+    fn reindex_positions(&mut self) {
+        self.spatial = SpatialIndex::build(&self.agents);
+    }
+
+    /// Put an agent in another cell
+    fn relocate_agent(&mut self, agent_idx: usize, to: (usize, usize)) {
+        let agent = &mut self.agents[agent_idx];
+        self.spatial.relocate(agent.id, (agent.physical.x, agent.physical.y), to);
+        (agent.physical.x, agent.physical.y) = to;
+    }
+
+    /// Living agents next to (or sharing a cell with) an agent, in list order
+    fn nearby_indices(&self, agent_idx: usize) -> Vec<usize> {
+        let agent = &self.agents[agent_idx];
+        let mut nearby: Vec<usize> = self
+            .spatial
+            .neighbors_within(agent.physical.x, agent.physical.y, 1)
+            .into_iter()
+            .filter_map(|id| self.agent_index(id))
+            .filter(|&i| i != agent_idx && self.agents[i].is_alive())
+            .collect();
+        nearby.sort_unstable();
+        nearby
+    }
+
+    /// Position of an agent in `agents`, if they are there
+    fn agent_index(&self, id: Uuid) -> Option<usize> {
+        let idx = self.agent_indices.get(&id).copied();
+        debug_assert!(idx.is_none_or(|i| self.agents[i].id == id), "agent index out of step");
+        idx
+    }
+
+    /// An agent by id
+    fn agent(&self, id: Uuid) -> Option<&Agent> {
+        self.agent_index(id).map(|i| &self.agents[i])
+    }
+
     /// Find an adjacent spawn position for a newborn
     fn find_adjacent_spawn(&self, x: usize, y: usize) -> (usize, usize) {
         // Try adjacent cells first
@@ -3038,8 +4678,11 @@ impl Engine {
         Ok(())
     }
 
-    /// Decay structures each epoch and remove destroyed ones
+    /// Decay structures each epoch, faster in harsh weather, and remove destroyed ones
     fn decay_structures(&mut self, epoch: usize) -> Result<()> {
+        let hazard_level = self.environment.state_at(epoch).hazard_level;
+        let wear = 1 + (hazard_level.max(0.0) * self.config.structures.hazard_decay).round() as u32;
+
         // Collect structures to decay and check for destruction
         let mut destroyed: Vec<(usize, usize, Uuid, String)> = Vec::new();
 
@@ -3049,7 +4692,7 @@ impl Engine {
                     if let Some(ref mut structure) = cell.structure {
                         // Only decay complete structures
                         if structure.is_complete() {
-                            structure.decay(1);
+                            structure.decay(wear);
 
                             if structure.is_destroyed() {
                                 let owner = structure.owner;
@@ -3075,18 +4718,86 @@ impl Engine {
         Ok(())
     }
 
+    /// Decide what happens to a beaten victim who was spared: a much stronger attacker
+    /// drives them off, otherwise they are robbed of food, or subdued if they carry none
+    fn resolve_beaten_victim(&mut self, epoch: usize, attacker_idx: usize, victim_idx: usize) -> Result<()> {
+        let attacker_id = self.agents[attacker_idx].id;
+        let victim_id = self.agents[victim_idx].id;
+        let attacker_name = self.agents[attacker_idx].name().to_string();
+        let dominant = self.agents[attacker_idx].physical.health
+            >= self.agents[victim_idx].physical.health + 0.3;
+
+        let (outcome, memory) = if dominant {
+            let attacker_pos = (self.agents[attacker_idx].physical.x, self.agents[attacker_idx].physical.y);
+            let from = (self.agents[victim_idx].physical.x, self.agents[victim_idx].physical.y);
+            let direction = Direction::toward(attacker_pos, from).unwrap_or(Direction::North);
+            let (dx, dy) = direction.delta();
+            let to = (
+                (from.0 as i32 + dx * 2).clamp(0, self.world.width as i32 - 1) as usize,
+                (from.1 as i32 + dy * 2).clamp(0, self.world.height as i32 - 1) as usize,
+            );
+            self.relocate_agent(victim_idx, to);
+            self.agents[victim_idx].physical.leave_shelter();
+            self.log_and_track(Event::moved(epoch, victim_id, from, to))?;
+            ("drove off".to_string(), format!("{} drove me off", attacker_name))
+        } else if self.agents[victim_idx].physical.food > 0 {
+            let amount = (self.agents[victim_idx].physical.food / 2).max(1);
+            let taken = self.agents[victim_idx].remove_food(amount);
+            self.agents[attacker_idx].add_food(taken);
+            (format!("took {} food from", taken), format!("{} beat me and took {} food", attacker_name, taken))
+        } else {
+            self.agents[victim_idx].physical.energy = 0.0;
+            ("subdued".to_string(), format!("{} beat me until I could not move", attacker_name))
+        };
+
+        self.log_and_track(Event::combat_outcome(epoch, attacker_id, victim_id, &outcome))?;
+        self.agents[victim_idx].memory.remember(Episode::conflict(epoch, &memory, -0.7, attacker_id));
+        Ok(())
+    }
+
+    /// Rest bonus from a completed shelter the agent is in or standing on.
+    /// Returns None if the agent is not sheltered.
+    fn shelter_rest_bonus(&self, agent_idx: usize) -> Option<f64> {
+        let agent = &self.agents[agent_idx];
+        let (x, y) = agent
+            .physical
+            .sheltered_at
+            .unwrap_or((agent.physical.x, agent.physical.y));
+        self.world
+            .get(x, y)
+            .and_then(|c| c.structure.as_ref())
+            .filter(|s| s.structure_type.is_shelter() && s.can_use(agent.id))
+            .map(|s| s.effective_rest_bonus())
+    }
+
+    /// Attack defense from a completed shelter the agent owns or is permitted in,
+    /// whether inside it or standing on its cell. None if the agent has no such cover.
+    fn shelter_defense(&self, agent: &Agent) -> Option<f64> {
+        let (x, y) = agent
+            .physical
+            .sheltered_at
+            .unwrap_or((agent.physical.x, agent.physical.y));
+        self.world
+            .get(x, y)
+            .and_then(|c| c.structure.as_ref())
+            .filter(|s| s.structure_type.is_shelter() && s.can_use(agent.id))
+            .map(|s| s.effective_defense())
+            .filter(|defense| *defense > 0.0)
+    }
+
     // ==================== Trade Maintenance ====================
 
     /// Expire trade proposals that have passed their expiry epoch
     fn expire_trade_proposals(&mut self, epoch: usize) -> Result<()> {
         // Collect proposals to expire
-        let to_expire: Vec<_> = self
+        let mut to_expire: Vec<_> = self
             .trade_state
             .proposals
             .iter()
             .filter(|(_, p)| p.is_pending() && p.is_expired(epoch))
             .map(|(id, p)| (*id, p.proposer, p.recipient))
             .collect();
+        to_expire.sort_by_key(|(id, _, _)| *id);
 
         for (proposal_id, proposer, recipient) in to_expire {
             // Mark as expired
@@ -3100,8 +4811,8 @@ impl Engine {
             debug!("Trade proposal {} expired", proposal_id);
         }
 
-        // Cleanup old completed proposals (keep last 50)
-        self.trade_state.cleanup_old_proposals(50);
+        // Cleanup old completed proposals (keep configured history)
+        self.trade_state.cleanup_old_proposals(self.config.trade.history_size);
 
         Ok(())
     }
@@ -3109,6 +4820,7 @@ impl Engine {
     /// Check service debt deadlines and apply renege penalties
     fn check_service_deadlines(&mut self, epoch: usize) -> Result<()> {
         let trade_config = self.config.trade.clone();
+        self.settle_due_gifts(epoch);
 
         // Collect overdue debts
         let overdue: Vec<_> = self
@@ -3126,8 +4838,8 @@ impl Engine {
             self.trade_state.service_debts[debt_idx].mark_reneged();
 
             // Find agent indices
-            let debtor_idx = self.agents.iter().position(|a| a.id == debtor);
-            let creditor_idx = self.agents.iter().position(|a| a.id == creditor);
+            let debtor_idx = self.agent_index(debtor);
+            let creditor_idx = self.agent_index(creditor);
 
             // Apply penalties
             if let (Some(d_idx), Some(c_idx)) = (debtor_idx, creditor_idx) {
@@ -3181,53 +4893,53 @@ impl Engine {
         Ok(())
     }
 
-    // ==================== Trade Helpers ====================
-
-    /// Check if an agent has the items required for a trade (excluding promises)
-    fn agent_has_items(&self, agent_idx: usize, items: &[TradeableItem]) -> bool {
-        let agent = &self.agents[agent_idx];
-
-        for item in items {
-            match item {
-                TradeableItem::Food(amount) => {
-                    if agent.physical.food < *amount {
-                        return false;
-                    }
-                }
-                TradeableItem::Materials(mat, amount) => {
-                    let has = agent.physical.materials.get(mat).copied().unwrap_or(0);
-                    if has < *amount {
-                        return false;
-                    }
-                }
-                TradeableItem::Tool(tool_id) => {
-                    if !agent.physical.tools.iter().any(|t| t.id == *tool_id) {
-                        return false;
-                    }
-                }
-                TradeableItem::ToolByType(tool_type) => {
-                    if !agent.physical.tools.iter().any(|t| t.tool_type == *tool_type) {
-                        return false;
-                    }
+    /// Food promised as a future gift is paid out of the debtor's stores when it
+    /// comes due, if there is enough of it; a debtor who can't pay reneges
+    fn settle_due_gifts(&mut self, epoch: usize) {
+        let due: Vec<(Uuid, Uuid, u32)> = self
+            .trade_state
+            .service_debts
+            .iter()
+            .filter(|d| d.is_overdue(epoch) && !d.reneged)
+            .filter_map(|d| match d.service {
+                ServiceType::FutureGift { amount, amount_given } => {
+                    Some((d.debtor, d.creditor, amount.saturating_sub(amount_given)))
                 }
-                // Promises don't require having anything
-                TradeableItem::TeachSkillPromise { .. }
-                | TradeableItem::HelpBuildPromise { .. }
-                | TradeableItem::FutureGiftPromise { .. }
-                | TradeableItem::AlliancePromise { .. } => {}
+                _ => None,
+            })
+            .collect();
+
+        for (debtor, creditor, owed) in due {
+            let debtor_idx = self.agent_index(debtor).filter(|&i| self.agents[i].is_alive());
+            let creditor_idx = self.agent_index(creditor).filter(|&i| self.agents[i].is_alive());
+            let (Some(d_idx), Some(c_idx)) = (debtor_idx, creditor_idx) else {
+                continue;
+            };
+            if self.agents[d_idx].physical.food < owed {
+                continue;
             }
+            self.agents[d_idx].physical.food -= owed;
+            self.agents[c_idx].add_food(owed);
+            let creditor_name = self.agents[c_idx].name().to_string();
+            self.agents[d_idx].memory.remember(Episode::new(
+                epoch,
+                format!("I paid {} the {} food I had promised", creditor_name, owed),
+                0.2,
+                vec![creditor],
+                EpisodeCategory::Social,
+            ));
+            self.check_give_fulfills_debt(debtor, creditor, owed, epoch);
         }
-
-        true
     }
 
+    // ==================== Trade Helpers ====================
+
     /// Transfer items from one agent to another
     fn transfer_items(&mut self, from_idx: usize, to_idx: usize, items: &[TradeableItem]) {
         for item in items {
             match item {
                 TradeableItem::Food(amount) => {
-                    let actual = self.agents[from_idx].physical.food.min(*amount);
-                    self.agents[from_idx].physical.food -= actual;
+                    let actual = self.agents[from_idx].remove_food(*amount);
                     self.agents[to_idx].physical.food += actual;
                 }
                 TradeableItem::Materials(mat, amount) => {
@@ -3258,6 +4970,108 @@ impl Engine {
         }
     }
 
+    /// Teach a skill to one student, at `effectiveness` of a one-on-one
+    /// lesson; returns whether the student learned anything
+    fn teach_student(
+        &mut self,
+        agent_idx: usize,
+        target_idx: usize,
+        skill: &str,
+        effectiveness: f64,
+        epoch: usize,
+    ) -> Result<bool> {
+        let agent_id = self.agents[agent_idx].id;
+        let target = self.agents[target_idx].id;
+        let teacher_level = self.agents[agent_idx].skills.level(skill);
+
+        let agent_name = self.agents[agent_idx].name().to_string();
+        let target_name = self.agents[target_idx].name().to_string();
+
+        // Calculate skill improvement
+        // Base: teacher_level * teaching_multiplier * learning_rate
+        // Bonus from target's openness (learning aptitude)
+        let learning_rate = self.config.skills.learning_rate;
+        let teaching_mult = self.config.skills.teaching_multiplier;
+        let teacher_teaching_skill = self.agents[agent_idx].skills.level("teaching");
+        let target_openness = self.agents[target_idx].identity.personality.openness;
+
+        let improvement = teacher_level
+            * learning_rate
+            * teaching_mult
+            * (1.0 + teacher_teaching_skill * 0.5)
+            * (1.0 + target_openness * 0.3)
+            * effectiveness;
+
+        // Target can't exceed teacher's level
+        let target_current = self.agents[target_idx].skills.level(skill);
+        let max_new_level = teacher_level.min(1.0);
+        let new_level = (target_current + improvement).min(max_new_level);
+
+        if new_level <= target_current {
+            return Ok(false);
+        }
+        self.agents[target_idx].skills.improve(skill, improvement, epoch);
+
+        // Log event
+        self.log_and_track(Event::skill_taught(
+            epoch,
+            agent_id,
+            target,
+            skill,
+            new_level,
+        ))?;
+
+        // Create memories
+        self.agents[agent_idx].memory.remember(Episode::social(
+            epoch,
+            &format!("I taught {} about {}", target_name, skill),
+            0.2,
+            target,
+        ));
+
+        self.agents[target_idx].memory.remember(Episode::social(
+            epoch,
+            &format!("{} taught me {}", agent_name, skill),
+            0.3,
+            agent_id,
+        ));
+
+        // Boost trust and sentiment
+        self.agents[target_idx].beliefs.update_trust(
+            agent_id,
+            &agent_name,
+            0.1,
+            epoch,
+        );
+        self.agents[target_idx].beliefs.update_sentiment(
+            agent_id,
+            &agent_name,
+            0.1,
+            epoch,
+        );
+
+        // Check if this fulfills a TeachSkill debt
+        self.check_teach_fulfills_debt(agent_id, target, skill, epoch);
+
+        debug!(
+            "{} taught {} to {} (now at {:.2})",
+            agent_name, skill, target_name, new_level
+        );
+        Ok(true)
+    }
+
+    /// The teacher's side of a lesson: practice at teaching, and the energy it took
+    fn finish_lesson(&mut self, agent_idx: usize, epoch: usize) {
+        // Teacher practices teaching skill
+        self.agents[agent_idx].skills.practice("teaching", epoch);
+        let practice_imp = self.config.skills.practice_improvement;
+        self.agents[agent_idx].skills.improve("teaching", practice_imp * 0.5, epoch);
+
+        // Energy cost for teaching
+        self.agents[agent_idx].physical.energy =
+            (self.agents[agent_idx].physical.energy - 0.1).max(0.0);
+    }
+
     // ==================== Service Debt Fulfillment ====================
 
     /// Check if a TEACH action fulfills a TeachSkill debt
@@ -3287,8 +5101,8 @@ impl Engine {
         self.trade_state.service_debts[debt_idx].fulfilled = true;
 
         // Apply trust bonus
-        let teacher_idx = self.agents.iter().position(|a| a.id == teacher);
-        let student_idx = self.agents.iter().position(|a| a.id == student);
+        let teacher_idx = self.agent_index(teacher);
+        let student_idx = self.agent_index(student);
 
         if let (Some(t_idx), Some(s_idx)) = (teacher_idx, student_idx) {
             let teacher_name = self.agents[t_idx].name().to_string();
@@ -3356,8 +5170,8 @@ impl Engine {
 
         // If fully fulfilled, apply trust bonus and log event
         if is_fulfilled {
-            let giver_idx = self.agents.iter().position(|a| a.id == giver);
-            let receiver_idx = self.agents.iter().position(|a| a.id == receiver);
+            let giver_idx = self.agent_index(giver);
+            let receiver_idx = self.agent_index(receiver);
 
             if let (Some(g_idx), Some(r_idx)) = (giver_idx, receiver_idx) {
                 let giver_name = self.agents[g_idx].name().to_string();
@@ -3398,6 +5212,59 @@ impl Engine {
         Some((debt_id, is_fulfilled))
     }
 
+    /// Put `progress` into the unfinished structure at `pos`, handling completion
+    /// and any HelpBuild debt the worker owes the owner
+    fn work_on_structure(&mut self, agent_idx: usize, pos: (usize, usize), progress: u32, epoch: usize) -> Result<()> {
+        let agent_id = self.agents[agent_idx].id;
+        let Some(s) = self.world.get_mut(pos.0, pos.1).and_then(|c| c.structure.as_mut()) else {
+            return Ok(());
+        };
+        let structure_owner = s.owner;
+        let was_complete = s.is_complete();
+        s.add_progress(progress, epoch);
+        let completed = !was_complete && s.is_complete();
+
+        // Practice crafting
+        self.agents[agent_idx].skills.practice("crafting", epoch);
+        self.agents[agent_idx].physical.energy = (self.agents[agent_idx].physical.energy - 0.15).max(0.0);
+
+        if completed {
+            self.finish_structure(agent_idx, pos, epoch)?;
+        }
+
+        // If helping someone else's structure, check for HelpBuild debt fulfillment
+        if structure_owner != agent_id {
+            self.check_build_fulfills_debt(agent_id, structure_owner, progress, epoch);
+        }
+        Ok(())
+    }
+
+    /// The agent put the last work into the structure at `pos`: it remembers the
+    /// feat (and where to find shelter) and the completion is logged
+    fn finish_structure(&mut self, agent_idx: usize, pos: (usize, usize), epoch: usize) -> Result<()> {
+        let Some(s) = self.world.get(pos.0, pos.1).and_then(|c| c.structure.as_ref()) else {
+            return Ok(());
+        };
+        let (structure_type, owner) = (s.structure_type, s.owner);
+        let agent = &mut self.agents[agent_idx];
+        debug!("{} completed building {}", agent.name(), s.display_name());
+        if structure_type.is_shelter() {
+            let note = if owner == agent.id {
+                format!("your {}", structure_type.display_name())
+            } else {
+                format!("a {} you helped build", structure_type.display_name())
+            };
+            agent.beliefs.remember_landmark(pos.0, pos.1, LandmarkKind::Shelter, &note, epoch);
+        }
+        agent.memory.remember(Episode::survival(
+            epoch,
+            &format!("I completed building a {}", structure_type.display_name()),
+            0.5,
+        ));
+        let builder = agent.id;
+        self.log_and_track(Event::structure_built(epoch, builder, pos.0, pos.1, structure_type.display_name()))
+    }
+
     /// Check if a BUILD action contributes to a HelpBuild debt
     /// Returns the debt ID and whether it's now fully fulfilled
     fn check_build_fulfills_debt(
@@ -3428,8 +5295,8 @@ impl Engine {
 
         // If fully fulfilled, apply trust bonus and log event
         if is_fulfilled {
-            let builder_idx = self.agents.iter().position(|a| a.id == builder);
-            let owner_idx = self.agents.iter().position(|a| a.id == structure_owner);
+            let builder_idx = self.agent_index(builder);
+            let owner_idx = self.agent_index(structure_owner);
 
             if let (Some(b_idx), Some(o_idx)) = (builder_idx, owner_idx) {
                 let builder_name = self.agents[b_idx].name().to_string();
@@ -3472,21 +5339,187 @@ impl Engine {
 
     // ==================== Alliance Helpers ====================
 
-    /// Find all allies of an agent who are nearby (adjacent) and alive
-    /// Returns Vec of (ally_id, ally_idx)
-    fn find_nearby_allies(&self, agent_id: Uuid, agent_idx: usize, epoch: usize) -> Vec<(Uuid, usize)> {
-        let agent = &self.agents[agent_idx];
+    /// Deliver remarks made in passing alongside other actions. They are heard
+    /// if speaker and listener are still side by side once the actions resolve
+    fn resolve_remarks(&mut self, epoch: usize, remarks: BTreeMap<Uuid, Remark>) -> Result<()> {
+        for (speaker, remark) in remarks {
+            let speaker_idx = self.agent_index(speaker).filter(|&i| self.agents[i].is_alive());
+            let target_idx = self.agent_index(remark.target).filter(|&i| self.agents[i].is_alive());
+            let (Some(speaker_idx), Some(target_idx)) = (speaker_idx, target_idx) else {
+                continue;
+            };
+            if !is_adjacent(&self.agents[speaker_idx], &self.agents[target_idx]) {
+                continue;
+            }
 
-        self.agents
+            self.log_and_track(Event::spoke(epoch, speaker, remark.target, &remark.message))?;
+            let speaker_name = self.agents[speaker_idx].name().to_string();
+            self.agents[target_idx].memory.remember(Episode::social(
+                epoch,
+                &format!("{} said to me in passing: \"{}\"", speaker_name, remark.message),
+                0.05,
+                speaker,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Agents who come face to face for the first time form an impression of
+    /// each other: gossip heard beforehand carries over, and a stranger's group
+    /// is judged by the members the observer already knows
+    fn meet_strangers(&mut self, epoch: usize) {
+        let mut meetings = Vec::new();
+        for (observer_idx, observer) in self.agents.iter().enumerate() {
+            if !observer.is_alive() {
+                continue;
+            }
+            for stranger_idx in self.nearby_indices(observer_idx) {
+                let stranger = &self.agents[stranger_idx];
+                if observer.beliefs.get_social(stranger.id).is_none_or(|b| !b.met) {
+                    let cue = self.group_cue(observer, stranger.id);
+                    meetings.push((observer_idx, stranger.id, stranger.name().to_string(), cue));
+                }
+            }
+        }
+
+        for (observer_idx, stranger_id, stranger_name, cue) in meetings {
+            if self.agents[observer_idx].beliefs.first_impression(stranger_id, &stranger_name, cue, epoch) {
+                debug!("{} meets {} for the first time", self.agents[observer_idx].name(), stranger_name);
+            }
+        }
+    }
+
+    /// What a stranger's group says about them to an observer: a share of the
+    /// observer's average trust and sentiment toward the members they know
+    fn group_cue(&self, observer: &Agent, stranger: Uuid) -> (f64, f64) {
+        let reputation = &self.config.reputation;
+        if !reputation.stranger_priors {
+            return (0.0, 0.0);
+        }
+        let Some(group) = self.group_tracker.group_of(stranger) else {
+            return (0.0, 0.0);
+        };
+        let known: Vec<_> = group
+            .members
             .iter()
-            .enumerate()
-            .filter(|(idx, ally)| {
-                *idx != agent_idx
-                    && ally.is_alive()
-                    && is_adjacent(agent, ally)
-                    && self.trade_state.has_alliance(agent_id, ally.id, epoch)
+            .filter(|&&id| id != stranger && id != observer.id)
+            .filter_map(|id| observer.beliefs.get_social(*id))
+            .collect();
+        if known.is_empty() {
+            return (0.0, 0.0);
+        }
+        let n = known.len() as f64;
+        let trust = known.iter().map(|b| b.trust).sum::<f64>() / n;
+        let sentiment = known.iter().map(|b| b.sentiment).sum::<f64>() / n;
+        (trust * reputation.group_weight, sentiment * reputation.group_weight)
+    }
+
+    /// A group leader speaks for their group toward another: peace lifts a
+    /// conflict to neutral (or neutral to friendly), war makes it hostile.
+    /// Members' feelings across the two groups move with the leader's word,
+    /// the leader's own group fully and the other group by half, and the
+    /// declared relationship holds for `treaty_epochs` whatever they feel
+    fn conduct_diplomacy(&mut self, agent_idx: usize, rival_name: &str, peace: bool, epoch: usize) -> Result<()> {
+        let agent_id = self.agents[agent_idx].id;
+        let verb = if peace { "propose peace" } else { "declare war" };
+        let Some(own) = self.group_tracker.group_of(agent_id).filter(|g| g.leader == Some(agent_id)).cloned() else {
+            self.record_action_failure(agent_id, verb, "you don't lead a group");
+            return Ok(());
+        };
+        let Some(rival) = self
+            .group_tracker
+            .current_groups()
+            .iter()
+            .find(|g| g.id != own.id && g.name.eq_ignore_ascii_case(rival_name.trim()))
+            .cloned()
+        else {
+            let reason = format!("there is no other group called {}", rival_name);
+            self.record_action_failure(agent_id, verb, &reason);
+            return Ok(());
+        };
+
+        let old_type = self.group_tracker.relation_between(own.id, rival.id);
+        let new_type = match (peace, old_type) {
+            (true, RivalryType::Hostile | RivalryType::Tense) => RivalryType::Neutral,
+            (true, RivalryType::Neutral) => RivalryType::Friendly,
+            (true, other) => other,
+            (false, _) => RivalryType::Hostile,
+        };
+        if new_type == old_type {
+            let reason = format!("you are already {} with {}", old_type.describe(), rival.name);
+            self.record_action_failure(agent_id, verb, &reason);
+            return Ok(());
+        }
+
+        let shift = if peace { self.config.groups.diplomacy_shift } else { -self.config.groups.diplomacy_shift };
+        for agent in self.agents.iter_mut().filter(|a| a.is_alive()) {
+            let (others, weight) = if own.members.contains(&agent.id) {
+                (&rival.members, 1.0)
+            } else if rival.members.contains(&agent.id) {
+                (&own.members, 0.5)
+            } else {
+                continue;
+            };
+            for other in others {
+                if let Some(belief) = agent.beliefs.social.get_mut(other) {
+                    belief.trust = (belief.trust + shift * weight).clamp(-1.0, 1.0);
+                    belief.sentiment = (belief.sentiment + shift * weight).clamp(-1.0, 1.0);
+                }
+            }
+        }
+
+        let until = epoch + self.config.groups.treaty_epochs;
+        self.group_tracker.declare(own.id, rival.id, new_type, until, epoch);
+        self.log_and_track(Event::rivalry_changed(
+            epoch,
+            &own.name,
+            &rival.name,
+            old_type.describe(),
+            new_type.describe(),
+        ))?;
+        info!(
+            "Relations: {} {} {} on behalf of {} ({} -> {})",
+            self.agents[agent_idx].name(),
+            if peace { "made peace with" } else { "declared war on" },
+            rival.name,
+            own.name,
+            old_type.describe(),
+            new_type.describe()
+        );
+        Ok(())
+    }
+
+    /// Find all allies of an agent who are nearby (adjacent) and alive
+    /// Returns Vec of (ally_id, ally_idx)
+    fn find_nearby_allies(&self, agent_id: Uuid, agent_idx: usize, epoch: usize) -> Vec<(Uuid, usize)> {
+        self.nearby_indices(agent_idx)
+            .into_iter()
+            .filter(|&idx| self.trade_state.has_alliance(agent_id, self.agents[idx].id, epoch))
+            .map(|idx| (self.agents[idx].id, idx))
+            .collect()
+    }
+
+    /// Living neighbours of an attack's victim who step in: allies by alliance
+    /// or group, and anyone who trusts or likes the victim enough, so long as
+    /// they can stand
+    fn find_defenders(&self, victim_idx: usize, attacker_id: Uuid, epoch: usize) -> Vec<usize> {
+        let victim = &self.agents[victim_idx];
+        let group = self.group_tracker.group_of(victim.id).map(|g| g.id);
+        let devotion = self.config.combat.ally_devotion;
+        self.nearby_indices(victim_idx)
+            .into_iter()
+            .filter(|&idx| {
+                let other = &self.agents[idx];
+                if other.id == attacker_id || other.physical.incapacitated {
+                    return false;
+                }
+                let same_group = group.is_some() && self.group_tracker.group_of(other.id).map(|g| g.id) == group;
+                let devoted = other
+                    .beliefs
+                    .get_social(victim.id)
+                    .is_some_and(|b| b.trust >= devotion || b.sentiment >= devotion);
+                same_group || devoted || self.trade_state.has_alliance(victim.id, other.id, epoch)
             })
-            .map(|(idx, ally)| (ally.id, idx))
             .collect()
     }
 
@@ -3551,7 +5584,7 @@ impl Engine {
         let groups = self.group_tracker.current_groups().to_vec();
 
         for group in groups {
-            let members: Vec<Uuid> = group.members.iter().copied().collect();
+            let members = group.member_ids();
 
             // For each territory owned by a group member
             for y in 0..self.world.height {
@@ -3575,14 +5608,1764 @@ impl Engine {
     }
 }
 
+/// Give roster agents the relationships and family ties the scenario starts them with
+fn seed_roster_relationships(roster: &[RosterEntry], agents: &mut [Agent]) -> Result<()> {
+    for (idx, entry) in roster.iter().enumerate() {
+        if roster[..idx].iter().any(|earlier| earlier.name.eq_ignore_ascii_case(&entry.name)) {
+            anyhow::bail!("roster names {} more than once", entry.name);
+        }
+    }
+
+    let find = |agents: &[Agent], owner: &str, name: &str| -> Result<usize> {
+        match agents.iter().position(|a| a.name().eq_ignore_ascii_case(name)) {
+            Some(idx) if !agents[idx].name().eq_ignore_ascii_case(owner) => Ok(idx),
+            Some(_) => anyhow::bail!("roster entry {} refers to themselves", owner),
+            None => anyhow::bail!("roster entry {} refers to unknown agent {}", owner, name),
+        }
+    };
+
+    for (idx, entry) in roster.iter().enumerate() {
+        for seed in &entry.relationships {
+            let other = find(agents, &entry.name, &seed.with)?;
+            let (other_id, other_name) = (agents[other].id, agents[other].name().to_string());
+            let belief = agents[idx].beliefs.get_or_create_social(other_id, &other_name);
+            belief.trust = seed.trust.clamp(-1.0, 1.0);
+            belief.sentiment = seed.sentiment.clamp(-1.0, 1.0);
+            belief.met = true;
+        }
+
+        for parent in &entry.parents {
+            let parent = find(agents, &entry.name, parent)?;
+            let (child_id, parent_id) = (agents[idx].id, agents[parent].id);
+            let family = &mut agents[idx].reproduction.family;
+            if !family.parents.contains(&parent_id) {
+                family.parents.push(parent_id);
+            }
+            if !agents[parent].reproduction.family.children.contains(&child_id) {
+                agents[parent].reproduction.family.children.push(child_id);
+            }
+        }
+
+        for mate in &entry.mates {
+            let mate = find(agents, &entry.name, mate)?;
+            let (own_id, mate_id) = (agents[idx].id, agents[mate].id);
+            if !agents[idx].reproduction.family.mate_history.contains(&mate_id) {
+                agents[idx].reproduction.family.mate_history.push(mate_id);
+            }
+            if !agents[mate].reproduction.family.mate_history.contains(&own_id) {
+                agents[mate].reproduction.family.mate_history.push(own_id);
+            }
+        }
+    }
+
+    // Only now that every parent is linked, so roster order doesn't matter
+    let mut settled = HashMap::new();
+    for idx in 0..agents.len() {
+        family_generation(idx, agents, &mut settled, &mut Vec::new())?;
+    }
+    for (idx, generation) in settled {
+        agents[idx].reproduction.family.generation = generation;
+    }
+    Ok(())
+}
+
+/// An agent's generation, one past their latest-born parent's, refusing
+/// anyone who turns out to be their own ancestor
+fn family_generation(
+    idx: usize,
+    agents: &[Agent],
+    settled: &mut HashMap<usize, usize>,
+    lineage: &mut Vec<usize>,
+) -> Result<usize> {
+    if let Some(&generation) = settled.get(&idx) {
+        return Ok(generation);
+    }
+    if lineage.contains(&idx) {
+        anyhow::bail!("roster entry {} is their own ancestor", agents[idx].name());
+    }
+    lineage.push(idx);
+    let mut generation = agents[idx].reproduction.family.generation;
+    for parent_id in &agents[idx].reproduction.family.parents {
+        if let Some(parent) = agents.iter().position(|a| a.id == *parent_id) {
+            generation = generation.max(family_generation(parent, agents, settled, lineage)? + 1);
+        }
+    }
+    lineage.pop();
+    settled.insert(idx, generation);
+    Ok(generation)
+}
+
 /// Manhattan distance between two positions
 fn manhattan_distance(a: (usize, usize), b: (usize, usize)) -> usize {
     ((a.0 as i32 - b.0 as i32).abs() + (a.1 as i32 - b.1 as i32).abs()) as usize
 }
 
-/// Check if two agents are adjacent (within 1 cell)
-fn is_adjacent(a: &Agent, b: &Agent) -> bool {
-    let dx = (a.physical.x as i32 - b.physical.x as i32).abs();
-    let dy = (a.physical.y as i32 - b.physical.y as i32).abs();
-    dx <= 1 && dy <= 1
+/// How others' average trust in an agent reads to that agent
+fn describe_reputation(trust: f64) -> &'static str {
+    if trust > 0.4 {
+        "hold you in high regard"
+    } else if trust > 0.1 {
+        "mostly trust you"
+    } else if trust > -0.1 {
+        "have yet to make up their minds about you"
+    } else if trust > -0.4 {
+        "are wary of you"
+    } else {
+        "distrust you"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structures::{Structure, StructureType};
+    use crate::config::{AgingConfig, InstinctConfig, RelationshipSeed, ScriptedEvent};
+    use crate::observation::EventType;
+    use crate::world::WorldEvent;
+
+    /// Build an engine with a small fully fertile world, writing output to a temp dir
+    fn test_engine(agent_count: usize) -> Engine {
+        let mut config = Config::default();
+        config.agents.count = agent_count;
+        config.world.fertile_fraction = 1.0;
+        config.world.forest_fraction = 0.0;
+        let output_dir = std::env::temp_dir().join(format!("terrarium-test-{}", Uuid::new_v4()));
+        Engine::new(config, output_dir.to_str().unwrap()).unwrap()
+    }
+
+    impl Engine {
+        /// Put an agent in a cell, keeping the spatial index in step
+        fn place(&mut self, idx: usize, x: usize, y: usize) {
+            self.relocate_agent(idx, (x, y));
+        }
+    }
+
+    #[test]
+    fn test_rest_in_shelter_recovers_more() {
+        let mut engine = test_engine(2);
+
+        let mut shelter = Structure::new(StructureType::Shelter, engine.agents[0].id, 20, ToolQuality::Standard, 0);
+        shelter.add_progress(20, 0);
+        engine.world.get_mut(0, 0).unwrap().structure = Some(shelter);
+
+        let sheltered = engine.agents[0].id;
+        let exposed = engine.agents[1].id;
+        engine.agents[0].physical.x = 0;
+        engine.agents[0].physical.y = 0;
+        engine.agents[1].physical.x = 5;
+        engine.agents[1].physical.y = 5;
+        for agent in &mut engine.agents {
+            agent.physical.energy = 0.2;
+        }
+
+        let actions = BTreeMap::from([(sheltered, Action::Rest), (exposed, Action::Rest)]);
+        engine.resolve_actions(1, actions).unwrap();
+
+        assert!(engine.agents[0].physical.energy > engine.agents[1].physical.energy);
+        assert!(engine.agents[0]
+            .memory
+            .recent
+            .iter()
+            .any(|e| e.description.contains("rested comfortably in my shelter")));
+    }
+
+    #[test]
+    fn test_lean_to_built_over_several_epochs_gives_rest_bonus() {
+        let mut engine = test_engine(2);
+        let (builder, other) = (engine.agents[0].id, engine.agents[1].id);
+        engine.agents[0].physical.add_material(MaterialType::Wood, 3);
+        engine.agents[0].physical.add_material(MaterialType::Fiber, 2);
+        let built = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = built.clone();
+        engine.subscribe(move |event| {
+            if matches!(event.event_type, EventType::StructureBuilt) {
+                seen.lock().unwrap().push((event.epoch, event.agent));
+            }
+        });
+
+        let build = BTreeMap::from([(builder, Action::Build { structure_type: StructureType::LeanTo })]);
+        let mut epoch = 1;
+        engine.resolve_actions(epoch, build.clone()).unwrap();
+        let complete = |engine: &Engine| engine.world.get(0, 0).unwrap().structure.as_ref().unwrap().is_complete();
+        assert!(!complete(&engine));
+        while !complete(&engine) && epoch < 10 {
+            epoch += 1;
+            engine.resolve_actions(epoch, build.clone()).unwrap();
+        }
+        assert!(complete(&engine));
+        assert!(engine.agents[0].physical.materials.values().all(|n| *n == 0));
+        assert_eq!(*built.lock().unwrap(), vec![(epoch, Some(builder))]);
+
+        for agent in &mut engine.agents {
+            agent.physical.energy = 0.2;
+        }
+        engine.resolve_actions(epoch + 1, BTreeMap::from([(builder, Action::Rest), (other, Action::Rest)])).unwrap();
+        assert!(engine.shelter_rest_bonus(0).is_some_and(|bonus| bonus > 0.0));
+        assert!(engine.agents[0].physical.energy > engine.agents[1].physical.energy);
+    }
+
+    #[test]
+    fn test_accepted_food_trade_moves_food_both_ways() {
+        let mut engine = test_engine(2);
+        let (aric, bria) = (engine.agents[0].id, engine.agents[1].id);
+        engine.agents[1].physical.x = 1;
+        for agent in &mut engine.agents {
+            agent.physical.food = 5;
+        }
+
+        let offer = Action::TradeOffer {
+            target: bria,
+            offering: vec![TradeableItem::Food(3)],
+            requesting: vec![TradeableItem::Food(1)],
+        };
+        engine.resolve_actions(1, BTreeMap::from([(aric, offer)])).unwrap();
+        assert_eq!(engine.trade_state.pending_proposals_for(bria).len(), 1);
+
+        // Nothing changes hands until the offer is taken up
+        assert_eq!((engine.agents[0].physical.food, engine.agents[1].physical.food), (5, 5));
+        engine.resolve_actions(2, BTreeMap::from([(bria, Action::TradeAccept { proposal_index: 0 })])).unwrap();
+        assert_eq!((engine.agents[0].physical.food, engine.agents[1].physical.food), (3, 7));
+        assert!(engine.trade_state.pending_proposals_for(bria).is_empty());
+
+        engine.resolve_actions(3, BTreeMap::from([(bria, Action::TradeAccept { proposal_index: 0 })])).unwrap();
+        assert!(engine.action_results[&bria].contains("no such offer"));
+    }
+
+    #[test]
+    fn test_failed_teach_reported_in_next_perception() {
+        let mut engine = test_engine(2);
+        let teacher = engine.agents[0].id;
+        let student = engine.agents[1].id;
+        engine.agents[0].physical.x = 2;
+        engine.agents[0].physical.y = 2;
+        engine.agents[1].physical.x = 3;
+        engine.agents[1].physical.y = 2;
+        engine.agents[0].skills.levels.insert("crafting".to_string(), 0.0);
+
+        let actions = BTreeMap::from([(
+            teacher,
+            Action::Teach { target: student, skill: "crafting".to_string() },
+        )]);
+        engine.resolve_actions(1, actions).unwrap();
+
+        let perception = engine.build_perception(&engine.agents[0], "", 0);
+        assert!(perception.contains("your attempt to teach failed"));
+        assert!(perception.contains("don't know crafting well enough"));
+
+        // The note only lasts for the following epoch
+        engine.resolve_actions(2, BTreeMap::from([(teacher, Action::Wait)])).unwrap();
+        let perception = engine.build_perception(&engine.agents[0], "", 0);
+        assert!(!perception.contains("Last action"));
+    }
+
+    #[test]
+    fn test_plague_appears_in_perception() {
+        let mut engine = test_engine(3);
+        engine.world.start_event(WorldEvent::Plague { severity: 0.5 }, 5);
+
+        for agent in &engine.agents {
+            let perception = engine.build_perception(agent, "", 0);
+            assert!(perception.contains("plague is sweeping the land"));
+        }
+
+        engine.config.world_events.broadcast = false;
+        let perception = engine.build_perception(&engine.agents[0], "", 0);
+        assert!(!perception.contains("plague"));
+    }
+
+    #[test]
+    fn test_set_goal_persists_into_next_prompt() {
+        let mut engine = test_engine(1);
+        let id = engine.agents[0].id;
+        let action = Action::parse("GOAL find a mate", &[]).unwrap();
+        engine.resolve_actions(1, BTreeMap::from([(id, action)])).unwrap();
+
+        // Next epoch's goal update keeps the self-chosen goal while needs are met
+        let agent = &mut engine.agents[0];
+        agent.physical.hunger = 0.1;
+        agent.physical.energy = 1.0;
+        agent.update_goal(&InstinctConfig::default());
+        assert!(agent.prompt_state(2).contains("Current focus: find a mate"));
+
+        // Survival needs preempt it
+        agent.physical.hunger = 0.9;
+        agent.update_goal(&InstinctConfig::default());
+        assert!(!agent.prompt_state(3).contains("find a mate"));
+    }
+
+    #[test]
+    fn test_dismantle_owned_structure_returns_materials() {
+        let mut engine = test_engine(1);
+        let owner = engine.agents[0].id;
+        engine.agents[0].physical.materials.clear();
+
+        let mut shelter = Structure::new(StructureType::Shelter, owner, 20, ToolQuality::Standard, 0);
+        shelter.add_progress(20, 0);
+        engine.world.get_mut(0, 0).unwrap().structure = Some(shelter);
+        engine.agents[0].physical.enter_shelter(0, 0);
+
+        engine.resolve_actions(1, BTreeMap::from([(owner, Action::Dismantle)])).unwrap();
+
+        assert!(engine.world.get(0, 0).unwrap().structure.is_none());
+        assert!(engine.agents[0].physical.materials.values().sum::<u32>() > 0);
+        assert!(!engine.agents[0].physical.is_sheltered());
+    }
+
+    #[test]
+    fn test_structures_wear_faster_in_hazards_until_destroyed() {
+        let mut engine = test_engine(1);
+        let owner = engine.agents[0].id;
+        let mut shelter = Structure::new(StructureType::Shelter, owner, 20, ToolQuality::Standard, 0);
+        shelter.add_progress(20, 0);
+        let full = shelter.durability;
+        engine.world.get_mut(0, 0).unwrap().structure = Some(shelter);
+        let durability = |engine: &Engine| engine.world.get(0, 0).unwrap().structure.as_ref().map(|s| s.durability);
+
+        engine.environment.base_hazard = 0.0;
+        engine.decay_structures(1).unwrap();
+        assert_eq!(durability(&engine), Some(full - 1));
+        engine.environment.base_hazard = 1.0;
+        engine.decay_structures(2).unwrap();
+        assert!(durability(&engine).unwrap() < full - 2);
+
+        let shelter = engine.world.get_mut(0, 0).unwrap().structure.as_mut().unwrap();
+        shelter.durability = 0;
+        assert_eq!(shelter.effective_rest_bonus(), 0.0);
+        shelter.durability = 1;
+        let destroyed = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = destroyed.clone();
+        engine.subscribe(move |event| {
+            if matches!(event.event_type, EventType::StructureDestroyed) {
+                seen.lock().unwrap().push(event.epoch);
+            }
+        });
+        engine.decay_structures(3).unwrap();
+        assert_eq!(durability(&engine), None);
+        assert_eq!(*destroyed.lock().unwrap(), vec![3]);
+    }
+
+    #[test]
+    fn test_finished_farm_adds_food_to_its_cell() {
+        let mut engine = test_engine(1);
+        let owner = engine.agents[0].id;
+        let mut farm = Structure::new(StructureType::Farm, owner, 10, ToolQuality::Standard, 0);
+        let cell = engine.world.get_mut(0, 0).unwrap();
+        cell.food = 0;
+        cell.food_capacity = 20;
+        cell.structure = Some(farm.clone());
+
+        // Nothing grows until the farm is finished
+        engine.process_structure_production(1).unwrap();
+        assert_eq!(engine.world.get(0, 0).unwrap().food, 0);
+
+        farm.add_progress(10, 1);
+        let expected = farm.effective_food_production();
+        assert!(expected > 0);
+        engine.world.get_mut(0, 0).unwrap().structure = Some(farm);
+        engine.process_structure_production(2).unwrap();
+        assert_eq!(engine.world.get(0, 0).unwrap().food, expected);
+    }
+
+    #[test]
+    fn test_farm_needs_fertile_ground() {
+        let mut engine = test_engine(2);
+        let (rocky, fertile) = (engine.agents[0].id, engine.agents[1].id);
+        engine.world.get_mut(0, 0).unwrap().terrain = Terrain::Barren;
+        for agent in &mut engine.agents {
+            agent.physical.add_material(MaterialType::Wood, 8);
+            agent.physical.add_material(MaterialType::Fiber, 4);
+            agent.physical.add_material(MaterialType::Stone, 2);
+        }
+
+        let farm = Action::Build { structure_type: StructureType::Farm };
+        engine.resolve_actions(1, BTreeMap::from([(rocky, farm.clone()), (fertile, farm)])).unwrap();
+
+        assert!(engine.world.get(0, 0).unwrap().structure.is_none());
+        assert!(engine.action_results[&rocky].contains("only be built on fertile ground"));
+        assert!(engine.world.get(3, 0).unwrap().structure.is_some());
+        assert!(!engine.action_results.contains_key(&fertile));
+
+        // Scenarios can open farming up to other terrain
+        engine.config.structures.farm.push(Terrain::Barren);
+        engine.resolve_actions(2, BTreeMap::from([(rocky, Action::Build { structure_type: StructureType::Farm })])).unwrap();
+        assert!(engine.world.get(0, 0).unwrap().structure.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_agent_collapses_until_rested() {
+        let mut engine = test_engine(1);
+        let id = engine.agents[0].id;
+        let env_state = engine.environment.state_at(1);
+        engine.agents[0].physical.energy = 0.0;
+        engine.agents[0].physical.hunger = 0.0;
+        engine.tick_needs(1, &env_state).unwrap();
+        engine.tick_needs(2, &env_state).unwrap();
+        assert!(engine.agents[0].physical.incapacitated);
+
+        let food = engine.agents[0].physical.food;
+        engine.resolve_actions(2, BTreeMap::from([(id, Action::Gather)])).unwrap();
+        assert_eq!(engine.agents[0].physical.food, food);
+        assert!(engine.action_results[&id].contains("too exhausted"));
+
+        // Decided without asking anyone, and resting brings them round
+        let (actions, _) = engine.deliberate(3, &env_state).await.unwrap();
+        assert!(matches!(actions[&id], Action::Rest));
+        let mut epoch = 3;
+        while engine.agents[0].physical.incapacitated {
+            assert!(epoch < 8, "still collapsed on day {}", epoch);
+            engine.resolve_actions(epoch, actions.clone()).unwrap();
+            epoch += 1;
+            engine.tick_needs(epoch, &env_state).unwrap();
+        }
+        engine.resolve_actions(epoch, BTreeMap::from([(id, Action::Gather)])).unwrap();
+        assert!(!engine.action_results.contains_key(&id));
+    }
+
+    #[test]
+    fn test_group_lesson_teaches_everyone_a_little_less() {
+        // A hunter with three would-be students around them
+        let lesson = |action: fn(Uuid) -> Action| {
+            let mut engine = test_engine(4);
+            for (idx, (x, y)) in [(5, 5), (4, 5), (6, 5), (5, 4)].into_iter().enumerate() {
+                engine.place(idx, x, y);
+                let agent = &mut engine.agents[idx];
+                agent.identity.personality.openness = 0.5;
+                agent.skills.levels.insert("hunting".to_string(), 0.0);
+                agent.skills.levels.insert("teaching".to_string(), 0.0);
+            }
+            engine.agents[0].skills.levels.insert("hunting".to_string(), 0.8);
+            let student = engine.agents[1].id;
+            engine.resolve_actions(1, BTreeMap::from([(engine.agents[0].id, action(student))])).unwrap();
+            engine.agents[1..].iter().map(|a| a.skills.level("hunting")).collect::<Vec<_>>()
+        };
+
+        let solo = lesson(|student| Action::Teach { target: student, skill: "hunting".to_string() })[0];
+        let class = lesson(|_| Action::TeachGroup { skill: "hunting".to_string() });
+        assert!(solo > 0.0);
+        assert!(class.iter().all(|&level| level > 0.0 && level < solo), "{:?} vs {}", class, solo);
+    }
+
+    #[test]
+    fn test_subscriber_sees_every_birth() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let mut engine = test_engine(4);
+        engine.config.reproduction.gestation_period = 1;
+        let births = Arc::new(AtomicUsize::new(0));
+        let seen = Arc::clone(&births);
+        engine.subscribe(move |event| {
+            if matches!(event.event_type, EventType::BirthOccurred) {
+                seen.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+        let logged = Arc::new(AtomicUsize::new(0));
+        let all = Arc::clone(&logged);
+        engine.subscribe(move |_| {
+            all.fetch_add(1, Ordering::SeqCst);
+        });
+
+        // Two courting couples, side by side
+        engine.agents[1].physical.x = 1;
+        engine.agents[3].physical.x = 7;
+        let ids: Vec<Uuid> = engine.agents.iter().map(|a| a.id).collect();
+        for (a, b) in [(0, 1), (2, 3)] {
+            engine.agents[a].reproduction.courtship_progress.insert(ids[b], 1.0);
+            engine.agents[b].reproduction.courtship_progress.insert(ids[a], 1.0);
+        }
+        for agent in &mut engine.agents {
+            agent.physical.food = 20;
+        }
+        engine.attempt_mating(1, ids[0], ids[1]).unwrap();
+        engine.attempt_mating(1, ids[2], ids[3]).unwrap();
+        engine.tick_gestations(2).unwrap();
+        engine.process_births();
+
+        assert_eq!(engine.agents.len(), 6);
+        assert_eq!(births.load(Ordering::SeqCst), 2);
+        let tracked = engine.recent_events.iter().filter(|e| matches!(e.event_type, EventType::BirthOccurred)).count();
+        assert_eq!(births.load(Ordering::SeqCst), tracked);
+        assert_eq!(logged.load(Ordering::SeqCst), engine.recent_events.len());
+    }
+
+    #[test]
+    fn test_starving_cluster_sets_out_together() {
+        let mut engine = test_engine(4);
+        for (agent, (x, y)) in engine.agents.iter_mut().zip([(4, 4), (5, 4), (4, 5), (9, 0)]) {
+            agent.physical.x = x;
+            agent.physical.y = y;
+            agent.physical.hunger = 0.9;
+        }
+        // Only one of them remembers better ground
+        engine.agents[1].beliefs.update_food_belief(8, 8, 10, 1);
+
+        for epoch in 1..=engine.config.migration.epochs {
+            engine.detect_migrations(epoch).unwrap();
+        }
+
+        for agent in &engine.agents[..3] {
+            let migration = agent.migration.as_ref().expect("cluster member should migrate");
+            assert_eq!(migration.direction, Direction::SouthEast);
+            assert_eq!(migration.destination, Some((8, 8)));
+            let perception = engine.build_perception(agent, "", 3);
+            assert!(perception.contains("2 others around you"), "{}", perception);
+            assert!(perception.contains("move on to the southeast"), "{}", perception);
+        }
+        // Starving alone is not an exodus
+        assert!(engine.agents[3].migration.is_none());
+        let began = engine.recent_events.iter().filter(|e| matches!(e.event_type, EventType::MigrationBegan));
+        assert_eq!(began.count(), 1);
+
+        // Setting out restarts the count
+        assert_eq!(engine.agents[0].physical.starving_epochs, 0);
+
+        // Fed again, the pull fades
+        engine.agents[0].physical.hunger = 0.2;
+        engine.detect_migrations(4).unwrap();
+        assert!(engine.agents[0].migration.is_none());
+
+        // Arriving ends it too, hungry or not
+        engine.agents[1].physical.x = 8;
+        engine.agents[1].physical.y = 8;
+        engine.detect_migrations(5).unwrap();
+        assert!(engine.agents[1].migration.is_none());
+        assert!(engine.agents[2].migration.is_some());
+    }
+
+    #[test]
+    fn test_long_dead_are_archived_but_still_found() {
+        let mut engine = test_engine(3);
+        engine.config.simulation.archive_dead_after = 2;
+        let (grandparent, parent, child) = (engine.agents[0].id, engine.agents[1].id, engine.agents[2].id);
+        engine.agents[1].reproduction.family.parents.push(grandparent);
+        engine.agents[2].reproduction.family.parents.push(parent);
+
+        engine.agents[0].physical.health = 0.0;
+        engine.handle_death(1, 0, "old age").unwrap();
+        engine.archive_dead(2);
+        assert_eq!(engine.agents.len(), 3, "recently dead stay active");
+
+        engine.archive_dead(3);
+        assert_eq!(engine.agents.len(), 2);
+        assert!(engine.agents.iter().all(|a| a.is_alive()));
+        assert!(engine.find_agent(grandparent).is_some_and(|a| !a.is_alive()));
+        assert!(engine.agent_view(grandparent).is_some());
+        let ancestors: Vec<Uuid> = engine.ancestors(child).iter().map(|a| a.id).collect();
+        assert_eq!(ancestors, vec![parent, grandparent]);
+        assert_eq!(engine.roster().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_large_population_epoch_resolves_quickly_and_keeps_its_index() {
+        let mut config = Config::default();
+        config.agents.count = 500;
+        config.world.width = 60;
+        config.world.height = 60;
+        config.simulation.archive_dead_after = 1;
+        config.llm.api_key_env = "TERRARIUM_TEST_NO_API_KEY".to_string();
+        let output_dir = std::env::temp_dir().join(format!("terrarium-test-{}", Uuid::new_v4()));
+        let mut engine = Engine::new(config, output_dir.to_str().unwrap()).unwrap();
+        // Only so many names to go round; the rest are settlers
+        for i in engine.agents.len()..500 {
+            engine.add_agent(Agent::new(format!("Settler {}", i), (i * 7) % 60, (i * 7) / 60 % 60, 3));
+        }
+
+        let started = std::time::Instant::now();
+        engine.step().await.unwrap();
+        assert!(started.elapsed() < std::time::Duration::from_secs(20), "epoch took {:?}", started.elapsed());
+
+        let in_step = |engine: &Engine| engine.agents.iter().enumerate().all(|(i, a)| engine.agent_index(a.id) == Some(i));
+        engine.pending_births.push(Agent::new("Newborn".to_string(), 0, 0, 0));
+        engine.process_births();
+        assert_eq!(engine.agents.len(), 501);
+        assert!(in_step(&engine));
+
+        let gone = engine.agents[3].id;
+        engine.agents[3].physical.health = 0.0;
+        engine.agents[3].physical.died_at = Some(0);
+        engine.archive_dead(5);
+        assert!(in_step(&engine));
+        assert_eq!(engine.agent_index(gone), None);
+    }
+
+    #[tokio::test]
+    async fn test_resumed_run_continues_from_its_snapshot() {
+        let mut config = Config::default();
+        config.agents.count = 4;
+        config.simulation.epochs = 10;
+        config.llm.api_key_env = "TERRARIUM_TEST_NO_API_KEY".to_string();
+        let output_dir = std::env::temp_dir().join(format!("terrarium-test-{}", Uuid::new_v4()));
+        let mut engine = Engine::new(config.clone(), output_dir.to_str().unwrap()).unwrap();
+        let ids: Vec<Uuid> = engine.agents.iter().map(|a| a.id).collect();
+        for _ in 0..5 {
+            engine.step().await.unwrap();
+        }
+        engine.trade_state.add_proposal(TradeProposal::new(ids[0], ids[1], vec![TradeableItem::Food(1)], vec![], 5, 20));
+        engine.save_snapshot(engine.world.epoch).unwrap();
+        let path = std::fs::read_dir(output_dir.join("states"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.file_stem().is_some_and(|stem| stem == "epoch_0005"))
+            .unwrap();
+
+        let resumed_dir = output_dir.join("resumed");
+        let mut resumed = Engine::from_snapshot(&path, config, resumed_dir.to_str().unwrap()).unwrap();
+        assert_eq!(resumed.world.epoch, 5);
+        // Agents may have made offers of their own along the way
+        let pending = engine.trade_state.pending_proposals_for(ids[1]).len();
+        assert!(pending >= 1);
+        assert_eq!(resumed.trade_state.pending_proposals_for(ids[1]).len(), pending);
+        assert_eq!(resumed.group_tracker.groups.len(), engine.group_tracker.groups.len());
+        let epochs = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = epochs.clone();
+        resumed.subscribe(move |event| seen.lock().unwrap().push(event.epoch));
+        for _ in 0..5 {
+            resumed.step().await.unwrap();
+        }
+
+        assert_eq!(resumed.world.epoch, 10);
+        assert!(resumed.is_complete());
+        let resumed_ids: Vec<Uuid> = resumed.agents.iter().map(|a| a.id).collect();
+        assert!(ids.iter().all(|id| resumed_ids.contains(id)));
+        let epochs = epochs.lock().unwrap();
+        assert_eq!(epochs.first(), Some(&5));
+        assert_eq!(epochs.iter().max(), Some(&9));
+    }
+
+    #[test]
+    fn test_network_timeline_gets_a_record_per_snapshot() {
+        let mut config = Config::default();
+        config.agents.count = 3;
+        config.simulation.network_timeline = true;
+        let output_dir = std::env::temp_dir().join(format!("terrarium-test-{}", Uuid::new_v4()));
+        let mut engine = Engine::new(config, output_dir.to_str().unwrap()).unwrap();
+        let ids: Vec<Uuid> = engine.agents.iter().map(|a| a.id).collect();
+        let name = engine.agents[1].name().to_string();
+
+        engine.agents[0].beliefs.update_trust(ids[1], &name, 0.5, 10);
+        engine.save_snapshot(10).unwrap();
+        engine.agents[0].beliefs.update_trust(ids[1], &name, -0.3, 20);
+        engine.save_snapshot(20).unwrap();
+
+        let timeline = std::fs::read_to_string(output_dir.join("network_timeline.jsonl")).unwrap();
+        let records: Vec<serde_json::Value> = timeline.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(records.len(), 2);
+        for (record, (epoch, trust)) in records.iter().zip([(10, 0.5), (20, 0.2)]) {
+            assert_eq!(record["epoch"], epoch);
+            assert_eq!(record["nodes"].as_array().unwrap().len(), 3);
+            let edges = record["edges"].as_array().unwrap();
+            assert_eq!(edges.len(), 1);
+            assert_eq!(edges[0]["source"], ids[0].to_string());
+            assert_eq!(edges[0]["target"], ids[1].to_string());
+            assert!((edges[0]["trust"].as_f64().unwrap() - trust).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_llm_cap_keeps_the_most_pressing_agents() {
+        let mut engine = test_engine(5);
+        engine.config.llm.max_llm_agents = Some(2);
+        engine.agents[1].physical.hunger = 0.9;
+        engine.agents[3].physical.health = 0.3;
+
+        let deciders = engine.llm_deciders(0.0).unwrap();
+        assert_eq!(deciders.len(), 2);
+        assert!(deciders.contains(&engine.agents[1].id) && deciders.contains(&engine.agents[3].id));
+
+        // With room for everyone, nobody is held back
+        engine.config.llm.max_llm_agents = Some(5);
+        assert!(engine.llm_deciders(0.0).is_none());
+    }
+
+    #[test]
+    fn test_courtship_does_not_cool_between_neighbours() {
+        let mut engine = test_engine(3);
+        let (near, far) = (engine.agents[1].id, engine.agents[2].id);
+        engine.agents[0].physical.x = 0;
+        engine.agents[0].physical.y = 0;
+        engine.agents[1].physical.x = 1;
+        engine.agents[1].physical.y = 0;
+        engine.agents[2].physical.x = 5;
+        engine.agents[2].physical.y = 5;
+        engine.agents[0].reproduction.courtship_progress.insert(near, 0.5);
+        engine.agents[0].reproduction.courtship_progress.insert(far, 0.5);
+
+        engine.tick_courtship_decay();
+
+        let progress = &engine.agents[0].reproduction.courtship_progress;
+        assert_eq!(progress[&near], 0.5);
+        assert!(progress[&far] < 0.5);
+    }
+
+    /// The events.jsonl of a short heuristic run from `seed`
+    async fn seeded_run_events(seed: u64, concurrency: usize) -> String {
+        let mut config = Config::default();
+        config.llm.concurrency = concurrency;
+        config.agents.count = 16;
+        config.simulation.epochs = 60;
+        config.simulation.seed = Some(seed);
+        config.llm.api_key_env = "TERRARIUM_TEST_NO_API_KEY".to_string();
+        let output_dir = std::env::temp_dir().join(format!("terrarium-test-{}", Uuid::new_v4()));
+        let mut engine = Engine::new(config, output_dir.to_str().unwrap()).unwrap();
+        engine.run().await.unwrap();
+        drop(engine);
+        let events = std::fs::read_to_string(output_dir.join("events.jsonl")).unwrap();
+        std::fs::remove_dir_all(&output_dir).unwrap();
+        events
+    }
+
+    #[tokio::test]
+    async fn test_same_seed_logs_the_same_events() {
+        let first = seeded_run_events(7, 1).await;
+        let second = seeded_run_events(7, 1).await;
+        assert!(first.contains("Gathered"));
+        assert!(first == second, "runs from the same seed diverged");
+    }
+
+    #[tokio::test]
+    async fn test_resolution_order_independent_of_concurrency() {
+        // Decisions finish in a different order with more calls in flight
+        let serial = seeded_run_events(7, 1).await;
+        let parallel = seeded_run_events(7, 8).await;
+        assert!(serial == parallel, "events depend on how many decisions ran at once");
+    }
+
+    #[test]
+    fn test_give_materials_to_neighbour() {
+        let mut engine = test_engine(2);
+        let (giver, receiver) = (engine.agents[0].id, engine.agents[1].id);
+        let receiver_name = engine.agents[1].name().to_string();
+        engine.agents[1].physical.x = 1;
+        engine.agents[1].physical.y = 0;
+        engine.agents[0].physical.materials.clear();
+        engine.agents[1].physical.materials.clear();
+        engine.agents[0].physical.add_material(MaterialType::Wood, 3);
+
+        let nearby = [(receiver, receiver_name.as_str())];
+        let action = Action::parse(&format!("GIVE {} 3 WOOD", receiver_name), &nearby).unwrap();
+        engine.resolve_actions(1, BTreeMap::from([(giver, action)])).unwrap();
+
+        assert_eq!(engine.agents[0].physical.materials.get(&MaterialType::Wood).copied().unwrap_or(0), 0);
+        assert_eq!(engine.agents[1].physical.materials.get(&MaterialType::Wood).copied(), Some(3));
+        assert!(engine.agents[0].memory.recent.iter().any(|e| e.description.starts_with("I gave 3 wood")));
+        assert!(engine.agents[1].memory.recent.iter().any(|e| e.description.ends_with("gave me 3 wood")));
+    }
+
+    #[test]
+    fn test_lone_infertile_agent_ends_run_after_grace() {
+        let mut engine = test_engine(1);
+        engine.config.reproduction.stop_when_nonviable = true;
+        engine.config.reproduction.nonviable_grace_epochs = 3;
+        engine.agents[0].physical.age = engine.config.aging.prime_end;
+
+        for epoch in 0..3 {
+            assert!(!engine.is_complete());
+            engine.track_viability(epoch);
+        }
+        assert!(engine.is_complete());
+    }
+
+    #[test]
+    fn test_perception_reports_days_since_meal() {
+        let mut engine = test_engine(1);
+        let id = engine.agents[0].id;
+        engine.agents[0].physical.food = 5;
+        engine.resolve_actions(1, BTreeMap::from([(id, Action::Eat)])).unwrap();
+
+        for (epoch, health) in [(2, 0.9), (3, 0.8), (4, 0.7), (5, 0.6)] {
+            engine.agents[0].physical.health = health;
+            engine.record_vitals(epoch);
+        }
+
+        let perception = engine.build_perception(&engine.agents[0], "", 6);
+        assert!(perception.contains("You last ate 5 days ago."));
+        assert!(perception.contains("Your health has been falling for 3 days."));
+    }
+
+    #[test]
+    fn test_non_lethal_attack_drives_victim_off() {
+        let mut engine = test_engine(2);
+        engine.config.combat.lethality = 0.0;
+        let (attacker, victim) = (engine.agents[0].id, engine.agents[1].id);
+        engine.agents[0].physical.x = 4;
+        engine.agents[0].physical.y = 4;
+        engine.agents[1].physical.x = 5;
+        engine.agents[1].physical.y = 4;
+        engine.agents[1].physical.health = 0.2;
+
+        engine.resolve_actions(1, BTreeMap::from([(attacker, Action::Attack { target: victim })])).unwrap();
+
+        let victim = &engine.agents[1];
+        assert!(victim.is_alive());
+        assert_eq!((victim.physical.x, victim.physical.y), (7, 4));
+    }
+
+    #[test]
+    fn test_attack_site_becomes_danger_landmark_to_avoid() {
+        let mut engine = test_engine(2);
+        let (attacker, victim) = (engine.agents[0].id, engine.agents[1].id);
+        engine.agents[1].physical.x = 1;
+        engine.agents[1].physical.y = 0;
+
+        engine.resolve_actions(1, BTreeMap::from([(attacker, Action::Attack { target: victim })])).unwrap();
+        assert!(engine.agents[1].beliefs.is_dangerous(1, 0, 1));
+
+        // Hungry on picked-clean ground, with good food remembered back at the attack site
+        let agent = &mut engine.agents[1];
+        agent.physical.x = 3;
+        agent.physical.food = 0;
+        agent.physical.hunger = 0.8;
+        agent.physical.health = 1.0;
+        agent.beliefs.update_food_belief(3, 0, 0, 2);
+        agent.beliefs.update_food_belief(1, 0, 8, 2);
+
+        let perception = engine.build_perception(&engine.agents[1], "", 2);
+        assert!(perception.contains("attacked you at (1, 0)"));
+
+        let action = crate::llm::heuristic_action(&Situation::alone(&engine.agents[1], 2), &engine.config.instincts);
+        assert!(matches!(action, Action::Gather));
+
+        // Without the bad memory they would head back for the food
+        engine.agents[1].beliefs.world.landmarks.clear();
+        let action = crate::llm::heuristic_action(&Situation::alone(&engine.agents[1], 2), &engine.config.instincts);
+        assert!(matches!(action, Action::Move(Direction::West)));
+    }
+
+    #[test]
+    fn test_single_move_deltas() {
+        let mut engine = test_engine(3);
+        let mover = engine.agents[0].id;
+
+        engine.resolve_actions(1, BTreeMap::from([(mover, Action::Move(Direction::East))])).unwrap();
+
+        let cells: Vec<(usize, usize)> = engine.changed_cells_since(1).iter().map(|c| (c.x, c.y)).collect();
+        assert_eq!(cells, vec![(0, 0), (1, 0)]);
+        let agents: Vec<Uuid> = engine.agent_deltas_since(1).iter().map(|a| a.id).collect();
+        assert_eq!(agents, vec![mover]);
+        assert!(engine.changed_cells_since(2).is_empty());
+
+        // Outside resolution, only agents whose state differs count as changed
+        let cells_before: Vec<CellFingerprint> = engine.world.cells.iter().map(cell_fingerprint).collect();
+        let agents_before = engine.agents.iter().map(|a| (a.id, agent_fingerprint(a))).collect();
+        engine.agents[2].physical.food += 1;
+        engine.agents[1].physical.age += 1;
+        engine.track_changes(2, &cells_before, &agents_before);
+        let agents: Vec<Uuid> = engine.agent_deltas_since(2).iter().map(|a| a.id).collect();
+        assert_eq!(agents, vec![engine.agents[2].id]);
+    }
+
+    #[test]
+    fn test_mountains_cost_more_to_climb_and_water_turns_back_non_swimmers() {
+        let mut engine = test_engine(2);
+        engine.world.get_mut(1, 0).unwrap().terrain = Terrain::Fertile;
+        engine.world.get_mut(1, 1).unwrap().terrain = Terrain::Mountain;
+        engine.world.get_mut(2, 1).unwrap().terrain = Terrain::Water;
+        for idx in 0..2 {
+            engine.place(idx, 0, idx);
+            engine.agents[idx].physical.energy = 1.0;
+        }
+        let (plains, climber) = (engine.agents[0].id, engine.agents[1].id);
+
+        let actions = BTreeMap::from([(plains, Action::Move(Direction::East)), (climber, Action::Move(Direction::East))]);
+        engine.resolve_actions(1, actions).unwrap();
+        let energy = |engine: &Engine, id| engine.agent(id).unwrap().physical.energy;
+        assert!(energy(&engine, climber) < energy(&engine, plains));
+
+        // Nobody starts out able to swim
+        let before = energy(&engine, climber);
+        engine.resolve_actions(2, BTreeMap::from([(climber, Action::Move(Direction::East))])).unwrap();
+        let agent = engine.agent(climber).unwrap();
+        assert_eq!((agent.physical.x, agent.physical.y), (1, 1));
+        assert_eq!(agent.physical.energy, before);
+        assert!(engine.action_results[&climber].contains("too deep"));
+
+        // but keeps trying at the shore until they can
+        for epoch in 3..20 {
+            engine.resolve_actions(epoch, BTreeMap::from([(climber, Action::Move(Direction::East))])).unwrap();
+            if engine.agent(climber).unwrap().physical.x == 2 {
+                break;
+            }
+        }
+        let agent = engine.agent(climber).unwrap();
+        assert_eq!((agent.physical.x, agent.physical.y), (2, 1));
+        assert!(agent.skills.level("swimming") >= engine.config.movement.swim_skill);
+    }
+
+    #[test]
+    fn test_traveller_walks_to_remembered_food_around_water() {
+        let mut engine = test_engine(1);
+        for cell in engine.world.cells.iter_mut() {
+            cell.terrain = Terrain::Fertile;
+        }
+        engine.world.get_mut(2, 0).unwrap().terrain = Terrain::Water;
+        engine.world.get_mut(2, 1).unwrap().terrain = Terrain::Water;
+        engine.place(0, 0, 0);
+        let traveller = engine.agents[0].id;
+        let action = Action::parse("TRAVEL (4, 0)", &[]).unwrap();
+
+        engine.resolve_actions(1, BTreeMap::from([(traveller, action.clone())])).unwrap();
+        let route = engine.agents[0].route.clone().expect("the way there is remembered");
+        assert_eq!(route.destination, (4, 0));
+        assert_eq!(route.steps.last(), Some(&(4, 0)));
+
+        for epoch in 2..10 {
+            engine.resolve_actions(epoch, BTreeMap::from([(traveller, action.clone())])).unwrap();
+        }
+        let agent = &engine.agents[0];
+        assert_eq!((agent.physical.x, agent.physical.y), (4, 0));
+        assert!(agent.route.is_none());
+        assert!(engine.action_results[&traveller].contains("already there"));
+    }
+
+    #[test]
+    fn test_gathering_in_a_forest_also_yields_wood() {
+        let mut engine = test_engine(1);
+        let forager = engine.agents[0].id;
+        let (x, y) = (engine.agents[0].physical.x, engine.agents[0].physical.y);
+        let cell = engine.world.get_mut(x, y).unwrap();
+        cell.terrain = Terrain::Forest;
+        cell.food = 10;
+        engine.agents[0].physical.materials.clear();
+
+        engine.resolve_actions(1, BTreeMap::from([(forager, Action::Gather)])).unwrap();
+
+        let agent = &engine.agents[0];
+        assert!(agent.physical.food > 0);
+        assert_eq!(agent.physical.material_count(MaterialType::Wood), 1);
+        assert!(agent.prompt_state(1).contains("as well as 1 wood"), "{}", agent.prompt_state(1));
+        let view = crate::observer::AgentView::from_agent(agent, &engine.agents, &engine.config.aging, &engine.trade_state);
+        assert_eq!(view.materials.get("wood"), Some(&1));
+    }
+
+    #[test]
+    fn test_crafted_basket_in_hand_raises_the_gather_yield() {
+        let mut engine = test_engine(1);
+        engine.config.world_events.enabled = false;
+        let crafter = engine.agents[0].id;
+        let (x, y) = (engine.agents[0].physical.x, engine.agents[0].physical.y);
+        engine.world.get_mut(x, y).unwrap().terrain = Terrain::Fertile;
+        let agent = &mut engine.agents[0];
+        agent.skills.levels.clear();
+        agent.physical.materials.clear();
+        agent.physical.tools.clear();
+        let gather = |engine: &mut Engine, epoch| {
+            engine.world.get_mut(x, y).unwrap().food = 20;
+            engine.agents[0].physical.food = 0;
+            engine.resolve_actions(epoch, BTreeMap::from([(crafter, Action::Gather)])).unwrap();
+            engine.agents[0].physical.food
+        };
+        let bare_handed = gather(&mut engine, 1);
+
+        engine.resolve_actions(2, BTreeMap::from([(crafter, Action::Craft { tool: ToolType::Basket })])).unwrap();
+        assert!(engine.action_results[&crafter].contains("skilled enough"));
+        engine.agents[0].skills.levels.insert("crafting".to_string(), 0.9);
+        engine.resolve_actions(3, BTreeMap::from([(crafter, Action::Craft { tool: ToolType::Basket })])).unwrap();
+        assert!(engine.action_results[&crafter].contains("need 4 more fiber and 1 more wood"));
+
+        engine.agents[0].physical.add_material(MaterialType::Fiber, 4);
+        engine.agents[0].physical.add_material(MaterialType::Wood, 1);
+        engine.resolve_actions(4, BTreeMap::from([(crafter, Action::Craft { tool: ToolType::Basket })])).unwrap();
+        assert!(engine.agents[0].physical.has_tool(ToolType::Basket));
+        assert!(engine.agents[0].physical.materials.is_empty());
+        engine.resolve_actions(5, BTreeMap::from([(crafter, Action::Equip { tool: ToolType::Basket })])).unwrap();
+
+        let with_basket = gather(&mut engine, 6);
+        assert!(with_basket > bare_handed, "{} with a basket, {} without", with_basket, bare_handed);
+    }
+
+    #[test]
+    fn test_poor_basket_breaks_after_its_uses_and_is_thrown_away() {
+        let mut engine = test_engine(1);
+        engine.config.world_events.enabled = false;
+        let forager = engine.agents[0].id;
+        let (x, y) = (engine.agents[0].physical.x, engine.agents[0].physical.y);
+        engine.world.get_mut(x, y).unwrap().terrain = Terrain::Fertile;
+        let basket = Tool::new(ToolType::Basket, ToolQuality::Poor, None, 0);
+        let uses = basket.max_durability as usize;
+        assert!(uses < Tool::new(ToolType::Basket, ToolQuality::Excellent, None, 0).max_durability as usize);
+        engine.agents[0].physical.tools = vec![basket];
+        engine.agents[0].physical.equip(ToolType::Basket);
+        let broke = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = broke.clone();
+        engine.subscribe(move |event| {
+            if matches!(event.event_type, EventType::ToolBroke) {
+                seen.lock().unwrap().push(event.epoch);
+            }
+        });
+
+        for epoch in 1..=uses {
+            engine.world.get_mut(x, y).unwrap().food = 20;
+            engine.agents[0].physical.food = 0;
+            engine.agents[0].physical.energy = 1.0;
+            engine.resolve_actions(epoch, BTreeMap::from([(forager, Action::Gather)])).unwrap();
+            let still_held = engine.agents[0].physical.has_tool(ToolType::Basket);
+            assert_eq!(still_held, epoch < uses, "after {} of {} uses", epoch, uses);
+        }
+        assert!(engine.agents[0].physical.tools.is_empty());
+        assert_eq!(engine.agents[0].physical.equipped, None);
+        assert_eq!(*broke.lock().unwrap(), vec![uses]);
+
+        let mut tool = Tool::new(ToolType::WoodenSpear, ToolQuality::Poor, None, 0);
+        let breaks: Vec<bool> = (0..tool.max_durability).map(|_| tool.use_once()).collect();
+        assert_eq!(breaks.iter().filter(|b| **b).count(), 1);
+        assert_eq!(breaks.last(), Some(&true));
+        assert!(!tool.use_once());
+    }
+
+    #[test]
+    fn test_attacked_agent_flees_away_from_the_attacker() {
+        let mut engine = test_engine(2);
+        engine.place(0, 4, 5);
+        engine.place(1, 5, 5);
+        let (attacker, victim) = (engine.agents[0].id, engine.agents[1].id);
+
+        engine.resolve_actions(1, BTreeMap::from([(victim, Action::Flee)])).unwrap();
+        assert!(engine.action_results[&victim].contains("nobody is after you"));
+
+        engine.resolve_actions(2, BTreeMap::from([(attacker, Action::Attack { target: victim })])).unwrap();
+        let threat = engine.agents[1].threat_source.clone().expect("the victim knows who attacked them");
+        assert_eq!((threat.attacker, threat.position), (attacker, (4, 5)));
+
+        // Too hurt to stand their ground, they run
+        engine.agents[1].physical.health = 0.3;
+        let action = crate::llm::heuristic_action(&Situation::alone(&engine.agents[1], 2), &engine.config.instincts);
+        assert!(matches!(action, Action::Flee));
+        engine.resolve_actions(3, BTreeMap::from([(victim, action)])).unwrap();
+        let agent = &engine.agents[1];
+        let chebyshev = agent.physical.x.abs_diff(4).max(agent.physical.y.abs_diff(5));
+        assert_eq!(chebyshev, 2, "fled to ({}, {})", agent.physical.x, agent.physical.y);
+        engine.agents[1].update_goal(&engine.config.instincts);
+        assert!(matches!(engine.agents[1].active_goal, Some(Goal::Flee)));
+
+        // Out of reach, they see to their other needs
+        engine.place(1, 8, 5);
+        let action = crate::llm::heuristic_action(&Situation::alone(&engine.agents[1], 3), &engine.config.instincts);
+        assert!(!matches!(action, Action::Flee), "{:?}", action);
+
+        // Boxed into a corner, they fail once and then stop trying
+        engine.place(1, 0, 0);
+        engine.agents[1].threat_source.as_mut().unwrap().position = (1, 1);
+        engine.resolve_actions(4, BTreeMap::from([(victim, Action::Flee)])).unwrap();
+        assert!(engine.action_results[&victim].contains("nowhere left to run"));
+        assert!(!engine.agents[1].should_flee(&engine.config.instincts));
+    }
+
+    #[test]
+    fn test_devoted_neighbour_steps_in_and_takes_part_of_the_blow() {
+        let mut engine = test_engine(3);
+        for (idx, x) in [4, 5, 6].into_iter().enumerate() {
+            engine.place(idx, x, 5);
+            engine.agents[idx].physical.health = 1.0;
+        }
+        let (attacker, victim, friend) = (engine.agents[0].id, engine.agents[1].id, engine.agents[2].id);
+        engine.agents[2].beliefs.update_trust(victim, "victim", 0.9, 0);
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = seen.clone();
+        engine.subscribe(move |event| {
+            log.lock().unwrap().push((event.event_type.clone(), event.data.damage, event.data.ally, event.data.damage_reduction));
+        });
+
+        engine.resolve_actions(1, BTreeMap::from([(attacker, Action::Attack { target: victim })])).unwrap();
+
+        let events = seen.lock().unwrap();
+        let (_, _, ally, reduction) =
+            events.iter().find(|e| matches!(e.0, EventType::AllyIntervened)).expect("the friend stepped in");
+        assert_eq!(*ally, Some(friend));
+        assert_eq!(*reduction, Some(engine.config.combat.ally_reduction));
+        let damage = events.iter().find(|e| matches!(e.0, EventType::Attacked)).and_then(|e| e.1).unwrap();
+        assert!((1.0 - engine.agents[1].physical.health - damage).abs() < 1e-9);
+        // The friend took their share of what they turned aside
+        let combat = &engine.config.combat;
+        let turned_aside = damage / (1.0 - combat.ally_reduction) - damage;
+        assert!((1.0 - engine.agents[2].physical.health - turned_aside * combat.ally_share).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_only_one_mover_fits_a_cell_of_one() {
+        let mut engine = test_engine(2);
+        engine.config.movement.max_occupancy = Some(1);
+        for (idx, x) in [(0, 2), (1, 4)] {
+            let y = engine.agents[idx].physical.y;
+            engine.place(idx, x, y);
+        }
+        let (west, east) = (engine.agents[0].id, engine.agents[1].id);
+
+        let actions = BTreeMap::from([(west, Action::Move(Direction::East)), (east, Action::Move(Direction::West))]);
+        engine.resolve_actions(1, actions).unwrap();
+
+        let positions: Vec<(usize, usize)> = engine.agents.iter().map(|a| (a.physical.x, a.physical.y)).collect();
+        let winner = west.min(east);
+        let (moved, stayed) = if winner == west { ((3, 0), (4, 0)) } else { ((2, 0), (3, 0)) };
+        assert_eq!(positions, vec![moved, stayed]);
+        let loser = west.max(east);
+        assert!(engine.action_results[&loser].contains("no room"));
+    }
+
+    #[test]
+    fn test_scripted_plague_starts_on_its_epoch() {
+        let mut engine = test_engine(2);
+        engine.config.world_events.enabled = false;
+        engine.config.scenario.scripted_events.push(ScriptedEvent {
+            epoch: 3,
+            action: ScriptedAction::WorldEvent {
+                event: WorldEvent::Plague { severity: 0.5 },
+                duration: Some(5),
+            },
+        });
+
+        for epoch in 1..=5 {
+            engine.world.tick(&engine.config.world, 1.0, "");
+            engine.apply_scripted_events(epoch).unwrap();
+        }
+        let scripted: Vec<&Event> =
+            engine.recent_events.iter().filter(|e| matches!(e.event_type, EventType::Scripted)).collect();
+        assert_eq!(scripted.len(), 1);
+        assert_eq!(scripted[0].epoch, 3);
+        let description = scripted[0].data.description.as_deref().unwrap();
+        assert!(description.to_lowercase().contains("plague"), "{}", description);
+    }
+
+    #[tokio::test]
+    async fn test_scripted_events_follow_their_epoch_start() {
+        let mut config = Config::default();
+        config.agents.count = 2;
+        config.llm.api_key_env = "TERRARIUM_TEST_NO_API_KEY".to_string();
+        config.world_events.enabled = false;
+        config.scenario.scripted_events.push(ScriptedEvent {
+            epoch: 1,
+            action: ScriptedAction::SpawnAgent { name: "Stranger".to_string(), x: None, y: None, model: None },
+        });
+        let output_dir = std::env::temp_dir().join(format!("terrarium-test-{}", Uuid::new_v4()));
+        let mut engine = Engine::new(config, output_dir.to_str().unwrap()).unwrap();
+
+        engine.run_epoch(0).await.unwrap();
+        engine.run_epoch(1).await.unwrap();
+
+        let order: Vec<(usize, bool)> = engine
+            .recent_events
+            .iter()
+            .filter(|e| matches!(e.event_type, EventType::EpochStart | EventType::Scripted))
+            .map(|e| (e.epoch, matches!(e.event_type, EventType::Scripted)))
+            .collect();
+        assert_eq!(order, vec![(0, false), (1, false), (1, true)]);
+    }
+
+    #[test]
+    fn test_equipped_spear_boosts_attack_until_unequipped() {
+        let mut engine = test_engine(2);
+        let (attacker, victim) = (engine.agents[0].id, engine.agents[1].id);
+        engine.agents[1].physical.x = 1;
+        engine.agents[1].physical.y = 0;
+        engine.agents[0]
+            .physical
+            .tools
+            .push(Tool::new(ToolType::WoodenSpear, ToolQuality::Excellent, None, 0));
+
+        engine.resolve_actions(1, BTreeMap::from([(attacker, Action::Equip { tool: ToolType::WoodenSpear })])).unwrap();
+        let multiplier = engine.agents[0].physical.attack_multiplier();
+        assert!((multiplier - 1.52).abs() < 1e-9);
+
+        engine.resolve_actions(2, BTreeMap::from([(attacker, Action::Attack { target: victim })])).unwrap();
+        let armed_damage = 1.0 - engine.agents[1].physical.health;
+        assert!(armed_damage >= 0.15 * multiplier - 1e-9);
+
+        engine.agents[1].physical.health = 1.0;
+        engine.resolve_actions(3, BTreeMap::from([(attacker, Action::Unequip)])).unwrap();
+        assert_eq!(engine.agents[0].physical.attack_multiplier(), 1.0);
+
+        engine.resolve_actions(4, BTreeMap::from([(attacker, Action::Attack { target: victim })])).unwrap();
+        let unarmed_damage = 1.0 - engine.agents[1].physical.health;
+        assert!(unarmed_damage <= 0.25 + 1e-9);
+    }
+
+    #[test]
+    fn test_dead_agents_food_is_left_for_survivors() {
+        let mut engine = test_engine(2);
+        let (dying, survivor) = (engine.agents[0].id, engine.agents[1].id);
+        engine.agents[1].physical.x = 0;
+        engine.agents[1].physical.y = 0;
+        engine.agents[0].physical.food = 4;
+        engine.agents[0].physical.tools.push(Tool::new(ToolType::StoneAxe, ToolQuality::Standard, None, 0));
+        engine.world.get_mut(0, 0).unwrap().food = 0;
+
+        engine.agents[0].physical.health = 0.0;
+        engine.handle_death(1, 0, "exhaustion").unwrap();
+        assert_eq!(engine.agents[0].physical.food, 0);
+        assert_eq!(engine.world.get(0, 0).unwrap().food, 4);
+        assert_eq!(engine.world.get(0, 0).unwrap().dropped_tools.len(), 1);
+
+        let food_before = engine.agents[1].physical.food;
+        engine.resolve_actions(2, BTreeMap::from([(survivor, Action::Gather)])).unwrap();
+        assert_eq!(engine.agents[1].physical.food, food_before + 4);
+        assert!(engine.agents[1].physical.has_tool(ToolType::StoneAxe));
+        assert!(engine.world.get(0, 0).unwrap().dropped_tools.is_empty());
+        assert!(engine.agents.iter().any(|a| a.id == dying && !a.is_alive()));
+    }
+
+    #[test]
+    fn test_group_member_perceives_hierarchy_rank() {
+        let mut engine = test_engine(3);
+        let ids: Vec<Uuid> = engine.agents.iter().map(|a| a.id).collect();
+        // Incoming trust: agent 2 most, then agent 0, then agent 1
+        let trust_in = [0.8, 0.4, 1.0];
+        for from in 0..3 {
+            for to in (0..3).filter(|&to| to != from) {
+                let name = engine.agents[to].name().to_string();
+                engine.agents[from].beliefs.update_trust(ids[to], &name, trust_in[to], 0);
+            }
+        }
+        engine.group_tracker.detect(&engine.agents, 1);
+        let group = engine.group_tracker.group_of(ids[0]).unwrap();
+        assert_eq!(group.rank_of(ids[2]), Some(1));
+        let group_name = group.name.clone();
+
+        let perception = engine.build_perception(&engine.agents[0], "", 1);
+        assert!(
+            perception.contains(&format!("you are the 2nd most trusted of the 3 members of {}", group_name)),
+            "{}",
+            perception
+        );
+        assert!(perception.contains("those who know you hold you in high regard"));
+
+        let perception = engine.build_perception(&engine.agents[2], "", 1);
+        assert!(perception.contains("they look to you to lead"));
+    }
+
+    #[test]
+    fn test_natural_death_follows_each_agents_own_lifespan() {
+        let mut aging = AgingConfig {
+            max_lifespan: 100_000,
+            lifespan_stddev: 10_000.0,
+            ..AgingConfig::default()
+        };
+        let (a, b) = (Agent::new("Aric".to_string(), 0, 0, 5), Agent::new("Bria".to_string(), 0, 0, 5));
+        assert_ne!(a.max_lifespan(&aging), b.max_lifespan(&aging));
+
+        let mut engine = test_engine(2);
+        aging.max_lifespan = 150;
+        aging.lifespan_stddev = 10.0;
+        aging.death_probability_rate = 0.0;
+        engine.config.aging = aging;
+        engine.agents[0].lifespan_deviation = 1.0;
+        engine.agents[1].lifespan_deviation = -1.0;
+        engine.agents[0].physical.age = 158;
+        engine.agents[1].physical.age = 139;
+
+        engine.tick_aging(1).unwrap();
+        assert!(engine.agents[0].is_alive());
+        assert!(!engine.agents[1].is_alive());
+
+        engine.tick_aging(2).unwrap();
+        assert!(!engine.agents[0].is_alive());
+        assert_eq!(engine.agents[0].physical.age, 160);
+    }
+
+    #[test]
+    fn test_broken_casual_promise_costs_trust() {
+        let mut engine = test_engine(2);
+        let (promiser, friend) = (engine.agents[0].id, engine.agents[1].id);
+        engine.agents[1].physical.x = 1;
+        engine.agents[1].physical.y = 0;
+
+        let promise = TradeableItem::FutureGiftPromise { amount: 3, deadline_epochs: 2 };
+        engine.resolve_actions(1, BTreeMap::from([(promiser, Action::Promise { target: friend, promise })])).unwrap();
+        // Nothing to pay with when the gift comes due
+        engine.agents[0].physical.food = 2;
+
+        let debts = engine.trade_state.debts_owed_by(promiser);
+        assert_eq!(debts.len(), 1);
+        assert_eq!(debts[0].creditor, friend);
+        assert_eq!(debts[0].deadline_epoch, Some(3));
+
+        engine.check_service_deadlines(3).unwrap();
+        assert!(engine.agents[1].beliefs.social.get(&promiser).is_none_or(|b| b.trust >= 0.0));
+
+        engine.check_service_deadlines(4).unwrap();
+        let trust = engine.agents[1].beliefs.social[&promiser].trust;
+        assert!((trust + engine.config.trade.renege_trust_penalty).abs() < 1e-9);
+        assert!(engine.trade_state.debts_owed_by(promiser).is_empty());
+    }
+
+    #[test]
+    fn test_due_gift_is_paid_from_the_debtors_stores() {
+        let mut engine = test_engine(2);
+        let (promiser, friend) = (engine.agents[0].id, engine.agents[1].id);
+        engine.agents[1].physical.x = 1;
+        engine.agents[0].physical.food = 5;
+        engine.agents[1].physical.food = 0;
+
+        let promise = TradeableItem::FutureGiftPromise { amount: 3, deadline_epochs: 2 };
+        engine.resolve_actions(1, BTreeMap::from([(promiser, Action::Promise { target: friend, promise })])).unwrap();
+        engine.check_service_deadlines(3).unwrap();
+        assert_eq!(engine.agents[1].physical.food, 0);
+
+        engine.check_service_deadlines(4).unwrap();
+        assert_eq!((engine.agents[0].physical.food, engine.agents[1].physical.food), (2, 3));
+        assert!(engine.agents[1].beliefs.social[&promiser].trust > 0.0);
+        assert!(engine.trade_state.debts_owed_by(promiser).is_empty());
+    }
+
+    #[test]
+    fn test_shared_knowledge_teaches_food_location() {
+        let mut engine = test_engine(2);
+        let (elder, listener) = (engine.agents[0].id, engine.agents[1].id);
+        engine.agents[1].physical.x = 1;
+        engine.agents[1].physical.y = 0;
+        engine.agents[0].beliefs.update_food_belief(7, 8, 12, 4);
+        assert!(engine.agents[1].beliefs.food_at(7, 8).is_none());
+
+        engine.resolve_actions(5, BTreeMap::from([(elder, Action::ShareKnowledge { target: listener })])).unwrap();
+
+        assert_eq!(engine.agents[1].beliefs.food_at(7, 8), Some(12));
+        let heard = engine.agents[1].beliefs.best_food_elsewhere(1, 0, 5).unwrap();
+        assert_eq!((heard.x, heard.y, heard.belief.last_seen_epoch), (7, 8, 4));
+    }
+
+    #[test]
+    fn test_shelter_reduces_attack_damage() {
+        let mut engine = test_engine(4);
+        let ids: Vec<Uuid> = engine.agents.iter().map(|a| a.id).collect();
+        // Pair 0 fights in the open at (0, 0); pair 1 attacks its victim inside a shelter at (5, 5)
+        for (idx, pos) in [(0, (0, 0)), (1, (1, 0)), (2, (5, 5)), (3, (6, 5))] {
+            engine.agents[idx].physical.x = pos.0;
+            engine.agents[idx].physical.y = pos.1;
+            engine.agents[idx].physical.health = 1.0;
+        }
+        let mut shelter = Structure::new(StructureType::Shelter, ids[3], 20, ToolQuality::Standard, 0);
+        shelter.add_progress(20, 0);
+        engine.world.get_mut(6, 5).unwrap().structure = Some(shelter);
+        engine.agents[3].physical.sheltered_at = Some((6, 5));
+
+        let perception = engine.build_perception(&engine.agents[3], "", 1);
+        assert!(perception.contains("You are defended by your shelter"));
+
+        let attacker_energy = engine.agents[2].physical.energy;
+        engine.resolve_actions(1, BTreeMap::from([
+            (ids[0], Action::Attack { target: ids[1] }),
+            (ids[2], Action::Attack { target: ids[3] }),
+        ])).unwrap();
+
+        let open_damage = 1.0 - engine.agents[1].physical.health;
+        let sheltered_damage = 1.0 - engine.agents[3].physical.health;
+        assert!(sheltered_damage > 0.0);
+        // Base damage varies by at most 0.1 on 0.15; the shelter halves it
+        assert!(sheltered_damage < open_damage, "{} vs {}", sheltered_damage, open_damage);
+        assert!(engine.agents[2].physical.energy < attacker_energy);
+    }
+
+    #[test]
+    fn test_roster_seeds_mutual_distrust() {
+        let mut config = Config::default();
+        config.agents.count = 3;
+        let rival = |name: &str, with: &str| RosterEntry {
+            name: name.to_string(),
+            model: None,
+            relationships: vec![RelationshipSeed { with: with.to_string(), trust: -0.7, sentiment: -0.5 }],
+            parents: Vec::new(),
+            mates: Vec::new(),
+        };
+        config.agents.roster = vec![rival("Aric", "Bria"), rival("Bria", "aric")];
+        let output_dir = std::env::temp_dir().join(format!("terrarium-test-{}", Uuid::new_v4()));
+        let engine = Engine::new(config.clone(), output_dir.to_str().unwrap()).unwrap();
+
+        let (aric, bria) = (&engine.agents[0], &engine.agents[1]);
+        assert_eq!(aric.beliefs.social[&bria.id].trust, -0.7);
+        assert_eq!(bria.beliefs.social[&aric.id].trust, -0.7);
+        assert_eq!(bria.beliefs.social[&aric.id].sentiment, -0.5);
+        assert!(engine.agents[2].beliefs.social.is_empty());
+
+        config.agents.roster[1].relationships[0].with = "Nobody".to_string();
+        assert!(Engine::new(config, output_dir.to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_roster_family_ties_are_checked_whatever_the_order() {
+        let entry = |name: &str, parents: &[&str]| RosterEntry {
+            name: name.to_string(),
+            model: None,
+            relationships: Vec::new(),
+            parents: parents.iter().map(|p| p.to_string()).collect(),
+            mates: Vec::new(),
+        };
+        let build = |roster: Vec<RosterEntry>| {
+            let mut config = Config::default();
+            config.agents.count = 3;
+            config.agents.roster = roster;
+            let output_dir = std::env::temp_dir().join(format!("terrarium-test-{}", Uuid::new_v4()));
+            Engine::new(config, output_dir.to_str().unwrap())
+        };
+
+        // The grandchild comes first, before their parent has a parent of their own
+        let engine = build(vec![entry("Cara", &["Bria"]), entry("Bria", &["Aric"]), entry("Aric", &[])]).unwrap();
+        let generations: Vec<usize> = engine.agents.iter().map(|a| a.reproduction.family.generation).collect();
+        assert_eq!(generations, vec![2, 1, 0]);
+
+        let cycle = build(vec![entry("Aric", &["Bria"]), entry("Bria", &["Aric"])]);
+        assert!(cycle.err().unwrap().to_string().contains("own ancestor"));
+        let twice = build(vec![entry("Aric", &[]), entry("aric", &[])]);
+        assert!(twice.err().unwrap().to_string().contains("more than once"));
+    }
+
+    #[test]
+    fn test_scavenging_remains_feeds_but_shames() {
+        let mut engine = test_engine(3);
+        engine.config.scavenging.enabled = true;
+        let ids: Vec<Uuid> = engine.agents.iter().map(|a| a.id).collect();
+        for (idx, (x, y)) in [(0, (4, 4)), (1, (4, 4)), (2, (5, 4))] {
+            engine.place(idx, x, y);
+        }
+        engine.agents[1].physical.health = 0.0;
+        engine.handle_death(3, 1, "starvation").unwrap();
+
+        let food_before = engine.agents[0].physical.food;
+        let belonging_before = engine.agents[0].beliefs.self_belief.perceived_belonging;
+        engine.resolve_actions(4, BTreeMap::from([(ids[0], Action::Scavenge)])).unwrap();
+
+        let scavenger = &engine.agents[0];
+        assert_eq!(scavenger.physical.food, food_before + engine.config.scavenging.remains_food);
+        assert!(scavenger.beliefs.self_belief.perceived_belonging <= belonging_before);
+        assert!(scavenger.memory.recent.iter().any(|e| e.description.contains("remains") && e.valence < 0.0));
+
+        let witness = &engine.agents[2].beliefs.social[&ids[0]];
+        assert!(witness.trust < 0.0);
+        assert!(witness.sentiment < 0.0);
+    }
+
+    #[test]
+    fn test_assisted_build_finishes_sooner_and_pays_labor_debt() {
+        // Agents 1 and 2 flank an unfinished shelter that agent 0 started
+        let days_to_finish = |helpers: usize| {
+            let mut engine = test_engine(3);
+            let owner = engine.agents[0].id;
+            engine.agents[2].physical.x = 5;
+            for helper in &mut engine.agents[1..] {
+                helper.skills.levels.insert("crafting".to_string(), 0.25);
+                helper.skills.levels.insert("teaching".to_string(), 0.0);
+            }
+            engine.world.get_mut(4, 0).unwrap().structure =
+                Some(Structure::new(StructureType::Shelter, owner, 12, ToolQuality::Standard, 0));
+            let debt = TradeableItem::HelpBuildPromise { labor_points: 20 };
+            let debt = ServiceDebt::from_promise(&debt, engine.agents[1].id, owner, Uuid::new_v4(), 0, 50).unwrap();
+            engine.trade_state.service_debts.push(debt);
+
+            let mut days = 0;
+            while !engine.world.get(4, 0).unwrap().structure.as_ref().unwrap().is_complete() && days < 20 {
+                days += 1;
+                let actions: BTreeMap<Uuid, Action> = engine.agents[1..=helpers]
+                    .iter()
+                    .map(|a| (a.id, Action::AssistBuild { structure: StructureType::Shelter }))
+                    .collect();
+                engine.resolve_actions(days, actions).unwrap();
+            }
+            (days, engine.trade_state.service_debts[0].service.clone())
+        };
+
+        let (alone, owed_alone) = days_to_finish(1);
+        let (together, _) = days_to_finish(2);
+        assert!(together < alone, "{} days together vs {} alone", together, alone);
+        assert!(matches!(owed_alone, ServiceType::HelpBuild { labor_contributed: 12, .. }), "{:?}", owed_alone);
+    }
+
+    #[test]
+    fn test_negative_gossip_precedes_first_meeting() {
+        let mut engine = test_engine(3);
+        let (aric, bria, corin) = (engine.agents[0].id, engine.agents[1].id, engine.agents[2].id);
+        engine.agents[0].beliefs.receive_gossip(corin, bria, "Bria", -0.9, -0.8, 0);
+
+        let (x, y) = (engine.agents[0].physical.x, engine.agents[0].physical.y);
+        engine.place(1, x + 1, y);
+        engine.meet_strangers(1);
+
+        let first_look = &engine.agents[0].beliefs.social[&bria];
+        assert!(first_look.met);
+        assert!(first_look.trust < 0.0 && first_look.sentiment < 0.0, "{:?}", first_look);
+        let unprejudiced = &engine.agents[1].beliefs.social[&aric];
+        assert!(unprejudiced.met);
+        assert_eq!((unprejudiced.trust, unprejudiced.sentiment), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_distrusted_group_colours_first_meeting_without_gossip() {
+        let mut engine = test_engine(4);
+        let ids: Vec<Uuid> = engine.agents.iter().map(|a| a.id).collect();
+        let names: Vec<String> = engine.agents.iter().map(|a| a.name().to_string()).collect();
+        // Agents 1 to 3 are a band; the observer, agent 0, has fallen out with
+        // two of them but never met the third or heard a word about them
+        for from in 1..4 {
+            for to in (1..4).filter(|&to| to != from) {
+                engine.agents[from].beliefs.update_trust(ids[to], &names[to], 0.8, 0);
+            }
+        }
+        for to in [1, 2] {
+            engine.agents[0].beliefs.update_trust(ids[to], &names[to], -0.6, 0);
+            engine.agents[0].beliefs.update_sentiment(ids[to], &names[to], -0.4, 0);
+        }
+        engine.group_tracker.detect(&engine.agents, 1);
+        assert!(engine.group_tracker.group_of(ids[3]).is_some());
+        assert!(engine.agents[0].beliefs.get_social(ids[3]).is_none());
+
+        for (idx, (x, y)) in [(5, 5), (0, 0), (9, 0), (6, 5)].into_iter().enumerate() {
+            engine.place(idx, x, y);
+        }
+        engine.meet_strangers(1);
+
+        let weight = engine.config.reputation.group_weight;
+        let first_look = &engine.agents[0].beliefs.social[&ids[3]];
+        assert!(first_look.met);
+        assert!((first_look.trust - -0.6 * weight).abs() < 1e-9, "{:?}", first_look);
+        assert!((first_look.sentiment - -0.4 * weight).abs() < 1e-9, "{:?}", first_look);
+    }
+
+    #[test]
+    fn test_gather_with_remark_gathers_and_speaks() {
+        let mut engine = test_engine(2);
+        let (aric, bria) = (engine.agents[0].id, engine.agents[1].id);
+        engine.agents[1].physical.x = engine.agents[0].physical.x + 1;
+        engine.agents[1].physical.y = engine.agents[0].physical.y;
+        let food_before = engine.agents[0].physical.food;
+
+        let line = r#"GATHER "Good berries here, Bria""#;
+        let nearby = [(bria, "Bria")];
+        let action = Action::parse(line, &nearby).unwrap();
+        let remark = Remark::parse(line, &nearby).unwrap();
+        assert!(matches!(action, Action::Gather));
+
+        engine.resolve_actions(1, BTreeMap::from([(aric, action)])).unwrap();
+        engine.resolve_remarks(1, BTreeMap::from([(aric, remark)])).unwrap();
+
+        assert!(engine.agents[0].physical.food > food_before);
+        let spoke = engine
+            .recent_events
+            .iter()
+            .find(|e| matches!(e.event_type, EventType::Spoke))
+            .expect("a Spoke event");
+        assert_eq!((spoke.agent, spoke.target), (Some(aric), Some(bria)));
+        assert_eq!(spoke.data.message.as_deref(), Some("Good berries here, Bria"));
+    }
+
+    #[test]
+    fn test_leader_proposing_peace_eases_hostile_rivalry() {
+        let mut engine = test_engine(6);
+        let ids: Vec<Uuid> = engine.agents.iter().map(|a| a.id).collect();
+        let names: Vec<String> = engine.agents.iter().map(|a| a.name().to_string()).collect();
+        // Two bands of three who trust each other and loathe the other band;
+        // agents 0 and 3 are the most trusted in theirs
+        let trust_in = [1.0, 0.6, 0.5, 1.0, 0.6, 0.5];
+        for from in 0..6 {
+            for to in (0..6).filter(|&to| to != from) {
+                let delta = if from / 3 == to / 3 { trust_in[to] } else { -0.6 };
+                engine.agents[from].beliefs.update_trust(ids[to], &names[to], delta, 0);
+            }
+        }
+        engine.group_tracker.detect(&engine.agents, 1);
+        let ours = engine.group_tracker.group_of(ids[0]).unwrap().clone();
+        let theirs = engine.group_tracker.group_of(ids[3]).unwrap().clone();
+        assert_eq!(ours.leader, Some(ids[0]));
+        assert_eq!(engine.group_tracker.relation_between(ours.id, theirs.id), RivalryType::Hostile);
+
+        let peace = Action::parse(&format!("PROPOSE_PEACE {}", theirs.name), &[]).unwrap();
+        engine.resolve_actions(2, BTreeMap::from([(ids[0], peace)])).unwrap();
+
+        let relation = engine.group_tracker.relation_between(ours.id, theirs.id);
+        assert!(matches!(relation, RivalryType::Neutral | RivalryType::Friendly), "{:?}", relation);
+        assert!(engine.agents[1].beliefs.get_social(ids[4]).unwrap().trust > -0.6);
+        let changed = engine
+            .recent_events
+            .iter()
+            .find(|e| matches!(e.event_type, EventType::RivalryChanged))
+            .expect("a RivalryChanged event");
+        assert_eq!(changed.data.old_rivalry_type.as_deref(), Some("hostile"));
+
+        // The peace holds through the next detection although feelings still run cold
+        engine.group_tracker.detect(&engine.agents, 3);
+        assert_eq!(engine.group_tracker.relation_between(ours.id, theirs.id), relation);
+    }
+
+    #[test]
+    fn test_expired_proposal_is_swept_then_cleaned_up() {
+        let mut engine = test_engine(2);
+        engine.config.trade.history_size = 1;
+        let (a, b) = (engine.agents[0].id, engine.agents[1].id);
+        let propose = |epoch| TradeProposal::new(a, b, vec![TradeableItem::Food(1)], vec![TradeableItem::Food(2)], epoch, 2);
+
+        let first = propose(0);
+        let first_id = first.id;
+        engine.trade_state.add_proposal(first);
+        engine.expire_trade_proposals(1).unwrap();
+        assert!(engine.trade_state.get_proposal(first_id).unwrap().is_pending());
+
+        engine.expire_trade_proposals(2).unwrap();
+        assert_eq!(engine.trade_state.get_proposal(first_id).unwrap().status, ProposalStatus::Expired);
+        assert!(engine.recent_events.iter().any(|e| matches!(e.event_type, EventType::TradeExpired) && e.epoch == 2));
+
+        // A later expiry pushes the first out of the one-proposal history
+        let second = propose(3);
+        let second_id = second.id;
+        engine.trade_state.add_proposal(second);
+        engine.expire_trade_proposals(5).unwrap();
+        assert!(engine.trade_state.get_proposal(first_id).is_none());
+        assert_eq!(engine.trade_state.get_proposal(second_id).unwrap().status, ProposalStatus::Expired);
+    }
+
+    #[test]
+    fn test_mating_short_of_food_logs_mate_failed() {
+        let mut engine = test_engine(2);
+        let (a, b) = (engine.agents[0].id, engine.agents[1].id);
+        engine.agents[1].physical.x = engine.agents[0].physical.x + 1;
+        engine.agents[1].physical.y = engine.agents[0].physical.y;
+        engine.agents[0].reproduction.courtship_progress.insert(b, 1.0);
+        engine.agents[1].reproduction.courtship_progress.insert(a, 1.0);
+        engine.agents[1].physical.food = 1;
+
+        let actions = BTreeMap::from([(a, Action::Mate { target: b }), (b, Action::Mate { target: a })]);
+        engine.resolve_mating(1, &actions).unwrap();
+
+        let failed = engine
+            .recent_events
+            .iter()
+            .find(|e| matches!(e.event_type, EventType::MateFailed))
+            .expect("a MateFailed event");
+        let reason = failed.data.description.as_deref().unwrap();
+        assert!(reason.contains("too little food"), "{}", reason);
+        assert!(engine.agents.iter().all(|x| x.reproduction.gestation.is_none()));
+        assert!(engine.action_results[&a].contains("too little food"));
+    }
+
+    #[test]
+    fn test_imminent_winter_warns_agents_to_prepare() {
+        let mut engine = test_engine(1);
+        engine.environment = EnvironmentConfig::earth_temperate();
+        engine.config.perception.warning_horizon = 10;
+
+        // Winter begins at epoch 75 of the 100-epoch cycle
+        let perception = engine.build_perception(&engine.agents[0], "", 70);
+        assert!(perception.contains("Hard times are coming: Winter in 5 days"), "{}", perception);
+
+        let perception = engine.build_perception(&engine.agents[0], "", 37);
+        assert!(!perception.contains("Hard times are coming"), "{}", perception);
+
+        engine.config.scenario.scripted_events.push(ScriptedEvent {
+            epoch: 40,
+            action: ScriptedAction::WorldEvent { event: WorldEvent::Plague { severity: 0.7 }, duration: None },
+        });
+        let perception = engine.build_perception(&engine.agents[0], "", 37);
+        assert!(perception.contains("a plague in 3 days"), "{}", perception);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_deliberation_gives_every_living_agent_an_action() {
+        let mut config = Config::default();
+        config.agents.count = 12;
+        config.llm.api_key_env = "TERRARIUM_TEST_NO_API_KEY".to_string();
+        config.llm.concurrency = 3;
+        let output_dir = std::env::temp_dir().join(format!("terrarium-test-{}", Uuid::new_v4()));
+        let mut engine = Engine::new(config, output_dir.to_str().unwrap()).unwrap();
+        engine.agents[4].physical.health = 0.0;
+        engine.agents[7].physical.incapacitated = true;
+
+        let env_state = engine.environment.state_at(0);
+        let (actions, _) = engine.deliberate(0, &env_state).await.unwrap();
+
+        let living: Vec<Uuid> = engine.agents.iter().filter(|a| a.is_alive()).map(|a| a.id).collect();
+        assert_eq!(actions.keys().copied().collect::<std::collections::BTreeSet<_>>(), living.into_iter().collect());
+        assert!(!actions.contains_key(&engine.agents[4].id));
+        assert!(matches!(actions[&engine.agents[7].id], Action::Eat | Action::Rest));
+    }
+
+    #[tokio::test]
+    async fn test_plague_takes_health_in_its_epoch() {
+        let mut config = Config::default();
+        config.agents.count = 3;
+        config.agents.starting_food = 0;
+        config.llm.api_key_env = "TERRARIUM_TEST_NO_API_KEY".to_string();
+        config.world_events.enabled = false;
+        let output_dir = std::env::temp_dir().join(format!("terrarium-test-{}", Uuid::new_v4()));
+        let mut engine = Engine::new(config, output_dir.to_str().unwrap()).unwrap();
+        let struck = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = struck.clone();
+        engine.subscribe(move |event| {
+            if matches!(event.event_type, EventType::WorldEventStruck) {
+                seen.lock().unwrap().push(event.data.members.clone().unwrap_or_default().len());
+            }
+        });
+
+        engine.world.start_event(WorldEvent::Plague { severity: 1.0 }, 5);
+        let before: Vec<f64> = engine.agents.iter().map(|a| a.physical.health).collect();
+        engine.run_epoch(0).await.unwrap();
+
+        for (agent, health) in engine.agents.iter().zip(before) {
+            assert!(agent.physical.health < health, "{}: {} -> {}", agent.name(), health, agent.physical.health);
+        }
+        assert_eq!(*struck.lock().unwrap(), vec![3]);
+    }
+
+    #[tokio::test]
+    async fn test_world_phase_follows_environment() {
+        let mut config = Config::default();
+        config.agents.count = 2;
+        config.llm.api_key_env = "TERRARIUM_TEST_NO_API_KEY".to_string();
+        config.scenario.scripted_events = vec![ScriptedEvent {
+            epoch: 2,
+            action: ScriptedAction::SetPhase { phase: "Winter".to_string() },
+        }];
+        let output_dir = std::env::temp_dir().join(format!("terrarium-test-{}", Uuid::new_v4()));
+        let mut engine = Engine::new(config, output_dir.to_str().unwrap()).unwrap();
+        assert_eq!(engine.world.phase, engine.environment.state_at(0).current_phase);
+
+        for epoch in 0..4 {
+            engine.run_epoch(epoch).await.unwrap();
+            assert_eq!(engine.world.phase, engine.environment.state_at(epoch).current_phase);
+        }
+        assert_eq!(engine.world.phase, "Winter");
+    }
+
+    #[tokio::test]
+    async fn test_profiler_times_every_phase() {
+        let mut config = Config::default();
+        config.agents.count = 3;
+        config.simulation.profile = true;
+        config.llm.api_key_env = "TERRARIUM_TEST_NO_API_KEY".to_string();
+        let output_dir = std::env::temp_dir().join(format!("terrarium-test-{}", Uuid::new_v4()));
+        let mut engine = Engine::new(config, output_dir.to_str().unwrap()).unwrap();
+
+        for epoch in 0..3 {
+            engine.run_epoch(epoch).await.unwrap();
+        }
+
+        for phase in crate::profiler::PHASES {
+            assert!(!engine.profiler.total(phase).is_zero(), "no time recorded for {}", phase);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reordered_pipeline_still_advances_epochs() {
+        use crate::pipeline::Step;
+
+        let mut config = Config::default();
+        config.agents.count = 3;
+        config.simulation.epochs = 3;
+        config.llm.api_key_env = "TERRARIUM_TEST_NO_API_KEY".to_string();
+        // Age first, settle mating straight after deliberation, then everything else
+        config.simulation.pipeline = vec![
+            Step::Age,
+            Step::Needs,
+            Step::Deliberate,
+            Step::Mate,
+            Step::Gestate,
+            Step::Resolve,
+            Step::Groups,
+            Step::Beliefs,
+        ];
+        let output_dir = std::env::temp_dir().join(format!("terrarium-test-{}", Uuid::new_v4()));
+        let mut engine = Engine::new(config.clone(), output_dir.to_str().unwrap()).unwrap();
+
+        engine.run().await.unwrap();
+
+        assert_eq!(engine.epoch(), 3);
+        assert!(engine.agents.iter().all(|a| a.physical.age >= 3));
+        assert!(engine.recent_events.iter().any(|e| matches!(e.event_type, EventType::EpochEnd) && e.epoch == 2));
+
+        config.simulation.pipeline.retain(|s| *s != Step::Beliefs);
+        assert!(Engine::new(config, output_dir.to_str().unwrap()).is_err());
+    }
 }
@@ -5,6 +5,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::structures::StructureType;
+
 /// Environment configuration
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct EnvironmentConfig {
@@ -37,6 +39,13 @@ pub struct EnvironmentConfig {
     /// Day length in epochs (0 = no day/night cycle)
     #[serde(default)]
     pub day_length: usize,
+    /// Epochs the cycle has been shifted by scripted phase changes
+    #[serde(default)]
+    pub cycle_offset: usize,
+    /// Fraction of the cycle, centred on each phase boundary, over which modifiers
+    /// blend from one phase's values to the next's (0.0 = abrupt change)
+    #[serde(default)]
+    pub transition_fraction: f64,
 }
 
 /// A phase within an environmental cycle (like a season)
@@ -99,6 +108,25 @@ impl HazardType {
             HazardType::Multiple => "multiple hazards",
         }
     }
+
+    /// How much of a structure's protection applies against this hazard (0.0 to 1.0).
+    /// Walls keep out weather, but radiation needs shielding and toxic air or vacuum
+    /// need a sealed atmosphere, which no current structure provides.
+    pub fn mitigated_by(&self, structure_type: StructureType) -> f64 {
+        match (self, structure_type) {
+            (HazardType::None, _) => 1.0,
+            (HazardType::Cold, StructureType::LeanTo) => 0.6,
+            (HazardType::Cold, _) => 1.0,
+            (HazardType::Heat, _) => 1.0,
+            (HazardType::DustStorm, StructureType::LeanTo) => 0.7,
+            (HazardType::DustStorm, _) => 1.0,
+            (HazardType::Radiation, _) => 0.3,
+            (HazardType::Toxic, _) => 0.1,
+            (HazardType::Vacuum, _) => 0.0,
+            (HazardType::Multiple, StructureType::LeanTo) => 0.4,
+            (HazardType::Multiple, _) => 0.6,
+        }
+    }
 }
 
 /// Temperature classification
@@ -150,6 +178,7 @@ pub struct EnvironmentState {
 impl EnvironmentConfig {
     /// Get the current environmental state for a given epoch
     pub fn state_at(&self, epoch: usize) -> EnvironmentState {
+        let epoch = epoch + self.cycle_offset;
         let cycle_number = epoch / self.cycle_length.max(1);
         let cycle_position = if self.cycle_length > 0 {
             (epoch % self.cycle_length) as f64 / self.cycle_length as f64
@@ -163,17 +192,24 @@ impl EnvironmentConfig {
             .or_else(|| self.phases.first());
 
         match current_phase {
-            Some(phase) => EnvironmentState {
-                current_phase: phase.name.clone(),
-                phase_description: phase.description.clone(),
-                food_regen_modifier: phase.food_regen_modifier,
-                hazard_level: self.base_hazard * phase.hazard_modifier,
-                hazard_type: self.hazard_type,
-                energy_drain: 0.05 * phase.energy_drain_modifier * (1.0 + self.base_hazard),
-                movement_cost: phase.movement_cost_modifier,
-                cycle_position,
-                cycle_number,
-            },
+            Some(phase) => {
+                let blend = self.transition_blend(phase, cycle_position);
+                let modifier = |f: fn(&Phase) -> f64| match blend {
+                    Some((neighbor, weight)) => f(phase) * weight + f(neighbor) * (1.0 - weight),
+                    None => f(phase),
+                };
+                EnvironmentState {
+                    current_phase: phase.name.clone(),
+                    phase_description: phase.description.clone(),
+                    food_regen_modifier: modifier(|p| p.food_regen_modifier),
+                    hazard_level: self.base_hazard * modifier(|p| p.hazard_modifier),
+                    hazard_type: self.hazard_type,
+                    energy_drain: 0.05 * modifier(|p| p.energy_drain_modifier) * (1.0 + self.base_hazard),
+                    movement_cost: modifier(|p| p.movement_cost_modifier),
+                    cycle_position,
+                    cycle_number,
+                }
+            }
             None => EnvironmentState {
                 current_phase: "Unknown".to_string(),
                 phase_description: String::new(),
@@ -188,6 +224,73 @@ impl EnvironmentConfig {
         }
     }
 
+    /// Phase covering a cycle position, wrapping around the cycle
+    fn phase_at(&self, position: f64) -> Option<&Phase> {
+        let position = position.rem_euclid(1.0);
+        self.phases.iter().find(|p| position >= p.start && position < p.end)
+    }
+
+    /// Within a transition window, the neighbouring phase being blended with and
+    /// the weight (0.5 to 1.0) the current phase keeps. None outside any window.
+    fn transition_blend(&self, phase: &Phase, position: f64) -> Option<(&Phase, f64)> {
+        let half = (self.transition_fraction / 2.0).min((phase.end - phase.start) / 2.0);
+        if half <= 0.0 {
+            return None;
+        }
+
+        let since_start = position - phase.start;
+        let until_end = phase.end - position;
+        let (neighbor, distance) = if since_start < half {
+            (self.phase_at(phase.start - f64::EPSILON)?, since_start)
+        } else if until_end <= half {
+            (self.phase_at(phase.end)?, until_end)
+        } else {
+            return None;
+        };
+        if std::ptr::eq(neighbor, phase) {
+            return None;
+        }
+        Some((neighbor, 0.5 + distance / (2.0 * half)))
+    }
+
+    /// Shift the cycle so the named phase begins at the given epoch
+    ///
+    /// Returns false if no phase has that name.
+    pub fn jump_to_phase(&mut self, epoch: usize, name: &str) -> bool {
+        let Some(phase) = self.phases.iter().find(|p| p.name.eq_ignore_ascii_case(name)) else {
+            return false;
+        };
+        let cycle_length = self.cycle_length.max(1);
+        let target = ((phase.start * cycle_length as f64).ceil() as usize).min(cycle_length - 1);
+        self.cycle_offset = (target + cycle_length - epoch % cycle_length) % cycle_length;
+        true
+    }
+
+    /// The next phase, other than the current one, whose hazard modifier reaches
+    /// `min_hazard_modifier`, with the number of epochs until it begins
+    pub fn next_harsh_phase(&self, epoch: usize, min_hazard_modifier: f64) -> Option<(&Phase, usize)> {
+        let cycle_length = self.cycle_length.max(1);
+        let now = (epoch + self.cycle_offset) % cycle_length;
+        let current = self.phase_at(now as f64 / cycle_length as f64);
+        self.phases
+            .iter()
+            .filter(|p| p.hazard_modifier >= min_hazard_modifier)
+            .filter(|p| !current.is_some_and(|c| std::ptr::eq(c, *p)))
+            .map(|p| {
+                let start = ((p.start * cycle_length as f64).ceil() as usize).min(cycle_length - 1);
+                (p, (start + cycle_length - now) % cycle_length)
+            })
+            .min_by_key(|(_, until)| *until)
+    }
+
+    /// Whether it is night at the given epoch
+    ///
+    /// Day and night alternate every `day_length` epochs; a `day_length` of 0
+    /// means there is no day/night cycle and it is never night.
+    pub fn is_night(&self, epoch: usize) -> bool {
+        self.day_length > 0 && (epoch / self.day_length) % 2 == 1
+    }
+
     /// Get perception description for agents
     pub fn describe(&self, epoch: usize) -> String {
         let state = self.state_at(epoch);
@@ -293,6 +396,8 @@ impl EnvironmentConfig {
             breathable_atmosphere: true,
             base_temperature: Temperature::Temperate,
             day_length: 0,
+            cycle_offset: 0,
+            transition_fraction: 0.0,
         }
     }
 
@@ -350,6 +455,8 @@ impl EnvironmentConfig {
             breathable_atmosphere: true,
             base_temperature: Temperature::Freezing,
             day_length: 0,
+            cycle_offset: 0,
+            transition_fraction: 0.0,
         }
     }
 
@@ -397,6 +504,8 @@ impl EnvironmentConfig {
             breathable_atmosphere: false,
             base_temperature: Temperature::Freezing,
             day_length: 1, // Sol is ~same as Earth day
+            cycle_offset: 0,
+            transition_fraction: 0.0,
         }
     }
 
@@ -434,6 +543,8 @@ impl EnvironmentConfig {
             breathable_atmosphere: false,
             base_temperature: Temperature::Freezing, // Average
             day_length: 14, // Half the cycle
+            cycle_offset: 0,
+            transition_fraction: 0.0,
         }
     }
 
@@ -481,6 +592,8 @@ impl EnvironmentConfig {
             breathable_atmosphere: true,
             base_temperature: Temperature::Temperate,
             day_length: 0,
+            cycle_offset: 0,
+            transition_fraction: 0.0,
         }
     }
 
@@ -518,6 +631,8 @@ impl EnvironmentConfig {
             breathable_atmosphere: false,
             base_temperature: Temperature::Hot,
             day_length: 0, // Tidally locked
+            cycle_offset: 0,
+            transition_fraction: 0.0,
         }
     }
 
@@ -565,6 +680,8 @@ impl EnvironmentConfig {
             breathable_atmosphere: true,
             base_temperature: Temperature::Hot,
             day_length: 0,
+            cycle_offset: 0,
+            transition_fraction: 0.0,
         }
     }
 
@@ -612,6 +729,8 @@ impl EnvironmentConfig {
             breathable_atmosphere: true, // Artificial
             base_temperature: Temperature::Temperate,
             day_length: 0, // 90-minute orbits, abstracted away
+            cycle_offset: 0,
+            transition_fraction: 0.0,
         }
     }
 
@@ -649,6 +768,34 @@ impl EnvironmentConfig {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_shelter_mitigates_cold_better_than_radiation() {
+        let shelter = StructureType::Shelter;
+        let protection = shelter.hazard_protection();
+        let cold_damage = 1.0 - protection * HazardType::Cold.mitigated_by(shelter);
+        let radiation_damage = 1.0 - protection * HazardType::Radiation.mitigated_by(shelter);
+        assert!(cold_damage < radiation_damage);
+        assert_eq!(HazardType::Vacuum.mitigated_by(shelter), 0.0);
+    }
+
+    #[test]
+    fn test_transition_smoothing_blends_across_boundary() {
+        let mut env = EnvironmentConfig::earth_temperate();
+        let spring = env.phases[0].food_regen_modifier;
+        let summer = env.phases[1].food_regen_modifier;
+        assert_eq!(env.state_at(26).food_regen_modifier, summer);
+
+        env.transition_fraction = 0.1;
+        let just_after = env.state_at(26);
+        assert_eq!(just_after.current_phase, "Summer");
+        let (low, high) = (spring.min(summer), spring.max(summer));
+        assert!(just_after.food_regen_modifier > low && just_after.food_regen_modifier < high);
+        assert!((just_after.food_regen_modifier - summer).abs() < (just_after.food_regen_modifier - spring).abs());
+
+        // Well inside the phase the value is untouched
+        assert_eq!(env.state_at(37).food_regen_modifier, summer);
+    }
+
     #[test]
     fn test_earth_temperate_phases() {
         let env = EnvironmentConfig::earth_temperate();
@@ -691,6 +838,19 @@ mod tests {
         assert!(storm.hazard_level > calm.hazard_level);
     }
 
+    #[test]
+    fn test_day_night_cycle() {
+        let earth = EnvironmentConfig::earth_temperate();
+        assert!(!earth.is_night(0));
+        assert!(!earth.is_night(51));
+
+        let moon = EnvironmentConfig::moon();
+        assert!(!moon.is_night(0));
+        assert!(!moon.is_night(13));
+        assert!(moon.is_night(14));
+        assert!(!moon.is_night(28));
+    }
+
     #[test]
     fn test_preset_lookup() {
         assert!(EnvironmentConfig::from_name("mars").is_some());
@@ -1,7 +1,8 @@
 //! Group and alliance detection from shared beliefs.
 //!
 //! Groups are detected when 3+ agents have mutual trust above a threshold.
-//! This module analyzes the social belief graph to find emergent alliances.
+//! This module analyzes the social belief graph to find emergent alliances,
+//! as maximal cliques by default or as looser communities if configured.
 //! Inter-group rivalries are detected based on cross-group trust/distrust.
 
 use serde::{Deserialize, Serialize};
@@ -9,6 +10,7 @@ use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 use crate::agent::Agent;
+use crate::config::GroupsConfig;
 
 /// Minimum trust for considering two agents as allies
 const TRUST_THRESHOLD: f64 = 0.3;
@@ -22,6 +24,19 @@ const TENSE_THRESHOLD: f64 = -0.1;
 const FRIENDLY_THRESHOLD: f64 = 0.1;
 const ALLIED_THRESHOLD: f64 = 0.3;
 
+/// How groups are carved out of the mutual trust graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupAlgorithm {
+    /// Everyone trusts everyone else (Bron-Kerbosch)
+    #[default]
+    MaximalClique,
+    /// Everyone connected by any chain of mutual trust
+    ConnectedComponent,
+    /// Connected agents who each trust at least `core_degree` others in the group
+    KCore,
+}
+
 /// A detected group/alliance
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Group {
@@ -93,10 +108,26 @@ pub struct Rivalry {
     pub shared_enemies: bool,
     /// Epoch when this relationship was first detected
     pub since_epoch: usize,
+    /// Set by a leader's peace or declaration of war: the type holds until this
+    /// epoch, whatever the members feel toward each other
+    #[serde(default)]
+    pub declared_until: Option<usize>,
+}
+
+impl Rivalry {
+    /// Whether this rivalry is between the two groups, in either order
+    pub fn between(&self, a: Uuid, b: Uuid) -> bool {
+        (self.group_a == a && self.group_b == b) || (self.group_a == b && self.group_b == a)
+    }
+
+    /// Whether a declared type still overrides the detected one
+    pub fn is_declared(&self, epoch: usize) -> bool {
+        self.declared_until.is_some_and(|until| epoch <= until)
+    }
 }
 
 /// Tracks groups over time
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GroupTracker {
     /// Currently active groups
     pub groups: Vec<Group>,
@@ -106,6 +137,9 @@ pub struct GroupTracker {
     next_group_num: usize,
     /// Current inter-group rivalries
     pub rivalries: Vec<Rivalry>,
+    /// Which algorithm finds groups (taken from the scenario, not snapshots)
+    #[serde(skip)]
+    config: GroupsConfig,
 }
 
 /// Result of group detection for an epoch
@@ -128,8 +162,15 @@ pub struct GroupChanges {
 }
 
 impl GroupTracker {
-    pub fn new() -> Self {
-        Self::default()
+    /// Track groups found by the configured algorithm
+    pub fn new(config: GroupsConfig) -> Self {
+        Self { config, ..Self::default() }
+    }
+
+    /// Pick up the groups and rivalries of a saved tracker, keeping this one's
+    /// detection settings
+    pub fn resume(&mut self, saved: GroupTracker) {
+        *self = Self { config: self.config.clone(), ..saved };
     }
 
     /// Detect groups from current agent states
@@ -140,11 +181,17 @@ impl GroupTracker {
         // Build the mutual trust graph
         let trust_graph = build_trust_graph(agents);
 
-        // Find all cliques of size >= MIN_GROUP_SIZE
-        let cliques = find_cliques(&trust_graph, MIN_GROUP_SIZE);
+        // Find all groups of size >= MIN_GROUP_SIZE
+        let memberships = match self.config.algorithm {
+            GroupAlgorithm::MaximalClique => find_cliques(&trust_graph, MIN_GROUP_SIZE),
+            GroupAlgorithm::ConnectedComponent => find_components(&trust_graph, MIN_GROUP_SIZE),
+            GroupAlgorithm::KCore => {
+                find_components(&k_core(&trust_graph, self.config.core_degree), MIN_GROUP_SIZE)
+            }
+        };
 
-        // Convert cliques to groups
-        let mut new_groups: Vec<Group> = cliques
+        // Convert memberships to groups
+        let mut new_groups: Vec<Group> = memberships
             .into_iter()
             .map(|members| {
                 let (avg_trust, avg_sentiment) = calculate_group_metrics(&members, agents);
@@ -153,7 +200,7 @@ impl GroupTracker {
                 let leader = hierarchy.first().map(|(id, _)| *id);
 
                 Group {
-                    id: Uuid::new_v4(),
+                    id: crate::rng::uuid(),
                     members,
                     formed_epoch: epoch,
                     average_trust: avg_trust,
@@ -197,8 +244,10 @@ impl GroupTracker {
                 new_group.name = old_group.name.clone();
 
                 // Check for membership changes
-                let added: Vec<Uuid> = new_group.members.difference(&old_group.members).copied().collect();
-                let removed: Vec<Uuid> = old_group.members.difference(&new_group.members).copied().collect();
+                let mut added: Vec<Uuid> = new_group.members.difference(&old_group.members).copied().collect();
+                let mut removed: Vec<Uuid> = old_group.members.difference(&new_group.members).copied().collect();
+                added.sort();
+                removed.sort();
 
                 if !added.is_empty() || !removed.is_empty() {
                     changes.changed.push((new_group.clone(), added, removed));
@@ -265,18 +314,19 @@ impl GroupTracker {
                 );
 
                 // Check for shared enemies
-                let shared_enemies = !group_a
-                    .shared_enemies
-                    .iter()
-                    .filter(|e| group_b.shared_enemies.contains(e))
-                    .next()
-                    .is_none();
+                let shared_enemies = have_common_enemy(&group_a.shared_enemies, &group_b.shared_enemies);
 
-                // Classify relationship type
-                let rivalry_type = classify_rivalry(avg_trust, shared_enemies);
+                // Classify relationship type, unless a leader's declaration still stands
+                let declared = self
+                    .rivalries
+                    .iter()
+                    .find(|r| r.between(group_a.id, group_b.id) && r.is_declared(epoch));
+                let rivalry_type = declared
+                    .map(|r| r.rivalry_type)
+                    .unwrap_or_else(|| classify_rivalry(avg_trust, shared_enemies));
 
                 // Only track non-neutral relationships or if shared enemies exist
-                if rivalry_type != RivalryType::Neutral || shared_enemies {
+                if rivalry_type != RivalryType::Neutral || shared_enemies || declared.is_some() {
                     new_rivalries.push(Rivalry {
                         group_a: group_a.id,
                         group_b: group_b.id,
@@ -285,6 +335,7 @@ impl GroupTracker {
                         avg_cross_sentiment: avg_sentiment,
                         shared_enemies,
                         since_epoch: epoch,
+                        declared_until: declared.and_then(|r| r.declared_until),
                     });
                 }
             }
@@ -332,6 +383,38 @@ impl GroupTracker {
         self.rivalries = new_rivalries;
     }
 
+    /// Set the relationship between two groups by decree, holding it until
+    /// `until` whatever their members feel
+    pub fn declare(&mut self, group_a: Uuid, group_b: Uuid, rivalry_type: RivalryType, until: usize, epoch: usize) {
+        let idx = match self.rivalries.iter().position(|r| r.between(group_a, group_b)) {
+            Some(idx) => idx,
+            None => {
+                self.rivalries.push(Rivalry {
+                    group_a,
+                    group_b,
+                    rivalry_type: RivalryType::Neutral,
+                    avg_cross_trust: 0.0,
+                    avg_cross_sentiment: 0.0,
+                    shared_enemies: false,
+                    since_epoch: epoch,
+                    declared_until: None,
+                });
+                self.rivalries.len() - 1
+            }
+        };
+        self.rivalries[idx].rivalry_type = rivalry_type;
+        self.rivalries[idx].declared_until = Some(until);
+    }
+
+    /// The relationship between two groups (neutral if untracked)
+    pub fn relation_between(&self, group_a: Uuid, group_b: Uuid) -> RivalryType {
+        self.rivalries
+            .iter()
+            .find(|r| r.between(group_a, group_b))
+            .map(|r| r.rivalry_type)
+            .unwrap_or(RivalryType::Neutral)
+    }
+
     /// Get rivalries involving a specific group
     pub fn rivalries_of(&self, group_id: Uuid) -> Vec<&Rivalry> {
         self.rivalries
@@ -406,9 +489,65 @@ fn find_cliques(graph: &HashMap<Uuid, HashSet<Uuid>>, min_size: usize) -> Vec<Ha
         }
     }
 
+    // Order cliques by their member ids so group detection is stable
+    maximal_cliques.sort_by_cached_key(|clique| {
+        let mut members: Vec<Uuid> = clique.iter().copied().collect();
+        members.sort();
+        members
+    });
     maximal_cliques
 }
 
+/// Find all connected components of at least min_size
+fn find_components(graph: &HashMap<Uuid, HashSet<Uuid>>, min_size: usize) -> Vec<HashSet<Uuid>> {
+    let mut components = Vec::new();
+    let mut seen: HashSet<Uuid> = HashSet::new();
+
+    for start in graph.keys() {
+        if !seen.insert(*start) {
+            continue;
+        }
+        let mut component: HashSet<Uuid> = HashSet::from([*start]);
+        let mut frontier = vec![*start];
+        while let Some(id) = frontier.pop() {
+            for neighbor in graph.get(&id).into_iter().flatten() {
+                if seen.insert(*neighbor) {
+                    component.insert(*neighbor);
+                    frontier.push(*neighbor);
+                }
+            }
+        }
+        if component.len() >= min_size {
+            components.push(component);
+        }
+    }
+
+    // Same ordering as cliques so group detection is stable
+    components.sort_by_key(|component| component.iter().min().copied());
+    components
+}
+
+/// The subgraph left after repeatedly removing agents with fewer than k neighbors
+fn k_core(graph: &HashMap<Uuid, HashSet<Uuid>>, k: usize) -> HashMap<Uuid, HashSet<Uuid>> {
+    let mut core = graph.clone();
+    loop {
+        let weak: Vec<Uuid> = core
+            .iter()
+            .filter(|(_, neighbors)| neighbors.len() < k)
+            .map(|(id, _)| *id)
+            .collect();
+        if weak.is_empty() {
+            return core;
+        }
+        for id in &weak {
+            core.remove(id);
+        }
+        for neighbors in core.values_mut() {
+            neighbors.retain(|n| !weak.contains(n));
+        }
+    }
+}
+
 /// Bron-Kerbosch algorithm for finding cliques
 fn bron_kerbosch(
     graph: &HashMap<Uuid, HashSet<Uuid>>,
@@ -425,13 +564,14 @@ fn bron_kerbosch(
         return;
     }
 
-    // Choose pivot to minimize branching
-    let pivot = p.union(&x).next().copied();
+    // Choose pivot to minimize branching (the lowest id, so the search runs the same way every time)
+    let pivot = p.union(&x).min().copied();
 
     let Some(pivot) = pivot else { return };
     let pivot_neighbors = graph.get(&pivot).cloned().unwrap_or_default();
 
-    let candidates: Vec<Uuid> = p.difference(&pivot_neighbors).copied().collect();
+    let mut candidates: Vec<Uuid> = p.difference(&pivot_neighbors).copied().collect();
+    candidates.sort();
 
     for v in candidates {
         let neighbors = graph.get(&v).cloned().unwrap_or_default();
@@ -490,11 +630,13 @@ fn find_shared_enemies(members: &HashSet<Uuid>, agents: &[Agent]) -> Vec<Uuid> {
     }
 
     // Shared enemies = distrusted by all members
-    enemy_counts
+    let mut enemies: Vec<Uuid> = enemy_counts
         .into_iter()
         .filter(|(_, count)| *count == members.len())
         .map(|(id, _)| id)
-        .collect()
+        .collect();
+    enemies.sort();
+    enemies
 }
 
 /// Calculate leadership hierarchy within a group
@@ -528,8 +670,12 @@ fn calculate_hierarchy(members: &HashSet<Uuid>, agents: &[Agent]) -> Vec<(Uuid,
         })
         .collect();
 
-    // Sort by score descending
-    scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    // Sort by score descending (ties broken by id so leadership is stable)
+    scores.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.0.cmp(&b.0))
+    });
 
     scores
 }
@@ -577,6 +723,11 @@ fn calculate_cross_group_metrics(
     }
 }
 
+/// Whether two groups' enemy lists name anyone in common
+fn have_common_enemy(enemies_a: &[Uuid], enemies_b: &[Uuid]) -> bool {
+    enemies_a.iter().any(|e| enemies_b.contains(e))
+}
+
 /// Classify the type of inter-group relationship based on cross-trust
 fn classify_rivalry(avg_trust: f64, shared_enemies: bool) -> RivalryType {
     if avg_trust < HOSTILE_THRESHOLD {
@@ -604,6 +755,13 @@ fn jaccard_similarity(a: &HashSet<Uuid>, b: &HashSet<Uuid>) -> f64 {
 }
 
 impl Group {
+    /// Member ids in id order
+    pub fn member_ids(&self) -> Vec<Uuid> {
+        let mut ids: Vec<Uuid> = self.members.iter().copied().collect();
+        ids.sort();
+        ids
+    }
+
     /// Get member names
     pub fn member_names<'a>(&self, agents: &'a [Agent]) -> Vec<&'a str> {
         self.members
@@ -621,6 +779,34 @@ impl Group {
             .map(|a| a.name())
             .collect()
     }
+
+    /// Position in the hierarchy (1 = leader), if the agent is a member
+    pub fn rank_of(&self, agent_id: Uuid) -> Option<usize> {
+        self.hierarchy.iter().position(|(id, _)| *id == agent_id).map(|i| i + 1)
+    }
+
+    /// How a member ranks within the group, for their own perception
+    pub fn describe_standing(&self, agent_id: Uuid) -> Option<String> {
+        let rank = self.rank_of(agent_id)?;
+        let size = self.hierarchy.len();
+        Some(if rank == 1 {
+            format!("you are the most trusted of the {} members of {}, and they look to you to lead", size, self.name)
+        } else {
+            format!("you are the {} most trusted of the {} members of {}", ordinal(rank), size, self.name)
+        })
+    }
+}
+
+/// English ordinal for a rank ("2nd", "3rd", "11th")
+fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
 }
 
 #[cfg(test)]
@@ -636,4 +822,48 @@ mod tests {
         let c: HashSet<Uuid> = HashSet::new();
         assert_eq!(jaccard_similarity(&a, &c), 0.0);
     }
+
+    #[test]
+    fn test_common_enemy_tips_friendly_groups_into_alliance() {
+        let (x, y, z) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        // Between the friendly and allied thresholds
+        let trust = 0.2;
+        let classify = |a: &[Uuid], b: &[Uuid]| classify_rivalry(trust, have_common_enemy(a, b));
+
+        assert_eq!(classify(&[x], &[y]), RivalryType::Friendly);
+        assert_eq!(classify(&[x], &[]), RivalryType::Friendly);
+        assert_eq!(classify(&[x, y], &[y, z]), RivalryType::Allied);
+        assert_eq!(classify(&[x, y, z], &[z, y, x]), RivalryType::Allied);
+
+        // A common enemy doesn't sweeten a cold relationship
+        assert_eq!(classify_rivalry(0.0, have_common_enemy(&[x], &[x])), RivalryType::Neutral);
+    }
+
+    #[test]
+    fn test_chain_of_trust_is_a_community_but_not_a_clique() {
+        // Aric - Bria - Cara - Dara: each trusts only their neighbours in the chain
+        let mut agents: Vec<Agent> = ["Aric", "Bria", "Cara", "Dara"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| Agent::new(name.to_string(), i, 0, 5))
+            .collect();
+        for i in 0..3 {
+            let (a, b) = (agents[i].clone(), agents[i + 1].clone());
+            agents[i].beliefs.update_trust(b.id, b.name(), 0.5, 0);
+            agents[i + 1].beliefs.update_trust(a.id, a.name(), 0.5, 0);
+        }
+
+        let mut cliques = GroupTracker::new(GroupsConfig::default());
+        assert!(cliques.detect(&agents, 1).formed.is_empty());
+
+        let config = GroupsConfig { algorithm: GroupAlgorithm::ConnectedComponent, ..GroupsConfig::default() };
+        let mut communities = GroupTracker::new(config);
+        let formed = communities.detect(&agents, 1).formed;
+        assert_eq!(formed.len(), 1);
+        assert_eq!(formed[0].members.len(), 4);
+
+        // Peeling away the ends, who trust only one other, unravels the whole chain
+        let config = GroupsConfig { algorithm: GroupAlgorithm::KCore, core_degree: 2, ..GroupsConfig::default() };
+        assert!(GroupTracker::new(config).detect(&agents, 1).formed.is_empty());
+    }
 }
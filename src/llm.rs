@@ -1,19 +1,266 @@
 use anyhow::{anyhow, Result};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
-use tracing::{debug, warn};
-
-use crate::action::Action;
-use crate::agent::Agent;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use tracing::{debug, info, warn};
+
+use crate::action::{Action, Direction, Remark};
+use crate::agent::{Agent, Drive, LandmarkKind, RecallConfig};
+use crate::config::InstinctConfig;
 use crate::trade::TradeableItem;
 
+/// The API a model is reached through
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    /// Anthropic's Messages API
+    #[default]
+    Anthropic,
+    /// The OpenAI chat completions API, also spoken by vLLM, Ollama and other
+    /// local servers
+    OpenAi,
+}
+
+impl Provider {
+    /// API root used when no `base_url` is configured
+    fn default_base_url(self) -> &'static str {
+        match self {
+            Provider::Anthropic => "https://api.anthropic.com/v1",
+            Provider::OpenAi => "https://api.openai.com/v1",
+        }
+    }
+}
+
 /// LLM client configuration
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LlmConfig {
-    pub provider: String,
+    pub provider: Provider,
+    /// API root to send requests to instead of the provider's own, e.g.
+    /// `http://localhost:11434/v1` for a local server. A server configured
+    /// here is called even when no API key is set
+    #[serde(default)]
+    pub base_url: Option<String>,
     pub model: String,
     pub api_key_env: String,
     pub max_tokens: usize,
     pub temperature: f64,
+    /// Template file replacing the built-in agent prompt (see `PromptTemplate`)
+    #[serde(default)]
+    pub prompt_template: Option<PathBuf>,
+    /// Decide for agents with nobody nearby and no pressing needs with the
+    /// heuristic instead of an LLM call
+    #[serde(default)]
+    pub skip_isolated: bool,
+    /// Let agents add a quoted remark to someone next to them after a gather
+    /// or move, spoken in passing without giving up the action
+    #[serde(default)]
+    pub allow_incidental_speech: bool,
+    /// Remember up to this many LLM decisions and reuse one when an agent
+    /// meets exactly the same situation again (0 = off)
+    #[serde(default)]
+    pub decision_cache: usize,
+    /// Chance of asking the LLM afresh even when a remembered decision fits,
+    /// so agents don't get stuck repeating themselves
+    #[serde(default = "default_cache_redecide_chance")]
+    pub cache_redecide_chance: f64,
+    /// Answer a prompt that was sent word for word before with the reply it
+    /// got then, instead of calling the API again
+    #[serde(default)]
+    pub response_cache: bool,
+    /// Most replies remembered under `response_cache`
+    #[serde(default = "default_response_cache_size")]
+    pub response_cache_size: usize,
+    /// How memories are picked for the prompt
+    #[serde(default)]
+    pub recall: RecallConfig,
+    /// Look for an action further into a reply that doesn't start with one
+    #[serde(default = "default_salvage_actions")]
+    pub salvage_actions: bool,
+    /// When no action can be found, ask once more for just the action
+    #[serde(default)]
+    pub reformat_retry: bool,
+    /// Most agents to ask the LLM each epoch; the rest, those in the least
+    /// salient situations, are decided by the heuristic (None = no cap)
+    #[serde(default)]
+    pub max_llm_agents: Option<usize>,
+    /// Most LLM calls in flight at once while agents deliberate
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+    /// Times to retry a call that was rate limited, hit a server error or
+    /// timed out before the heuristic decides instead
+    #[serde(default = "default_max_retries")]
+    pub max_retries: usize,
+    /// Wait before the first retry in milliseconds, doubling with each one
+    #[serde(default = "default_retry_delay_ms")]
+    pub retry_delay_ms: u64,
+}
+
+fn default_cache_redecide_chance() -> f64 { 0.1 }
+fn default_response_cache_size() -> usize { 256 }
+fn default_concurrency() -> usize { 8 }
+fn default_max_retries() -> usize { 3 }
+fn default_retry_delay_ms() -> u64 { 500 }
+fn default_salvage_actions() -> bool { true }
+
+/// Follow-up when a reply held no recognisable action
+const REFORMAT_PROMPT: &str = "Your reply did not contain an action I could recognise. \
+Answer with exactly one action from the list, written as shown (for example GATHER or MOVE NORTH), and nothing else.";
+
+/// The built-in agent prompt
+const DEFAULT_PROMPT_TEMPLATE: &str = r#"{state}
+
+{relations}
+
+{memories}
+
+## Current Situation (Day {epoch})
+{perception}
+## Available Actions
+{actions}
+
+## Instructions
+Think about your current needs, your personality, and your goals.
+Decide what to do. Respond with your reasoning (1-2 sentences) then your chosen action.
+
+Format your response like this:
+REASONING: [your thinking]
+ACTION: [one action from the list above]
+
+Example:
+REASONING: I am hungry and there is food here. I should gather some.
+ACTION: GATHER"#;
+
+/// Placeholders a template must contain, or agents can't see where they are or what they can do
+const REQUIRED_PLACEHOLDERS: [&str; 3] = ["state", "perception", "actions"];
+
+/// Agent prompt text with named placeholders filled in at call time:
+/// `{name}`, `{epoch}`, `{state}`, `{memories}`, `{relations}`, `{perception}` and `{actions}`
+#[derive(Debug, Clone)]
+pub struct PromptTemplate {
+    text: String,
+}
+
+impl PromptTemplate {
+    /// Parse a template, checking that the required placeholders are present
+    pub fn parse(text: &str) -> Result<Self> {
+        let missing: Vec<String> = REQUIRED_PLACEHOLDERS
+            .iter()
+            .map(|p| format!("{{{}}}", p))
+            .filter(|p| !text.contains(p.as_str()))
+            .collect();
+        if !missing.is_empty() {
+            return Err(anyhow!("Prompt template is missing {}", missing.join(", ")));
+        }
+        Ok(Self { text: text.to_string() })
+    }
+
+    /// Load a template from a file
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Cannot read prompt template {}: {}", path.display(), e))?;
+        Self::parse(&text)
+    }
+
+    /// Fill in placeholders in a single pass, so braces inside the values
+    /// (or unknown placeholders) are left as they are
+    pub fn render(&self, values: &[(&str, &str)]) -> String {
+        let mut out = String::with_capacity(self.text.len());
+        let mut rest = self.text.as_str();
+        while let Some(start) = rest.find('{') {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            let value = after.find('}').and_then(|end| {
+                values
+                    .iter()
+                    .find(|(key, _)| *key == &after[..end])
+                    .map(|(_, value)| (end, *value))
+            });
+            match value {
+                Some((end, value)) => {
+                    out.push_str(value);
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    out.push('{');
+                    rest = after;
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+impl Default for PromptTemplate {
+    fn default() -> Self {
+        Self {
+            text: DEFAULT_PROMPT_TEMPLATE.to_string(),
+        }
+    }
+}
+
+/// A trade offered to an agent: index, proposer id and name, what is offered,
+/// what is asked and any deadline
+pub type PendingTrade<'a> = (usize, uuid::Uuid, &'a str, String, String, Option<usize>);
+
+/// A promise between an agent and someone else: their id and name, the
+/// service and any deadline
+pub type Promise<'a> = (uuid::Uuid, &'a str, String, Option<usize>);
+
+/// What an agent weighs when deciding on its action
+pub struct Situation<'a> {
+    pub agent: &'a Agent,
+    pub perception: String,
+    pub nearby: Vec<(uuid::Uuid, &'a str)>,
+    pub pending_trades: Vec<PendingTrade<'a>>,
+    /// Promises the agent owes others
+    pub debts_owed: Vec<Promise<'a>>,
+    /// Promises others owe the agent
+    pub credits_owed: Vec<Promise<'a>>,
+    /// Number of pending trade proposals the agent has made
+    pub my_proposals: usize,
+    pub epoch: usize,
+    /// Current environmental hazard (0.0 = safe)
+    pub hazard_level: f64,
+    /// Whether to ask the LLM rather than the heuristic
+    pub ask_llm: bool,
+    /// Seed for this agent's chance choices, drawn while situations are
+    /// gathered in agent order, so calls finishing out of order can't
+    /// reorder draws from the shared stream
+    pub rng_seed: u64,
+}
+
+impl Situation<'_> {
+    /// The generator behind this agent's chance choices
+    pub fn rng(&self) -> ChaCha8Rng {
+        ChaCha8Rng::seed_from_u64(self.rng_seed)
+    }
+}
+
+#[cfg(test)]
+impl<'a> Situation<'a> {
+    /// An agent alone on a calm day, with nothing outstanding
+    pub fn alone(agent: &'a Agent, epoch: usize) -> Self {
+        Self {
+            agent,
+            perception: String::new(),
+            nearby: Vec::new(),
+            pending_trades: Vec::new(),
+            debts_owed: Vec::new(),
+            credits_owed: Vec::new(),
+            my_proposals: 0,
+            epoch,
+            hazard_level: 0.0,
+            ask_llm: true,
+            rng_seed: crate::rng::random(crate::rng::Stream::Decisions),
+        }
+    }
 }
 
 /// LLM client for agent deliberation
@@ -21,6 +268,59 @@ pub struct LlmClient {
     client: reqwest::Client,
     config: LlmConfig,
     api_key: Option<String>,
+    template: PromptTemplate,
+    /// LLM calls skipped under `skip_isolated` since last taken
+    skipped_calls: AtomicUsize,
+    /// Decisions remembered under `decision_cache`, keyed by situation
+    cache: Mutex<LruCache<Action>>,
+    /// Replies remembered under `response_cache`, keyed by prompt
+    responses: Mutex<LruCache<String>>,
+}
+
+/// Recent values keyed by a hash of what produced them (a situation or a
+/// prompt), the least recently used forgotten first
+#[derive(Debug)]
+struct LruCache<V> {
+    capacity: usize,
+    entries: HashMap<u64, V>,
+    /// Keys from least to most recently used
+    order: VecDeque<u64>,
+    hits: usize,
+    misses: usize,
+}
+
+impl<V: Clone> LruCache<V> {
+    fn new(capacity: usize) -> Self {
+        Self { capacity, entries: HashMap::new(), order: VecDeque::new(), hits: 0, misses: 0 }
+    }
+
+    /// The value remembered under a key, counting the hit or miss
+    fn get(&mut self, key: u64) -> Option<V> {
+        let value = self.entries.get(&key).cloned();
+        if value.is_some() {
+            self.hits += 1;
+            self.touch(key);
+        } else {
+            self.misses += 1;
+        }
+        value
+    }
+
+    fn insert(&mut self, key: u64, value: V) {
+        self.entries.insert(key, value);
+        self.touch(key);
+        while self.entries.len() > self.capacity {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+
+    fn touch(&mut self, key: u64) {
+        self.order.retain(|k| *k != key);
+        self.order.push_back(key);
+    }
 }
 
 #[derive(Serialize)]
@@ -48,75 +348,261 @@ struct ContentBlock {
     text: String,
 }
 
+#[derive(Serialize)]
+struct ChatRequest {
+    model: String,
+    max_tokens: usize,
+    temperature: f64,
+    messages: Vec<Message>,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatMessage {
+    content: String,
+}
+
+/// A reply with an unsuccessful status
+#[derive(Debug)]
+struct ApiError {
+    status: reqwest::StatusCode,
+    body: String,
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "API error {}: {}", self.status, self.body)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Whether a failed call is worth retrying: rate limited, a server error, or
+/// the connection failing or timing out
+fn is_transient(err: &anyhow::Error) -> bool {
+    if let Some(err) = err.downcast_ref::<ApiError>() {
+        return err.status == reqwest::StatusCode::TOO_MANY_REQUESTS || err.status.is_server_error();
+    }
+    err.downcast_ref::<reqwest::Error>().is_some_and(|err| err.is_timeout() || err.is_connect())
+}
+
+/// Who the model plays, sent ahead of every prompt
+const SYSTEM_PROMPT: &str = "You are a person living in a small world. You make decisions based on your personality, needs, and goals. Be consistent with your character. Respond concisely.";
+
+/// The text of a reply body in the provider's response format
+fn reply_text(provider: Provider, body: &str) -> Result<String> {
+    let text = match provider {
+        Provider::Anthropic => {
+            let response: AnthropicResponse = serde_json::from_str(body)?;
+            response.content.into_iter().next().map(|c| c.text)
+        }
+        Provider::OpenAi => {
+            let response: ChatResponse = serde_json::from_str(body)?;
+            response.choices.into_iter().next().map(|c| c.message.content)
+        }
+    };
+    text.ok_or_else(|| anyhow!("Empty response"))
+}
+
 impl LlmClient {
-    /// Create a new LLM client
-    pub fn new(config: LlmConfig) -> Self {
+    /// Create a new LLM client, loading the prompt template if one is configured
+    pub fn new(config: LlmConfig) -> Result<Self> {
         let api_key = std::env::var(&config.api_key_env).ok();
 
-        if api_key.is_none() {
+        if api_key.is_none() && config.base_url.is_none() {
             warn!(
                 "API key not found in {}. Will use heuristic fallback.",
                 config.api_key_env
             );
         }
 
-        Self {
+        let template = match &config.prompt_template {
+            Some(path) => PromptTemplate::load(path)?,
+            None => PromptTemplate::default(),
+        };
+
+        Ok(Self {
             client: reqwest::Client::new(),
+            cache: Mutex::new(LruCache::new(config.decision_cache)),
+            responses: Mutex::new(LruCache::new(config.response_cache_size)),
             config,
             api_key,
-        }
+            template,
+            skipped_calls: AtomicUsize::new(0),
+        })
     }
 
     /// Check if LLM is available
     pub fn is_available(&self) -> bool {
-        self.api_key.is_some()
+        self.api_key.is_some() || self.config.base_url.is_some()
     }
 
-    /// Get an action from the LLM
-    /// pending_trades: Vec of (proposal_index, proposer_id, proposer_name, offering_desc, requesting_desc, expires_in)
-    /// debts_owed: Vec of (creditor_id, creditor_name, service_description, deadline_in) for debts the agent owes
-    /// credits_owed: Vec of (debtor_id, debtor_name, service_description, deadline_in) for debts others owe this agent
-    /// my_proposals: number of pending trade proposals this agent has made
+    /// Number of LLM calls skipped for isolated agents since the last call
+    pub fn take_skipped_calls(&self) -> usize {
+        self.skipped_calls.swap(0, Ordering::Relaxed)
+    }
+
+    /// Number of prompts answered from the response cache so far
+    pub fn cache_hits(&self) -> usize {
+        self.responses.lock().unwrap_or_else(|e| e.into_inner()).hits
+    }
+
+    /// Log how often remembered decisions and replies stood in for an LLM call
+    pub fn log_cache_summary(&self) {
+        let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        let lookups = cache.hits + cache.misses;
+        if lookups > 0 {
+            info!(
+                "Decision cache: {} of {} decisions reused ({:.0}% hit rate)",
+                cache.hits,
+                lookups,
+                cache.hits as f64 / lookups as f64 * 100.0
+            );
+        }
+        let responses = self.responses.lock().unwrap_or_else(|e| e.into_inner());
+        let lookups = responses.hits + responses.misses;
+        if lookups > 0 {
+            info!(
+                "Response cache: {} of {} prompts answered from memory ({:.0}% hit rate)",
+                responses.hits,
+                lookups,
+                responses.hits as f64 / lookups as f64 * 100.0
+            );
+        }
+    }
+
+    /// The remembered decision for a situation, or a fresh one from `decide`
+    /// that is remembered for next time. A reused decision comes without a remark;
+    /// when `decide` can't make one out, the agent waits and nothing is remembered
+    /// redecide: ask afresh even if a decision is remembered
+    async fn cached<F, Fut>(&self, key: u64, redecide: bool, decide: F) -> Result<(Action, Option<Remark>)>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<Option<(Action, Option<Remark>)>>>,
+    {
+        if self.config.decision_cache == 0 {
+            return Ok(decide().await?.unwrap_or((Action::Wait, None)));
+        }
+        let remembered = {
+            let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+            if redecide {
+                cache.misses += 1;
+                None
+            } else {
+                cache.get(key)
+            }
+        };
+        if let Some(action) = remembered {
+            return Ok((action, None));
+        }
+
+        let Some((action, remark)) = decide().await? else {
+            return Ok((Action::Wait, None));
+        };
+        self.cache.lock().unwrap_or_else(|e| e.into_inner()).insert(key, action.clone());
+        Ok((action, remark))
+    }
+
+    /// Get an action from the LLM (or the heuristic, when no LLM is to be
+    /// asked), with any remark made in passing
+    /// instincts: drive order for the heuristic fallback
     pub async fn decide_action(
         &self,
-        agent: &Agent,
-        world_perception: &str,
-        nearby_agents: &[(uuid::Uuid, &str)],
-        epoch: usize,
-        pending_trades: &[(usize, uuid::Uuid, &str, String, String, Option<usize>)],
-        debts_owed: &[(uuid::Uuid, &str, String, Option<usize>)],
-        credits_owed: &[(uuid::Uuid, &str, String, Option<usize>)],
-        my_proposals: usize,
-    ) -> Result<Action> {
-        // If no API key, use heuristic
-        if !self.is_available() {
-            return Ok(heuristic_action(agent, nearby_agents, pending_trades, debts_owed));
+        situation: &Situation<'_>,
+        instincts: &InstinctConfig,
+    ) -> Result<(Action, Option<Remark>)> {
+        // If no API key, or the agent isn't to be asked this epoch, use heuristic
+        if !situation.ask_llm || !self.is_available() {
+            return Ok((heuristic_action(situation, instincts), None));
         }
 
-        let prompt = self.build_prompt(
-            agent,
-            world_perception,
-            nearby_agents,
-            epoch,
-            pending_trades,
-            debts_owed,
-            credits_owed,
-            my_proposals,
-        );
-        let response = self.call_api(&prompt).await?;
-
-        debug!("Agent {} reasoning: {}", agent.name(), response);
+        // Nothing social or urgent going on: not worth an LLM call
+        if self.config.skip_isolated && is_routine(situation) {
+            self.skipped_calls.fetch_add(1, Ordering::Relaxed);
+            return Ok((heuristic_action(situation, instincts), None));
+        }
 
-        // Parse action from response
-        let action = Action::parse(&response, nearby_agents).unwrap_or_else(|| {
-            warn!(
-                "Could not parse action from: {}. Defaulting to WAIT",
-                response
+        let Situation { agent, perception, nearby, pending_trades, debts_owed, credits_owed, .. } = situation;
+        let nearby_agents = &nearby[..];
+        let company = (nearby, pending_trades, debts_owed, credits_owed);
+        let key = situation_key(agent, perception, company);
+        // A stream of the situation's generator apart from the heuristic's
+        let mut roll = situation.rng();
+        roll.set_stream(1);
+        let redecide = roll.random::<f64>() < self.config.cache_redecide_chance;
+        let decision = self.cached(key, redecide, || async {
+            let prompt = self.build_prompt(
+                agent,
+                perception,
+                nearby_agents,
+                situation.epoch,
+                pending_trades,
+                debts_owed,
+                credits_owed,
+                situation.my_proposals,
             );
-            Action::Wait
-        });
+            let response = self.call_api(agent, &prompt).await?;
+
+            debug!("Agent {} reasoning: {}", agent.name(), response);
 
-        Ok(action)
+            // Parse action from response
+            let mut action = self.extract_action(agent, &response, nearby_agents);
+            if action.is_none() && self.config.reformat_retry {
+                let retry = format!("{}\n\n{}\n\n{}", prompt, response, REFORMAT_PROMPT);
+                let reformatted = self.call_api(agent, &retry).await?;
+                debug!("Agent {} reformatted: {}", agent.name(), reformatted);
+                action = self.extract_action(agent, &reformatted, nearby_agents);
+            }
+            let Some(action) = action else {
+                warn!(
+                    "Could not parse action from: {}. Defaulting to WAIT",
+                    response
+                );
+                return Ok(None);
+            };
+            let remark = self.incidental_remark(&action, &response, nearby_agents);
+
+            Ok(Some((action, remark)))
+        })
+        .await;
+
+        // One failed call shouldn't end the run
+        decision.or_else(|err| {
+            warn!("LLM call for {} failed ({}); the heuristic decides instead", agent.name(), err);
+            Ok((heuristic_action(situation, instincts), None))
+        })
+    }
+
+    /// A remark made in passing, if enabled; only gathering and moving leave
+    /// an agent free to talk as well
+    fn incidental_remark(&self, action: &Action, response: &str, nearby_agents: &[(uuid::Uuid, &str)]) -> Option<Remark> {
+        if !self.config.allow_incidental_speech || !matches!(action, Action::Gather | Action::Move(_)) {
+            return None;
+        }
+        Remark::parse(response, nearby_agents)
+    }
+
+    /// The action a reply starts with, or failing that one salvaged from
+    /// further in (when enabled)
+    fn extract_action(&self, agent: &Agent, response: &str, nearby_agents: &[(uuid::Uuid, &str)]) -> Option<Action> {
+        if let Some(action) = Action::parse(response, nearby_agents) {
+            return Some(action);
+        }
+        if !self.config.salvage_actions {
+            return None;
+        }
+        let action = Action::salvage(response, nearby_agents)?;
+        info!("Salvaged {:?} for {} from: {}", action, agent.name(), response);
+        Some(action)
     }
 
     fn build_prompt(
@@ -153,7 +639,10 @@ impl LlmClient {
         let teachable_skills = agent.skills.teachable_skills();
 
         // Get tool-unlocked actions (already lowercase from unlocked_actions)
-        let unlocked_actions = agent.physical.unlocked_actions();
+        let mut unlocked_actions = agent.physical.unlocked_actions();
+        if !agent.physical.tools.is_empty() {
+            unlocked_actions.push("equip");
+        }
 
         // For now, empty craftable tools and structures (would need Registry access)
         let craftable_tools = Vec::new();
@@ -178,50 +667,41 @@ impl LlmClient {
             })
             .collect();
 
-        format!(
-            r#"{}
-
-## Current Situation (Day {})
-{}
-
-{}
-{}
-## Available Actions
-{}
-
-## Instructions
-Think about your current needs, your personality, and your goals.
-Decide what to do. Respond with your reasoning (1-2 sentences) then your chosen action.
-
-Format your response like this:
-REASONING: [your thinking]
-ACTION: [one action from the list above]
+        let perception = format!("{}\n\n{}\n{}", world_perception, nearby_desc, trade_context);
+        let actions = Action::available_actions_prompt(
+            nearby_agents,
+            &teachable_skills,
+            &unlocked_actions,
+            &craftable_tools,
+            &buildable_structures,
+            has_shelter,
+            has_storage,
+            owns_structure,
+            is_sheltered,
+            can_mark_territory,
+            &trespassers,
+            is_challenged,
+            &pending_offer_descs,
+            my_proposals,
+        );
+        let actions = if self.config.allow_incidental_speech && !nearby_agents.is_empty() {
+            format!(
+                "{}\nYou may end a GATHER or MOVE with a short remark in quotes to someone next to you, e.g. GATHER \"Good berries here, {}\"",
+                actions, nearby_agents[0].1
+            )
+        } else {
+            actions
+        };
 
-Example:
-REASONING: I am hungry and there is food here. I should gather some.
-ACTION: GATHER"#,
-            agent.prompt_state(epoch),
-            epoch,
-            world_perception,
-            nearby_desc,
-            trade_context,
-            Action::available_actions_prompt(
-                nearby_agents,
-                &teachable_skills,
-                &unlocked_actions,
-                &craftable_tools,
-                &buildable_structures,
-                has_shelter,
-                has_storage,
-                owns_structure,
-                is_sheltered,
-                can_mark_territory,
-                &trespassers,
-                is_challenged,
-                &pending_offer_descs,
-                my_proposals,
-            ),
-        )
+        self.template.render(&[
+            ("name", agent.name()),
+            ("epoch", &epoch.to_string()),
+            ("state", &agent.prompt_state(epoch)),
+            ("memories", &agent.memory.prompt_summary(epoch, &self.config.recall)),
+            ("relations", &agent.beliefs.prompt_summary(epoch)),
+            ("perception", &perception),
+            ("actions", &actions),
+        ])
     }
 
     /// Build trade context section for prompt
@@ -300,60 +780,194 @@ ACTION: GATHER"#,
         sections.join("")
     }
 
-    async fn call_api(&self, prompt: &str) -> Result<String> {
-        let api_key = self
-            .api_key
-            .as_ref()
-            .ok_or_else(|| anyhow!("No API key"))?;
+    /// Build the API request for an agent, honoring their model override
+    fn build_request(&self, agent: &Agent, prompt: &str) -> AnthropicRequest {
+        let model = agent.identity.model.as_deref().unwrap_or(&self.config.model);
 
-        let request = AnthropicRequest {
-            model: self.config.model.clone(),
+        AnthropicRequest {
+            model: model.to_string(),
             max_tokens: self.config.max_tokens,
             temperature: self.config.temperature,
-            system: "You are a person living in a small world. You make decisions based on your personality, needs, and goals. Be consistent with your character. Respond concisely.".to_string(),
+            system: SYSTEM_PROMPT.to_string(),
             messages: vec![Message {
                 role: "user".to_string(),
                 content: prompt.to_string(),
             }],
-        };
+        }
+    }
 
-        let response = self
-            .client
-            .post("https://api.anthropic.com/v1/messages")
-            .header("x-api-key", api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
+    /// Build the chat completions request for an agent, the system prompt
+    /// going first as a message of its own
+    fn build_chat_request(&self, agent: &Agent, prompt: &str) -> ChatRequest {
+        let model = agent.identity.model.as_deref().unwrap_or(&self.config.model);
+
+        ChatRequest {
+            model: model.to_string(),
+            max_tokens: self.config.max_tokens,
+            temperature: self.config.temperature,
+            messages: vec![
+                Message {
+                    role: "system".to_string(),
+                    content: SYSTEM_PROMPT.to_string(),
+                },
+                Message {
+                    role: "user".to_string(),
+                    content: prompt.to_string(),
+                },
+            ],
+        }
+    }
+
+    /// The reply to a prompt, remembered from an earlier identical prompt to the
+    /// same model when the response cache is on
+    async fn call_api(&self, agent: &Agent, prompt: &str) -> Result<String> {
+        self.cached_response(agent, prompt, || self.with_retries(agent, || self.send_request(agent, prompt))).await
+    }
+
+    /// Make a call, retrying transient failures up to `max_retries` times with
+    /// exponential backoff and jitter
+    async fn with_retries<F, Fut>(&self, agent: &Agent, mut call: F) -> Result<String>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<String>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match call().await {
+                Err(err) if attempt < self.config.max_retries && is_transient(&err) => {
+                    attempt += 1;
+                    // Unseeded on purpose: retries must not shift the simulation's streams
+                    let jitter = 0.5 + rand::random::<f64>();
+                    let delay = self.config.retry_delay_ms as f64 * 2f64.powi(attempt as i32 - 1) * jitter;
+                    warn!(
+                        "LLM call for {} failed ({}); retry {} of {} in {:.0}ms",
+                        agent.name(),
+                        err,
+                        attempt,
+                        self.config.max_retries,
+                        delay
+                    );
+                    tokio::time::sleep(std::time::Duration::from_millis(delay as u64)).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// The reply remembered for a prompt, or a fresh one from `call` that is
+    /// remembered for next time
+    async fn cached_response<F, Fut>(&self, agent: &Agent, prompt: &str, call: F) -> Result<String>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<String>>,
+    {
+        if !self.config.response_cache {
+            return call().await;
+        }
+        let model = agent.identity.model.as_deref().unwrap_or(&self.config.model);
+        let mut hasher = DefaultHasher::new();
+        (model, prompt).hash(&mut hasher);
+        let key = hasher.finish();
+
+        let remembered = self.responses.lock().unwrap_or_else(|e| e.into_inner()).get(key);
+        if let Some(response) = remembered {
+            debug!("Answered {}'s prompt from the response cache", agent.name());
+            return Ok(response);
+        }
+        let response = call().await?;
+        self.responses.lock().unwrap_or_else(|e| e.into_inner()).insert(key, response.clone());
+        Ok(response)
+    }
+
+    async fn send_request(&self, agent: &Agent, prompt: &str) -> Result<String> {
+        if !self.is_available() {
+            return Err(anyhow!("No API key"));
+        }
+        let provider = self.config.provider;
+        let base_url = self.config.base_url.as_deref().unwrap_or(provider.default_base_url());
+        let base_url = base_url.trim_end_matches('/');
+
+        let request = match provider {
+            Provider::Anthropic => {
+                let request = self
+                    .client
+                    .post(format!("{}/messages", base_url))
+                    .header("anthropic-version", "2023-06-01")
+                    .json(&self.build_request(agent, prompt));
+                match &self.api_key {
+                    Some(api_key) => request.header("x-api-key", api_key),
+                    None => request,
+                }
+            }
+            Provider::OpenAi => {
+                let request = self
+                    .client
+                    .post(format!("{}/chat/completions", base_url))
+                    .json(&self.build_chat_request(agent, prompt));
+                match &self.api_key {
+                    Some(api_key) => request.bearer_auth(api_key),
+                    None => request,
+                }
+            }
+        };
+        let response = request.header("content-type", "application/json").send().await?;
 
         if !response.status().is_success() {
             let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            return Err(anyhow!("API error {}: {}", status, text));
+            let body = response.text().await.unwrap_or_default();
+            return Err(ApiError { status, body }.into());
         }
 
-        let response: AnthropicResponse = response.json().await?;
-
-        response
-            .content
-            .first()
-            .map(|c| c.text.clone())
-            .ok_or_else(|| anyhow!("Empty response"))
+        reply_text(provider, &response.text().await?)
     }
 }
 
+/// Hash of what an agent's decision hinges on: who they are, their body
+/// (needs rounded to tenths), goal, what they perceive and the `company` of
+/// nearby agents, trades and promises around them. The day itself is left
+/// out, so the same situation on another day hashes the same
+fn situation_key(agent: &Agent, perception: &str, company: impl Hash) -> u64 {
+    let tenths = |v: f64| (v * 10.0).round() as i64;
+    let physical = &agent.physical;
+    let mut hasher = DefaultHasher::new();
+    agent.id.hash(&mut hasher);
+    (physical.x, physical.y, physical.food).hash(&mut hasher);
+    (tenths(physical.hunger), tenths(physical.energy), tenths(physical.health)).hash(&mut hasher);
+    format!("{:?}", agent.active_goal).hash(&mut hasher);
+    perception.hash(&mut hasher);
+    company.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Whether a decision is routine enough for the heuristic: nobody nearby, no
+/// trades or promises outstanding, and body and surroundings in no danger
+fn is_routine(situation: &Situation<'_>) -> bool {
+    let physical = &situation.agent.physical;
+    let critical = physical.hunger > 0.6
+        || physical.energy < 0.3
+        || physical.health < 0.5
+        || situation.hazard_level > 0.5;
+    let social = !situation.nearby.is_empty()
+        || !situation.pending_trades.is_empty()
+        || !situation.debts_owed.is_empty()
+        || !situation.credits_owed.is_empty();
+    !critical && !social
+}
+
+/// How much an agent's situation calls for careful thought: pressing needs,
+/// hazard, company and outstanding trades or promises
+pub(crate) fn situation_salience(agent: &Agent, company: usize, dealings: usize, hazard_level: f64) -> f64 {
+    let physical = &agent.physical;
+    physical.hunger + (1.0 - physical.energy) + (1.0 - physical.health) + hazard_level
+        + 0.5 * (company + dealings) as f64
+}
+
 /// Heuristic action when no LLM available
-/// pending_trades: Vec of (proposal_index, proposer_id, proposer_name, offering_desc, requesting_desc, expires_in)
-/// debts_owed: Vec of (creditor_id, creditor_name, service_description, deadline_in) for debts the agent owes
-fn heuristic_action(
-    agent: &Agent,
-    nearby_agents: &[(uuid::Uuid, &str)],
-    pending_trades: &[(usize, uuid::Uuid, &str, String, String, Option<usize>)],
-    debts_owed: &[(uuid::Uuid, &str, String, Option<usize>)],
-) -> Action {
-    use rand::Rng;
-    let mut rng = rand::rng();
+/// instincts: the order in which survival, social, reproductive and exploring drives are served
+pub(crate) fn heuristic_action(situation: &Situation<'_>, instincts: &InstinctConfig) -> Action {
+    let mut rng = situation.rng();
+    let (agent, epoch, hazard_level) = (situation.agent, situation.epoch, situation.hazard_level);
+    let (nearby_agents, pending_trades, debts_owed) = (&situation.nearby[..], &situation.pending_trades, &situation.debts_owed);
 
     // Priority 0a: Fulfill debts to nearby creditors
     for (creditor_id, creditor_name, service_desc, _deadline) in debts_owed {
@@ -514,28 +1128,237 @@ fn heuristic_action(
         }
     }
 
+    // Drives in instinct order; near-starvation puts survival first, and
+    // wandering is the fallback below whatever its place
+    let mut order = instincts.order.clone();
+    if agent.physical.hunger > instincts.starvation_hunger {
+        order.retain(|d| *d != Drive::Survival);
+        order.insert(0, Drive::Survival);
+    }
+    for drive in order {
+        let action = match drive {
+            Drive::Survival => survival_action(agent, epoch, hazard_level, instincts, &mut rng),
+            Drive::Social => social_action(agent, nearby_agents, &mut rng),
+            Drive::Reproduction => reproduction_action(agent, nearby_agents, &mut rng),
+            Drive::Exploration => exploration_action(agent, epoch, &mut rng),
+        };
+        if let Some(action) = action {
+            return action;
+        }
+    }
+
+    // Priority 8: Teach if skilled and agreeable
+    let teachable = agent.skills.teachable_skills();
+    if !teachable.is_empty()
+        && !nearby_agents.is_empty()
+        && agent.physical.energy > 0.3
+        && (agent.identity.personality.agreeableness > 0.5
+            || agent.skills.level("teaching") > 0.5)
+        && rng.random::<f64>() < 0.15  // 15% chance
+    {
+        // Pick a random skill to teach and a random nearby agent
+        let skill = teachable[rng.random_range(0..teachable.len())].clone();
+        let (target, _) = nearby_agents[rng.random_range(0..nearby_agents.len())];
+        return Action::Teach { target, skill };
+    }
+
+    // Priority 9: Trade if we have materials and could use food (or vice versa)
+    if !nearby_agents.is_empty() && rng.random::<f64>() < 0.25 {
+        // Check if we have excess materials to trade for food
+        let total_materials: u32 = agent.physical.materials.values().sum();
+
+        if total_materials > 3 && agent.physical.food < 5 {
+            // Offer materials for food (the first kind we hold enough of)
+            if let Some((mat_type, amount)) = agent.physical.materials.iter()
+                .filter(|(_, amt)| **amt >= 2)
+                .map(|(m, a)| (*m, *a))
+                .min()
+            {
+                let (target, _) = nearby_agents[rng.random_range(0..nearby_agents.len())];
+                let trade_amount = amount.min(3);
+                return Action::TradeOffer {
+                    target,
+                    offering: vec![TradeableItem::Materials(mat_type, trade_amount)],
+                    requesting: vec![TradeableItem::Food(trade_amount * 2)],
+                };
+            }
+        }
+
+        // Or if we have excess food and need materials
+        if agent.physical.food > 8 && total_materials < 5 {
+            let (target, _) = nearby_agents[rng.random_range(0..nearby_agents.len())];
+            return Action::TradeOffer {
+                target,
+                offering: vec![TradeableItem::Food(3)],
+                requesting: vec![TradeableItem::Materials(crate::crafting::MaterialType::Wood, 2)],
+            };
+        }
+
+        // Sometimes offer a future gift promise for immediate materials
+        if total_materials < 3 && agent.physical.food < 8 && rng.random::<f64>() < 0.3 {
+            let (target, _) = nearby_agents[rng.random_range(0..nearby_agents.len())];
+            // Promise 5 food in exchange for 2 wood now
+            return Action::TradeOffer {
+                target,
+                offering: vec![TradeableItem::FutureGiftPromise { amount: 5, deadline_epochs: 15 }],
+                requesting: vec![TradeableItem::Materials(crate::crafting::MaterialType::Wood, 2)],
+            };
+        }
+    }
+
+    // Otherwise: an everyday choice, weighted by temperament
+    let heuristic = &instincts.heuristic;
+    let personality = &agent.identity.personality;
+    let lean = |level: f64| (1.0 + heuristic.personality * (2.0 * level - 1.0)).max(0.0);
+    let company = if nearby_agents.is_empty() { 0.0 } else { 1.0 };
+    let weights = [
+        heuristic.explore * lean(personality.risk_tolerance()),
+        heuristic.gather * lean(personality.conscientiousness),
+        heuristic.gather_materials * lean(personality.conscientiousness),
+        heuristic.rest,
+        heuristic.socialize * lean(personality.extraversion) * company,
+        heuristic.wait,
+    ];
+    let mut roll = rng.random::<f64>() * weights.iter().sum::<f64>();
+    let choice = weights.iter().position(|w| {
+        roll -= w;
+        roll < 0.0
+    });
+    match choice {
+        Some(0) => {
+            let is_safe = |d: &Direction| is_safe_step(agent, epoch, d);
+            let safe: Vec<Direction> = Direction::ALL.into_iter().filter(is_safe).collect();
+            if safe.is_empty() {
+                Action::Move(Direction::ALL[rng.random_range(0..8)])
+            } else {
+                Action::Move(safe[rng.random_range(0..safe.len())])
+            }
+        }
+        Some(1) => Action::Gather,
+        Some(2) => Action::GatherMaterials, // Gather wood, stone, etc.
+        Some(3) => Action::Rest,
+        Some(4) => {
+            let (target, _) = nearby_agents[rng.random_range(0..nearby_agents.len())];
+            Action::ShareKnowledge { target }
+        }
+        _ => Action::Wait,
+    }
+}
+
+/// Run from an attacker, eat, rest or take cover, flee danger, or find food,
+/// if the body needs it
+fn survival_action(
+    agent: &Agent,
+    epoch: usize,
+    hazard_level: f64,
+    instincts: &InstinctConfig,
+    rng: &mut ChaCha8Rng,
+) -> Option<Action> {
+    // Priority 0: Get clear of a nearby attacker while too hurt to fight back
+    if agent.should_flee(instincts) {
+        debug!("Heuristic: {} fleeing an attacker", agent.name());
+        return Some(Action::Flee);
+    }
+
     // Priority 1: Eat if hungry and have food
-    if agent.physical.hunger > 0.6 && agent.physical.food > 0 {
-        return Action::Eat;
+    let hungry = agent.physical.hunger > instincts.hunger_threshold;
+    if hungry && agent.physical.food > 0 {
+        return Some(Action::Eat);
+    }
+
+    // Priority 2: Rest if exhausted; cautious agents rest up to twice as soon
+    // and take cover. Not when hungry with nothing to eat: resting heals
+    // nothing, only eating does, so finding food comes first
+    let must_forage = hungry && agent.physical.food == 0;
+    let pos = (agent.physical.x, agent.physical.y);
+    let caution = 1.0 - agent.identity.personality.risk_tolerance();
+    let on_shelter = agent
+        .beliefs
+        .landmarks_near(pos.0, pos.1, 0)
+        .iter()
+        .any(|l| l.kind == LandmarkKind::Shelter);
+    let worn_down = agent.physical.energy < instincts.energy_threshold * (1.0 + caution);
+    if !must_forage && (worn_down || (hazard_level > 0.3 && caution > 0.5)) {
+        if on_shelter && !agent.physical.is_sheltered() {
+            return Some(Action::EnterShelter);
+        }
+        // Sit out the hazard if already under cover
+        if worn_down || agent.physical.is_sheltered() {
+            return Some(Action::Rest);
+        }
     }
 
-    // Priority 2: Rest if exhausted
-    if agent.physical.energy < 0.2 {
-        return Action::Rest;
+    let is_safe = |d: &Direction| is_safe_step(agent, epoch, d);
+
+    // Priority 2b: Get away from somewhere we came to harm
+    if agent.beliefs.is_dangerous(pos.0, pos.1, epoch) {
+        let safe: Vec<Direction> = Direction::ALL.into_iter().filter(is_safe).collect();
+        if !safe.is_empty() {
+            let direction = safe[rng.random_range(0..safe.len())];
+            debug!("Heuristic: {} leaving a dangerous place {}", agent.name(), direction.name());
+            return Some(Action::Move(direction));
+        }
     }
 
-    // Priority 3: Gather if low on food
+    // Priority 2c: Keep going with the others when a starving cluster has set
+    // out, stopping to forage wherever the way passes food
+    if let Some(migration) = &agent.migration {
+        if agent.beliefs.food_at(pos.0, pos.1).is_some_and(|amount| amount > 0) {
+            return Some(Action::Gather);
+        }
+        // Steer for the remembered ground; once there, forage like anyone else
+        let heading = match migration.destination {
+            Some(to) => Direction::toward(pos, to),
+            None => Some(migration.direction),
+        };
+        if let Some(direction) = heading.filter(is_safe) {
+            debug!("Heuristic: {} migrating {} with the others", agent.name(), direction.name());
+            return Some(Action::Move(direction));
+        }
+    }
+
+    // Priority 3: Gather if low on food, or head for better ground if this area is picked clean
     if agent.physical.food < 3 {
-        return Action::Gather;
+        let depleted = agent.beliefs.food_at(pos.0, pos.1).unwrap_or(0) <= 1;
+        if let Some(direction) = agent
+            .beliefs
+            .best_food_elsewhere(pos.0, pos.1, epoch)
+            .filter(|_| depleted)
+            .and_then(|loc| Direction::toward(pos, (loc.x, loc.y)))
+            .filter(is_safe)
+        {
+            debug!("Heuristic: {} migrating {} toward remembered food", agent.name(), direction.name());
+            return Some(Action::Move(direction));
+        }
+        return Some(Action::Gather);
+    }
+
+    None
+}
+
+/// Set off somewhere new, by a safe step, once the agent has picked its spot clean
+fn exploration_action(agent: &Agent, epoch: usize, rng: &mut ChaCha8Rng) -> Option<Action> {
+    if !agent.has_exhausted_spot() {
+        return None;
+    }
+    let safe: Vec<Direction> = Direction::ALL.into_iter().filter(|d| is_safe_step(agent, epoch, d)).collect();
+    if safe.is_empty() {
+        return None;
     }
+    let direction = safe[rng.random_range(0..safe.len())];
+    debug!("Heuristic: {} exploring {}", agent.name(), direction.name());
+    Some(Action::Move(direction))
+}
 
+/// Share food or gossip with those nearby
+fn social_action(agent: &Agent, nearby_agents: &[(uuid::Uuid, &str)], rng: &mut ChaCha8Rng) -> Option<Action> {
     // Priority 4: Give food to nearby hungry agent if agreeable
     if agent.identity.personality.agreeableness > 0.7
         && agent.physical.food > 5
         && !nearby_agents.is_empty()
     {
         let (target, _) = nearby_agents[rng.random_range(0..nearby_agents.len())];
-        return Action::Give { target, amount: 1 };
+        return Some(Action::Give { target, amount: 1 });
     }
 
     // Priority 5: Gossip if extraverted and have opinions to share
@@ -551,11 +1374,11 @@ fn heuristic_action(
                     // Subject is nearby
                     && nearby_agents.iter().any(|(nid, _)| nid == *id)
             })
-            .max_by(|(_, a), (_, b)| {
-                // Prefer strongest feelings
+            .max_by(|(a_id, a), (b_id, b)| {
+                // Prefer strongest feelings, then the lowest id
                 let a_strength = a.trust.abs() + a.sentiment.abs();
                 let b_strength = b.trust.abs() + b.sentiment.abs();
-                a_strength.partial_cmp(&b_strength).unwrap_or(std::cmp::Ordering::Equal)
+                a_strength.partial_cmp(&b_strength).unwrap_or(std::cmp::Ordering::Equal).then(b_id.cmp(a_id))
             })
             .map(|(id, _)| *id);
 
@@ -567,11 +1390,16 @@ fn heuristic_action(
 
             if !potential_targets.is_empty() {
                 let (target, _) = potential_targets[rng.random_range(0..potential_targets.len())];
-                return Action::Gossip { target: *target, about };
+                return Some(Action::Gossip { target: *target, about });
             }
         }
     }
 
+    None
+}
+
+/// Court a nearby agent or mate with one whose courtship is far enough along
+fn reproduction_action(agent: &Agent, nearby_agents: &[(uuid::Uuid, &str)], rng: &mut ChaCha8Rng) -> Option<Action> {
     // Priority 6: Court if extraverted/agreeable and conditions are right
     if (agent.identity.personality.extraversion > 0.5 || agent.identity.personality.agreeableness > 0.5)
         && !nearby_agents.is_empty()
@@ -592,7 +1420,7 @@ fn heuristic_action(
 
         if !potential_partners.is_empty() {
             let (target, _) = potential_partners[rng.random_range(0..potential_partners.len())];
-            return Action::Court { target: *target };
+            return Some(Action::Court { target: *target });
         }
     }
 
@@ -610,104 +1438,367 @@ fn heuristic_action(
                 **score >= 0.7  // Threshold met
                     && nearby_agents.iter().any(|(nid, _)| nid == *id)  // Is nearby
             })
-            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+            .max_by(|(a_id, a), (b_id, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal).then(b_id.cmp(a_id)))
             .map(|(id, _)| *id);
 
         if let Some(target) = mate_candidate {
-            return Action::Mate { target };
+            return Some(Action::Mate { target });
         }
     }
 
-    // Priority 8: Teach if skilled and agreeable
-    let teachable = agent.skills.teachable_skills();
-    if !teachable.is_empty()
-        && !nearby_agents.is_empty()
-        && agent.physical.energy > 0.3
-        && (agent.identity.personality.agreeableness > 0.5
-            || agent.skills.level("teaching") > 0.5)
-        && rng.random::<f64>() < 0.15  // 15% chance
-    {
-        // Pick a random skill to teach and a random nearby agent
-        let skill = teachable[rng.random_range(0..teachable.len())].clone();
-        let (target, _) = nearby_agents[rng.random_range(0..nearby_agents.len())];
-        return Action::Teach { target, skill };
-    }
-
-    // Priority 9: Trade if we have materials and could use food (or vice versa)
-    if !nearby_agents.is_empty() && rng.random::<f64>() < 0.25 {
-        // Check if we have excess materials to trade for food
-        let total_materials: u32 = agent.physical.materials.values().sum();
-
-        if total_materials > 3 && agent.physical.food < 5 {
-            // Offer materials for food
-            if let Some((mat_type, amount)) = agent.physical.materials.iter()
-                .filter(|(_, amt)| **amt >= 2)
-                .map(|(m, a)| (*m, *a))
-                .next()
-            {
-                let (target, _) = nearby_agents[rng.random_range(0..nearby_agents.len())];
-                let trade_amount = amount.min(3);
-                return Action::TradeOffer {
-                    target,
-                    offering: vec![TradeableItem::Materials(mat_type, trade_amount)],
-                    requesting: vec![TradeableItem::Food(trade_amount * 2)],
-                };
-            }
-        }
-
-        // Or if we have excess food and need materials
-        if agent.physical.food > 8 && total_materials < 5 {
-            let (target, _) = nearby_agents[rng.random_range(0..nearby_agents.len())];
-            return Action::TradeOffer {
-                target,
-                offering: vec![TradeableItem::Food(3)],
-                requesting: vec![TradeableItem::Materials(crate::crafting::MaterialType::Wood, 2)],
-            };
-        }
-
-        // Sometimes offer a future gift promise for immediate materials
-        if total_materials < 3 && agent.physical.food < 8 && rng.random::<f64>() < 0.3 {
-            let (target, _) = nearby_agents[rng.random_range(0..nearby_agents.len())];
-            // Promise 5 food in exchange for 2 wood now
-            return Action::TradeOffer {
-                target,
-                offering: vec![TradeableItem::FutureGiftPromise { amount: 5, deadline_epochs: 15 }],
-                requesting: vec![TradeableItem::Materials(crate::crafting::MaterialType::Wood, 2)],
-            };
-        }
-    }
+    None
+}
 
-    // Otherwise: random action
-    match rng.random_range(0..12) {
-        0..=4 => {
-            // Move in random direction
-            let directions = [
-                crate::action::Direction::North,
-                crate::action::Direction::South,
-                crate::action::Direction::East,
-                crate::action::Direction::West,
-                crate::action::Direction::NorthEast,
-                crate::action::Direction::NorthWest,
-                crate::action::Direction::SouthEast,
-                crate::action::Direction::SouthWest,
-            ];
-            Action::Move(directions[rng.random_range(0..8)])
-        }
-        5..=6 => Action::Gather,
-        7..=8 => Action::GatherMaterials, // Gather wood, stone, etc.
-        9 => Action::Rest,
-        _ => Action::Wait,
-    }
+/// Whether a step in this direction stays on the map and out of remembered danger
+fn is_safe_step(agent: &Agent, epoch: usize, direction: &Direction) -> bool {
+    direction
+        .step((agent.physical.x, agent.physical.y))
+        .map(|(x, y)| !agent.beliefs.is_dangerous(x, y, epoch))
+        .unwrap_or(false)
 }
 
 impl Default for LlmConfig {
     fn default() -> Self {
         Self {
-            provider: "anthropic".to_string(),
+            provider: Provider::Anthropic,
+            base_url: None,
             model: "claude-sonnet-4-20250514".to_string(),
             api_key_env: "ANTHROPIC_API_KEY".to_string(),
             max_tokens: 500,
             temperature: 0.7,
+            prompt_template: None,
+            skip_isolated: false,
+            allow_incidental_speech: false,
+            decision_cache: 0,
+            cache_redecide_chance: 0.1,
+            response_cache: false,
+            response_cache_size: 256,
+            recall: RecallConfig::default(),
+            salvage_actions: true,
+            reformat_retry: false,
+            max_llm_agents: None,
+            concurrency: 8,
+            max_retries: 3,
+            retry_delay_ms: 500,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hungry_agent_migrates_toward_remembered_food() {
+        let mut agent = Agent::new("Aric".to_string(), 5, 5, 0);
+        agent.physical.hunger = 0.8;
+        agent.beliefs.update_food_belief(5, 5, 0, 10);
+        agent.beliefs.update_food_belief(2, 5, 8, 8);
+
+        let action = heuristic_action(&Situation::alone(&agent, 10), &InstinctConfig::default());
+        assert!(matches!(action, Action::Move(Direction::West)));
+
+        // With nothing better remembered, they stay and forage
+        agent.beliefs.world.food_locations.retain(|b| (b.x, b.y) == (5, 5));
+        let action = heuristic_action(&Situation::alone(&agent, 10), &InstinctConfig::default());
+        assert!(matches!(action, Action::Gather));
+    }
+
+    #[test]
+    fn test_fed_agent_explores_once_spot_is_picked_clean() {
+        let mut agent = Agent::new("Aric".to_string(), 5, 5, 6);
+        agent.identity.personality.extraversion = 0.0;
+        agent.beliefs.update_food_belief(5, 5, 0, 10);
+
+        let action = heuristic_action(&Situation::alone(&agent, 10), &InstinctConfig::default());
+        assert!(matches!(action, Action::Move(_)), "{:?}", action);
+    }
+
+    #[test]
+    fn test_migrant_heads_for_destination_and_forages_on_the_way() {
+        let mut agent = Agent::new("Aric".to_string(), 5, 5, 6);
+        agent.physical.hunger = 0.9;
+        agent.physical.food = 0;
+        agent.migration = Some(crate::agent::Migration {
+            direction: Direction::North,
+            destination: Some((8, 8)),
+            companions: 3,
+            began: 9,
+        });
+        agent.beliefs.update_food_belief(5, 5, 0, 10);
+        let action = heuristic_action(&Situation::alone(&agent, 10), &InstinctConfig::default());
+        assert!(matches!(action, Action::Move(Direction::SouthEast)), "{:?}", action);
+
+        agent.beliefs.update_food_belief(5, 5, 4, 10);
+        let action = heuristic_action(&Situation::alone(&agent, 10), &InstinctConfig::default());
+        assert!(matches!(action, Action::Gather), "{:?}", action);
+    }
+
+    #[test]
+    fn test_cautious_agent_rests_sooner_than_bold_one() {
+        let tired = |neuroticism: f64, openness: f64, energy: f64| {
+            let mut agent = Agent::new("Aric".to_string(), 5, 5, 0);
+            agent.identity.personality.neuroticism = neuroticism;
+            agent.identity.personality.openness = openness;
+            agent.physical.energy = energy;
+            agent
+        };
+
+        // Moderately tired: the anxious agent rests, the bold one keeps foraging
+        let anxious = tired(0.9, 0.1, 0.3);
+        let bold = tired(0.1, 0.9, 0.3);
+        assert!(matches!(heuristic_action(&Situation::alone(&anxious, 10), &InstinctConfig::default()), Action::Rest));
+        assert!(matches!(heuristic_action(&Situation::alone(&bold, 10), &InstinctConfig::default()), Action::Gather));
+
+        // Exhausted: both rest
+        let bold = tired(0.1, 0.9, 0.15);
+        assert!(matches!(heuristic_action(&Situation::alone(&bold, 10), &InstinctConfig::default()), Action::Rest));
+    }
+
+    #[test]
+    fn test_hurt_hungry_cautious_agent_eats_rather_than_rests() {
+        let mut agent = Agent::new("Aric".to_string(), 5, 5, 2);
+        agent.identity.personality.neuroticism = 0.95;
+        agent.identity.personality.openness = 0.05;
+        agent.physical.health = 0.2;
+        agent.physical.energy = 0.25;
+        agent.physical.hunger = 0.8;
+        assert!(matches!(heuristic_action(&Situation::alone(&agent, 10), &InstinctConfig::default()), Action::Eat));
+
+        // With nothing to eat, they forage instead of resting until they starve
+        agent.physical.food = 0;
+        assert!(matches!(heuristic_action(&Situation::alone(&agent, 10), &InstinctConfig::default()), Action::Gather));
+    }
+
+    #[test]
+    fn test_extravert_socializes_more_than_introvert() {
+        crate::rng::seed(11);
+        let neighbour = uuid::Uuid::new_v4();
+        let nearby = [(neighbour, "Bria")];
+        let social_decisions = |extraversion: f64| {
+            let mut agent = Agent::new("Aric".to_string(), 5, 5, 3);
+            agent.identity.personality.extraversion = extraversion;
+            agent.identity.personality.agreeableness = 0.3;
+            (0..500)
+                .map(|_| heuristic_action(&Situation { nearby: nearby.to_vec(), ..Situation::alone(&agent, 10) }, &InstinctConfig::default()))
+                .filter(|a| matches!(a, Action::ShareKnowledge { .. } | Action::Court { .. } | Action::Gossip { .. }))
+                .count()
+        };
+
+        let (outgoing, shy) = (social_decisions(0.95), social_decisions(0.05));
+        assert!(outgoing > shy * 2, "extravert {} vs introvert {}", outgoing, shy);
+    }
+
+    #[test]
+    fn test_custom_prompt_template_renders_in_its_own_order() {
+        let path = std::env::temp_dir().join(format!("terrarium-prompt-{}.txt", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "Choices:\n{actions}\nWorld:\n{perception}\nYou are {name} on day {epoch}.\n{state}\n{unknown}").unwrap();
+        let config = LlmConfig {
+            prompt_template: Some(path.clone()),
+            ..LlmConfig::default()
+        };
+        let client = LlmClient::new(config).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let agent = Agent::new("Aric".to_string(), 0, 0, 5);
+        let prompt = client.build_prompt(&agent, "You stand on {fertile} ground.", &[], 7, &[], &[], &[], 0);
+
+        let actions = prompt.find("GATHER - collect food").unwrap();
+        let world = prompt.find("You stand on {fertile} ground.").unwrap();
+        let name = prompt.find("You are Aric on day 7.").unwrap();
+        let state = prompt.find("Physical state:").unwrap();
+        assert!(actions < world && world < name && name < state);
+        assert!(prompt.starts_with("Choices:\n"));
+        assert!(prompt.ends_with("{unknown}"));
+
+        assert!(PromptTemplate::parse("{state} {perception}").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_isolated_healthy_agent_skips_llm() {
+        let config = LlmConfig {
+            skip_isolated: true,
+            ..LlmConfig::default()
+        };
+        let client = LlmClient {
+            api_key: Some("test-key".to_string()),
+            ..LlmClient::new(config).unwrap()
+        };
+        let agent = Agent::new("Aric".to_string(), 0, 0, 5);
+
+        // Would fail without network if it reached the API
+        client.decide_action(&Situation::alone(&agent, 1), &InstinctConfig::default()).await.unwrap();
+        assert_eq!(client.take_skipped_calls(), 1);
+        assert_eq!(client.take_skipped_calls(), 0);
+
+        let bria = (uuid::Uuid::new_v4(), "Bria");
+        assert!(!is_routine(&Situation { nearby: vec![bria], ..Situation::alone(&agent, 1) }));
+        let mut starving = agent.clone();
+        starving.physical.hunger = 0.9;
+        assert!(!is_routine(&Situation::alone(&starving, 1)));
+    }
+
+    #[test]
+    fn test_heuristic_choice_ignores_draws_made_meanwhile() {
+        let agent = Agent::new("Aric".to_string(), 5, 5, 6);
+        let situation = Situation::alone(&agent, 10);
+        let first = heuristic_action(&situation, &InstinctConfig::default());
+        // Other agents' decisions finishing first mustn't change this one
+        for _ in 0..10 {
+            crate::rng::random::<u64>(crate::rng::Stream::Decisions);
         }
+        let second = heuristic_action(&situation, &InstinctConfig::default());
+        assert_eq!(format!("{:?}", first), format!("{:?}", second));
+    }
+
+    #[test]
+    fn test_only_gathering_and_moving_carry_a_remark() {
+        let config = LlmConfig {
+            allow_incidental_speech: true,
+            ..LlmConfig::default()
+        };
+        let client = LlmClient::new(config).unwrap();
+        let bria = uuid::Uuid::new_v4();
+        let nearby = [(bria, "Bria")];
+        let remark = |action: Action| client.incidental_remark(&action, r#"... "Good berries here, Bria""#, &nearby);
+
+        assert!(remark(Action::Gather).is_some());
+        assert!(remark(Action::Move(Direction::North)).is_some());
+        assert!(remark(Action::Attack { target: bria }).is_none());
+        assert!(remark(Action::Wait).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_same_situation_reuses_cached_decision() {
+        let config = LlmConfig {
+            decision_cache: 8,
+            ..LlmConfig::default()
+        };
+        let client = LlmClient::new(config).unwrap();
+        let mut agent = Agent::new("Aric".to_string(), 0, 0, 5);
+        let calls = AtomicUsize::new(0);
+        let decide = || async {
+            calls.fetch_add(1, Ordering::Relaxed);
+            Ok(Some((Action::Gather, None)))
+        };
+
+        let key = situation_key(&agent, "Berries grow here.", ());
+        client.cached(key, false, decide).await.unwrap();
+        let (action, _) = client.cached(key, false, decide).await.unwrap();
+        assert!(matches!(action, Action::Gather));
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        {
+            let cache = client.cache.lock().unwrap();
+            assert_eq!((cache.hits, cache.misses), (1, 1));
+        }
+
+        agent.physical.hunger += 0.3;
+        assert_ne!(situation_key(&agent, "Berries grow here.", ()), key);
+
+        // A reply nobody could make sense of is not remembered
+        let unreadable = || async {
+            calls.fetch_add(1, Ordering::Relaxed);
+            Ok(None)
+        };
+        let other = situation_key(&agent, "Berries grow here.", ());
+        let (action, _) = client.cached(other, false, unreadable).await.unwrap();
+        assert!(matches!(action, Action::Wait));
+        client.cached(other, false, unreadable).await.unwrap();
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+    }
+
+    #[tokio::test]
+    async fn test_repeated_prompt_is_answered_from_response_cache() {
+        let config = LlmConfig {
+            response_cache: true,
+            ..LlmConfig::default()
+        };
+        let client = LlmClient::new(config).unwrap();
+        let mut agent = Agent::new("Aric".to_string(), 0, 0, 5);
+        let calls = AtomicUsize::new(0);
+        let call = || async {
+            calls.fetch_add(1, Ordering::Relaxed);
+            Ok("GATHER".to_string())
+        };
+
+        client.cached_response(&agent, "Berries grow here.", call).await.unwrap();
+        let response = client.cached_response(&agent, "Berries grow here.", call).await.unwrap();
+        assert_eq!(response, "GATHER");
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        assert_eq!(client.cache_hits(), 1);
+
+        // Another prompt, or the same one to another model, is a fresh call
+        client.cached_response(&agent, "Nothing grows here.", call).await.unwrap();
+        agent.identity.model = Some("claude-haiku".to_string());
+        client.cached_response(&agent, "Berries grow here.", call).await.unwrap();
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn test_agent_model_override() {
+        let client = LlmClient::new(LlmConfig::default()).unwrap();
+        let mut agent = Agent::new("Aric".to_string(), 0, 0, 5);
+
+        assert_eq!(client.build_request(&agent, "hi").model, LlmConfig::default().model);
+
+        agent.identity.model = Some("claude-3-5-haiku-20241022".to_string());
+        assert_eq!(client.build_request(&agent, "hi").model, "claude-3-5-haiku-20241022");
+    }
+
+    #[test]
+    fn test_openai_reply_is_read_from_first_choice() {
+        let body = r#"{
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "model": "llama3",
+            "choices": [
+                {"index": 0, "message": {"role": "assistant", "content": "MOVE NORTH"}, "finish_reason": "stop"}
+            ],
+            "usage": {"prompt_tokens": 812, "completion_tokens": 3, "total_tokens": 815}
+        }"#;
+
+        let reply = reply_text(Provider::OpenAi, body).unwrap();
+        assert_eq!(reply, "MOVE NORTH");
+        assert!(matches!(Action::parse(&reply, &[]), Some(Action::Move(Direction::North))));
+        assert!(reply_text(Provider::OpenAi, r#"{"choices": []}"#).is_err());
+        assert!(reply_text(Provider::Anthropic, body).is_err());
+
+        let config: LlmConfig = toml::from_str(
+            "provider = \"openai\"\nbase_url = \"http://localhost:11434/v1\"\nmodel = \"llama3\"\napi_key_env = \"OLLAMA_KEY\"\nmax_tokens = 200\ntemperature = 0.7",
+        )
+        .unwrap();
+        assert_eq!(config.provider, Provider::OpenAi);
+    }
+
+    #[tokio::test]
+    async fn test_transient_failures_are_retried_until_success() {
+        let config = LlmConfig {
+            retry_delay_ms: 1,
+            ..LlmConfig::default()
+        };
+        let client = LlmClient::new(config).unwrap();
+        let agent = Agent::new("Aric".to_string(), 0, 0, 5);
+        let calls = AtomicUsize::new(0);
+        let flaky = |failures: usize, status: reqwest::StatusCode| {
+            let calls = &calls;
+            move || async move {
+                if calls.fetch_add(1, Ordering::Relaxed) < failures {
+                    return Err(ApiError { status, body: String::new() }.into());
+                }
+                Ok("GATHER".to_string())
+            }
+        };
+
+        let reply = client.with_retries(&agent, flaky(2, reqwest::StatusCode::TOO_MANY_REQUESTS)).await.unwrap();
+        assert!(matches!(Action::parse(&reply, &[]), Some(Action::Gather)));
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+
+        // Past max_retries, or on an error that won't go away, the failure stands
+        calls.store(0, Ordering::Relaxed);
+        assert!(client.with_retries(&agent, flaky(4, reqwest::StatusCode::BAD_GATEWAY)).await.is_err());
+        assert_eq!(calls.load(Ordering::Relaxed), 4);
+        calls.store(0, Ordering::Relaxed);
+        assert!(client.with_retries(&agent, flaky(1, reqwest::StatusCode::UNAUTHORIZED)).await.is_err());
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
     }
 }
@@ -1,10 +1,13 @@
 use anyhow::Result;
-use clap::Parser;
-use tracing::info;
+use clap::{Parser, Subcommand};
+use std::path::Path;
+use tracing::{info, warn};
 use tracing_subscriber::EnvFilter;
 
 mod action;
 mod agent;
+mod analysis;
+mod batch;
 mod config;
 mod crafting;
 mod engine;
@@ -13,6 +16,11 @@ mod groups;
 mod llm;
 mod observation;
 mod observer;
+mod pipeline;
+mod profiler;
+mod rng;
+mod spatial;
+mod stream;
 mod structures;
 mod trade;
 mod tui;
@@ -27,6 +35,9 @@ use environment::EnvironmentConfig;
 #[command(about = "A societal simulation engine where LLM-powered agents form emergent civilizations")]
 #[command(version)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Path to scenario configuration file
     #[arg(short, long)]
     scenario: Option<String>,
@@ -47,6 +58,10 @@ struct Args {
     #[arg(long)]
     tui: bool,
 
+    /// Don't capture the mouse in the TUI (for terminals that misreport it)
+    #[arg(long)]
+    no_mouse: bool,
+
     /// Override environment preset (earth, mars, moon, antarctica, exoplanet, desert, station)
     #[arg(long)]
     environment: Option<String>,
@@ -54,6 +69,47 @@ struct Args {
     /// List available environment presets
     #[arg(long)]
     list_environments: bool,
+
+    /// Resume a headless run from a state snapshot (.json, .msgpack or .bin)
+    #[arg(long)]
+    resume: Option<String>,
+
+    /// Seed for the simulation RNG (base seed in batch mode)
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Run the scenario this many times headless and aggregate the results
+    #[arg(long)]
+    runs: Option<usize>,
+
+    /// Log per-phase epoch timings (also enabled by -vvv)
+    #[arg(long)]
+    profile: bool,
+
+    /// Also write events and agent snapshots to this SQLite database (needs the `sqlite` feature)
+    #[arg(long)]
+    db: Option<String>,
+
+    /// Stream events live over WebSocket at ws://ADDR/stream (headless runs)
+    #[arg(long, value_name = "ADDR")]
+    stream: Option<String>,
+
+    /// Most clients following the live stream at once
+    #[arg(long, default_value_t = 16)]
+    stream_clients: usize,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Write a markdown report (report.md) on a finished run from its events and snapshots
+    Analyze {
+        /// Output directory of the run
+        run_dir: String,
+
+        /// Relationships to list in each ranking
+        #[arg(long, default_value_t = 5)]
+        top: usize,
+    },
 }
 
 #[tokio::main]
@@ -73,6 +129,13 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    if let Some(Command::Analyze { run_dir, top }) = &args.command {
+        let run_dir = Path::new(run_dir);
+        analysis::analyze(run_dir, *top)?;
+        println!("Report written to {}", run_dir.join("report.md").display());
+        return Ok(());
+    }
+
     // Initialize logging
     let filter = match args.verbose {
         0 => "terrarium=info",
@@ -102,6 +165,18 @@ async fn main() -> Result<()> {
         config.simulation.epochs = epochs;
     }
 
+    if let Some(seed) = args.seed {
+        config.simulation.seed = Some(seed);
+    }
+
+    if let Some(db) = &args.db {
+        config.simulation.database = Some(db.clone());
+    }
+
+    if args.profile || args.verbose >= 3 {
+        config.simulation.profile = true;
+    }
+
     // Override environment if specified
     if let Some(env_name) = &args.environment {
         if let Some(env_config) = EnvironmentConfig::from_name(env_name) {
@@ -119,19 +194,61 @@ async fn main() -> Result<()> {
         config.meta.name, config.agents.count, config.simulation.epochs
     );
 
-    if args.tui {
+    if let Some(runs) = args.runs {
+        // Run a batch of seeded runs into a fresh directory
+        let base_seed = config.simulation.seed.unwrap_or_else(rand::random);
+        let batch_dir = Path::new(&args.output).join(format!("batch_{}", chrono::Local::now().format("%Y%m%d_%H%M%S")));
+        let summary = batch::run_batch(&config, &batch_dir, runs, base_seed).await?;
+        info!(
+            "Batch of {} runs (base seed {}): final population {:.1} ± {:.1}, extinction rate {:.0}%",
+            runs,
+            base_seed,
+            summary.final_population.mean,
+            summary.final_population.stddev,
+            summary.extinction_rate * 100.0
+        );
+        info!("Output written to {}/", batch_dir.display());
+        info!("  - run_NNN/: One directory per run");
+        info!("  - summary.json: Aggregate statistics across runs");
+        return Ok(());
+    }
+
+    let network_timeline = config.simulation.network_timeline;
+    let tui_unavailable = args.tui.then(tui::unsupported_reason).flatten();
+    if let Some(reason) = tui_unavailable {
+        warn!("Can't start the TUI: {}; running headless", reason);
+    }
+
+    if args.tui && tui_unavailable.is_none() {
         // Run with TUI viewer
-        tui::run(config, &args.output).await?;
+        tui::run(config, &args.output, !args.no_mouse).await?;
     } else {
         // Run headless (batch mode)
-        let mut engine = Engine::new(config, &args.output)?;
+        let mut engine = match &args.resume {
+            Some(path) => {
+                info!("Resuming from snapshot {}", path);
+                Engine::from_snapshot(Path::new(path), config, &args.output)?
+            }
+            None => Engine::new(config, &args.output)?,
+        };
+        if let Some(address) = &args.stream {
+            let listener = tokio::net::TcpListener::bind(address).await?;
+            tokio::spawn(stream::serve(listener, engine.stream_events(), args.stream_clients));
+        }
         engine.run().await?;
     }
 
     info!("Output written to {}/", args.output);
     info!("  - events.jsonl: Full event log");
     info!("  - chronicle.md: Human-readable narrative");
-    info!("  - states/: Periodic state snapshots");
+    info!("  - states/: Periodic state snapshots and ASCII maps");
+    info!("  - provenance.json: Version, seed and resolved configuration of the run");
+    if network_timeline {
+        info!("  - network_timeline.jsonl: Social network at each snapshot");
+    }
+    if let Some(db) = &args.db {
+        info!("  - {}: Events and agent snapshots for SQL queries", db);
+    }
 
     Ok(())
 }
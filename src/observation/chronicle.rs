@@ -1,19 +1,108 @@
 use std::collections::HashMap;
 use std::fs::{self, File};
-use std::io::{BufWriter, Write};
-use std::path::PathBuf;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use super::events::{Event, EventType};
+use super::lives::{Lives, LivesFormat};
+use super::rumors::Rumors;
+use super::milestones::Milestones;
+use super::sink::EventSink;
 use crate::agent::Agent;
+use crate::config::RumorConfig;
+use crate::groups::GroupTracker;
+use crate::observer::{render_map_ascii, WorldView};
+use crate::trade::TradeState;
 use crate::world::World;
 
+/// How much detail the chronicle narrates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ChronicleVerbosity {
+    /// Only major events (deaths, conflict, trade)
+    Minimal,
+    /// Major events plus conceptions and births
+    #[default]
+    Normal,
+    /// Everything narratable, including courtship
+    Verbose,
+}
+
+/// Voice the chronicle is told in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ChronicleStyle {
+    /// "**Aric** attacked **Bria**!", a history written afterwards
+    #[default]
+    ThirdPersonPast,
+    /// "**Aric** attacks **Bria**!", a live feed
+    PresentTense,
+    /// "I attacked **Bria**!", one agent's journal of the events they took part in
+    FirstPersonPerProtagonist,
+}
+
+/// On-disk encoding for state snapshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotFormat {
+    /// Indented JSON, easiest to read by hand
+    #[default]
+    JsonPretty,
+    /// Compact JSON
+    Json,
+    /// MessagePack, compact and quick to write
+    #[serde(alias = "msgpack")]
+    MessagePack,
+    /// Bincode, the most compact
+    Bincode,
+}
+
+impl SnapshotFormat {
+    /// File extension for snapshots in this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SnapshotFormat::JsonPretty | SnapshotFormat::Json => "json",
+            SnapshotFormat::MessagePack => "msgpack",
+            SnapshotFormat::Bincode => "bin",
+        }
+    }
+
+    /// Infer the format from a snapshot's file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "json" => Some(SnapshotFormat::Json),
+            "msgpack" => Some(SnapshotFormat::MessagePack),
+            "bin" => Some(SnapshotFormat::Bincode),
+            _ => None,
+        }
+    }
+}
+
 /// Generates human-readable chronicle from events
 pub struct Chronicle {
     output_dir: PathBuf,
     events_file: BufWriter<File>,
     chronicle_file: BufWriter<File>,
     agent_names: HashMap<Uuid, String>,
+    verbosity: ChronicleVerbosity,
+    snapshot_format: SnapshotFormat,
+    milestones: Milestones,
+    lives: Lives,
+    lives_format: LivesFormat,
+    /// Gossip and the deeds it is judged against; None when not tracked
+    rumors: Option<Rumors>,
+    style: ChronicleStyle,
+    /// Name of the journal keeper in first-person style (None = the first agent)
+    protagonist_name: Option<String>,
+    protagonist: Option<Uuid>,
+    /// Further destinations for events and agent snapshots
+    sinks: Vec<Box<dyn EventSink>>,
+    /// Epoch of the latest epoch-start event, for dating arrivals
+    epoch: usize,
+    /// Hash of the configuration that produced the run
+    config_hash: Option<String>,
 }
 
 impl Chronicle {
@@ -32,14 +121,76 @@ impl Chronicle {
             events_file,
             chronicle_file,
             agent_names: HashMap::new(),
+            verbosity: ChronicleVerbosity::default(),
+            snapshot_format: SnapshotFormat::default(),
+            milestones: Milestones::new(),
+            lives: Lives::new(),
+            lives_format: LivesFormat::default(),
+            rumors: None,
+            style: ChronicleStyle::default(),
+            protagonist_name: None,
+            protagonist: None,
+            sinks: Vec::new(),
+            epoch: 0,
+            config_hash: None,
         })
     }
 
-    /// Register agent names for narrative generation
+    /// Note the hash of the configuration behind the run, for the footer
+    pub fn set_provenance(&mut self, config_hash: &str) {
+        self.config_hash = Some(config_hash.to_string());
+    }
+
+    /// Set how much detail the chronicle narrates
+    pub fn set_verbosity(&mut self, verbosity: ChronicleVerbosity) {
+        self.verbosity = verbosity;
+    }
+
+    /// Set how state snapshots are encoded
+    pub fn set_snapshot_format(&mut self, format: SnapshotFormat) {
+        self.snapshot_format = format;
+    }
+
+    /// Set where lifetime summaries are written at the end of the run
+    pub fn set_lives_format(&mut self, format: LivesFormat) {
+        self.lives_format = format;
+    }
+
+    /// Judge gossip against its subjects' deeds and write `rumors.json` at
+    /// the end of the run, if enabled
+    pub fn set_rumors(&mut self, config: RumorConfig) {
+        self.rumors = config.enabled.then(|| Rumors::new(config));
+    }
+
+    /// Set the voice of the narrative and, for first-person style, whose journal it is
+    pub fn set_style(&mut self, style: ChronicleStyle, protagonist: Option<String>) {
+        self.style = style;
+        self.protagonist_name = protagonist;
+    }
+
+    /// Also send events and agent snapshots to `sink`
+    #[cfg_attr(not(feature = "sqlite"), allow(dead_code))]
+    pub fn add_sink(&mut self, sink: Box<dyn EventSink>) {
+        self.sinks.push(sink);
+    }
+
+    /// Register agent names for narrative generation and start following their lives
     pub fn register_agents(&mut self, agents: &[Agent]) {
         for agent in agents {
             self.agent_names.insert(agent.id, agent.name().to_string());
         }
+        if self.protagonist.is_none() {
+            self.protagonist = agents
+                .iter()
+                .find(|a| self.protagonist_name.as_deref().is_none_or(|name| a.name().eq_ignore_ascii_case(name)))
+                .map(|a| a.id);
+        }
+        self.lives.register(agents, self.epoch);
+    }
+
+    /// Note agents' current skill levels and aspiration progress for their lifetime summaries
+    pub fn observe_agents(&mut self, agents: &[Agent]) {
+        self.lives.observe(agents);
     }
 
     /// Write the chronicle header
@@ -56,6 +207,7 @@ impl Chronicle {
             world.width, world.height, agents.len()
         )?;
         writeln!(self.chronicle_file)?;
+        self.milestones.set_population(world.epoch, agents.len());
         writeln!(self.chronicle_file, "## The Inhabitants")?;
         writeln!(self.chronicle_file)?;
 
@@ -84,6 +236,18 @@ impl Chronicle {
         // Write to events.jsonl
         let json = serde_json::to_string(event)?;
         writeln!(self.events_file, "{}", json)?;
+        for sink in &mut self.sinks {
+            sink.record(event)?;
+        }
+
+        self.milestones.record(event, &self.agent_names);
+        self.lives.record(event, &self.agent_names);
+        if let Some(rumors) = &mut self.rumors {
+            rumors.record(event);
+        }
+        if let EventType::EpochStart = event.event_type {
+            self.epoch = event.epoch;
+        }
 
         // Write significant events to chronicle
         if let Some(narrative) = self.event_to_narrative(event) {
@@ -94,89 +258,238 @@ impl Chronicle {
         Ok(())
     }
 
-    /// Flush both files
+    /// Flush both files and any extra sinks
     pub fn flush(&mut self) -> anyhow::Result<()> {
         self.events_file.flush()?;
         self.chronicle_file.flush()?;
+        for sink in &mut self.sinks {
+            sink.flush()?;
+        }
         Ok(())
     }
 
     /// Convert an event to narrative (returns None for insignificant events)
     fn event_to_narrative(&self, event: &Event) -> Option<String> {
-        let agent_name = event.agent.and_then(|id| self.agent_names.get(&id));
-        let target_name = event.target.and_then(|id| self.agent_names.get(&id));
+        if let Some(protagonist) = self.protagonist() {
+            let involved = [event.agent, event.target, event.data.ally, event.data.parent_a, event.data.parent_b];
+            if !matches!(event.event_type, EventType::EpochStart) && !involved.contains(&Some(protagonist)) {
+                return None;
+            }
+        }
+        let agent_name = self.subject(event.agent);
+        let target_name = self.object(event.target);
+        let tense = |past, present| self.tense(past, present);
 
         match &event.event_type {
             EventType::EpochStart => {
-                Some(format!("### Day {}\n", event.epoch))
+                match &event.data.description {
+                    Some(phase) if !phase.is_empty() => Some(format!("### Day {} ({})\n", event.epoch, phase)),
+                    _ => Some(format!("### Day {}\n", event.epoch)),
+                }
             }
             EventType::Spoke => {
                 let agent = agent_name?;
                 let target = target_name?;
                 let message = event.data.message.as_ref()?;
-                Some(format!("**{}** said to **{}**: \"{}\"", agent, target, message))
+                Some(format!("{} {} {}: \"{}\"", agent, tense("said to", "says to"), target, message))
             }
             EventType::Gave => {
                 let agent = agent_name?;
                 let target = target_name?;
-                let amount = event.data.amount?;
-                Some(format!("**{}** gave {} food to **{}**.", agent, amount, target))
+                let gift = match &event.data.description {
+                    Some(item) => item.clone(),
+                    None => format!("{} food", event.data.amount?),
+                };
+                Some(format!("{} {} {} to {}.", agent, tense("gave", "gives"), gift, target))
             }
             EventType::Attacked => {
                 let agent = agent_name?;
                 let target = target_name?;
-                Some(format!("**{}** attacked **{}**!", agent, target))
+                Some(format!("{} {} {}!", agent, tense("attacked", "attacks"), target))
+            }
+            EventType::CombatOutcome => {
+                let agent = agent_name?;
+                let target = target_name?;
+                let outcome = event.data.description.as_ref()?;
+                let outcome = match self.style {
+                    ChronicleStyle::PresentTense => present_tense(outcome),
+                    _ => outcome.clone(),
+                };
+                Some(format!("{} {} {}.", agent, outcome, target))
             }
             EventType::AllyIntervened => {
                 let target = target_name?;
-                let ally_name = event.data.ally.and_then(|id| self.agent_names.get(&id))?;
+                let ally_name = self.subject(event.data.ally)?;
                 let reduction = event.data.damage_reduction.unwrap_or(0.0) * 100.0;
-                Some(format!("**{}** defended **{}**, reducing damage by {:.0}%.", ally_name, target, reduction))
+                Some(format!(
+                    "{} {} {}, reducing damage by {:.0}%.",
+                    ally_name,
+                    tense("defended", "defends"),
+                    target,
+                    reduction
+                ))
             }
             EventType::Died => {
                 let agent = agent_name?;
                 let cause = event.data.description.as_deref().unwrap_or("unknown causes");
-                Some(format!("**{}** has died from {}.", agent, cause))
+                if self.is_protagonist(event.agent) {
+                    Some(format!("I am dying of {}.", cause))
+                } else {
+                    Some(format!("{} {} from {}.", agent, tense("has died", "dies"), cause))
+                }
+            }
+            EventType::Scavenged => {
+                let agent = agent_name?;
+                let dead = target_name?;
+                Some(format!("Driven by hunger, {} {} the remains of {}.", agent, tense("ate from", "eats from"), dead))
             }
             EventType::TradeProposed => {
                 let agent = agent_name?;
                 let target = target_name?;
                 let offering = event.data.trade_offering.as_deref().unwrap_or("items");
                 let requesting = event.data.trade_requesting.as_deref().unwrap_or("items");
-                Some(format!("**{}** proposed a trade to **{}**: offering {} for {}.", agent, target, offering, requesting))
+                Some(format!(
+                    "{} {} a trade to {}: offering {} for {}.",
+                    agent,
+                    tense("proposed", "proposes"),
+                    target,
+                    offering,
+                    requesting
+                ))
             }
             EventType::TradeAccepted => {
                 let agent = agent_name?;
                 let target = target_name?;
-                Some(format!("**{}** accepted a trade from **{}**.", agent, target))
+                Some(format!("{} {} a trade from {}.", agent, tense("accepted", "accepts"), target))
             }
             EventType::TradeDeclined => {
                 let agent = agent_name?;
                 let target = target_name?;
-                Some(format!("**{}** declined a trade from **{}**.", agent, target))
+                Some(format!("{} {} a trade from {}.", agent, tense("declined", "declines"), target))
             }
             EventType::TradeCountered => {
                 let agent = agent_name?;
                 let target = target_name?;
                 let offering = event.data.trade_offering.as_deref().unwrap_or("items");
                 let requesting = event.data.trade_requesting.as_deref().unwrap_or("items");
-                Some(format!("**{}** counter-offered to **{}**: offering {} for {}.", agent, target, offering, requesting))
+                Some(format!(
+                    "{} {} {}: offering {} for {}.",
+                    agent,
+                    tense("counter-offered to", "counter-offers to"),
+                    target,
+                    offering,
+                    requesting
+                ))
             }
             EventType::TradeExpired => {
-                let agent = agent_name?;
+                let agent = self.object(event.agent)?;
                 let target = target_name?;
-                Some(format!("A trade proposal from **{}** to **{}** expired.", agent, target))
+                Some(format!("A trade proposal from {} to {} {}.", agent, target, tense("expired", "expires")))
             }
             EventType::TradeReneged => {
                 let agent = agent_name?;
                 let target = target_name?;
                 let service = event.data.service_type.as_deref().unwrap_or("their promise");
-                Some(format!("**{}** reneged on {} to **{}**!", agent, service, target))
+                Some(format!("{} {} {} to {}!", agent, tense("reneged on", "reneges on"), service, target))
+            }
+            EventType::GoalSet => {
+                let agent = agent_name?;
+                let goal = event.data.description.as_ref()?;
+                let resolves = if self.is_protagonist(event.agent) { "resolve" } else { "resolves" };
+                Some(format!("{} {} to {}.", agent, resolves, goal))
+            }
+            EventType::WorldEventStruck => {
+                let headline = event.data.description.as_ref()?;
+                match event.data.members.as_ref().map_or(0, Vec::len) {
+                    0 => Some(format!("*{}.*", headline)),
+                    count => Some(format!("*{}; {} {} caught in it.*", headline, count, if count == 1 { "was" } else { "were" })),
+                }
+            }
+            EventType::StructureBuilt if self.verbosity >= ChronicleVerbosity::Normal => {
+                let agent = agent_name?;
+                let structure_type = event.data.description.as_ref()?;
+                Some(format!("{} {} building a {}.", agent, tense("finished", "finishes"), structure_type))
+            }
+            EventType::MigrationBegan => {
+                let count = event.data.members.as_ref()?.len();
+                let direction = event.data.description.as_ref()?;
+                Some(format!("Driven by hunger, {} set out together to the {}.", count, direction))
+            }
+            EventType::Courted if self.verbosity >= ChronicleVerbosity::Verbose => {
+                let agent = agent_name?;
+                let target = target_name?;
+                Some(format!("{} {} {}.", agent, tense("courted", "courts"), target))
+            }
+            EventType::MateFailed if self.verbosity >= ChronicleVerbosity::Verbose => {
+                let pair = self.pair(event.agent, event.target)?;
+                let reason = event.data.description.as_deref().unwrap_or("it came to nothing");
+                Some(format!("{} {}, but {}.", pair, tense("tried to mate", "try to mate"), reason))
+            }
+            EventType::Conceived if self.verbosity >= ChronicleVerbosity::Normal => {
+                let parents = self.pair(event.data.parent_a, event.data.parent_b)?;
+                Some(format!("{} are expecting a child.", parents))
+            }
+            EventType::BirthOccurred if self.verbosity >= ChronicleVerbosity::Normal => {
+                let parent_a = self.object(event.data.parent_a)?;
+                let parent_b = self.object(event.data.parent_b)?;
+                let parents = if self.is_protagonist(event.data.parent_a) {
+                    format!("{} and {}", parent_b, parent_a)
+                } else {
+                    format!("{} and {}", parent_a, parent_b)
+                };
+                let child = event.data.child_name.as_ref()?;
+                let resemblance = match event.data.about {
+                    Some(parent) => format!(", and {} after {}", tense("took", "takes"), self.object(Some(parent))?),
+                    None => String::new(),
+                };
+                Some(format!("**{}** {} to {}{}.", child, tense("was born", "is born"), parents, resemblance))
             }
             _ => None, // Don't narrate routine events
         }
     }
 
+    /// The agent whose journal this is, in first-person style
+    fn protagonist(&self) -> Option<Uuid> {
+        match self.style {
+            ChronicleStyle::FirstPersonPerProtagonist => self.protagonist,
+            _ => None,
+        }
+    }
+
+    fn is_protagonist(&self, id: Option<Uuid>) -> bool {
+        id.is_some() && id == self.protagonist()
+    }
+
+    /// An agent doing something: "**Aric**", or "I" for the protagonist
+    fn subject(&self, id: Option<Uuid>) -> Option<String> {
+        if self.is_protagonist(id) {
+            return Some("I".to_string());
+        }
+        id.and_then(|id| self.agent_names.get(&id)).map(|name| format!("**{}**", name))
+    }
+
+    /// An agent something is done to: "**Aric**", or "me" for the protagonist
+    fn object(&self, id: Option<Uuid>) -> Option<String> {
+        if self.is_protagonist(id) {
+            return Some("me".to_string());
+        }
+        self.subject(id)
+    }
+
+    /// Two agents acting together, the protagonist named last ("**Bria** and I")
+    fn pair(&self, a: Option<Uuid>, b: Option<Uuid>) -> Option<String> {
+        let (a, b) = if self.is_protagonist(a) { (b, a) } else { (a, b) };
+        Some(format!("{} and {}", self.subject(a)?, self.subject(b)?))
+    }
+
+    /// The past or present form of a verb phrase, as the style calls for
+    fn tense<'a>(&self, past: &'a str, present: &'a str) -> &'a str {
+        match self.style {
+            ChronicleStyle::PresentTense => present,
+            _ => past,
+        }
+    }
+
     /// Write the chronicle footer
     pub fn write_footer(&mut self, world: &World, agents: &[Agent]) -> anyhow::Result<()> {
         writeln!(self.chronicle_file)?;
@@ -209,6 +522,16 @@ impl Chronicle {
             }
         }
 
+        let milestones = self.milestones.milestones();
+        if !milestones.is_empty() {
+            writeln!(self.chronicle_file)?;
+            writeln!(self.chronicle_file, "## Milestones")?;
+            writeln!(self.chronicle_file)?;
+            for milestone in &milestones {
+                writeln!(self.chronicle_file, "- {} on day {}.", milestone.description, milestone.epoch)?;
+            }
+        }
+
         writeln!(self.chronicle_file)?;
         writeln!(self.chronicle_file, "---")?;
         writeln!(self.chronicle_file)?;
@@ -217,28 +540,181 @@ impl Chronicle {
             "*Chronicle generated by Terrarium v{}*",
             env!("CARGO_PKG_VERSION")
         )?;
+        if let Some(hash) = &self.config_hash {
+            writeln!(self.chronicle_file)?;
+            writeln!(self.chronicle_file, "*Configuration {} (see provenance.json)*", hash)?;
+        }
 
         self.flush()?;
+        self.write_lives()?;
+        if let Some(rumors) = &self.rumors {
+            rumors.write(&self.output_dir)?;
+        }
+        Ok(())
+    }
+
+    /// Write every agent's lifetime summary in the configured format
+    fn write_lives(&self) -> anyhow::Result<()> {
+        match self.lives_format {
+            LivesFormat::Off => {}
+            LivesFormat::Markdown => fs::write(self.output_dir.join("lives.md"), self.lives.to_markdown())?,
+            LivesFormat::Json => fs::write(
+                self.output_dir.join("lives.json"),
+                serde_json::to_string_pretty(&self.lives.summaries())?,
+            )?,
+        }
         Ok(())
     }
 
     /// Save a state snapshot
-    pub fn save_snapshot(&self, epoch: usize, world: &World, agents: &[Agent]) -> anyhow::Result<()> {
+    pub fn save_snapshot(&mut self, snapshot: &Snapshot) -> anyhow::Result<()> {
+        let (epoch, agents) = (snapshot.epoch, &snapshot.agents);
+        for sink in &mut self.sinks {
+            sink.snapshot_agents(epoch, agents)?;
+        }
+
         let states_dir = self.output_dir.join("states");
         fs::create_dir_all(&states_dir)?;
 
-        let snapshot = Snapshot { epoch, world: world.clone(), agents: agents.to_vec() };
-        let path = states_dir.join(format!("epoch_{:04}.json", epoch));
-        let file = File::create(path)?;
-        serde_json::to_writer_pretty(file, &snapshot)?;
+        let glyph_of = |id| agents.iter().find(|a| a.id == id).map(|a| a.identity.phenotype.glyph);
+        let map = render_map_ascii(&WorldView::from_world(&snapshot.world, agents), glyph_of);
+        fs::write(states_dir.join(format!("map_{:04}.txt", epoch)), map)?;
+
+        let path = states_dir.join(format!("epoch_{:04}.{}", epoch, self.snapshot_format.extension()));
+        snapshot.save(&path, self.snapshot_format)
+    }
+}
+
+/// "drove off" -> "drives off", for combat outcomes recorded in the past tense
+fn present_tense(phrase: &str) -> String {
+    let (verb, rest) = phrase.split_once(' ').unwrap_or((phrase, ""));
+    let verb = match verb {
+        "drove" => "drives",
+        "took" => "takes",
+        "subdued" => "subdues",
+        other => other,
+    };
+    format!("{} {}", verb, rest).trim_end().to_string()
+}
+
+/// Full simulation state at an epoch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub epoch: usize,
+    pub world: World,
+    pub agents: Vec<Agent>,
+    /// Groups and rivalries, so social structure survives a resume
+    #[serde(default)]
+    pub groups: GroupTracker,
+    /// Open trade offers and service debts
+    #[serde(default)]
+    pub trade: TradeState,
+}
 
+impl Snapshot {
+    /// Write the snapshot to a file in the given format
+    pub fn save(&self, path: &Path, format: SnapshotFormat) -> anyhow::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        match format {
+            SnapshotFormat::JsonPretty => serde_json::to_writer_pretty(&mut file, self)?,
+            SnapshotFormat::Json => serde_json::to_writer(&mut file, self)?,
+            SnapshotFormat::MessagePack => rmp_serde::encode::write_named(&mut file, self)?,
+            SnapshotFormat::Bincode => bincode::serialize_into(&mut file, self)?,
+        }
+        file.flush()?;
         Ok(())
     }
+
+    /// Load a snapshot, picking the decoder from the file extension
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let format = SnapshotFormat::from_path(path)
+            .ok_or_else(|| anyhow::anyhow!("unrecognised snapshot file: {}", path.display()))?;
+        let file = BufReader::new(File::open(path)?);
+        let snapshot = match format {
+            SnapshotFormat::JsonPretty | SnapshotFormat::Json => serde_json::from_reader(file)?,
+            SnapshotFormat::MessagePack => rmp_serde::from_read(file)?,
+            SnapshotFormat::Bincode => bincode::deserialize_from(file)?,
+        };
+        Ok(snapshot)
+    }
 }
 
-#[derive(serde::Serialize)]
-struct Snapshot {
-    epoch: usize,
-    world: World,
-    agents: Vec<Agent>,
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_chronicle() -> Chronicle {
+        let dir = std::env::temp_dir().join(format!("terrarium-chronicle-{}", Uuid::new_v4()));
+        Chronicle::new(dir.to_str().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_conception_narrative() {
+        let mut chronicle = test_chronicle();
+        let aric = Uuid::new_v4();
+        let bria = Uuid::new_v4();
+        chronicle.agent_names.insert(aric, "Aric".to_string());
+        chronicle.agent_names.insert(bria, "Bria".to_string());
+
+        let event = Event::conceived(12, aric, bria);
+        assert_eq!(
+            chronicle.event_to_narrative(&event).as_deref(),
+            Some("**Aric** and **Bria** are expecting a child.")
+        );
+
+        // Courtship is only narrated at verbose level
+        let courted = Event::courted(12, aric, bria, 0.4);
+        assert!(chronicle.event_to_narrative(&courted).is_none());
+
+        chronicle.set_verbosity(ChronicleVerbosity::Minimal);
+        assert!(chronicle.event_to_narrative(&event).is_none());
+    }
+
+    #[test]
+    fn test_present_tense_and_first_person_attack() {
+        let mut chronicle = test_chronicle();
+        let aric = Uuid::new_v4();
+        let bria = Uuid::new_v4();
+        chronicle.agent_names.insert(aric, "Aric".to_string());
+        chronicle.agent_names.insert(bria, "Bria".to_string());
+        let event = Event::attacked(30, aric, bria, 0.2);
+
+        assert_eq!(chronicle.event_to_narrative(&event).as_deref(), Some("**Aric** attacked **Bria**!"));
+
+        chronicle.set_style(ChronicleStyle::PresentTense, None);
+        assert_eq!(chronicle.event_to_narrative(&event).as_deref(), Some("**Aric** attacks **Bria**!"));
+
+        chronicle.set_style(ChronicleStyle::FirstPersonPerProtagonist, None);
+        chronicle.protagonist = Some(bria);
+        assert_eq!(chronicle.event_to_narrative(&event).as_deref(), Some("**Aric** attacked me!"));
+        let elsewhere = Event::attacked(30, aric, Uuid::new_v4(), 0.2);
+        assert!(chronicle.event_to_narrative(&elsewhere).is_none());
+    }
+
+    #[test]
+    fn test_message_pack_snapshot_round_trip() {
+        let mut chronicle = test_chronicle();
+        chronicle.set_snapshot_format(SnapshotFormat::MessagePack);
+        let world = World::new(&crate::world::WorldConfig {
+            width: 5,
+            height: 5,
+            fertile_fraction: 0.5,
+            initial_food_per_fertile: 10,
+            food_regen_rate: 0.1,
+            forest_fraction: 0.0,
+            desert_fraction: 0.0,
+            mountain_fraction: 0.0,
+            water_fraction: 0.0,
+            terrain_yields: crate::world::TerrainYields::default(),
+        });
+        let agents = vec![Agent::new("Aric".to_string(), 1, 2, 5), Agent::new("Bria".to_string(), 3, 4, 5)];
+
+        let original =
+            Snapshot { epoch: 7, world, agents, groups: GroupTracker::default(), trade: TradeState::default() };
+        chronicle.save_snapshot(&original).unwrap();
+        let path = chronicle.output_dir.join("states").join("epoch_0007.msgpack");
+        let loaded = Snapshot::load(&path).unwrap();
+
+        assert_eq!(serde_json::to_value(&loaded).unwrap(), serde_json::to_value(&original).unwrap());
+    }
 }
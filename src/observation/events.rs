@@ -21,6 +21,7 @@ pub enum EventType {
     Rested,
     HealthChanged,
     Died,
+    Scavenged,
 
     // Social
     Spoke,
@@ -30,6 +31,7 @@ pub enum EventType {
     // Conflict
     Attacked,
     AllyIntervened,
+    CombatOutcome,
 
     // Groups
     GroupFormed,
@@ -42,8 +44,18 @@ pub enum EventType {
     RivalryChanged,
     RivalryEnded,
 
+    // Migration
+    MigrationBegan,
+
+    // World events
+    WorldEventStruck,
+
+    // Scenario scripting
+    Scripted,
+
     // Reproduction
     Courted,
+    MateFailed,
     Conceived,
     BirthOccurred,
 
@@ -66,8 +78,10 @@ pub enum EventType {
     TerritoryLost,
 
     // Structures
+    StructureBuilt,
     FarmProduced,
     StructureDestroyed,
+    StructureDismantled,
 
     // Trade
     TradeProposed,
@@ -79,6 +93,9 @@ pub enum EventType {
     TradeReneged,
     ServiceFulfilled,
 
+    // Intentions
+    GoalSet,
+
     // Meta
     EpochStart,
     EpochEnd,
@@ -98,13 +115,16 @@ pub struct EventData {
     pub damage: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
-    /// Third party in gossip events
+    /// Third party in gossip events; the parent a newborn takes after in birth events
     #[serde(skip_serializing_if = "Option::is_none")]
     pub about: Option<Uuid>,
+    /// Sentiment passed on about the third party in gossip events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sentiment: Option<f64>,
     /// Group name for group events
     #[serde(skip_serializing_if = "Option::is_none")]
     pub group_name: Option<String>,
-    /// Member IDs for group events
+    /// Member IDs for group events (for membership changes, those who joined)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub members: Option<Vec<Uuid>>,
     /// New leader for leadership change events
@@ -188,13 +208,16 @@ pub struct EventData {
 }
 
 impl Event {
-    pub fn epoch_start(epoch: usize) -> Self {
+    pub fn epoch_start(epoch: usize, phase: &str) -> Self {
         Self {
             epoch,
             event_type: EventType::EpochStart,
             agent: None,
             target: None,
-            data: EventData::empty(),
+            data: EventData {
+                description: Some(phase.to_string()),
+                ..EventData::empty()
+            },
         }
     }
 
@@ -281,6 +304,19 @@ impl Event {
         }
     }
 
+    pub fn gave_item(epoch: usize, agent: Uuid, target: Uuid, item: &str) -> Self {
+        Self {
+            epoch,
+            event_type: EventType::Gave,
+            agent: Some(agent),
+            target: Some(target),
+            data: EventData {
+                description: Some(item.to_string()),
+                ..EventData::empty()
+            },
+        }
+    }
+
     pub fn attacked(epoch: usize, agent: Uuid, target: Uuid, damage: f64) -> Self {
         Self {
             epoch,
@@ -294,6 +330,19 @@ impl Event {
         }
     }
 
+    pub fn combat_outcome(epoch: usize, attacker: Uuid, victim: Uuid, outcome: &str) -> Self {
+        Self {
+            epoch,
+            event_type: EventType::CombatOutcome,
+            agent: Some(attacker),
+            target: Some(victim),
+            data: EventData {
+                description: Some(outcome.to_string()),
+                ..EventData::empty()
+            },
+        }
+    }
+
     pub fn ally_intervened(
         epoch: usize,
         attacker: Uuid,
@@ -327,7 +376,20 @@ impl Event {
         }
     }
 
-    pub fn gossiped(epoch: usize, agent: Uuid, target: Uuid, about: Uuid, sentiment: &str) -> Self {
+    pub fn scavenged(epoch: usize, agent: Uuid, dead: Uuid, food_gained: u32) -> Self {
+        Self {
+            epoch,
+            event_type: EventType::Scavenged,
+            agent: Some(agent),
+            target: Some(dead),
+            data: EventData {
+                amount: Some(food_gained),
+                ..EventData::empty()
+            },
+        }
+    }
+
+    pub fn gossiped(epoch: usize, agent: Uuid, target: Uuid, about: Uuid, tone: &str, sentiment: f64) -> Self {
         Self {
             epoch,
             event_type: EventType::Gossiped,
@@ -335,7 +397,8 @@ impl Event {
             target: Some(target),
             data: EventData {
                 about: Some(about),
-                description: Some(sentiment.to_string()),
+                description: Some(tone.to_string()),
+                sentiment: Some(sentiment),
                 ..EventData::empty()
             },
         }
@@ -355,6 +418,51 @@ impl Event {
         }
     }
 
+    /// A plague, disaster, famine or time of plenty takes effect on `affected`
+    pub fn world_event_struck(epoch: usize, headline: &str, affected: Vec<Uuid>) -> Self {
+        Self {
+            epoch,
+            event_type: EventType::WorldEventStruck,
+            agent: None,
+            target: None,
+            data: EventData {
+                description: Some(headline.to_string()),
+                members: Some(affected),
+                ..EventData::empty()
+            },
+        }
+    }
+
+    /// A scenario's scripted action took place; `agent` is whoever it brought in
+    pub fn scripted(epoch: usize, description: &str, agent: Option<Uuid>) -> Self {
+        Self {
+            epoch,
+            event_type: EventType::Scripted,
+            agent,
+            target: None,
+            data: EventData {
+                description: Some(description.to_string()),
+                ..EventData::empty()
+            },
+        }
+    }
+
+    /// A starving cluster sets out together; `to` is the remembered food they make for
+    pub fn migration_began(epoch: usize, members: Vec<Uuid>, direction: &str, to: Option<(usize, usize)>) -> Self {
+        Self {
+            epoch,
+            event_type: EventType::MigrationBegan,
+            agent: None,
+            target: None,
+            data: EventData {
+                to,
+                description: Some(direction.to_string()),
+                members: Some(members),
+                ..EventData::empty()
+            },
+        }
+    }
+
     pub fn group_dissolved(epoch: usize, group_name: &str, members: Vec<Uuid>) -> Self {
         Self {
             epoch,
@@ -369,7 +477,7 @@ impl Event {
         }
     }
 
-    pub fn group_changed(epoch: usize, group_name: &str, description: &str) -> Self {
+    pub fn group_changed(epoch: usize, group_name: &str, description: &str, joined: Vec<Uuid>) -> Self {
         Self {
             epoch,
             event_type: EventType::GroupChanged,
@@ -378,6 +486,7 @@ impl Event {
             data: EventData {
                 group_name: Some(group_name.to_string()),
                 description: Some(description.to_string()),
+                members: (!joined.is_empty()).then_some(joined),
                 ..EventData::empty()
             },
         }
@@ -476,6 +585,20 @@ impl Event {
         }
     }
 
+    /// A mutually agreed mating that a precondition blocked
+    pub fn mate_failed(epoch: usize, agent: Uuid, target: Uuid, reason: &str) -> Self {
+        Self {
+            epoch,
+            event_type: EventType::MateFailed,
+            agent: Some(agent),
+            target: Some(target),
+            data: EventData {
+                description: Some(reason.to_string()),
+                ..EventData::empty()
+            },
+        }
+    }
+
     pub fn conceived(epoch: usize, parent_a: Uuid, parent_b: Uuid) -> Self {
         Self {
             epoch,
@@ -708,6 +831,21 @@ impl Event {
         }
     }
 
+    pub fn structure_built(epoch: usize, builder: Uuid, x: usize, y: usize, structure_type: &str) -> Self {
+        Self {
+            epoch,
+            event_type: EventType::StructureBuilt,
+            agent: Some(builder),
+            target: None,
+            data: EventData {
+                description: Some(structure_type.to_string()),
+                territory_x: Some(x),
+                territory_y: Some(y),
+                ..EventData::empty()
+            },
+        }
+    }
+
     pub fn structure_destroyed(epoch: usize, owner: Uuid, x: usize, y: usize, structure_type: &str) -> Self {
         Self {
             epoch,
@@ -723,6 +861,21 @@ impl Event {
         }
     }
 
+    pub fn structure_dismantled(epoch: usize, owner: Uuid, x: usize, y: usize, structure_type: &str) -> Self {
+        Self {
+            epoch,
+            event_type: EventType::StructureDismantled,
+            agent: Some(owner),
+            target: None,
+            data: EventData {
+                description: Some(structure_type.to_string()),
+                territory_x: Some(x),
+                territory_y: Some(y),
+                ..EventData::empty()
+            },
+        }
+    }
+
     // Trade events
 
     pub fn trade_proposed(
@@ -878,6 +1031,19 @@ impl Event {
             },
         }
     }
+
+    pub fn goal_set(epoch: usize, agent: Uuid, goal: &str) -> Self {
+        Self {
+            epoch,
+            event_type: EventType::GoalSet,
+            agent: Some(agent),
+            target: None,
+            data: EventData {
+                description: Some(goal.to_string()),
+                ..EventData::empty()
+            },
+        }
+    }
 }
 
 impl EventData {
@@ -890,6 +1056,7 @@ impl EventData {
             damage: None,
             description: None,
             about: None,
+            sentiment: None,
             group_name: None,
             members: None,
             new_leader: None,
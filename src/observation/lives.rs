@@ -0,0 +1,280 @@
+//! Per-agent lifetime summaries, assembled from the event stream for post-run
+//! study of individual arcs.
+
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use uuid::Uuid;
+
+use super::events::{Event, EventType};
+use crate::agent::Agent;
+
+/// Most notable events kept per life
+const MAX_NOTABLE: usize = 12;
+
+/// Where per-agent lifetime summaries are written at the end of a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LivesFormat {
+    /// Don't write lifetime summaries
+    Off,
+    /// `lives.md`, one section per agent
+    #[default]
+    Markdown,
+    /// `lives.json`, for analysis scripts
+    Json,
+}
+
+/// One agent's life from birth (or arrival) to death (or the end of the run)
+#[derive(Debug, Clone, Serialize)]
+pub struct LifeSummary {
+    pub id: Uuid,
+    pub name: String,
+    pub generation: usize,
+    /// Epoch the agent was born or arrived
+    pub born: usize,
+    pub died: Option<usize>,
+    pub cause_of_death: Option<String>,
+    /// Names of the agent's children
+    pub children: Vec<String>,
+    /// Highest level reached in each skill
+    pub peak_skills: BTreeMap<String, f64>,
+    /// Groups the agent founded or joined, in order
+    pub groups: Vec<String>,
+    /// What the agent wanted from life, e.g. "to become powerful and influential"
+    pub aspiration: String,
+    /// Furthest the agent came toward it (0.0 to 1.0), if it is measured
+    pub aspiration_reached: Option<f64>,
+    /// "Day 12: crafted a stone axe", ...
+    pub notable: Vec<String>,
+}
+
+impl LifeSummary {
+    fn new(agent: &Agent, epoch: usize) -> Self {
+        Self {
+            id: agent.id,
+            name: agent.name().to_string(),
+            generation: agent.reproduction.family.generation,
+            born: epoch,
+            died: None,
+            cause_of_death: None,
+            children: Vec::new(),
+            peak_skills: BTreeMap::new(),
+            groups: Vec::new(),
+            aspiration: agent.identity.aspiration.describe().to_string(),
+            aspiration_reached: None,
+            notable: Vec::new(),
+        }
+    }
+
+    fn note(&mut self, epoch: usize, what: String) {
+        if self.notable.len() < MAX_NOTABLE {
+            self.notable.push(format!("Day {}: {}", epoch, what));
+        }
+    }
+
+    fn join(&mut self, group: &str) {
+        if !self.groups.iter().any(|g| g == group) {
+            self.groups.push(group.to_string());
+        }
+    }
+}
+
+/// Follows every agent who ever lived through the run
+#[derive(Debug, Default)]
+pub struct Lives {
+    lives: HashMap<Uuid, LifeSummary>,
+    /// Agents in the order they appeared
+    order: Vec<Uuid>,
+}
+
+impl Lives {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start following agents who were born or arrived at `epoch`
+    pub fn register(&mut self, agents: &[Agent], epoch: usize) {
+        for agent in agents {
+            if let Entry::Vacant(entry) = self.lives.entry(agent.id) {
+                entry.insert(LifeSummary::new(agent, epoch));
+                self.order.push(agent.id);
+            }
+        }
+        self.observe(agents);
+    }
+
+    /// Raise peak skill levels and aspiration progress to where the agents stand now
+    pub fn observe(&mut self, agents: &[Agent]) {
+        for agent in agents {
+            let Some(life) = self.lives.get_mut(&agent.id) else {
+                continue;
+            };
+            for (skill, level) in &agent.skills.levels {
+                let peak = life.peak_skills.entry(skill.clone()).or_insert(0.0);
+                *peak = peak.max(*level);
+            }
+            if agent.legacy.progress.is_some() {
+                life.aspiration_reached = Some(agent.legacy.peak_progress);
+            }
+        }
+    }
+
+    /// Note an event in the lives of those it involves
+    pub fn record(&mut self, event: &Event, names: &HashMap<Uuid, String>) {
+        let name = |id: Option<Uuid>| {
+            id.and_then(|id| names.get(&id))
+                .cloned()
+                .unwrap_or_else(|| "someone".to_string())
+        };
+        let epoch = event.epoch;
+
+        match event.event_type {
+            EventType::Died => {
+                if let Some(life) = event.agent.and_then(|id| self.lives.get_mut(&id)) {
+                    life.died = Some(epoch);
+                    life.cause_of_death = event.data.description.clone();
+                }
+            }
+            EventType::BirthOccurred => {
+                let child = event.data.child_name.clone().unwrap_or_else(|| "a child".to_string());
+                for parent in [event.data.parent_a, event.data.parent_b].into_iter().flatten() {
+                    if let Some(life) = self.lives.get_mut(&parent) {
+                        life.children.push(child.clone());
+                        life.note(epoch, format!("had a child, {}", child));
+                    }
+                }
+            }
+            EventType::GroupFormed | EventType::GroupChanged => {
+                let group = event.data.group_name.as_deref().unwrap_or("a band");
+                for member in event.data.members.iter().flatten() {
+                    if let Some(life) = self.lives.get_mut(member) {
+                        life.join(group);
+                    }
+                }
+            }
+            EventType::Crafted => {
+                if let Some(life) = event.agent.and_then(|id| self.lives.get_mut(&id)) {
+                    let tool = event.data.tool_name.as_deref().unwrap_or("a tool");
+                    life.note(epoch, format!("crafted a {}", tool));
+                }
+            }
+            EventType::SkillTaught => {
+                let skill = event.data.skill_name.as_deref().unwrap_or("a skill");
+                let (teacher, student) = (name(event.agent), name(event.target));
+                if let Some(life) = event.agent.and_then(|id| self.lives.get_mut(&id)) {
+                    life.note(epoch, format!("taught {} to {}", skill, student));
+                }
+                if let Some(life) = event.target.and_then(|id| self.lives.get_mut(&id)) {
+                    life.note(epoch, format!("learned {} from {}", skill, teacher));
+                }
+            }
+            EventType::Attacked => {
+                let (attacker, victim) = (name(event.agent), name(event.target));
+                if let Some(life) = event.agent.and_then(|id| self.lives.get_mut(&id)) {
+                    life.note(epoch, format!("attacked {}", victim));
+                }
+                if let Some(life) = event.target.and_then(|id| self.lives.get_mut(&id)) {
+                    life.note(epoch, format!("was attacked by {}", attacker));
+                }
+            }
+            EventType::TerritoryMarked => {
+                if let Some(life) = event.agent.and_then(|id| self.lives.get_mut(&id)) {
+                    let x = event.data.territory_x.unwrap_or(0);
+                    let y = event.data.territory_y.unwrap_or(0);
+                    life.note(epoch, format!("claimed territory at ({}, {})", x, y));
+                }
+            }
+            EventType::TradeAccepted => {
+                let (accepter, proposer) = (name(event.agent), name(event.target));
+                if let Some(life) = event.agent.and_then(|id| self.lives.get_mut(&id)) {
+                    life.note(epoch, format!("traded with {}", proposer));
+                }
+                if let Some(life) = event.target.and_then(|id| self.lives.get_mut(&id)) {
+                    life.note(epoch, format!("traded with {}", accepter));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Every life followed, in the order the agents appeared
+    pub fn summaries(&self) -> Vec<&LifeSummary> {
+        self.order.iter().filter_map(|id| self.lives.get(id)).collect()
+    }
+
+    /// Lifetime summaries as a markdown document
+    pub fn to_markdown(&self) -> String {
+        let mut doc = "# Lives\n".to_string();
+        for life in self.summaries() {
+            let _ = writeln!(doc, "\n## {}\n", life.name);
+            let span = match (life.died, &life.cause_of_death) {
+                (Some(died), Some(cause)) => format!("died on day {} ({})", died, cause),
+                (Some(died), None) => format!("died on day {}", died),
+                (None, _) => "still alive at the end".to_string(),
+            };
+            let _ = writeln!(doc, "- Generation {}, from day {}; {}", life.generation, life.born, span);
+            if !life.children.is_empty() {
+                let _ = writeln!(doc, "- Children ({}): {}", life.children.len(), life.children.join(", "));
+            }
+            if !life.peak_skills.is_empty() {
+                let skills: Vec<String> = life
+                    .peak_skills
+                    .iter()
+                    .map(|(skill, level)| format!("{} {:.0}%", skill, level * 100.0))
+                    .collect();
+                let _ = writeln!(doc, "- Peak skills: {}", skills.join(", "));
+            }
+            if !life.groups.is_empty() {
+                let _ = writeln!(doc, "- Groups: {}", life.groups.join(", "));
+            }
+            match life.aspiration_reached {
+                Some(reached) => {
+                    let _ = writeln!(doc, "- Aspired {} (came {:.0}% of the way)", life.aspiration, reached * 100.0);
+                }
+                None => {
+                    let _ = writeln!(doc, "- Aspired {}", life.aspiration);
+                }
+            }
+            for note in &life.notable {
+                let _ = writeln!(doc, "- {}", note);
+            }
+        }
+        doc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parent_and_group_member_life_summary() {
+        let mut aric = Agent::new("Aric".to_string(), 0, 0, 5);
+        let bria = Agent::new("Bria".to_string(), 1, 0, 5);
+        aric.skills.levels.insert("hunting".to_string(), 0.4);
+        let names = HashMap::from([(aric.id, "Aric".to_string()), (bria.id, "Bria".to_string())]);
+
+        let mut lives = Lives::new();
+        lives.register(&[aric.clone(), bria.clone()], 0);
+        aric.skills.levels.insert("hunting".to_string(), 0.6);
+        lives.observe(std::slice::from_ref(&aric));
+
+        let cara = Uuid::new_v4();
+        lives.record(&Event::birth_occurred(12, aric.id, bria.id, cara, "Cara"), &names);
+        lives.record(&Event::group_formed(20, "The Kin", vec![bria.id]), &names);
+        lives.record(&Event::group_changed(25, "The Kin", "Aric joined", vec![aric.id]), &names);
+
+        let summaries = lives.summaries();
+        let life = summaries.iter().find(|l| l.id == aric.id).unwrap();
+        assert_eq!(life.children, vec!["Cara".to_string()]);
+        assert_eq!(life.groups, vec!["The Kin".to_string()]);
+        assert_eq!(life.peak_skills["hunting"], 0.6);
+        assert!(life.notable.iter().any(|n| n == "Day 12: had a child, Cara"));
+
+        let doc = lives.to_markdown();
+        assert!(doc.contains("- Children (1): Cara"));
+        assert!(doc.contains("- Groups: The Kin"));
+    }
+}
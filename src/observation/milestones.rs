@@ -0,0 +1,130 @@
+//! Civilization-level milestones: firsts and extremes picked out of the event stream.
+
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use super::events::{Event, EventType};
+
+/// A notable moment in the colony's history
+#[derive(Debug, Clone, PartialEq)]
+pub struct Milestone {
+    pub epoch: usize,
+    pub description: String,
+}
+
+/// Watches events for firsts (first birth, first alliance, ...) and extremes
+/// (population peak)
+#[derive(Debug, Default)]
+pub struct Milestones {
+    /// Firsts in the order they happened
+    firsts: Vec<Milestone>,
+    /// Kinds of first already recorded
+    seen: HashSet<&'static str>,
+    /// Current living population
+    population: usize,
+    /// Highest population so far and when it was reached
+    peak: Option<(usize, usize)>,
+}
+
+impl Milestones {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the starting population
+    pub fn set_population(&mut self, epoch: usize, population: usize) {
+        self.population = population;
+        self.update_peak(epoch);
+    }
+
+    /// Note an event, recording it if it is the first of its kind
+    pub fn record(&mut self, event: &Event, names: &HashMap<Uuid, String>) {
+        let name = |id: Option<Uuid>| {
+            id.and_then(|id| names.get(&id))
+                .map(|n| format!("**{}**", n))
+                .unwrap_or_else(|| "someone".to_string())
+        };
+
+        let first = match event.event_type {
+            EventType::BirthOccurred => {
+                self.population += 1;
+                self.update_peak(event.epoch);
+                let child = event.data.child_name.as_deref().unwrap_or("a child");
+                Some(("birth", format!("The first child, **{}**, was born", child)))
+            }
+            EventType::Died => {
+                self.population = self.population.saturating_sub(1);
+                Some(("death", format!("{} was the first to die", name(event.agent))))
+            }
+            EventType::GroupFormed => {
+                let group = event.data.group_name.as_deref().unwrap_or("a band");
+                Some(("group", format!("The first alliance, {}, formed", group)))
+            }
+            EventType::TradeAccepted => Some(("trade", "The first trade was struck".to_string())),
+            EventType::Crafted => Some(("craft", format!("{} crafted the first tool", name(event.agent)))),
+            EventType::Attacked => Some(("attack", format!("{} struck the first blow", name(event.agent)))),
+            EventType::TerritoryMarked => {
+                Some(("territory", format!("{} claimed the first territory", name(event.agent))))
+            }
+            EventType::SkillTaught => Some(("teaching", format!("{} taught the first lesson", name(event.agent)))),
+            EventType::MigrationBegan => {
+                let count = event.data.members.as_ref().map_or(0, Vec::len);
+                let direction = event.data.description.as_deref().unwrap_or("away");
+                Some(("migration", format!("{} starving agents set out {} in the first exodus", count, direction)))
+            }
+            _ => None,
+        };
+
+        if let Some((_, description)) = first.filter(|(kind, _)| self.seen.insert(kind)) {
+            self.firsts.push(Milestone {
+                epoch: event.epoch,
+                description,
+            });
+        }
+    }
+
+    fn update_peak(&mut self, epoch: usize) {
+        if self.peak.is_none_or(|(peak, _)| self.population > peak) {
+            self.peak = Some((self.population, epoch));
+        }
+    }
+
+    /// All milestones, including the population peak, in chronological order
+    pub fn milestones(&self) -> Vec<Milestone> {
+        let mut milestones = self.firsts.clone();
+        if let Some((peak, epoch)) = self.peak {
+            milestones.push(Milestone {
+                epoch,
+                description: format!("Population peaked at {}", peak),
+            });
+        }
+        milestones.sort_by_key(|m| m.epoch);
+        milestones
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_birth_and_group_formation_milestones() {
+        let mut milestones = Milestones::new();
+        milestones.set_population(0, 2);
+        let (aric, bria, cara) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let names = HashMap::from([(aric, "Aric".to_string()), (bria, "Bria".to_string())]);
+
+        milestones.record(&Event::birth_occurred(12, aric, bria, cara, "Cara"), &names);
+        milestones.record(&Event::group_formed(20, "The Kin", vec![aric, bria, cara]), &names);
+        milestones.record(&Event::group_formed(25, "The Others", vec![aric, bria]), &names);
+
+        assert_eq!(
+            milestones.milestones(),
+            vec![
+                Milestone { epoch: 12, description: "The first child, **Cara**, was born".to_string() },
+                Milestone { epoch: 12, description: "Population peaked at 3".to_string() },
+                Milestone { epoch: 20, description: "The first alliance, The Kin, formed".to_string() },
+            ]
+        );
+    }
+}
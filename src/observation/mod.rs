@@ -1,5 +1,16 @@
 pub mod chronicle;
 pub mod events;
+pub mod lives;
+pub mod milestones;
+pub mod network;
+pub mod provenance;
+pub mod rumors;
+pub mod sink;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
 
-pub use chronicle::Chronicle;
+pub use chronicle::{Chronicle, ChronicleStyle, ChronicleVerbosity, Snapshot, SnapshotFormat};
 pub use events::{Event, EventType};
+pub use lives::LivesFormat;
+pub use network::NetworkRecord;
+pub use provenance::{content_hash, Provenance};
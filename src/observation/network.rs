@@ -0,0 +1,97 @@
+//! The social network over time: `network_timeline.jsonl` gets one record per
+//! snapshot epoch with every trust/sentiment edge, plus who belongs to which
+//! group and how the groups stand toward each other, for dynamic-graph tools.
+
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::agent::Agent;
+use crate::groups::{Group, Rivalry, RivalryType};
+
+/// An agent in the network
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkNode {
+    pub id: Uuid,
+    pub name: String,
+    /// Name of the group the agent belongs to, if any
+    pub group: Option<String>,
+}
+
+/// How one agent regards another
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkEdge {
+    pub source: Uuid,
+    pub target: Uuid,
+    pub trust: f64,
+    pub sentiment: f64,
+}
+
+/// How two groups stand toward each other
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupRelation {
+    pub group_a: String,
+    pub group_b: String,
+    pub rivalry_type: RivalryType,
+}
+
+/// The network at one epoch
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkRecord {
+    pub epoch: usize,
+    /// Living agents
+    pub nodes: Vec<NetworkNode>,
+    /// Each living agent's beliefs about the other living agents
+    pub edges: Vec<NetworkEdge>,
+    pub rivalries: Vec<GroupRelation>,
+}
+
+impl NetworkRecord {
+    pub fn capture(epoch: usize, agents: &[Agent], groups: &[Group], rivalries: &[Rivalry]) -> Self {
+        let living: Vec<&Agent> = agents.iter().filter(|a| a.is_alive()).collect();
+        let group_of = |id: Uuid| groups.iter().find(|g| g.members.contains(&id)).map(|g| g.name.clone());
+        let group_name = |id: Uuid| groups.iter().find(|g| g.id == id).map(|g| g.name.clone());
+
+        let nodes = living
+            .iter()
+            .map(|a| NetworkNode { id: a.id, name: a.name().to_string(), group: group_of(a.id) })
+            .collect();
+        let mut edges = Vec::new();
+        for source in &living {
+            for target in &living {
+                if let Some(belief) = source.beliefs.get_social(target.id) {
+                    edges.push(NetworkEdge {
+                        source: source.id,
+                        target: target.id,
+                        trust: belief.trust,
+                        sentiment: belief.sentiment,
+                    });
+                }
+            }
+        }
+        let rivalries = rivalries
+            .iter()
+            .filter_map(|r| {
+                Some(GroupRelation {
+                    group_a: group_name(r.group_a)?,
+                    group_b: group_name(r.group_b)?,
+                    rivalry_type: r.rivalry_type,
+                })
+            })
+            .collect();
+
+        Self { epoch, nodes, edges, rivalries }
+    }
+
+    /// Append this record as one line of `network_timeline.jsonl`
+    pub fn append(&self, output_dir: &Path) -> anyhow::Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(output_dir.join("network_timeline.jsonl"))?;
+        writeln!(file, "{}", serde_json::to_string(self)?)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+//! What produced a run: `provenance.json` records the version, seed and the
+//! fully resolved configuration, so an output directory can be matched to its
+//! inputs.
+
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use crate::config::Config;
+
+/// Stable 64-bit FNV-1a hash as 16 hex digits (unlike `DefaultHasher`, the
+/// same across Rust releases)
+pub fn content_hash(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// Inputs of a run
+#[derive(Debug, Clone, Serialize)]
+pub struct Provenance {
+    pub version: String,
+    pub seed: Option<u64>,
+    /// Hash of the scenario file as read; None when run on the defaults
+    pub scenario_hash: Option<String>,
+    /// Hash of the resolved configuration, command-line overrides and seed included
+    pub config_hash: String,
+    pub config: serde_json::Value,
+}
+
+impl Provenance {
+    pub fn of(config: &Config) -> anyhow::Result<Self> {
+        // Through a `Value` so map keys come out sorted and the hash is stable
+        let resolved = serde_json::to_value(config)?;
+        Ok(Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            seed: config.simulation.seed,
+            scenario_hash: config.scenario_hash.clone(),
+            config_hash: content_hash(resolved.to_string().as_bytes()),
+            config: resolved,
+        })
+    }
+
+    /// Write `provenance.json` into the run directory
+    pub fn write(&self, output_dir: &Path) -> anyhow::Result<()> {
+        fs::write(output_dir.join("provenance.json"), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_config_and_seed_share_a_hash() {
+        let seeded = |seed: u64| {
+            let mut config = Config::default();
+            config.simulation.seed = Some(seed);
+            Provenance::of(&config).unwrap()
+        };
+
+        assert_eq!(seeded(7).config_hash, seeded(7).config_hash);
+        assert_ne!(seeded(7).config_hash, seeded(8).config_hash);
+        assert_eq!(seeded(7).config["simulation"]["seed"], 7);
+        assert_eq!(content_hash(b""), "cbf29ce484222325");
+    }
+}
@@ -0,0 +1,152 @@
+//! How true gossip is: each rumor passed on is set against what its subject
+//! has actually been doing lately, and `rumors.json` reports how far social
+//! knowledge has drifted from the deeds behind it.
+
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::Path;
+use uuid::Uuid;
+
+use super::events::{Event, EventType};
+use crate::config::RumorConfig;
+
+/// One piece of gossip and how well it matched its subject's conduct
+#[derive(Debug, Clone, Serialize)]
+pub struct Rumor {
+    pub epoch: usize,
+    pub teller: Uuid,
+    pub listener: Uuid,
+    pub subject: Uuid,
+    /// Sentiment the teller passed on (-1.0 to 1.0)
+    pub sentiment: f64,
+    /// The subject's recent conduct (-1.0 hostile to 1.0 benevolent); None
+    /// if it had done nothing telling within the window
+    pub conduct: Option<f64>,
+    /// 1.0 when the rumor matches the conduct, 0.0 when it is its opposite
+    pub fidelity: Option<f64>,
+    pub low_fidelity: bool,
+}
+
+/// Aggregate written to `rumors.json`
+#[derive(Debug, Clone, Serialize)]
+pub struct RumorReport {
+    /// Rumors whose subject had a record to judge them by
+    pub judged: usize,
+    pub mean_fidelity: Option<f64>,
+    /// Judged rumors below the configured fidelity
+    pub low_fidelity: usize,
+    pub rumors: Vec<Rumor>,
+}
+
+/// Follows agents' deeds and the gossip told about them
+#[derive(Debug, Default)]
+pub struct Rumors {
+    config: RumorConfig,
+    /// Epoch and weight of each agent's recent telling deeds, oldest first
+    deeds: HashMap<Uuid, VecDeque<(usize, f64)>>,
+    rumors: Vec<Rumor>,
+}
+
+impl Rumors {
+    pub fn new(config: RumorConfig) -> Self {
+        Self { config, ..Self::default() }
+    }
+
+    /// Note a deed or a rumor
+    pub fn record(&mut self, event: &Event) {
+        let Some(agent) = event.agent else {
+            return;
+        };
+        let weight = match event.event_type {
+            EventType::Gave | EventType::SkillTaught | EventType::ServiceFulfilled => 1.0,
+            EventType::Attacked | EventType::TradeReneged => -1.0,
+            EventType::Gossiped => {
+                self.judge(event, agent);
+                return;
+            }
+            _ => return,
+        };
+        self.deeds.entry(agent).or_default().push_back((event.epoch, weight));
+    }
+
+    fn judge(&mut self, event: &Event, teller: Uuid) {
+        let (Some(listener), Some(subject), Some(sentiment)) = (event.target, event.data.about, event.data.sentiment)
+        else {
+            return;
+        };
+        let conduct = self.conduct(subject, event.epoch);
+        let fidelity = conduct.map(|conduct| 1.0 - (sentiment - conduct).abs() / 2.0);
+        self.rumors.push(Rumor {
+            epoch: event.epoch,
+            teller,
+            listener,
+            subject,
+            sentiment,
+            conduct,
+            fidelity,
+            low_fidelity: fidelity.is_some_and(|f| f < self.config.low_fidelity),
+        });
+    }
+
+    /// Mean weight of the agent's deeds within the window before `epoch`
+    fn conduct(&mut self, agent: Uuid, epoch: usize) -> Option<f64> {
+        let deeds = self.deeds.get_mut(&agent)?;
+        while deeds.front().is_some_and(|(at, _)| at + self.config.window < epoch) {
+            deeds.pop_front();
+        }
+        if deeds.is_empty() {
+            return None;
+        }
+        Some(deeds.iter().map(|(_, weight)| weight).sum::<f64>() / deeds.len() as f64)
+    }
+
+    pub fn report(&self) -> RumorReport {
+        let fidelities: Vec<f64> = self.rumors.iter().filter_map(|r| r.fidelity).collect();
+        RumorReport {
+            judged: fidelities.len(),
+            mean_fidelity: (!fidelities.is_empty()).then(|| fidelities.iter().sum::<f64>() / fidelities.len() as f64),
+            low_fidelity: self.rumors.iter().filter(|r| r.low_fidelity).count(),
+            rumors: self.rumors.clone(),
+        }
+    }
+
+    /// Write `rumors.json` into the run directory
+    pub fn write(&self, output_dir: &Path) -> anyhow::Result<()> {
+        fs::write(output_dir.join("rumors.json"), serde_json::to_string_pretty(&self.report())?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slander_of_a_giver_is_low_fidelity() {
+        let (giver, friend, teller, listener) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut rumors = Rumors::new(RumorConfig::default());
+        rumors.record(&Event::gave(10, giver, friend, 3));
+        rumors.record(&Event::gave(12, giver, listener, 2));
+
+        rumors.record(&Event::gossiped(14, teller, listener, giver, "negative", -0.8));
+        rumors.record(&Event::gossiped(14, friend, listener, giver, "positive", 0.7));
+        // Nothing on record for the teller, so nothing to judge by
+        rumors.record(&Event::gossiped(14, friend, listener, teller, "negative", -0.5));
+
+        let report = rumors.report();
+        assert_eq!(report.rumors.len(), 3);
+        assert_eq!(report.judged, 2);
+        assert_eq!(report.low_fidelity, 1);
+        let slander = &report.rumors[0];
+        assert!(slander.low_fidelity);
+        assert!((slander.fidelity.unwrap() - 0.1).abs() < 1e-9);
+        assert!(!report.rumors[1].low_fidelity);
+        assert_eq!(report.rumors[2].fidelity, None);
+
+        // Deeds older than the window are forgotten
+        let late = RumorConfig::default().window + 13;
+        rumors.record(&Event::gossiped(late, teller, listener, giver, "negative", -0.8));
+        assert_eq!(rumors.report().rumors[3].conduct, None);
+    }
+}
@@ -0,0 +1,20 @@
+//! Extra destinations for the event stream besides `events.jsonl`.
+
+use super::events::Event;
+use crate::agent::Agent;
+
+/// Receives every logged event and the agents at each snapshot
+pub trait EventSink {
+    /// Store one event
+    fn record(&mut self, event: &Event) -> anyhow::Result<()>;
+
+    /// Store the agents as they stand at a snapshot epoch
+    fn snapshot_agents(&mut self, _epoch: usize, _agents: &[Agent]) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Make everything written so far durable
+    fn flush(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+//! SQLite event sink, for querying a run with SQL instead of filtering
+//! `events.jsonl` (e.g. every attack made by the second generation).
+
+use rusqlite::{params, Connection};
+use std::path::Path;
+
+use super::events::Event;
+use super::sink::EventSink;
+use crate::agent::Agent;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS events (
+        id INTEGER PRIMARY KEY,
+        epoch INTEGER NOT NULL,
+        type TEXT NOT NULL,
+        agent TEXT,
+        target TEXT,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS events_by_type ON events (type, epoch);
+    CREATE INDEX IF NOT EXISTS events_by_agent ON events (agent);
+    CREATE TABLE IF NOT EXISTS agents (
+        epoch INTEGER NOT NULL,
+        id TEXT NOT NULL,
+        name TEXT NOT NULL,
+        generation INTEGER NOT NULL,
+        age INTEGER NOT NULL,
+        alive INTEGER NOT NULL,
+        x INTEGER NOT NULL,
+        y INTEGER NOT NULL,
+        health REAL NOT NULL,
+        hunger REAL NOT NULL,
+        energy REAL NOT NULL,
+        food INTEGER NOT NULL,
+        PRIMARY KEY (epoch, id)
+    );
+";
+
+/// Writes events and agent snapshots into a SQLite database
+pub struct SqliteSink {
+    conn: Connection,
+}
+
+impl SqliteSink {
+    /// Open (or create) the database at `path` and set up its tables
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        // Events arrive one at a time; batch them into a transaction per flush
+        conn.execute_batch("BEGIN")?;
+        Ok(Self { conn })
+    }
+}
+
+impl EventSink for SqliteSink {
+    fn record(&mut self, event: &Event) -> anyhow::Result<()> {
+        self.conn.execute(
+            "INSERT INTO events (epoch, type, agent, target, data) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                event.epoch as i64,
+                format!("{:?}", event.event_type),
+                event.agent.map(|id| id.to_string()),
+                event.target.map(|id| id.to_string()),
+                serde_json::to_string(&event.data)?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn snapshot_agents(&mut self, epoch: usize, agents: &[Agent]) -> anyhow::Result<()> {
+        let mut insert = self.conn.prepare_cached(
+            "INSERT OR REPLACE INTO agents (epoch, id, name, generation, age, alive, x, y, health, hunger, energy, food)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        )?;
+        for agent in agents {
+            insert.execute(params![
+                epoch as i64,
+                agent.id.to_string(),
+                agent.name(),
+                agent.reproduction.family.generation as i64,
+                agent.age() as i64,
+                agent.is_alive(),
+                agent.physical.x as i64,
+                agent.physical.y as i64,
+                agent.physical.health,
+                agent.physical.hunger,
+                agent.physical.energy,
+                agent.physical.food,
+            ])?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> anyhow::Result<()> {
+        self.conn.execute_batch("COMMIT; BEGIN")?;
+        Ok(())
+    }
+}
+
+impl Drop for SqliteSink {
+    fn drop(&mut self) {
+        let _ = self.conn.execute_batch("COMMIT");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::engine::Engine;
+    use rusqlite::Connection;
+    use std::fs;
+
+    #[tokio::test]
+    async fn test_short_run_fills_a_queryable_database() {
+        let dir = std::env::temp_dir().join(format!("terrarium-sqlite-{}", uuid::Uuid::new_v4()));
+        let db = dir.join("run.db");
+        let mut config = Config::default();
+        config.agents.count = 3;
+        config.simulation.epochs = 3;
+        config.simulation.database = Some(db.to_string_lossy().into_owned());
+        config.llm.api_key_env = "TERRARIUM_TEST_NO_API_KEY".to_string();
+
+        let mut engine = Engine::new(config, &dir.to_string_lossy()).unwrap();
+        engine.run().await.unwrap();
+        drop(engine);
+
+        let logged = fs::read_to_string(dir.join("events.jsonl")).unwrap().lines().count();
+        let conn = Connection::open(&db).unwrap();
+        let stored: usize = conn.query_row("SELECT COUNT(*) FROM events", [], |row| row.get(0)).unwrap();
+        assert_eq!(stored, logged);
+
+        let epoch_starts: usize = conn
+            .query_row("SELECT COUNT(*) FROM events WHERE type = 'EpochStart'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(epoch_starts, 3);
+
+        let founders: usize = conn
+            .query_row("SELECT COUNT(*) FROM agents WHERE epoch = 0 AND generation = 0", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(founders, 3);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -4,12 +4,14 @@
 //! The views are read-only snapshots that decouple clients from engine internals.
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
-use crate::agent::{Agent, Goal};
+use crate::agent::{Agent, Goal, Phenotype};
 use crate::config::AgingConfig;
 use crate::observation::{Event, EventType};
-use crate::world::{Terrain, World};
+use crate::trade::{TradeState, TradeStats};
+use crate::world::{Cell, Terrain, World};
 
 /// View of the entire world state
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -63,7 +65,15 @@ pub struct AgentView {
     pub hunger: f64,
     pub energy: f64,
     pub food: u32,
+    /// Materials carried, by name
+    #[serde(default)]
+    pub materials: BTreeMap<String, u32>,
+    /// Tools carried
+    #[serde(default)]
+    pub tools: Vec<String>,
     pub alive: bool,
+    /// Collapsed from exhaustion
+    pub incapacitated: bool,
 
     // Aging
     pub age: usize,
@@ -73,6 +83,7 @@ pub struct AgentView {
     // Identity
     pub personality_summary: String,
     pub aspiration: String,
+    pub phenotype: Phenotype,
 
     // Cognitive
     pub current_goal: Option<String>,
@@ -84,6 +95,9 @@ pub struct AgentView {
 
     // Skills
     pub skills: Vec<SkillView>,
+
+    // Trade
+    pub trade_stats: TradeStats,
 }
 
 /// View of a social belief
@@ -143,7 +157,7 @@ pub struct TradeStateView {
 }
 
 /// View of an event for display
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EventView {
     pub epoch: usize,
     pub description: String,
@@ -151,7 +165,7 @@ pub struct EventView {
 }
 
 /// Simplified event types for display
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum EventViewType {
     Movement,
     Gathering,
@@ -161,7 +175,9 @@ pub enum EventViewType {
     Gift,
     Attack,
     AllyIntervened,
+    CombatOutcome,
     Death,
+    Scavenging,
     Gossip,
     GroupFormed,
     GroupDissolved,
@@ -170,7 +186,10 @@ pub enum EventViewType {
     RivalryFormed,
     RivalryChanged,
     RivalryEnded,
+    Migration,
+    WorldEvent,
     Courtship,
+    MateFailed,
     Conception,
     Birth,
     SkillTaught,
@@ -188,8 +207,10 @@ pub enum EventViewType {
     TerritoryFight,
     TerritoryLost,
     // Structures
+    StructureBuilt,
     FarmProduced,
     StructureDestroyed,
+    StructureDismantled,
     // Trade
     TradeProposed,
     TradeAccepted,
@@ -199,67 +220,18 @@ pub enum EventViewType {
     TradeCancelled,
     TradeReneged,
     ServiceFulfilled,
+    GoalSet,
     Meta,
 }
 
 impl WorldView {
     /// Create a world view from the world and agents
     pub fn from_world(world: &World, agents: &[Agent]) -> Self {
-        let mut cells = Vec::with_capacity(world.cells.len());
-
-        for cell in &world.cells {
-            let occupants: Vec<Uuid> = agents
-                .iter()
-                .filter(|a| a.is_alive() && a.physical.x == cell.x && a.physical.y == cell.y)
-                .map(|a| a.id)
-                .collect();
-
-            // Build structure view if present
-            let structure = cell.structure.as_ref().map(|s| {
-                let owner_name = agents
-                    .iter()
-                    .find(|a| a.id == s.owner)
-                    .map(|a| a.name().to_string());
-
-                StructureView {
-                    structure_type: format!("{:?}", s.structure_type),
-                    display_name: s.display_name(),
-                    is_complete: s.is_complete(),
-                    build_percent: if s.build_required > 0 {
-                        (s.build_progress as f64 / s.build_required as f64) * 100.0
-                    } else {
-                        100.0
-                    },
-                    owner_name,
-                }
-            });
-
-            // Build territory view if present
-            let territory = cell.territory.as_ref().map(|t| {
-                let owner_name = agents
-                    .iter()
-                    .find(|a| a.id == t.owner)
-                    .map(|a| a.name().to_string())
-                    .unwrap_or_else(|| "Unknown".to_string());
-
-                TerritoryView {
-                    owner_id: t.owner,
-                    owner_name,
-                    strength: t.strength,
-                    guest_count: t.allowed_guests.len(),
-                }
-            });
-
-            cells.push(CellView {
-                x: cell.x,
-                y: cell.y,
-                terrain: cell.terrain,
-                food: cell.food,
-                occupants,
-                structure,
-                territory,
-            });
-        }
+        let cells = world
+            .cells
+            .iter()
+            .map(|cell| CellView::from_cell(cell, agents))
+            .collect();
 
         Self {
             epoch: world.epoch,
@@ -279,9 +251,104 @@ impl WorldView {
     }
 }
 
+/// Plain-text map of the world, one character per cell and one line per row:
+/// an agent's glyph (`@` if unknown, `2`-`9` for a crowd), `#` a structure (`+`
+/// while being built), then terrain: `*` fertile with food, `,` bare fertile,
+/// `T` forest, `.` barren, `~` desert
+pub fn render_map_ascii(world: &WorldView, glyph_of: impl Fn(Uuid) -> Option<char>) -> String {
+    let mut map = String::with_capacity((world.width + 1) * world.height);
+    for y in 0..world.height {
+        for x in 0..world.width {
+            let Some(cell) = world.get(x, y) else {
+                continue;
+            };
+            let glyph = match (cell.occupants.len(), &cell.structure) {
+                (1, _) => glyph_of(cell.occupants[0]).unwrap_or('@'),
+                (n, _) if n > 1 => char::from_digit(n.min(9) as u32, 10).unwrap_or('@'),
+                (_, Some(structure)) if structure.is_complete => '#',
+                (_, Some(_)) => '+',
+                _ => match cell.terrain {
+                    Terrain::Fertile if cell.food > 0 => '*',
+                    Terrain::Fertile => ',',
+                    Terrain::Forest => 'T',
+                    Terrain::Barren => '.',
+                    Terrain::Desert => '~',
+                    Terrain::Mountain => '^',
+                    Terrain::Water => '=',
+                },
+            };
+            map.push(glyph);
+        }
+        map.push('\n');
+    }
+    map
+}
+
+impl CellView {
+    /// Create a view of a single cell
+    pub fn from_cell(cell: &Cell, agents: &[Agent]) -> Self {
+        let occupants: Vec<Uuid> = agents
+            .iter()
+            .filter(|a| a.is_alive() && a.physical.x == cell.x && a.physical.y == cell.y)
+            .map(|a| a.id)
+            .collect();
+
+        // Build structure view if present
+        let structure = cell.structure.as_ref().map(|s| {
+            let owner_name = agents
+                .iter()
+                .find(|a| a.id == s.owner)
+                .map(|a| a.name().to_string());
+
+            StructureView {
+                structure_type: format!("{:?}", s.structure_type),
+                display_name: s.display_name(),
+                is_complete: s.is_complete(),
+                build_percent: if s.build_required > 0 {
+                    (s.build_progress as f64 / s.build_required as f64) * 100.0
+                } else {
+                    100.0
+                },
+                owner_name,
+            }
+        });
+
+        // Build territory view if present
+        let territory = cell.territory.as_ref().map(|t| {
+            let owner_name = agents
+                .iter()
+                .find(|a| a.id == t.owner)
+                .map(|a| a.name().to_string())
+                .unwrap_or_else(|| "Unknown".to_string());
+
+            TerritoryView {
+                owner_id: t.owner,
+                owner_name,
+                strength: t.strength,
+                guest_count: t.allowed_guests.len(),
+            }
+        });
+
+        Self {
+            x: cell.x,
+            y: cell.y,
+            terrain: cell.terrain,
+            food: cell.food,
+            occupants,
+            structure,
+            territory,
+        }
+    }
+}
+
 impl AgentView {
     /// Create an agent view from an agent
-    pub fn from_agent(agent: &Agent, agents: &[Agent], aging_config: &AgingConfig) -> Self {
+    pub fn from_agent(
+        agent: &Agent,
+        agents: &[Agent],
+        aging_config: &AgingConfig,
+        trade_state: &TradeState,
+    ) -> Self {
         // Build personality summary
         let p = &agent.identity.personality;
         let mut traits = Vec::new();
@@ -401,17 +468,28 @@ impl AgentView {
             hunger: agent.physical.hunger,
             energy: agent.physical.energy,
             food: agent.physical.food,
+            materials: agent
+                .physical
+                .materials
+                .iter()
+                .filter(|(_, n)| **n > 0)
+                .map(|(m, n)| (m.display_name().to_string(), *n))
+                .collect(),
+            tools: agent.physical.tools.iter().map(|t| t.tool_type.display_name().to_string()).collect(),
             alive: agent.is_alive(),
+            incapacitated: agent.physical.incapacitated,
             age: agent.physical.age,
             life_stage: agent.life_stage(aging_config).to_string(),
             generation: agent.reproduction.family.generation,
             personality_summary,
             aspiration: agent.identity.aspiration.describe().to_string(),
+            phenotype: agent.identity.phenotype,
             current_goal: agent.active_goal.as_ref().map(|g| g.describe().to_string()),
             recent_memories,
             social_beliefs,
             reproduction,
             skills,
+            trade_stats: trade_state.stats_for(agent.id),
         }
     }
 }
@@ -425,7 +503,8 @@ impl EventView {
             .collect()
     }
 
-    fn from_event(event: &Event, agents: &[Agent]) -> Option<Self> {
+    /// View of one event, or None for those not shown (epoch boundaries)
+    pub fn from_event(event: &Event, agents: &[Agent]) -> Option<Self> {
         let agent_name = |id: Uuid| {
             agents
                 .iter()
@@ -473,9 +552,12 @@ impl EventView {
             EventType::Gave => {
                 let name = agent_name(event.agent?);
                 let target_name = agent_name(event.target?);
-                let amount = event.data.amount?;
+                let gift = match &event.data.description {
+                    Some(item) => item.clone(),
+                    None => format!("{} food", event.data.amount?),
+                };
                 (
-                    format!("{} gave {} food to {}", name, amount, target_name),
+                    format!("{} gave {} to {}", name, gift, target_name),
                     EventViewType::Gift,
                 )
             }
@@ -487,6 +569,15 @@ impl EventView {
                     EventViewType::Attack,
                 )
             }
+            EventType::CombatOutcome => {
+                let name = agent_name(event.agent?);
+                let target_name = agent_name(event.target?);
+                let outcome = event.data.description.as_deref()?;
+                (
+                    format!("{} {} {}", name, outcome, target_name),
+                    EventViewType::CombatOutcome,
+                )
+            }
             EventType::AllyIntervened => {
                 let target_name = agent_name(event.target?);
                 let ally_id = event.data.ally?;
@@ -505,6 +596,14 @@ impl EventView {
                     EventViewType::Death,
                 )
             }
+            EventType::Scavenged => {
+                let name = agent_name(event.agent?);
+                let dead = agent_name(event.target?);
+                (
+                    format!("{} ate from the remains of {}", name, dead),
+                    EventViewType::Scavenging,
+                )
+            }
             EventType::Gossiped => {
                 let name = agent_name(event.agent?);
                 let target_name = agent_name(event.target?);
@@ -584,6 +683,22 @@ impl EventView {
                     EventViewType::RivalryEnded,
                 )
             }
+            EventType::WorldEventStruck => {
+                let headline = event.data.description.clone().unwrap_or_else(|| "Something happened".to_string());
+                (headline, EventViewType::WorldEvent)
+            }
+            EventType::Scripted => {
+                let description = event.data.description.clone().unwrap_or_else(|| "Something happened".to_string());
+                (description, EventViewType::Meta)
+            }
+            EventType::MigrationBegan => {
+                let member_count = event.data.members.as_ref().map(|m| m.len()).unwrap_or(0);
+                let direction = event.data.description.as_deref().unwrap_or("away");
+                (
+                    format!("{} starving agents set out {} together", member_count, direction),
+                    EventViewType::Migration,
+                )
+            }
             EventType::Courted => {
                 let name = agent_name(event.agent?);
                 let target_name = agent_name(event.target?);
@@ -593,6 +708,15 @@ impl EventView {
                     EventViewType::Courtship,
                 )
             }
+            EventType::MateFailed => {
+                let name = agent_name(event.agent?);
+                let target_name = agent_name(event.target?);
+                let reason = event.data.description.as_deref().unwrap_or("unknown");
+                (
+                    format!("{} and {} failed to mate: {}", name, target_name, reason),
+                    EventViewType::MateFailed,
+                )
+            }
             EventType::Conceived => {
                 let parent_a = event.data.parent_a.map(agent_name).unwrap_or_else(|| "Unknown".to_string());
                 let parent_b = event.data.parent_b.map(agent_name).unwrap_or_else(|| "Unknown".to_string());
@@ -742,6 +866,14 @@ impl EventView {
                     EventViewType::FarmProduced,
                 )
             }
+            EventType::StructureBuilt => {
+                let name = agent_name(event.agent?);
+                let structure_type = event.data.description.as_deref().unwrap_or("structure");
+                (
+                    format!("{} finished building a {}", name, structure_type),
+                    EventViewType::StructureBuilt,
+                )
+            }
             EventType::StructureDestroyed => {
                 let name = agent_name(event.agent?);
                 let structure_type = event.data.description.as_deref().unwrap_or("structure");
@@ -750,6 +882,14 @@ impl EventView {
                     EventViewType::StructureDestroyed,
                 )
             }
+            EventType::StructureDismantled => {
+                let name = agent_name(event.agent?);
+                let structure_type = event.data.description.as_deref().unwrap_or("structure");
+                (
+                    format!("{} dismantled their {}", name, structure_type),
+                    EventViewType::StructureDismantled,
+                )
+            }
             // Trade events
             EventType::TradeProposed => {
                 let proposer = agent_name(event.agent?);
@@ -819,6 +959,14 @@ impl EventView {
                     EventViewType::ServiceFulfilled,
                 )
             }
+            EventType::GoalSet => {
+                let name = agent_name(event.agent?);
+                let goal = event.data.description.as_deref().unwrap_or("something");
+                (
+                    format!("{} resolves to {}", name, goal),
+                    EventViewType::GoalSet,
+                )
+            }
         };
 
         Some(Self {
@@ -838,3 +986,27 @@ pub enum SimulationCommand {
     SetSpeed(u32), // ms per epoch
     Stop,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_ascii_map_marks_agent_at_its_cell() {
+        let mut config = Config::default().world;
+        config.width = 6;
+        config.height = 4;
+        let world = World::new(&config);
+        let agent = Agent::new("Aric".to_string(), 2, 3, 5);
+        let glyph_of = |id| (id == agent.id).then_some(agent.identity.phenotype.glyph);
+
+        let map = render_map_ascii(&WorldView::from_world(&world, std::slice::from_ref(&agent)), glyph_of);
+        let rows: Vec<&str> = map.lines().collect();
+
+        assert_eq!(rows.len(), 4);
+        assert!(rows.iter().all(|row| row.chars().count() == 6));
+        assert_eq!(rows[3].chars().nth(2), Some('A'));
+        assert_eq!(map.matches('A').count(), 1);
+    }
+}
@@ -0,0 +1,116 @@
+//! The order of the steps in an epoch, configurable so experiments can, say,
+//! age agents before they reproduce or settle mating before other actions.
+//!
+//! The world tick opens every epoch and the chronicle closes it; the steps in
+//! between run in the order the pipeline lists them.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// One step of an epoch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Step {
+    /// Hunger, energy drain, hazards and deaths from them; strangers meet
+    Needs,
+    /// Every living agent decides on an action
+    Deliberate,
+    /// Decided actions and remarks take effect; trade deadlines are checked
+    Resolve,
+    /// Agents who both chose to mate try to conceive
+    Mate,
+    /// Pregnancies advance, courtship fades and the newborn arrive
+    Gestate,
+    /// Agents grow older and may die of old age
+    Age,
+    /// Beliefs, territories, structures and remains are brought up to date
+    Beliefs,
+    /// Groups and rivalries are detected
+    Groups,
+}
+
+impl Step {
+    /// Every step, in the default order
+    pub const ALL: [Step; 8] = [
+        Step::Needs,
+        Step::Deliberate,
+        Step::Resolve,
+        Step::Mate,
+        Step::Gestate,
+        Step::Age,
+        Step::Beliefs,
+        Step::Groups,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Step::Needs => "needs",
+            Step::Deliberate => "deliberate",
+            Step::Resolve => "resolve",
+            Step::Mate => "mate",
+            Step::Gestate => "gestate",
+            Step::Age => "age",
+            Step::Beliefs => "beliefs",
+            Step::Groups => "groups",
+        }
+    }
+
+    /// Profiler phase the step's time is charged to
+    pub fn phase(self) -> &'static str {
+        match self {
+            Step::Needs => "world",
+            Step::Deliberate => "deliberation",
+            Step::Resolve => "resolution",
+            Step::Mate | Step::Gestate => "mating",
+            Step::Age => "aging",
+            Step::Beliefs => "upkeep",
+            Step::Groups => "groups",
+        }
+    }
+}
+
+/// The standard order: needs, deliberate, resolve, mate, gestate, age, beliefs,
+/// groups (aging after reproduction, so newborns get their first epoch)
+pub fn default_pipeline() -> Vec<Step> {
+    Step::ALL.to_vec()
+}
+
+/// Check that every step runs exactly once, and that agents deliberate before
+/// the steps that act on their decisions
+pub fn validate(pipeline: &[Step]) -> Result<()> {
+    for step in Step::ALL {
+        match pipeline.iter().filter(|s| **s == step).count() {
+            0 => anyhow::bail!("epoch pipeline is missing the {} step", step.name()),
+            1 => {}
+            n => anyhow::bail!("epoch pipeline runs the {} step {} times", step.name(), n),
+        }
+    }
+    let position = |step: Step| pipeline.iter().position(|s| *s == step);
+    for step in [Step::Resolve, Step::Mate] {
+        if position(step) < position(Step::Deliberate) {
+            anyhow::bail!("epoch pipeline runs the {} step before agents deliberate", step.name());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pipeline_needs_each_step_once_after_deliberation() {
+        assert!(validate(&default_pipeline()).is_ok());
+
+        let missing = [Step::Needs, Step::Deliberate, Step::Resolve, Step::Mate, Step::Gestate, Step::Age, Step::Beliefs];
+        assert!(validate(&missing).unwrap_err().to_string().contains("missing the groups step"));
+
+        let mut doubled = default_pipeline();
+        doubled.push(Step::Age);
+        assert!(validate(&doubled).unwrap_err().to_string().contains("age step 2 times"));
+
+        let mut early_mating = default_pipeline();
+        early_mating.swap(1, 3);
+        assert!(validate(&early_mating).is_err());
+    }
+}
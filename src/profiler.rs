@@ -0,0 +1,108 @@
+//! Wall-clock profiling of the phases of an epoch.
+
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// Phases timed in each epoch, in the order they run
+pub const PHASES: [&str; 8] = [
+    "world",
+    "deliberation",
+    "resolution",
+    "mating",
+    "aging",
+    "upkeep",
+    "groups",
+    "chronicle",
+];
+
+/// Records how long each phase of an epoch takes and keeps running totals
+#[derive(Debug)]
+pub struct Profiler {
+    enabled: bool,
+    /// Durations for the epoch in progress, indexed like `PHASES`
+    current: [Duration; PHASES.len()],
+    /// Totals over all finished epochs, indexed like `PHASES`
+    totals: [Duration; PHASES.len()],
+    epochs: usize,
+    phase_start: Instant,
+}
+
+impl Profiler {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            current: [Duration::ZERO; PHASES.len()],
+            totals: [Duration::ZERO; PHASES.len()],
+            epochs: 0,
+            phase_start: Instant::now(),
+        }
+    }
+
+    /// Start timing a new phase
+    pub fn start(&mut self) {
+        self.phase_start = Instant::now();
+    }
+
+    /// Charge the time since `start` to a phase
+    pub fn stop(&mut self, phase: &str) {
+        if !self.enabled {
+            return;
+        }
+        if let Some(idx) = PHASES.iter().position(|p| *p == phase) {
+            self.current[idx] += self.phase_start.elapsed();
+        }
+    }
+
+    /// Log this epoch's timings and fold them into the totals
+    pub fn finish_epoch(&mut self, epoch: usize) {
+        if !self.enabled {
+            return;
+        }
+        let total: Duration = self.current.iter().sum();
+        info!("Epoch {} took {:.1?}: {}", epoch, total, describe(&self.current, 1));
+
+        for (sum, phase) in self.totals.iter_mut().zip(self.current) {
+            *sum += phase;
+        }
+        self.current = [Duration::ZERO; PHASES.len()];
+        self.epochs += 1;
+    }
+
+    /// Total time spent in a phase over all finished epochs
+    #[cfg(test)]
+    pub fn total(&self, phase: &str) -> Duration {
+        PHASES
+            .iter()
+            .position(|p| *p == phase)
+            .map(|idx| self.totals[idx])
+            .unwrap_or_default()
+    }
+
+    /// Log mean per-epoch timings over the whole run
+    pub fn log_summary(&self) {
+        if !self.enabled || self.epochs == 0 {
+            return;
+        }
+        let total: Duration = self.totals.iter().sum();
+        info!(
+            "Profile over {} epochs ({:.1?} total), mean per epoch: {}",
+            self.epochs,
+            total,
+            describe(&self.totals, self.epochs as u32)
+        );
+    }
+}
+
+/// "deliberation 10.2ms (83%), resolution 1.1ms (9%), ..." with durations divided by `per`
+fn describe(durations: &[Duration; PHASES.len()], per: u32) -> String {
+    let total: Duration = durations.iter().sum();
+    PHASES
+        .iter()
+        .zip(durations)
+        .map(|(phase, d)| {
+            let share = if total.is_zero() { 0.0 } else { d.as_secs_f64() / total.as_secs_f64() * 100.0 };
+            format!("{} {:.1?} ({:.0}%)", phase, *d / per, share)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
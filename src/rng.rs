@@ -0,0 +1,161 @@
+//! Simulation-wide random number generators.
+//!
+//! All simulation randomness (world generation, personalities, chance rolls and
+//! the ids of agents, groups, trades, structures and tools) goes through here
+//! instead of `rand::rng()` or `Uuid::new_v4()`, so a run can be repeated from its seed.
+//! Each subsystem draws from its own stream, derived from the master seed, so an
+//! extra draw in one (say, a new combat roll) doesn't shift what the others see.
+//! LLM replies are outside our control and still vary between runs.
+
+use rand::distr::{Distribution, StandardUniform};
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use uuid::Uuid;
+
+/// Subsystems with their own random stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    /// Terrain generation and world events
+    World,
+    /// Personalities, names, skills and lifespans of new agents
+    Agents,
+    /// Attack damage, lethality and territory disputes
+    Combat,
+    /// Who carries a child and how traits are inherited
+    Mating,
+    /// Foraging, hunting and fishing rolls
+    Gathering,
+    /// Natural death
+    Aging,
+    /// Choices made by the heuristic decider and by instincts
+    Decisions,
+    /// Ids of agents, groups, trades, structures and tools
+    Ids,
+}
+
+impl Stream {
+    pub const ALL: [Stream; 8] = [
+        Stream::World,
+        Stream::Agents,
+        Stream::Combat,
+        Stream::Mating,
+        Stream::Gathering,
+        Stream::Aging,
+        Stream::Decisions,
+        Stream::Ids,
+    ];
+
+    /// This stream's seed under a master seed
+    pub fn seed_from(self, master: u64) -> u64 {
+        master.wrapping_add((self as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+    }
+}
+
+/// One generator per stream
+pub struct Streams {
+    rngs: [ChaCha8Rng; Stream::ALL.len()],
+}
+
+impl Streams {
+    /// Streams derived deterministically from a master seed
+    pub fn seeded(master: u64) -> Self {
+        Self { rngs: Stream::ALL.map(|s| ChaCha8Rng::seed_from_u64(s.seed_from(master))) }
+    }
+
+    fn from_os() -> Self {
+        Self { rngs: Stream::ALL.map(|_| ChaCha8Rng::from_os_rng()) }
+    }
+
+    /// The generator behind a stream
+    pub fn get(&mut self, stream: Stream) -> &mut ChaCha8Rng {
+        &mut self.rngs[stream as usize]
+    }
+}
+
+#[cfg(not(test))]
+static STREAMS: std::sync::Mutex<Option<Streams>> = std::sync::Mutex::new(None);
+
+// Tests run side by side, so each test thread keeps its own streams rather
+// than reseeding and drawing from the others'
+#[cfg(test)]
+thread_local! {
+    static STREAMS: std::cell::RefCell<Option<Streams>> = const { std::cell::RefCell::new(None) };
+}
+
+#[cfg(not(test))]
+fn with_streams<T>(f: impl FnOnce(&mut Option<Streams>) -> T) -> T {
+    f(&mut STREAMS.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+#[cfg(test)]
+fn with_streams<T>(f: impl FnOnce(&mut Option<Streams>) -> T) -> T {
+    STREAMS.with_borrow_mut(f)
+}
+
+/// Reseed every stream from a master seed; until the first call they are seeded from the OS
+pub fn seed(seed: u64) {
+    with_streams(|streams| *streams = Some(Streams::seeded(seed)));
+}
+
+/// Handle to one of the shared simulation streams
+pub struct SimRng(Stream);
+
+/// Get a handle to a simulation stream (seedable replacement for `rand::rng()`)
+pub fn rng(stream: Stream) -> SimRng {
+    SimRng(stream)
+}
+
+/// Random value from a simulation stream (seedable replacement for `rand::random()`)
+pub fn random<T>(stream: Stream) -> T
+where
+    StandardUniform: Distribution<T>,
+{
+    rng(stream).random()
+}
+
+/// Random v4 UUID drawn from the ids stream
+pub fn uuid() -> Uuid {
+    uuid::Builder::from_random_bytes(random(Stream::Ids)).into_uuid()
+}
+
+fn with_rng<T>(stream: Stream, f: impl FnOnce(&mut ChaCha8Rng) -> T) -> T {
+    with_streams(|streams| f(streams.get_or_insert_with(Streams::from_os).get(stream)))
+}
+
+impl RngCore for SimRng {
+    fn next_u32(&mut self) -> u32 {
+        with_rng(self.0, |rng| rng.next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        with_rng(self.0, |rng| rng.next_u64())
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        with_rng(self.0, |rng| rng.fill_bytes(dst))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extra_combat_draw_leaves_mating_stream_alone() {
+        let mating_draws = |extra_combat_draw: bool| {
+            let mut streams = Streams::seeded(42);
+            let mut draws = Vec::new();
+            for _ in 0..5 {
+                if extra_combat_draw {
+                    let _: f64 = streams.get(Stream::Combat).random();
+                }
+                let _: f64 = streams.get(Stream::Combat).random();
+                draws.push(streams.get(Stream::Mating).random::<u64>());
+            }
+            draws
+        };
+
+        assert_eq!(mating_draws(false), mating_draws(true));
+        assert_ne!(Stream::Mating.seed_from(42), Stream::Combat.seed_from(42));
+    }
+}
@@ -0,0 +1,87 @@
+//! Who stands where: agents grouped by cell, so finding the agents near
+//! someone looks at the few cells around them instead of everyone.
+
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::agent::Agent;
+
+/// Agents by the cell they stand in, dead or alive
+#[derive(Debug, Clone, Default)]
+pub struct SpatialIndex {
+    cells: HashMap<(usize, usize), Vec<Uuid>>,
+}
+
+impl SpatialIndex {
+    pub fn build(agents: &[Agent]) -> Self {
+        let mut index = Self::default();
+        for agent in agents {
+            index.insert(agent.id, (agent.physical.x, agent.physical.y));
+        }
+        index
+    }
+
+    pub fn insert(&mut self, id: Uuid, pos: (usize, usize)) {
+        self.cells.entry(pos).or_default().push(id);
+    }
+
+    /// Note that an agent moved from one cell to another
+    pub fn relocate(&mut self, id: Uuid, from: (usize, usize), to: (usize, usize)) {
+        if from == to {
+            return;
+        }
+        if let Some(ids) = self.cells.get_mut(&from) {
+            ids.retain(|other| *other != id);
+            if ids.is_empty() {
+                self.cells.remove(&from);
+            }
+        }
+        self.insert(id, to);
+    }
+
+    /// Everyone within `radius` cells of (x, y) in both directions, whoever
+    /// stands on (x, y) itself included
+    pub fn neighbors_within(&self, x: usize, y: usize, radius: usize) -> Vec<Uuid> {
+        let mut neighbors = Vec::new();
+        for ny in y.saturating_sub(radius)..=y.saturating_add(radius) {
+            for nx in x.saturating_sub(radius)..=x.saturating_add(radius) {
+                if let Some(ids) = self.cells.get(&(nx, ny)) {
+                    neighbors.extend_from_slice(ids);
+                }
+            }
+        }
+        neighbors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::is_adjacent;
+
+    #[test]
+    fn test_neighbors_within_finds_exactly_the_adjacent() {
+        let agents: Vec<Agent> = (0..1000)
+            .map(|i| Agent::new(format!("Agent {}", i), (i * 37) % 40, (i * 11) % 25, 0))
+            .collect();
+        let mut index = SpatialIndex::build(&agents);
+
+        for agent in agents.iter().step_by(7) {
+            let mut found = index.neighbors_within(agent.physical.x, agent.physical.y, 1);
+            let mut adjacent: Vec<Uuid> = agents.iter().filter(|b| is_adjacent(agent, b)).map(|b| b.id).collect();
+            found.sort();
+            adjacent.sort();
+            assert_eq!(found, adjacent, "around ({}, {})", agent.physical.x, agent.physical.y);
+        }
+
+        // Corners don't wrap, and a moved agent is found only where they went
+        assert!(index.neighbors_within(0, 0, 1).iter().all(|id| {
+            let agent = agents.iter().find(|a| a.id == *id).unwrap();
+            agent.physical.x <= 1 && agent.physical.y <= 1
+        }));
+        let mover = &agents[0];
+        index.relocate(mover.id, (mover.physical.x, mover.physical.y), (39, 24));
+        assert!(!index.neighbors_within(mover.physical.x, mover.physical.y, 0).contains(&mover.id));
+        assert!(index.neighbors_within(39, 24, 0).contains(&mover.id));
+    }
+}
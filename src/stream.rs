@@ -0,0 +1,281 @@
+//! Live event stream: a WebSocket endpoint at `/stream` that pushes each event
+//! as it is logged, as the JSON of its `EventView`, so a client can follow a
+//! run without polling. Clients see events from the moment they connect; one
+//! that falls too far behind is dropped. Pings are answered, and a close from
+//! the client ends its stream.
+
+use anyhow::Result;
+use base64::Engine as _;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+
+use crate::observer::EventView;
+
+/// Events held for clients still catching up; a client further behind is dropped
+pub const BUFFER: usize = 256;
+
+/// Appended to a client's key to prove the server speaks WebSocket (RFC 6455)
+const HANDSHAKE_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Longest handshake request read
+const MAX_REQUEST: usize = 8192;
+
+/// Frame opcodes (RFC 6455 section 5.2)
+const OP_TEXT: u8 = 0x1;
+const OP_CLOSE: u8 = 0x8;
+const OP_PING: u8 = 0x9;
+const OP_PONG: u8 = 0xA;
+
+/// Longest frame accepted from a client; they have nothing to say beyond pings
+const MAX_FRAME: usize = 8192;
+
+/// Accept clients on `listener` and stream them `events`, at most
+/// `max_clients` at a time
+pub async fn serve(listener: TcpListener, events: broadcast::Sender<EventView>, max_clients: usize) -> Result<()> {
+    info!("Streaming events at ws://{}/stream", listener.local_addr()?);
+    let clients = Arc::new(AtomicUsize::new(0));
+    loop {
+        let (socket, peer) = listener.accept().await?;
+        let slot = ClientSlot::claim(&clients, max_clients);
+        let events = events.clone();
+        tokio::spawn(async move {
+            if let Err(err) = follow(socket, slot, &events).await {
+                debug!("Stream client {} left: {}", peer, err);
+            }
+        });
+    }
+}
+
+/// A place among the connected clients, given up when dropped
+struct ClientSlot(Arc<AtomicUsize>);
+
+impl ClientSlot {
+    /// A slot, unless `max_clients` are already connected
+    fn claim(clients: &Arc<AtomicUsize>, max_clients: usize) -> Option<Self> {
+        clients
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| (n < max_clients).then_some(n + 1))
+            .ok()
+            .map(|_| Self(clients.clone()))
+    }
+}
+
+impl Drop for ClientSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Complete the handshake with one client and send it events until it falls
+/// behind, goes away or the run ends
+async fn follow(mut socket: TcpStream, slot: Option<ClientSlot>, events: &broadcast::Sender<EventView>) -> Result<()> {
+    let request = read_request(&mut socket).await?;
+    if !request.starts_with("GET /stream ") && !request.starts_with("GET /stream?") {
+        socket.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n").await?;
+        return Ok(());
+    }
+    let Some(key) = websocket_key(&request) else {
+        socket.write_all(b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n").await?;
+        return Ok(());
+    };
+    let Some(_slot) = slot else {
+        socket.write_all(b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n").await?;
+        return Ok(());
+    };
+
+    // Subscribe before the handshake completes, so nothing logged after it is missed
+    let mut receiver = events.subscribe();
+    let response = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        accept_key(key)
+    );
+    socket.write_all(response.as_bytes()).await?;
+
+    // Frames from the client are read on their own task, so a read cut short
+    // by an event going out never loses part of one
+    let (mut reader, mut writer) = socket.into_split();
+    let (frames_tx, mut frames) = tokio::sync::mpsc::channel(8);
+    tokio::spawn(async move {
+        while let Ok(frame) = read_frame(&mut reader).await {
+            if frames_tx.send(frame).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    loop {
+        tokio::select! {
+            event = receiver.recv() => match event {
+                Ok(view) => writer.write_all(&text_frame(&serde_json::to_string(&view)?)).await?,
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    warn!("Dropping a stream client {} events behind", missed);
+                    break;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            incoming = frames.recv() => match incoming {
+                Some((OP_PING, payload)) => writer.write_all(&frame(OP_PONG, &payload)).await?,
+                // Answer a close, then hang up
+                Some((OP_CLOSE, _)) => break,
+                Some(_) => {}
+                // The client went away without a word
+                None => return Ok(()),
+            },
+        }
+    }
+    writer.write_all(&frame(OP_CLOSE, &[])).await?;
+    Ok(())
+}
+
+/// One frame from a client: its opcode and unmasked payload
+async fn read_frame(reader: &mut (impl AsyncRead + Unpin)) -> Result<(u8, Vec<u8>)> {
+    let mut header = [0u8; 2];
+    reader.read_exact(&mut header).await?;
+    let len = match header[1] & 0x7f {
+        126 => reader.read_u16().await? as usize,
+        127 => reader.read_u64().await? as usize,
+        len => len as usize,
+    };
+    if len > MAX_FRAME {
+        anyhow::bail!("frame of {} bytes", len);
+    }
+    let mut mask = [0u8; 4];
+    if header[1] & 0x80 != 0 {
+        reader.read_exact(&mut mask).await?;
+    }
+    let mut payload = vec![0u8; len];
+    reader.read_exact(&mut payload).await?;
+    for (i, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[i % 4];
+    }
+    Ok((header[0] & 0x0f, payload))
+}
+
+/// The handshake request, up to the blank line ending its headers
+async fn read_request(socket: &mut TcpStream) -> Result<String> {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.ends_with(b"\r\n\r\n") {
+        let n = socket.read(&mut buf).await?;
+        if n == 0 || request.len() + n > MAX_REQUEST {
+            anyhow::bail!("incomplete handshake");
+        }
+        request.extend_from_slice(&buf[..n]);
+    }
+    Ok(String::from_utf8_lossy(&request).into_owned())
+}
+
+/// The client's `Sec-WebSocket-Key` header
+fn websocket_key(request: &str) -> Option<&str> {
+    request.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim().eq_ignore_ascii_case("sec-websocket-key").then(|| value.trim())
+    })
+}
+
+/// `Sec-WebSocket-Accept` answering a client's key
+fn accept_key(key: &str) -> String {
+    let digest = ring::digest::digest(&ring::digest::SHA1_FOR_LEGACY_USE_ONLY, format!("{}{}", key, HANDSHAKE_GUID).as_bytes());
+    base64::engine::general_purpose::STANDARD.encode(digest.as_ref())
+}
+
+/// An unmasked text frame, as a server sends them
+fn text_frame(text: &str) -> Vec<u8> {
+    frame(OP_TEXT, text.as_bytes())
+}
+
+/// An unmasked, unfragmented frame
+fn frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0x80 | opcode];
+    match payload.len() {
+        len if len < 126 => frame.push(len as u8),
+        len if len <= u16::MAX as usize => {
+            frame.push(126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::engine::Engine;
+
+    #[tokio::test]
+    async fn test_client_receives_events_logged_after_it_connects() {
+        let mut config = Config::default();
+        config.agents.count = 4;
+        config.llm.api_key_env = "TERRARIUM_TEST_NO_API_KEY".to_string();
+        let output_dir = std::env::temp_dir().join(format!("terrarium-test-{}", uuid::Uuid::new_v4()));
+        let mut engine = Engine::new(config, output_dir.to_str().unwrap()).unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener, engine.stream_events(), 1));
+
+        let mut client = TcpStream::connect(address).await.unwrap();
+        client
+            .write_all(b"GET /stream HTTP/1.1\r\nHost: localhost\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n")
+            .await
+            .unwrap();
+        let response = read_request(&mut client).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 101"), "{}", response);
+        // The example from RFC 6455
+        assert!(response.contains("Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo="), "{}", response);
+
+        // A second client is over the cap
+        let mut extra = TcpStream::connect(address).await.unwrap();
+        extra.write_all(b"GET /stream HTTP/1.1\r\nSec-WebSocket-Key: eA==\r\n\r\n").await.unwrap();
+        assert!(read_request(&mut extra).await.unwrap().starts_with("HTTP/1.1 503"));
+
+        engine.step().await.unwrap();
+        let first = engine.recent_event_views().into_iter().next().expect("an epoch with no events");
+
+        let mut header = [0u8; 2];
+        client.read_exact(&mut header).await.unwrap();
+        assert_eq!(header[0], 0x81);
+        let len = match header[1] {
+            126 => client.read_u16().await.unwrap() as usize,
+            len => len as usize,
+        };
+        let mut payload = vec![0u8; len];
+        client.read_exact(&mut payload).await.unwrap();
+        let frame: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(frame["epoch"], 0);
+        assert_eq!(frame["description"], first.description.as_str());
+        assert_eq!(frame["event_type"], serde_json::to_value(first.event_type).unwrap());
+
+        // Client frames are masked
+        let masked = |opcode: u8, payload: &[u8]| {
+            let mask = [0x12, 0x34, 0x56, 0x78];
+            let mut frame = vec![0x80 | opcode, 0x80 | payload.len() as u8];
+            frame.extend_from_slice(&mask);
+            frame.extend(payload.iter().enumerate().map(|(i, byte)| byte ^ mask[i % 4]));
+            frame
+        };
+        // Past any events still queued for the client, the reply to a frame
+        let mut reply_to = async |sent: Vec<u8>| {
+            client.write_all(&sent).await.unwrap();
+            loop {
+                let (opcode, payload) = read_frame(&mut client).await.unwrap();
+                if opcode != OP_TEXT {
+                    return (opcode, payload);
+                }
+            }
+        };
+        assert_eq!(reply_to(masked(OP_PING, b"still there?")).await, (OP_PONG, b"still there?".to_vec()));
+        assert_eq!(reply_to(masked(OP_CLOSE, &[])).await, (OP_CLOSE, Vec::new()));
+        let mut rest = Vec::new();
+        client.read_to_end(&mut rest).await.unwrap();
+        assert!(rest.is_empty());
+    }
+}
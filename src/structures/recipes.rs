@@ -134,6 +134,16 @@ impl StructureRecipeRegistry {
         Self { recipes }
     }
 
+    /// Registry with each structure's terrain restrictions replaced by `allowed`
+    /// (an empty list allows any terrain)
+    pub fn with_terrain(allowed: impl Fn(StructureType) -> Vec<TerrainType>) -> Self {
+        let mut registry = Self::new();
+        for recipe in registry.recipes.values_mut() {
+            recipe.allowed_terrain = allowed(recipe.output);
+        }
+        registry
+    }
+
     /// Get recipe for a structure type
     pub fn get(&self, structure_type: StructureType) -> Option<&StructureRecipe> {
         self.recipes.get(&structure_type)
@@ -6,6 +6,8 @@ use uuid::Uuid;
 
 use crate::crafting::{MaterialType, ToolQuality};
 
+use super::StructureRecipe;
+
 /// Types of structures that can be built
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum StructureType {
@@ -77,6 +79,17 @@ impl StructureType {
         }
     }
 
+    /// Fraction of attack damage kept off those inside (0.0 to 1.0)
+    pub fn attack_defense(&self) -> f64 {
+        match self {
+            StructureType::LeanTo => 0.25,
+            StructureType::Shelter => 0.5,
+            StructureType::Storage => 0.0,
+            StructureType::Workbench => 0.0,
+            StructureType::Farm => 0.0,
+        }
+    }
+
     /// Crafting quality bonus (percentage)
     pub fn crafting_bonus(&self) -> f64 {
         match self {
@@ -233,7 +246,7 @@ impl Structure {
         };
 
         Self {
-            id: Uuid::new_v4(),
+            id: crate::rng::uuid(),
             structure_type,
             owner,
             allowed_guests: Vec::new(),
@@ -289,9 +302,29 @@ impl Structure {
         self.durability as f64 / self.max_durability as f64
     }
 
+    /// Materials recovered when dismantling: half the build cost, scaled by durability,
+    /// plus anything held in storage
+    pub fn salvage(&self, recipe: &StructureRecipe) -> HashMap<MaterialType, u32> {
+        let ratio = self.durability_ratio();
+        let mut salvaged: HashMap<MaterialType, u32> = recipe
+            .materials
+            .iter()
+            .map(|(material, amount)| (*material, (*amount as f64 * 0.5 * ratio).round() as u32))
+            .filter(|(_, amount)| *amount > 0)
+            .collect();
+
+        if let Some(ref inventory) = self.inventory {
+            for (material, amount) in &inventory.materials {
+                *salvaged.entry(*material).or_insert(0) += amount;
+            }
+        }
+
+        salvaged
+    }
+
     /// Get effective hazard protection (quality and durability adjusted)
     pub fn effective_protection(&self) -> f64 {
-        if !self.is_complete() {
+        if !self.is_complete() || self.is_destroyed() {
             return 0.0;
         }
         let base = self.structure_type.hazard_protection() * self.quality.effectiveness_modifier();
@@ -299,9 +332,18 @@ impl Structure {
         base * (0.5 + 0.5 * self.durability_ratio())
     }
 
+    /// Get effective attack defense (quality and durability adjusted)
+    pub fn effective_defense(&self) -> f64 {
+        if !self.is_complete() || self.is_destroyed() {
+            return 0.0;
+        }
+        let base = self.structure_type.attack_defense() * self.quality.effectiveness_modifier();
+        (base * (0.5 + 0.5 * self.durability_ratio())).min(0.9)
+    }
+
     /// Get effective rest bonus (quality and durability adjusted)
     pub fn effective_rest_bonus(&self) -> f64 {
-        if !self.is_complete() {
+        if !self.is_complete() || self.is_destroyed() {
             return 0.0;
         }
         let base = self.structure_type.rest_bonus() * self.quality.effectiveness_modifier();
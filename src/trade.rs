@@ -120,7 +120,7 @@ impl TradeProposal {
         expiry_epochs: usize,
     ) -> Self {
         Self {
-            id: Uuid::new_v4(),
+            id: crate::rng::uuid(),
             proposer,
             recipient,
             offering,
@@ -142,7 +142,7 @@ impl TradeProposal {
         expiry_epochs: usize,
     ) -> Self {
         Self {
-            id: Uuid::new_v4(),
+            id: crate::rng::uuid(),
             // Swap proposer/recipient for counter
             proposer: original.recipient,
             recipient: original.proposer,
@@ -305,7 +305,7 @@ impl ServiceDebt {
         };
 
         Some(Self {
-            id: Uuid::new_v4(),
+            id: crate::rng::uuid(),
             debtor,
             creditor,
             service,
@@ -369,6 +369,46 @@ impl ServiceDebt {
     }
 }
 
+/// Summary of an agent's trading track record
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradeStats {
+    /// Trades the agent took part in that were accepted
+    pub accepted: usize,
+    /// Proposals the agent declined
+    pub declined: usize,
+    /// Promises the agent failed to keep
+    pub reneged: usize,
+}
+
+impl TradeStats {
+    /// Whether the agent has any trading record at all
+    pub fn is_empty(&self) -> bool {
+        self.accepted == 0 && self.declined == 0 && self.reneged == 0
+    }
+
+    /// Short reputation label for perception and display
+    pub fn reputation(&self) -> &'static str {
+        if self.reneged > 0 && self.reneged * 2 >= self.accepted {
+            "flaky"
+        } else if self.accepted > 0 {
+            "fair"
+        } else {
+            "untested"
+        }
+    }
+
+    /// Human-readable description
+    pub fn describe(&self) -> String {
+        format!(
+            "{} trader ({} accepted, {} declined, {} reneged)",
+            self.reputation(),
+            self.accepted,
+            self.declined,
+            self.reneged
+        )
+    }
+}
+
 /// Trade system state held by the engine
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TradeState {
@@ -400,19 +440,27 @@ impl TradeState {
     }
 
     /// Get pending proposals for a recipient
+    /// Oldest first, so proposal indices shown to agents stay stable
     pub fn pending_proposals_for(&self, recipient: Uuid) -> Vec<&TradeProposal> {
-        self.proposals
+        let mut pending: Vec<&TradeProposal> = self
+            .proposals
             .values()
             .filter(|p| p.recipient == recipient && p.status == ProposalStatus::Pending)
-            .collect()
+            .collect();
+        pending.sort_by_key(|p| (p.created_epoch, p.id));
+        pending
     }
 
     /// Get pending proposals from a proposer
+    /// Oldest first, so proposal indices shown to agents stay stable
     pub fn pending_proposals_from(&self, proposer: Uuid) -> Vec<&TradeProposal> {
-        self.proposals
+        let mut pending: Vec<&TradeProposal> = self
+            .proposals
             .values()
             .filter(|p| p.proposer == proposer && p.status == ProposalStatus::Pending)
-            .collect()
+            .collect();
+        pending.sort_by_key(|p| (p.created_epoch, p.id));
+        pending
     }
 
     /// Count pending proposals from an agent
@@ -420,6 +468,36 @@ impl TradeState {
         self.pending_proposals_from(proposer).len()
     }
 
+    /// Get all resolved (non-pending) proposals involving an agent, oldest first
+    pub fn history_for(&self, agent: Uuid) -> Vec<&TradeProposal> {
+        let mut history: Vec<&TradeProposal> = self
+            .proposals
+            .values()
+            .filter(|p| p.status != ProposalStatus::Pending)
+            .filter(|p| p.proposer == agent || p.recipient == agent)
+            .collect();
+        history.sort_by_key(|p| (p.created_epoch, p.id));
+        history
+    }
+
+    /// Summarize an agent's trading record
+    pub fn stats_for(&self, agent: Uuid) -> TradeStats {
+        let mut stats = TradeStats::default();
+        for proposal in self.history_for(agent) {
+            match proposal.status {
+                ProposalStatus::Accepted => stats.accepted += 1,
+                ProposalStatus::Declined if proposal.recipient == agent => stats.declined += 1,
+                _ => {}
+            }
+        }
+        stats.reneged = self
+            .service_debts
+            .iter()
+            .filter(|d| d.debtor == agent && d.reneged)
+            .count();
+        stats
+    }
+
     /// Add a service debt
     pub fn add_debt(&mut self, debt: ServiceDebt) {
         self.service_debts.push(debt);
@@ -465,7 +543,7 @@ impl TradeState {
             .collect();
 
         // Sort by epoch, oldest first
-        completed.sort_by_key(|(_, epoch)| *epoch);
+        completed.sort_by_key(|(id, epoch)| (*epoch, *id));
 
         // Remove oldest if over limit
         let to_remove = completed.len().saturating_sub(keep_count);
@@ -474,3 +552,55 @@ impl TradeState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_for_accepted_and_reneged() {
+        let alice = Uuid::new_v4();
+        let bob = Uuid::new_v4();
+        let mut state = TradeState::new();
+
+        let mut accepted = TradeProposal::new(
+            alice,
+            bob,
+            vec![TradeableItem::Food(3)],
+            vec![TradeableItem::TeachSkillPromise { skill: "foraging".to_string() }],
+            1,
+            5,
+        );
+        accepted.status = ProposalStatus::Accepted;
+        let source = accepted.id;
+        state.add_proposal(accepted);
+
+        // Still pending, so not part of the history
+        state.add_proposal(TradeProposal::new(alice, bob, vec![TradeableItem::Food(1)], vec![], 2, 5));
+
+        let mut debt = ServiceDebt::from_promise(
+            &TradeableItem::TeachSkillPromise { skill: "foraging".to_string() },
+            bob,
+            alice,
+            source,
+            1,
+            20,
+        )
+        .unwrap();
+        debt.mark_reneged();
+        state.add_debt(debt);
+
+        assert_eq!(state.history_for(bob).len(), 1);
+
+        let stats = state.stats_for(bob);
+        assert_eq!(stats.accepted, 1);
+        assert_eq!(stats.declined, 0);
+        assert_eq!(stats.reneged, 1);
+        assert_eq!(stats.reputation(), "flaky");
+
+        let stats = state.stats_for(alice);
+        assert_eq!(stats.accepted, 1);
+        assert_eq!(stats.reneged, 0);
+        assert_eq!(stats.reputation(), "fair");
+    }
+}
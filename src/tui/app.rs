@@ -2,6 +2,9 @@
 
 use uuid::Uuid;
 
+use crate::engine::Engine;
+use crate::observer::{AgentView, WorldView};
+
 /// TUI application state
 pub struct App {
     /// Whether simulation is running (auto-advancing)
@@ -33,6 +36,47 @@ pub struct App {
 
     /// Show trades panel (replaces events when active)
     pub show_trades: bool,
+
+    /// What the map shows, kept from frame to frame
+    pub map: Option<MapViews>,
+}
+
+/// The world and living agents as the map last drew them, patched with what
+/// changed since rather than rebuilt every frame
+pub struct MapViews {
+    pub world: WorldView,
+    pub agents: Vec<AgentView>,
+    /// The engine's epoch when last brought up to date
+    synced: usize,
+}
+
+impl MapViews {
+    pub fn capture(engine: &Engine) -> Self {
+        let mut agents = engine.agent_views();
+        agents.retain(|a| a.alive);
+        Self { world: engine.world_view(), agents, synced: engine.epoch() }
+    }
+
+    /// Bring the views up to the engine's current epoch
+    pub fn refresh(&mut self, engine: &Engine) {
+        if engine.epoch() == self.synced {
+            return;
+        }
+        let width = self.world.width;
+        for cell in engine.changed_cells_since(self.synced) {
+            let idx = cell.y * width + cell.x;
+            self.world.cells[idx] = cell;
+        }
+        for agent in engine.agent_deltas_since(self.synced) {
+            match self.agents.iter().position(|a| a.id == agent.id) {
+                Some(idx) => self.agents[idx] = agent,
+                None => self.agents.push(agent),
+            }
+        }
+        self.agents.retain(|a| a.alive);
+        self.synced = engine.epoch();
+        self.world.epoch = self.synced;
+    }
 }
 
 impl App {
@@ -48,6 +92,7 @@ impl App {
             show_events: true,
             show_agent: true,
             show_trades: false,
+            map: None,
         }
     }
 
@@ -11,12 +11,14 @@ pub use app::App;
 
 use anyhow::Result;
 use crossterm::{
+    cursor,
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::prelude::*;
-use std::io::{self, Stdout};
+use std::io::{self, IsTerminal, Stdout, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 
 use crate::config::Config;
@@ -24,49 +26,78 @@ use crate::engine::Engine;
 
 type Tui = Terminal<CrosstermBackend<Stdout>>;
 
-/// Run the TUI application
-pub async fn run(config: Config, output_dir: &str) -> Result<()> {
-    // Initialize terminal
-    let mut terminal = setup_terminal()?;
+/// Whether the terminal is in raw mode on the alternate screen and needs restoring
+static TERMINAL_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Why the TUI can't run here, if it can't
+pub fn unsupported_reason() -> Option<&'static str> {
+    if !io::stdout().is_terminal() {
+        Some("output is not a terminal")
+    } else if std::env::var("TERM").is_ok_and(|term| term == "dumb") {
+        Some("the terminal is too limited (TERM=dumb)")
+    } else {
+        None
+    }
+}
 
+/// Run the TUI application, capturing the mouse if `mouse` is set
+pub async fn run(config: Config, output_dir: &str, mouse: bool) -> Result<()> {
     // Create engine and app
     let mut engine = Engine::new(config, output_dir)?;
     engine.initialize()?;
 
     let mut app = App::new();
 
+    // Initialize terminal
+    let mut terminal = setup_terminal(mouse)?;
+
     // Main loop
     let result = run_app(&mut terminal, &mut engine, &mut app).await;
 
-    // Finalize
+    // Restore terminal, then finalize
+    restore_terminal();
     engine.finalize()?;
 
-    // Restore terminal
-    restore_terminal(&mut terminal)?;
-
     result
 }
 
-/// Set up the terminal for TUI rendering
-fn setup_terminal() -> Result<Tui> {
+/// Set up the terminal for TUI rendering; a panic restores it before the
+/// message is printed
+fn setup_terminal(mouse: bool) -> Result<Tui> {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        default_hook(info);
+    }));
+
     enable_raw_mode()?;
+    TERMINAL_ACTIVE.store(true, Ordering::SeqCst);
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    if let Err(e) = execute!(stdout, EnterAlternateScreen) {
+        restore_terminal();
+        return Err(e.into());
+    }
+    if mouse {
+        // Some terminals can't report the mouse; the keyboard still works
+        let _ = execute!(stdout, EnableMouseCapture);
+    }
     let backend = CrosstermBackend::new(stdout);
     let terminal = Terminal::new(backend)?;
     Ok(terminal)
 }
 
-/// Restore the terminal to normal mode
-fn restore_terminal(terminal: &mut Tui) -> Result<()> {
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
-    Ok(())
+/// Restore the terminal to normal mode; does nothing if it already is
+pub fn restore_terminal() {
+    let _ = restore_to(&mut io::stdout());
+}
+
+fn restore_to(out: &mut impl Write) -> io::Result<()> {
+    if !TERMINAL_ACTIVE.swap(false, Ordering::SeqCst) {
+        return Ok(());
+    }
+    let raw_mode = disable_raw_mode();
+    execute!(out, LeaveAlternateScreen, DisableMouseCapture, cursor::Show)?;
+    raw_mode
 }
 
 /// Main application loop
@@ -122,3 +153,49 @@ async fn run_app(terminal: &mut Tui, engine: &mut Engine, app: &mut App) -> Resu
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restore_after_panic_is_idempotent() {
+        let outcome = std::panic::catch_unwind(|| {
+            TERMINAL_ACTIVE.store(true, Ordering::SeqCst);
+            panic!("simulated engine failure mid-run");
+        });
+        assert!(outcome.is_err());
+
+        let (mut first, mut second) = (Vec::new(), Vec::new());
+        restore_to(&mut first).unwrap();
+        restore_to(&mut second).unwrap();
+        assert!(!first.is_empty());
+        assert!(second.is_empty());
+        assert!(!TERMINAL_ACTIVE.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_patched_map_matches_a_fresh_one() {
+        let mut config = Config::default();
+        config.agents.count = 8;
+        config.llm.api_key_env = "TERRARIUM_TEST_NO_API_KEY".to_string();
+        let output_dir = std::env::temp_dir().join(format!("terrarium-test-{}", uuid::Uuid::new_v4()));
+        let mut engine = Engine::new(config, output_dir.to_str().unwrap()).unwrap();
+        let mut map = app::MapViews::capture(&engine);
+        for _ in 0..5 {
+            engine.step().await.unwrap();
+            map.refresh(&engine);
+        }
+
+        let fresh = app::MapViews::capture(&engine);
+        let json = |views: &app::MapViews| serde_json::to_string(&views.world).unwrap();
+        assert_eq!(json(&map), json(&fresh));
+        let placed = |views: &app::MapViews| {
+            let mut agents: Vec<_> = views.agents.iter().map(|a| (a.id, a.position, a.food)).collect();
+            agents.sort();
+            agents
+        };
+        assert_eq!(placed(&map), placed(&fresh));
+        std::fs::remove_dir_all(&output_dir).ok();
+    }
+}
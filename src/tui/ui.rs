@@ -8,6 +8,7 @@ use ratatui::{
     Frame,
 };
 
+use super::app::MapViews;
 use super::widgets;
 use super::App;
 use crate::engine::Engine;
@@ -72,11 +73,12 @@ pub fn draw(frame: &mut Frame, engine: &Engine, app: &mut App) {
 }
 
 /// Draw the world map
-fn draw_world(frame: &mut Frame, area: Rect, engine: &Engine, app: &App) {
-    let world_view = engine.world_view();
-    let agent_views = engine.agent_views();
+fn draw_world(frame: &mut Frame, area: Rect, engine: &Engine, app: &mut App) {
+    let selected = app.selected_agent;
+    let map = app.map.get_or_insert_with(|| MapViews::capture(engine));
+    map.refresh(engine);
 
-    widgets::world::draw(frame, area, &world_view, &agent_views, app.selected_agent);
+    widgets::world::draw(frame, area, &map.world, &map.agents, selected);
 }
 
 /// Draw the events panel
@@ -112,11 +112,16 @@ fn draw_stats(frame: &mut Frame, area: Rect, agent: &AgentView) {
     } else {
         Color::Red
     };
+    let energy_label = if agent.incapacitated {
+        format!("{}% (collapsed)", energy_pct)
+    } else {
+        format!("{}%", energy_pct)
+    };
     let energy_gauge = Gauge::default()
         .block(Block::default().title("Energy"))
         .gauge_style(Style::default().fg(energy_color))
         .percent(energy_pct)
-        .label(format!("{}%", energy_pct));
+        .label(energy_label);
     frame.render_widget(energy_gauge, chunks[2]);
 
     // Position, age, and inventory
@@ -127,6 +132,7 @@ fn draw_stats(frame: &mut Frame, area: Rect, agent: &AgentView) {
         "ancient" => Color::Red,
         _ => Color::White,
     };
+    let (r, g, b) = agent.phenotype.rgb();
     let gen_display = if agent.generation > 0 {
         format!(" Gen {}", agent.generation)
     } else {
@@ -139,6 +145,8 @@ fn draw_stats(frame: &mut Frame, area: Rect, agent: &AgentView) {
             Style::default().fg(age_color),
         ),
         Span::styled(gen_display, Style::default().fg(Color::Magenta)),
+        Span::raw("  Looks: "),
+        Span::styled(agent.phenotype.glyph.to_string(), Style::default().fg(Color::Rgb(r, g, b)).add_modifier(Modifier::BOLD)),
         Span::raw("  Pos: "),
         Span::styled(
             format!("({}, {})", agent.position.0, agent.position.1),
@@ -299,6 +307,25 @@ fn draw_info(frame: &mut Frame, area: Rect, agent: &AgentView, show_full: bool)
         }
     }
 
+    // Trade record
+    if !agent.trade_stats.is_empty() {
+        let stats = &agent.trade_stats;
+        let reputation_color = match stats.reputation() {
+            "fair" => Color::Green,
+            "flaky" => Color::Red,
+            _ => Color::White,
+        };
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::raw("Trader: "),
+            Span::styled(stats.reputation(), Style::default().fg(reputation_color)),
+            Span::styled(
+                format!(" ({}✓ {}✗ {} reneged)", stats.accepted, stats.declined, stats.reneged),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+    }
+
     // Recent memories (if full view)
     if show_full && !agent.recent_memories.is_empty() {
         lines.push(Line::from(""));
@@ -55,8 +55,10 @@ pub fn draw(frame: &mut Frame, area: Rect, events: &[EventView], current_epoch:
             EventViewType::Speech => ("", Style::default().fg(Color::Yellow)),
             EventViewType::Gift => ("→", Style::default().fg(Color::Magenta)),
             EventViewType::Attack => ("!", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+            EventViewType::CombatOutcome => ("»", Style::default().fg(Color::LightRed)),
             EventViewType::AllyIntervened => ("⛨", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
             EventViewType::Death => ("†", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+            EventViewType::Scavenging => ("‡", Style::default().fg(Color::DarkGray).add_modifier(Modifier::BOLD)),
             EventViewType::Gossip => ("◊", Style::default().fg(Color::LightMagenta)),
             EventViewType::GroupFormed => ("★", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
             EventViewType::GroupDissolved => ("☆", Style::default().fg(Color::DarkGray)),
@@ -65,7 +67,10 @@ pub fn draw(frame: &mut Frame, area: Rect, events: &[EventView], current_epoch:
             EventViewType::RivalryFormed => ("⚔", Style::default().fg(Color::Red)),
             EventViewType::RivalryChanged => ("↔", Style::default().fg(Color::LightRed)),
             EventViewType::RivalryEnded => ("☮", Style::default().fg(Color::Green)),
+            EventViewType::Migration => ("⇶", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            EventViewType::WorldEvent => ("☄", Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD)),
             EventViewType::Courtship => ("♥", Style::default().fg(Color::LightMagenta)),
+            EventViewType::MateFailed => ("♡", Style::default().fg(Color::DarkGray)),
             EventViewType::Conception => ("♥", Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)),
             EventViewType::Birth => ("★", Style::default().fg(Color::LightGreen).add_modifier(Modifier::BOLD)),
             EventViewType::SkillTaught => ("✦", Style::default().fg(Color::Cyan)),
@@ -84,7 +89,9 @@ pub fn draw(frame: &mut Frame, area: Rect, events: &[EventView], current_epoch:
             EventViewType::TerritoryLost => ("▢", Style::default().fg(Color::DarkGray)),
             // Structure events
             EventViewType::FarmProduced => ("♠", Style::default().fg(Color::Green)),
+            EventViewType::StructureBuilt => ("⌂", Style::default().fg(Color::LightYellow)),
             EventViewType::StructureDestroyed => ("✗", Style::default().fg(Color::Red)),
+            EventViewType::StructureDismantled => ("⚒", Style::default().fg(Color::DarkGray)),
             // Trade events
             EventViewType::TradeProposed => ("⇄", Style::default().fg(Color::Yellow)),
             EventViewType::TradeAccepted => ("✓", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
@@ -94,6 +101,7 @@ pub fn draw(frame: &mut Frame, area: Rect, events: &[EventView], current_epoch:
             EventViewType::TradeCancelled => ("⊘", Style::default().fg(Color::DarkGray)),
             EventViewType::TradeReneged => ("!", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
             EventViewType::ServiceFulfilled => ("✓", Style::default().fg(Color::Cyan)),
+            EventViewType::GoalSet => ("➚", Style::default().fg(Color::Yellow)),
             EventViewType::Meta => ("", Style::default().fg(Color::DarkGray)),
         };
 
@@ -65,8 +65,8 @@ pub fn draw(
             };
 
             let (ch, style) = if let Some(agent) = agent_here {
-                // Agent present
-                let first_char = agent.name.chars().next().unwrap_or('?');
+                // Agent present, in their family's glyph and colour
+                let (r, g, b) = agent.phenotype.rgb();
                 let is_selected = selected == Some(agent.id);
 
                 let mut style = if is_selected {
@@ -75,7 +75,7 @@ pub fn draw(
                         .bg(Color::Yellow)
                         .add_modifier(Modifier::BOLD)
                 } else {
-                    Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+                    Style::default().fg(Color::Rgb(r, g, b)).add_modifier(Modifier::BOLD)
                 };
 
                 // Apply territory background if not selected (selected has priority)
@@ -85,7 +85,7 @@ pub fn draw(
                     }
                 }
 
-                (first_char, style)
+                (agent.phenotype.glyph, style)
             } else if let Some(cell) = cell {
                 // No agent - check for structure first
                 if let Some(ref structure) = cell.structure {
@@ -121,7 +121,14 @@ pub fn draw(
                                 ('.', Style::default().fg(Color::DarkGray))
                             }
                         }
+                        Terrain::Forest => {
+                            let color = if cell.food > 10 { Color::Green } else { Color::DarkGray };
+                            ('♣', Style::default().fg(color))
+                        }
                         Terrain::Barren => ('.', Style::default().fg(Color::Rgb(50, 50, 50))),
+                        Terrain::Desert => ('~', Style::default().fg(Color::Rgb(120, 100, 50))),
+                        Terrain::Mountain => ('^', Style::default().fg(Color::Gray)),
+                        Terrain::Water => ('≈', Style::default().fg(Color::Blue)),
                     };
                     // Apply territory background
                     if let Some(bg) = territory_bg {
@@ -144,12 +151,12 @@ pub fn draw(
         let (x, y) = agent.position;
         if y < lines.len() && x * 2 < inner.width as usize {
             // Mark death location with a cross
-            let first_char = agent.name.chars().next().unwrap_or('?');
+            let glyph = agent.phenotype.glyph;
             lines[y] = {
                 let mut spans: Vec<Span> = lines[y].spans.clone();
                 if x < spans.len() {
                     spans[x] = Span::styled(
-                        format!("{} ", first_char.to_ascii_lowercase()),
+                        format!("{} ", glyph.to_ascii_lowercase()),
                         Style::default().fg(Color::DarkGray),
                     );
                 }
@@ -1,7 +1,10 @@
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
 use uuid::Uuid;
 
+use crate::crafting::Tool;
 use crate::structures::Structure;
 
 /// A territorial claim on a cell
@@ -19,6 +22,99 @@ pub struct TerritoryClaim {
     pub strength: f64,
 }
 
+/// A rectangular region of the world
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Region {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Region {
+    /// Check if a position lies inside this region
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+}
+
+/// A world-wide or regional event affecting everyone in its area
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WorldEvent {
+    /// Disease sweeping the land (severity 0.0 to 1.0)
+    Plague { severity: f64 },
+    /// Unusually plentiful food (None = everywhere)
+    Abundance { region: Option<Region> },
+    /// Food is hard to come by (None = everywhere)
+    Scarcity { region: Option<Region> },
+    /// Localized catastrophe around a point
+    Disaster { x: usize, y: usize, radius: usize },
+}
+
+impl WorldEvent {
+    /// Whether this event affects the given position
+    pub fn affects(&self, x: usize, y: usize) -> bool {
+        match self {
+            WorldEvent::Plague { .. } => true,
+            WorldEvent::Abundance { region } | WorldEvent::Scarcity { region } => {
+                region.map(|r| r.contains(x, y)).unwrap_or(true)
+            }
+            WorldEvent::Disaster { x: dx, y: dy, radius } => {
+                x.abs_diff(*dx) <= *radius && y.abs_diff(*dy) <= *radius
+            }
+        }
+    }
+
+    /// Description for agent perception
+    pub fn describe(&self) -> String {
+        match self {
+            WorldEvent::Plague { severity } => {
+                let strength = if *severity > 0.6 { "deadly" } else { "spreading" };
+                format!("A {} plague is sweeping the land", strength)
+            }
+            WorldEvent::Abundance { .. } => "Food is unusually plentiful here".to_string(),
+            WorldEvent::Scarcity { .. } => "Food has become scarce here".to_string(),
+            WorldEvent::Disaster { x, y, .. } => {
+                format!("A disaster has struck near ({}, {})", x, y)
+            }
+        }
+    }
+
+    /// What happened, for the chronicle
+    pub fn headline(&self) -> String {
+        match self {
+            WorldEvent::Plague { severity } => {
+                let strength = if *severity > 0.6 { "A deadly" } else { "A" };
+                format!("{} plague broke out", strength)
+            }
+            WorldEvent::Abundance { .. } => "Food grew unusually plentiful".to_string(),
+            WorldEvent::Scarcity { .. } => "Famine set in".to_string(),
+            WorldEvent::Disaster { x, y, .. } => format!("Disaster struck near ({}, {})", x, y),
+        }
+    }
+
+    /// How a coming catastrophe is foretold; None for events nobody need fear
+    pub fn forecast(&self) -> Option<String> {
+        match self {
+            WorldEvent::Plague { .. } => Some("a plague".to_string()),
+            WorldEvent::Abundance { .. } => None,
+            WorldEvent::Scarcity { .. } => Some("a famine".to_string()),
+            WorldEvent::Disaster { x, y, .. } => Some(format!("a disaster near ({}, {})", x, y)),
+        }
+    }
+}
+
+/// A world event with its active period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveWorldEvent {
+    pub event: WorldEvent,
+    pub started_epoch: usize,
+    pub ends_epoch: usize,
+    /// Whether the engine has brought the event to bear yet
+    #[serde(default)]
+    pub struck: bool,
+}
+
 /// Terrain type alias for external modules
 pub type TerrainType = Terrain;
 
@@ -29,6 +125,16 @@ pub struct World {
     pub height: usize,
     pub cells: Vec<Cell>,
     pub epoch: usize,
+    /// World events currently in effect
+    #[serde(default)]
+    pub active_events: Vec<ActiveWorldEvent>,
+    /// World events that have ended
+    #[serde(default)]
+    pub event_history: Vec<ActiveWorldEvent>,
+    /// Environment phase of the latest epoch, as given by `EnvironmentConfig::state_at`;
+    /// the world keeps no season cycle of its own
+    #[serde(default)]
+    pub phase: String,
 }
 
 /// A single cell in the grid
@@ -43,6 +149,22 @@ pub struct Cell {
     pub structure: Option<Structure>,
     /// Territory claim on this cell (if any)
     pub territory: Option<TerritoryClaim>,
+    /// Tools left lying here, e.g. by someone who died on this cell
+    #[serde(default)]
+    pub dropped_tools: Vec<Tool>,
+    /// Bodies of those who died here (only kept when scavenging is enabled)
+    #[serde(default)]
+    pub remains: Vec<Remains>,
+}
+
+/// What is left of an agent who died on a cell
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Remains {
+    pub agent_id: Uuid,
+    pub name: String,
+    pub died_epoch: usize,
+    /// Food still to be had from them
+    pub food: u32,
 }
 
 /// Terrain types
@@ -50,36 +172,168 @@ pub struct Cell {
 pub enum Terrain {
     Fertile,
     Barren,
+    /// Woodland: more food than open ground, strongly seasonal
+    Forest,
+    /// Dry land with a little food that barely grows back
+    Desert,
+    /// High, rocky ground: slow going and almost no food
+    Mountain,
+    /// Lakes and rivers, crossed only by those who can swim
+    Water,
+}
+
+impl Terrain {
+    /// Multiplier on the energy it takes to step onto this terrain
+    pub fn movement_cost(self) -> f64 {
+        match self {
+            Terrain::Fertile | Terrain::Barren => 1.0,
+            Terrain::Forest => 1.25,
+            Terrain::Desert => 1.5,
+            Terrain::Mountain => 2.5,
+            Terrain::Water => 2.0,
+        }
+    }
+
+    /// Whether anyone can step onto this terrain; water takes a swimmer
+    pub fn is_passable(self) -> bool {
+        !matches!(self, Terrain::Water)
+    }
 }
 
 /// Configuration for world generation
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorldConfig {
     pub width: usize,
     pub height: usize,
     pub fertile_fraction: f64,
     pub initial_food_per_fertile: u32,
     pub food_regen_rate: f64,
+    /// Share of food-bearing cells that are forest rather than open ground
+    #[serde(default = "default_forest_fraction")]
+    pub forest_fraction: f64,
+    /// Share of barren cells that are desert rather than bare rock
+    #[serde(default = "default_desert_fraction")]
+    pub desert_fraction: f64,
+    /// Share of barren cells that are mountain
+    #[serde(default)]
+    pub mountain_fraction: f64,
+    /// Share of barren cells that are water
+    #[serde(default)]
+    pub water_fraction: f64,
+    /// Food capacity and regeneration per terrain type
+    #[serde(default)]
+    pub terrain_yields: TerrainYields,
+}
+
+fn default_forest_fraction() -> f64 { 0.3 }
+fn default_desert_fraction() -> f64 { 0.5 }
+fn default_one() -> f64 { 1.0 }
+
+/// How much food one terrain type holds and how fast it grows back
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerrainYield {
+    /// Maximum food a cell can hold
+    pub capacity: u32,
+    /// Regeneration multiplier on top of the world's base rate
+    #[serde(default = "default_one")]
+    pub regen: f64,
+    /// Extra regeneration multipliers keyed by season; a key applies when the
+    /// current phase name contains it (so "spring" also covers "Spring Thaw")
+    #[serde(default)]
+    pub seasons: HashMap<String, f64>,
+}
+
+impl TerrainYield {
+    fn new(capacity: u32, regen: f64, seasons: &[(&str, f64)]) -> Self {
+        Self {
+            capacity,
+            regen,
+            seasons: seasons.iter().map(|(name, m)| (name.to_string(), *m)).collect(),
+        }
+    }
+
+    /// Regeneration multiplier during the named phase
+    pub fn regen_in(&self, phase: &str) -> f64 {
+        let phase = phase.to_lowercase();
+        let seasonal = self
+            .seasons
+            .iter()
+            .find(|(season, _)| phase.contains(&season.to_lowercase()))
+            .map(|(_, m)| *m)
+            .unwrap_or(1.0);
+        self.regen * seasonal
+    }
+}
+
+/// Food yields for every terrain type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TerrainYields {
+    pub fertile: TerrainYield,
+    pub barren: TerrainYield,
+    pub forest: TerrainYield,
+    pub desert: TerrainYield,
+    pub mountain: TerrainYield,
+    pub water: TerrainYield,
+}
+
+impl Default for TerrainYields {
+    fn default() -> Self {
+        Self {
+            fertile: TerrainYield::new(20, 1.0, &[]),
+            barren: TerrainYield::new(0, 0.0, &[]),
+            forest: TerrainYield::new(25, 1.0, &[("spring", 1.5), ("summer", 1.2), ("winter", 0.4)]),
+            desert: TerrainYield::new(6, 0.2, &[]),
+            mountain: TerrainYield::new(3, 0.2, &[]),
+            water: TerrainYield::new(0, 0.0, &[]),
+        }
+    }
+}
+
+impl TerrainYields {
+    /// Yield settings for a terrain type
+    pub fn get(&self, terrain: Terrain) -> &TerrainYield {
+        match terrain {
+            Terrain::Fertile => &self.fertile,
+            Terrain::Barren => &self.barren,
+            Terrain::Forest => &self.forest,
+            Terrain::Desert => &self.desert,
+            Terrain::Mountain => &self.mountain,
+            Terrain::Water => &self.water,
+        }
+    }
 }
 
 impl World {
     /// Create a new world from configuration
     pub fn new(config: &WorldConfig) -> Self {
-        let mut rng = rand::rng();
+        let mut rng = crate::rng::rng(crate::rng::Stream::World);
         let mut cells = Vec::with_capacity(config.width * config.height);
 
         for y in 0..config.height {
             for x in 0..config.width {
                 let terrain = if rng.random::<f64>() < config.fertile_fraction {
-                    Terrain::Fertile
+                    if rng.random::<f64>() < config.forest_fraction {
+                        Terrain::Forest
+                    } else {
+                        Terrain::Fertile
+                    }
                 } else {
-                    Terrain::Barren
+                    // One roll splits the barren land, so worlds without mountains or water are unchanged
+                    let roll = rng.random::<f64>();
+                    if roll < config.desert_fraction {
+                        Terrain::Desert
+                    } else if roll < config.desert_fraction + config.mountain_fraction {
+                        Terrain::Mountain
+                    } else if roll < config.desert_fraction + config.mountain_fraction + config.water_fraction {
+                        Terrain::Water
+                    } else {
+                        Terrain::Barren
+                    }
                 };
 
-                let (food, food_capacity) = match terrain {
-                    Terrain::Fertile => (config.initial_food_per_fertile, 20),
-                    Terrain::Barren => (0, 0),
-                };
+                let food_capacity = config.terrain_yields.get(terrain).capacity;
+                let food = config.initial_food_per_fertile.min(food_capacity);
 
                 cells.push(Cell {
                     x,
@@ -89,6 +343,8 @@ impl World {
                     food_capacity,
                     structure: None,
                     territory: None,
+                    dropped_tools: Vec::new(),
+                    remains: Vec::new(),
                 });
             }
         }
@@ -98,6 +354,9 @@ impl World {
             height: config.height,
             cells,
             epoch: 0,
+            active_events: Vec::new(),
+            event_history: Vec::new(),
+            phase: String::new(),
         }
     }
 
@@ -143,11 +402,13 @@ impl World {
     ///
     /// The `modifier` parameter adjusts the regeneration rate based on environmental
     /// conditions (seasons, weather, etc.). A modifier of 1.0 is normal, < 1.0 reduces
-    /// regeneration, > 1.0 increases it.
-    pub fn regenerate_resources(&mut self, regen_rate: f64, modifier: f64) {
+    /// regeneration, > 1.0 increases it. On top of that each terrain type grows back
+    /// at its own rate, which may depend on the current `phase`.
+    pub fn regenerate_resources(&mut self, config: &WorldConfig, modifier: f64, phase: &str) {
         for cell in &mut self.cells {
-            if cell.terrain == Terrain::Fertile && cell.food < cell.food_capacity {
-                let effective_rate = regen_rate * modifier;
+            if cell.food < cell.food_capacity {
+                let terrain_rate = config.terrain_yields.get(cell.terrain).regen_in(phase);
+                let effective_rate = config.food_regen_rate * modifier * terrain_rate;
                 let regen = (cell.food_capacity as f64 * effective_rate).ceil() as u32;
                 cell.food = (cell.food + regen).min(cell.food_capacity);
             }
@@ -158,9 +419,78 @@ impl World {
     ///
     /// The `food_regen_modifier` adjusts resource regeneration based on environmental
     /// conditions (1.0 = normal, < 1.0 = scarce, > 1.0 = abundant).
-    pub fn tick(&mut self, regen_rate: f64, food_regen_modifier: f64) {
+    pub fn tick(&mut self, config: &WorldConfig, food_regen_modifier: f64, phase: &str) {
         self.epoch += 1;
-        self.regenerate_resources(regen_rate, food_regen_modifier);
+        self.phase = phase.to_string();
+        self.regenerate_resources(config, food_regen_modifier, phase);
+        self.expire_events();
+    }
+
+    /// Start a world event lasting `duration` epochs
+    pub fn start_event(&mut self, event: WorldEvent, duration: usize) {
+        self.start_event_at(event, self.epoch, duration);
+    }
+
+    /// Start a world event at a specific epoch lasting `duration` epochs
+    pub fn start_event_at(&mut self, event: WorldEvent, epoch: usize, duration: usize) {
+        self.active_events.push(ActiveWorldEvent {
+            event,
+            started_epoch: epoch,
+            ends_epoch: epoch + duration,
+            struck: false,
+        });
+    }
+
+    /// Randomly begin a new world event with the given chance
+    pub fn maybe_generate_event(&mut self, chance: f64, duration: usize) -> Option<WorldEvent> {
+        let mut rng = crate::rng::rng(crate::rng::Stream::World);
+        if rng.random::<f64>() >= chance {
+            return None;
+        }
+
+        let random_region = |rng: &mut crate::rng::SimRng| {
+            let width = (self.width / 2).max(1);
+            let height = (self.height / 2).max(1);
+            Region {
+                x: rng.random_range(0..=self.width - width),
+                y: rng.random_range(0..=self.height - height),
+                width,
+                height,
+            }
+        };
+
+        let event = match rng.random_range(0..4) {
+            0 => WorldEvent::Plague { severity: rng.random_range(0.2..0.8) },
+            1 => WorldEvent::Abundance { region: Some(random_region(&mut rng)) },
+            2 => WorldEvent::Scarcity { region: Some(random_region(&mut rng)) },
+            _ => WorldEvent::Disaster {
+                x: rng.random_range(0..self.width),
+                y: rng.random_range(0..self.height),
+                radius: 1,
+            },
+        };
+
+        self.start_event(event.clone(), duration);
+        Some(event)
+    }
+
+    /// Move ended events into the history
+    fn expire_events(&mut self) {
+        let epoch = self.epoch;
+        let (ended, active) = std::mem::take(&mut self.active_events)
+            .into_iter()
+            .partition(|e| epoch >= e.ends_epoch);
+        self.active_events = active;
+        self.event_history.extend::<Vec<_>>(ended);
+    }
+
+    /// Descriptions of active world events affecting a position
+    pub fn describe_events_at(&self, x: usize, y: usize) -> Vec<String> {
+        self.active_events
+            .iter()
+            .filter(|e| e.event.affects(x, y))
+            .map(|e| e.event.describe())
+            .collect()
     }
 
     /// Describe a cell for agent perception
@@ -170,6 +500,10 @@ impl World {
                 let terrain_desc = match cell.terrain {
                     Terrain::Fertile => "fertile ground",
                     Terrain::Barren => "barren land",
+                    Terrain::Forest => "forest",
+                    Terrain::Desert => "desert",
+                    Terrain::Mountain => "mountain",
+                    Terrain::Water => "water",
                 };
                 let food_desc = if cell.food > 10 {
                     "abundant food"
@@ -256,6 +590,54 @@ fn direction_name(from_x: usize, from_y: usize, to_x: usize, to_y: usize) -> &'s
     }
 }
 
+/// Cheapest way from one cell to another (A*), stepping to any of the eight
+/// neighbours over passable ground (or water too, for a swimmer) and paying
+/// each cell's movement cost on entering it. The cells after `from`, ending
+/// with `to`; None if `to` can't be reached
+pub fn pathfind(from: (usize, usize), to: (usize, usize), world: &World, can_swim: bool) -> Option<Vec<(usize, usize)>> {
+    let walkable = |cell: &Cell| can_swim || cell.terrain.is_passable();
+    if world.get(from.0, from.1).is_none() || !walkable(world.get(to.0, to.1)?) {
+        return None;
+    }
+    // Costs in thousandths, so the open set orders them exactly
+    let cost = |cell: &Cell| (cell.terrain.movement_cost() * 1000.0).round() as u64;
+    // No step costs less than 1000, so straight-line steps never overestimate
+    let estimate = |(x, y): (usize, usize)| x.abs_diff(to.0).max(y.abs_diff(to.1)) as u64 * 1000;
+
+    let mut open = BinaryHeap::from([Reverse((estimate(from), from))]);
+    let mut spent: HashMap<(usize, usize), u64> = HashMap::from([(from, 0)]);
+    let mut came_from: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+    while let Some(Reverse((priority, at))) = open.pop() {
+        if at == to {
+            let mut path = Vec::new();
+            let mut step = to;
+            while step != from {
+                path.push(step);
+                step = came_from[&step];
+            }
+            path.reverse();
+            return Some(path);
+        }
+        let so_far = spent[&at];
+        if priority > so_far + estimate(at) {
+            continue; // Already reached more cheaply
+        }
+        for cell in world.adjacent(at.0, at.1) {
+            if !walkable(cell) {
+                continue;
+            }
+            let next = (cell.x, cell.y);
+            let total = so_far + cost(cell);
+            if spent.get(&next).is_none_or(|&best| total < best) {
+                spent.insert(next, total);
+                came_from.insert(next, at);
+                open.push(Reverse((total + estimate(next), next)));
+            }
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -268,6 +650,11 @@ mod tests {
             fertile_fraction: 0.3,
             initial_food_per_fertile: 15,
             food_regen_rate: 0.1,
+            forest_fraction: 0.0,
+            desert_fraction: 0.0,
+            mountain_fraction: 0.0,
+            water_fraction: 0.0,
+            terrain_yields: TerrainYields::default(),
         };
         let world = World::new(&config);
         assert_eq!(world.cells.len(), 100);
@@ -283,6 +670,11 @@ mod tests {
             fertile_fraction: 1.0,
             initial_food_per_fertile: 10,
             food_regen_rate: 0.1,
+            forest_fraction: 0.0,
+            desert_fraction: 0.0,
+            mountain_fraction: 0.0,
+            water_fraction: 0.0,
+            terrain_yields: TerrainYields::default(),
         };
         let world = World::new(&config);
         assert!(world.get(0, 0).is_some());
@@ -298,6 +690,11 @@ mod tests {
             fertile_fraction: 1.0,
             initial_food_per_fertile: 10,
             food_regen_rate: 0.1,
+            forest_fraction: 0.0,
+            desert_fraction: 0.0,
+            mountain_fraction: 0.0,
+            water_fraction: 0.0,
+            terrain_yields: TerrainYields::default(),
         };
         let world = World::new(&config);
 
@@ -309,4 +706,109 @@ mod tests {
         let adj = world.adjacent(2, 2);
         assert_eq!(adj.len(), 8);
     }
+
+    #[test]
+    fn test_regional_events() {
+        let config = WorldConfig {
+            width: 10,
+            height: 10,
+            fertile_fraction: 1.0,
+            initial_food_per_fertile: 10,
+            food_regen_rate: 0.1,
+            forest_fraction: 0.0,
+            desert_fraction: 0.0,
+            mountain_fraction: 0.0,
+            water_fraction: 0.0,
+            terrain_yields: TerrainYields::default(),
+        };
+        let mut world = World::new(&config);
+        let region = Region { x: 0, y: 0, width: 5, height: 5 };
+        world.start_event(WorldEvent::Scarcity { region: Some(region) }, 2);
+
+        assert_eq!(world.describe_events_at(1, 1).len(), 1);
+        assert!(world.describe_events_at(8, 8).is_empty());
+
+        world.tick(&config, 1.0, "Spring");
+        assert_eq!(world.active_events.len(), 1);
+        world.tick(&config, 1.0, "Spring");
+        assert!(world.active_events.is_empty());
+        assert_eq!(world.event_history.len(), 1);
+    }
+
+    #[test]
+    fn test_forest_outgrows_desert_in_spring() {
+        let config = WorldConfig {
+            width: 2,
+            height: 1,
+            fertile_fraction: 1.0,
+            initial_food_per_fertile: 10,
+            food_regen_rate: 0.1,
+            forest_fraction: 0.0,
+            desert_fraction: 0.0,
+            mountain_fraction: 0.0,
+            water_fraction: 0.0,
+            terrain_yields: TerrainYields::default(),
+        };
+        let mut world = World::new(&config);
+        for (cell, terrain) in world.cells.iter_mut().zip([Terrain::Forest, Terrain::Desert]) {
+            cell.terrain = terrain;
+            cell.food_capacity = config.terrain_yields.get(terrain).capacity;
+            cell.food = 0;
+        }
+
+        world.regenerate_resources(&config, 1.0, "Spring");
+        let (forest, desert) = (world.cells[0].food, world.cells[1].food);
+        assert!(forest > desert, "forest {} vs desert {}", forest, desert);
+
+        world.cells[0].food = 0;
+        world.regenerate_resources(&config, 1.0, "Winter");
+        assert!(world.cells[0].food < forest);
+    }
+
+    #[test]
+    fn test_path_goes_around_water_and_over_no_mountain_it_can_skirt() {
+        let config = WorldConfig {
+            width: 7,
+            height: 5,
+            fertile_fraction: 1.0,
+            initial_food_per_fertile: 10,
+            food_regen_rate: 0.1,
+            forest_fraction: 0.0,
+            desert_fraction: 0.0,
+            mountain_fraction: 0.0,
+            water_fraction: 0.0,
+            terrain_yields: TerrainYields::default(),
+        };
+        let mut world = World::new(&config);
+        // A lake across x = 3 with a way through only at the bottom
+        for y in 0..4 {
+            world.get_mut(3, y).unwrap().terrain = Terrain::Water;
+        }
+        let path = pathfind((0, 0), (6, 0), &world, false).expect("a way around the lake");
+        assert!(path.contains(&(3, 4)), "{:?}", path);
+        assert_eq!(path.last(), Some(&(6, 0)));
+        let mut at: (usize, usize) = (0, 0);
+        for &step in &path {
+            assert!(at.0.abs_diff(step.0) <= 1 && at.1.abs_diff(step.1) <= 1, "{:?} to {:?}", at, step);
+            assert!(world.get(step.0, step.1).unwrap().terrain.is_passable());
+            at = step;
+        }
+
+        // A mountain in the way is skirted when going round costs less than climbing
+        world.get_mut(1, 4).unwrap().terrain = Terrain::Mountain;
+        let path = pathfind((0, 4), (2, 4), &world, false).unwrap();
+        assert_eq!(path.len(), 2);
+        assert!(!path.contains(&(1, 4)), "{:?}", path);
+
+        // Sealing the gap leaves no way across, and nobody can walk onto water
+        world.get_mut(3, 4).unwrap().terrain = Terrain::Water;
+        assert_eq!(pathfind((0, 0), (6, 0), &world, false), None);
+        assert_eq!(pathfind((0, 0), (3, 0), &world, false), None);
+        assert_eq!(pathfind((2, 2), (2, 2), &world, false), Some(Vec::new()));
+
+        // A swimmer goes straight across
+        let path = pathfind((0, 0), (6, 0), &world, true).unwrap();
+        assert_eq!(path.len(), 6);
+        assert!(path.contains(&(3, 0)), "{:?}", path);
+    }
 }